@@ -0,0 +1,64 @@
+use discv5::enr::NodeId;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::enr::Enr;
+use crate::types::portal::{OverlayTuning, PruneInfo, SubnetworkSummary};
+
+/// Node management JSON-RPC endpoints. Only served when the admin module is selected explicitly
+/// with `--<transport>.api`.
+#[rpc(client, server, namespace = "admin")]
+pub trait AdminApi {
+    /// Returns the overlay parameters of a subnetwork, eg. "history".
+    #[method(name = "overlayConfig")]
+    async fn overlay_config(&self, network: String) -> RpcResult<OverlayTuning>;
+
+    /// Updates the overlay parameters of a subnetwork that are set in `config`, and returns the
+    /// resulting parameters. A query parallelism or query timeout of 0 is rejected.
+    #[method(name = "setOverlayConfig")]
+    async fn set_overlay_config(
+        &self,
+        network: String,
+        config: OverlayTuning,
+    ) -> RpcResult<OverlayTuning>;
+
+    /// Changes the storage capacity of a subnetwork, pruning the farthest content if the store
+    /// no longer fits.
+    #[method(name = "setStorageCapacity")]
+    async fn set_storage_capacity(&self, network: String, capacity_mb: u64)
+        -> RpcResult<PruneInfo>;
+
+    /// Prunes the farthest content of a subnetwork while its store is over capacity.
+    #[method(name = "prune")]
+    async fn prune(&self, network: String) -> RpcResult<PruneInfo>;
+
+    /// Adds a node to the discv5 routing table and to the routing table of every active
+    /// subnetwork.
+    #[method(name = "addBootnode")]
+    async fn add_bootnode(&self, enr: Enr) -> RpcResult<bool>;
+
+    /// Removes a node from the discv5 routing table and from the routing table of every active
+    /// subnetwork.
+    #[method(name = "removeBootnode")]
+    async fn remove_bootnode(&self, node_id: NodeId) -> RpcResult<bool>;
+
+    /// Bans a peer for `duration_secs`, or until the node restarts if not set, and removes it
+    /// from every routing table.
+    #[method(name = "banPeer")]
+    async fn ban_peer(&self, node_id: NodeId, duration_secs: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts a ban on a peer.
+    #[method(name = "unbanPeer")]
+    async fn unban_peer(&self, node_id: NodeId) -> RpcResult<bool>;
+
+    /// Replaces the log filter, using the same syntax as `RUST_LOG`.
+    #[method(name = "setLogFilter")]
+    async fn set_log_filter(&self, filter: String) -> RpcResult<bool>;
+
+    /// Returns the storage, message and uTP metrics of a subnetwork.
+    #[method(name = "summary")]
+    async fn summary(&self, network: String) -> RpcResult<SubnetworkSummary>;
+
+    /// Shuts the node down gracefully.
+    #[method(name = "shutdown")]
+    async fn shutdown(&self) -> RpcResult<bool>;
+}
@@ -1,9 +1,12 @@
 use crate::types::content_key::beacon::BeaconContentKey;
 use crate::types::enr::Enr;
-use crate::types::portal::FindNodesInfo;
+use crate::types::gossip_trace::{GossipTrace, OfferTrace};
 use crate::types::portal::{
-    AcceptInfo, ContentInfo, DataRadius, PaginateLocalContentInfo, PongInfo, TraceContentInfo,
+    AcceptInfo, BeaconContentInfo, BeaconPaginateLocalContentInfo, BeaconTraceContentInfo,
+    DataRadius, PongInfo,
 };
+use crate::types::portal::{FindNodesInfo, TraceNodesInfo};
+use crate::types::query_trace::QueryTrace;
 use crate::RoutingTableInfo;
 use crate::{BeaconContentValue, PossibleBeaconContentValue};
 use discv5::enr::NodeId;
@@ -49,10 +52,17 @@ pub trait BeaconNetworkApi {
     #[method(name = "beaconRecursiveFindNodes")]
     async fn recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<Vec<Enr>>;
 
+    /// Lookup a target node within in the network. Return tracing info.
+    #[method(name = "beaconTraceRecursiveFindNodes")]
+    async fn trace_recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<TraceNodesInfo>;
+
     /// Send FINDCONTENT message to get the content with a content key.
     #[method(name = "beaconFindContent")]
-    async fn find_content(&self, enr: Enr, content_key: BeaconContentKey)
-        -> RpcResult<ContentInfo>;
+    async fn find_content(
+        &self,
+        enr: Enr,
+        content_key: BeaconContentKey,
+    ) -> RpcResult<BeaconContentInfo>;
 
     /// Lookup a target content key in the network
     #[method(name = "beaconRecursiveFindContent")]
@@ -66,7 +76,12 @@ pub trait BeaconNetworkApi {
     async fn trace_recursive_find_content(
         &self,
         content_key: BeaconContentKey,
-    ) -> RpcResult<TraceContentInfo>;
+    ) -> RpcResult<BeaconTraceContentInfo>;
+
+    /// Return the traces of recently completed content lookups, newest first.
+    /// Returns all retained traces if `limit` is not provided.
+    #[method(name = "beaconRecentQueryTraces")]
+    async fn recent_query_traces(&self, limit: Option<u64>) -> RpcResult<Vec<QueryTrace>>;
 
     /// Pagination of local content keys
     #[method(name = "beaconPaginateLocalContentKeys")]
@@ -74,10 +89,10 @@ pub trait BeaconNetworkApi {
         &self,
         offset: u64,
         limit: u64,
-    ) -> RpcResult<PaginateLocalContentInfo>;
+    ) -> RpcResult<BeaconPaginateLocalContentInfo>;
 
     /// Send the provided content value to interested peers. Clients may choose to send to some or all peers.
-    /// Return the number of peers that the content was gossiped to.
+    /// Return the number of peers that the content was successfully transferred to.
     #[method(name = "beaconGossip")]
     async fn gossip(
         &self,
@@ -85,6 +100,16 @@ pub trait BeaconNetworkApi {
         content_value: BeaconContentValue,
     ) -> RpcResult<u32>;
 
+    /// Send the provided content value to interested peers, and wait for the outcome of every
+    /// offer. Return the peers selected for the content, the content keys each peer accepted
+    /// and the outcome of each uTP transfer.
+    #[method(name = "beaconTraceGossip")]
+    async fn trace_gossip(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: BeaconContentValue,
+    ) -> RpcResult<GossipTrace>;
+
     /// Send an OFFER request with given ContentKey, to the designated peer and wait for a response.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist receive.
     #[method(name = "beaconOffer")]
@@ -95,6 +120,17 @@ pub trait BeaconNetworkApi {
         content_value: Option<BeaconContentValue>,
     ) -> RpcResult<AcceptInfo>;
 
+    /// Send an OFFER request with given ContentKey, to the designated peer and wait for the
+    /// uTP transfer of the accepted content. Return which content keys were accepted and the
+    /// outcome of the uTP transfer.
+    #[method(name = "beaconTraceOffer")]
+    async fn trace_offer(
+        &self,
+        enr: Enr,
+        content_key: BeaconContentKey,
+        content_value: Option<BeaconContentValue>,
+    ) -> RpcResult<OfferTrace>;
+
     /// Store content key with a content data to the local database.
     #[method(name = "beaconStore")]
     async fn store(
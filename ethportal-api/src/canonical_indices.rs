@@ -0,0 +1,43 @@
+use crate::types::content_key::canonical_indices::CanonicalIndicesContentKey;
+use crate::types::portal::DataRadius;
+use crate::{CanonicalIndicesContentValue, PossibleCanonicalIndicesContentValue};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Portal Canonical Indices JSON-RPC endpoints
+#[rpc(client, server, namespace = "portal")]
+pub trait CanonicalIndicesNetworkApi {
+    /// Returns the node data radios
+    #[method(name = "canonicalIndicesRadius")]
+    async fn radius(&self) -> RpcResult<DataRadius>;
+
+    /// Lookup a target content key in the network
+    #[method(name = "canonicalIndicesRecursiveFindContent")]
+    async fn recursive_find_content(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+    ) -> RpcResult<PossibleCanonicalIndicesContentValue>;
+
+    /// Send the provided content value to interested peers. Clients may choose to send to some or all peers.
+    /// Return the number of peers that the content was gossiped to.
+    #[method(name = "canonicalIndicesGossip")]
+    async fn gossip(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+        content_value: CanonicalIndicesContentValue,
+    ) -> RpcResult<u32>;
+
+    /// Store content key with a content data to the local database.
+    #[method(name = "canonicalIndicesStore")]
+    async fn store(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+        content_value: CanonicalIndicesContentValue,
+    ) -> RpcResult<bool>;
+
+    /// Get a content from the local database
+    #[method(name = "canonicalIndicesLocalContent")]
+    async fn local_content(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+    ) -> RpcResult<PossibleCanonicalIndicesContentValue>;
+}
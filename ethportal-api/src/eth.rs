@@ -1,6 +1,9 @@
 use ethereum_types::{H256, U256};
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_rpc_types::Block;
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
+use reth_rpc_types::{Block, Header};
 
 /// Web3 JSON-RPC endpoints
 #[rpc(client, server, namespace = "eth")]
@@ -14,4 +17,16 @@ pub trait EthApi {
         block_hash: H256,
         hydrated_transactions: bool,
     ) -> RpcResult<Block>;
+
+    #[method(name = "sendRawTransaction")]
+    async fn send_raw_transaction(&self, raw_transaction: String) -> RpcResult<H256>;
+
+    /// Only "newHeads" is supported. The heads are verified by the beacon light client, which runs
+    /// when trin is started with the beacon network and `--trusted-block-root`.
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = Header
+    )]
+    async fn subscribe(&self, kind: String) -> SubscriptionResult;
 }
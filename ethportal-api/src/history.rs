@@ -1,9 +1,12 @@
 use crate::types::content_key::history::HistoryContentKey;
 use crate::types::enr::Enr;
-use crate::types::portal::FindNodesInfo;
+use crate::types::gossip_trace::{GossipTrace, OfferOutcome, OfferTrace};
 use crate::types::portal::{
-    AcceptInfo, ContentInfo, DataRadius, PaginateLocalContentInfo, PongInfo, TraceContentInfo,
+    AcceptInfo, DataRadius, HistoryContentInfo, HistoryPaginateLocalContentInfo,
+    HistoryTraceContentInfo, PongInfo,
 };
+use crate::types::portal::{FindNodesInfo, TraceNodesInfo};
+use crate::types::query_trace::QueryTrace;
 use crate::RoutingTableInfo;
 use crate::{HistoryContentValue, PossibleHistoryContentValue};
 use discv5::enr::NodeId;
@@ -49,27 +52,36 @@ pub trait HistoryNetworkApi {
     #[method(name = "historyRecursiveFindNodes")]
     async fn recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<Vec<Enr>>;
 
+    /// Lookup a target node within in the network. Return tracing info.
+    #[method(name = "historyTraceRecursiveFindNodes")]
+    async fn trace_recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<TraceNodesInfo>;
+
     /// Send FINDCONTENT message to get the content with a content key.
     #[method(name = "historyFindContent")]
     async fn find_content(
         &self,
         enr: Enr,
         content_key: HistoryContentKey,
-    ) -> RpcResult<ContentInfo>;
+    ) -> RpcResult<HistoryContentInfo>;
 
     /// Lookup a target content key in the network
     #[method(name = "historyRecursiveFindContent")]
     async fn recursive_find_content(
         &self,
         content_key: HistoryContentKey,
-    ) -> RpcResult<ContentInfo>;
+    ) -> RpcResult<HistoryContentInfo>;
 
     /// Lookup a target content key in the network. Return tracing info.
     #[method(name = "historyTraceRecursiveFindContent")]
     async fn trace_recursive_find_content(
         &self,
         content_key: HistoryContentKey,
-    ) -> RpcResult<TraceContentInfo>;
+    ) -> RpcResult<HistoryTraceContentInfo>;
+
+    /// Return the traces of recently completed content lookups, newest first.
+    /// Returns all retained traces if `limit` is not provided.
+    #[method(name = "historyRecentQueryTraces")]
+    async fn recent_query_traces(&self, limit: Option<u64>) -> RpcResult<Vec<QueryTrace>>;
 
     /// Pagination of local content keys
     #[method(name = "historyPaginateLocalContentKeys")]
@@ -77,10 +89,10 @@ pub trait HistoryNetworkApi {
         &self,
         offset: u64,
         limit: u64,
-    ) -> RpcResult<PaginateLocalContentInfo>;
+    ) -> RpcResult<HistoryPaginateLocalContentInfo>;
 
     /// Send the provided content value to interested peers. Clients may choose to send to some or all peers.
-    /// Return the number of peers that the content was gossiped to.
+    /// Return the number of peers that the content was successfully transferred to.
     #[method(name = "historyGossip")]
     async fn gossip(
         &self,
@@ -88,6 +100,16 @@ pub trait HistoryNetworkApi {
         content_value: HistoryContentValue,
     ) -> RpcResult<u32>;
 
+    /// Send the provided content value to interested peers, and wait for the outcome of every
+    /// offer. Return the peers selected for the content, the content keys each peer accepted
+    /// and the outcome of each uTP transfer.
+    #[method(name = "historyTraceGossip")]
+    async fn trace_gossip(
+        &self,
+        content_key: HistoryContentKey,
+        content_value: HistoryContentValue,
+    ) -> RpcResult<GossipTrace>;
+
     /// Send an OFFER request with given ContentKey, to the designated peer and wait for a response.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist receive.
     #[method(name = "historyOffer")]
@@ -98,6 +120,27 @@ pub trait HistoryNetworkApi {
         content_value: Option<HistoryContentValue>,
     ) -> RpcResult<AcceptInfo>;
 
+    /// Send an OFFER request with given ContentKey, to the designated peer and wait for the
+    /// uTP transfer of the accepted content. Return which content keys were accepted and the
+    /// outcome of the uTP transfer.
+    #[method(name = "historyTraceOffer")]
+    async fn trace_offer(
+        &self,
+        enr: Enr,
+        content_key: HistoryContentKey,
+        content_value: Option<HistoryContentValue>,
+    ) -> RpcResult<OfferTrace>;
+
+    /// Offer many content items to the designated peer, split into as many OFFER requests as
+    /// needed, and wait for the uTP transfers of the accepted content. Return the outcome of
+    /// offering each content item, with its content key, in the order they were given.
+    #[method(name = "historyOfferMany")]
+    async fn offer_many(
+        &self,
+        enr: Enr,
+        content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+    ) -> RpcResult<Vec<(String, OfferOutcome)>>;
+
     /// Store content key with a content data to the local database.
     #[method(name = "historyStore")]
     async fn store(
@@ -6,23 +6,30 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod admin;
 mod beacon;
+mod canonical_indices;
 mod dashboard;
 pub mod discv5;
 mod eth;
 mod history;
+mod portal;
 pub mod types;
 pub mod utils;
 mod web3;
 
 pub use crate::discv5::{Discv5ApiClient, Discv5ApiServer};
+pub use admin::{AdminApiClient, AdminApiServer};
 pub use beacon::{BeaconNetworkApiClient, BeaconNetworkApiServer};
+pub use canonical_indices::{CanonicalIndicesNetworkApiClient, CanonicalIndicesNetworkApiServer};
 pub use eth::{EthApiClient, EthApiServer};
 pub use history::{HistoryNetworkApiClient, HistoryNetworkApiServer};
+pub use portal::{PortalApiClient, PortalApiServer};
 pub use web3::{Web3ApiClient, Web3ApiServer};
 
 pub use types::content_key::{
     beacon::{BeaconContentKey, LightClientBootstrapKey, LightClientUpdatesByRangeKey},
+    canonical_indices::{BlockNumberKey, CanonicalIndicesContentKey, TransactionHashKey},
     error::ContentKeyError,
     history::{
         BlockBodyKey, BlockHeaderKey, BlockReceiptsKey, EpochAccumulatorKey, HistoryContentKey,
@@ -30,14 +37,21 @@ pub use types::content_key::{
     },
     overlay::{IdentityContentKey, OverlayContentKey},
     state::StateContentKey,
+    transaction_gossip::{TransactionGossipContentKey, TransactionKey},
 };
 
 pub use types::consensus;
 pub use types::consensus::light_client;
 pub use types::content_value::{
     beacon::{BeaconContentValue, PossibleBeaconContentValue},
+    canonical_indices::{
+        BlockHashIndex, CanonicalIndicesContentValue, PossibleCanonicalIndicesContentValue,
+        TransactionIndex,
+    },
     error::ContentValueError,
     history::{HistoryContentValue, PossibleHistoryContentValue},
+    state::{PossibleStateContentValue, StateContentValue},
+    transaction_gossip::TransactionGossipContentValue,
 };
 pub use types::execution::block_body::*;
 pub use types::execution::header::*;
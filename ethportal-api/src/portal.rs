@@ -0,0 +1,15 @@
+use jsonrpsee::{core::SubscriptionResult, proc_macros::rpc};
+
+use crate::types::portal::PortalEvent;
+
+/// Portal JSON-RPC endpoints shared by all subnetworks
+#[rpc(client, server, namespace = "portal")]
+pub trait PortalApi {
+    /// Stream the overlay events of a subnetwork, eg. "history" or "beacon".
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = PortalEvent
+    )]
+    async fn subscribe(&self, network: String) -> SubscriptionResult;
+}
@@ -1,29 +1,56 @@
 use clap::error::{Error, ErrorKind};
-use clap::{arg, Args, Parser, Subcommand};
+use clap::parser::ValueSource;
+use clap::{arg, ArgAction, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use ethereum_types::H256;
-use std::{env, ffi::OsString, fmt, net::SocketAddr, path::PathBuf, str::FromStr};
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    env,
+    ffi::OsString,
+    fmt, fs,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use strum::{AsRefStr, EnumString, EnumVariantNames, VariantNames};
 use url::Url;
 
 use crate::types::bootnodes::Bootnodes;
+use crate::types::portal::OverlayTuning;
 
 pub const DEFAULT_MASTER_ACC_PATH: &str = "validation_assets/merge_macc.bin";
 pub const DEFAULT_WEB3_IPC_PATH: &str = "/tmp/trin-jsonrpc.ipc";
 pub const DEFAULT_WEB3_HTTP_ADDRESS: &str = "http://127.0.0.1:8545/";
 pub const DEFAULT_WEB3_HTTP_PORT: u16 = 8545;
+pub const DEFAULT_WEB3_WS_ADDRESS: &str = "ws://127.0.0.1:8546/";
 pub const DEFAULT_WEB3_WS_PORT: u16 = 8546;
 const DEFAULT_DISCOVERY_PORT: &str = "9000";
+const DEFAULT_DISCOVERY_ADDRESS: &str = "0.0.0.0";
+/// The testnet STUN server is only reachable over IPv4, so a public STUN server that publishes an
+/// IPv6 address is used for IPv6 listen addresses.
+pub const DEFAULT_IPV6_STUN_SERVER: &str = "stun.l.google.com:19302";
 pub const BEACON_NETWORK: &str = "beacon";
+pub const CANONICAL_INDICES_NETWORK: &str = "canonical-indices";
 pub const HISTORY_NETWORK: &str = "history";
 pub const STATE_NETWORK: &str = "state";
+pub const TRANSACTION_GOSSIP_NETWORK: &str = "transaction-gossip";
 const DEFAULT_SUBNETWORKS: &str = "history";
 pub const DEFAULT_STORAGE_CAPACITY_MB: &str = "100";
+pub const DEFAULT_CACHE_CAPACITY_MB: u32 = 10;
 pub const DEFAULT_WEB3_TRANSPORT: &str = "ipc";
+pub const DEFAULT_MAX_PEER_REQUESTS_PER_SEC: &str = "50";
+pub const DEFAULT_MAX_REQUESTS_PER_SEC: &str = "1000";
+pub const DEFAULT_MAX_PEER_UTP_STREAMS: &str = "8";
+pub const DEFAULT_MAX_UTP_STREAMS: &str = "128";
+pub const DEFAULT_MAX_PEER_BYTES_PER_MIN: &str = "268435456";
+pub const DEFAULT_MAX_BYTES_PER_MIN: &str = "2147483648";
 
 use crate::dashboard::grafana::{GrafanaAPI, DASHBOARD_TEMPLATES};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Web3TransportType {
     HTTP,
+    WS,
     IPC,
 }
 
@@ -31,6 +58,7 @@ impl fmt::Display for Web3TransportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::HTTP => write!(f, "http"),
+            Self::WS => write!(f, "ws"),
             Self::IPC => write!(f, "ipc"),
         }
     }
@@ -42,12 +70,113 @@ impl FromStr for Web3TransportType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "http" => Ok(Web3TransportType::HTTP),
+            "ws" => Ok(Web3TransportType::WS),
             "ipc" => Ok(Web3TransportType::IPC),
-            _ => Err("Invalid web3-transport arg. Expected either 'http' or 'ipc'"),
+            _ => Err("Invalid web3-transport arg. Expected 'http', 'ws' or 'ipc'"),
         }
     }
 }
 
+/// Represents RPC modules that are supported by Trin
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Hash, AsRefStr, EnumVariantNames, EnumString, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum PortalRpcModule {
+    /// `admin_` module
+    Admin,
+    /// `portal_beacon` module
+    Beacon,
+    /// `portal_canonicalIndices` module
+    CanonicalIndices,
+    /// `discv5_` module
+    Discv5,
+    /// `eth_` module
+    Eth,
+    /// `portal_history` module
+    History,
+    /// `portal_subscribe` module
+    Portal,
+    /// `web3_` module
+    Web3,
+}
+
+impl PortalRpcModule {
+    /// Returns all variants of the enum
+    pub const fn all_variants() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+}
+
+impl fmt::Display for PortalRpcModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_ref())
+    }
+}
+
+/// The rpc modules selected with a `--<transport>.api` flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcApiSelection {
+    /// Every module of the active networks, including admin.
+    All,
+    /// Only the given modules. Whether they are served by the active networks is only known once
+    /// the networks are started.
+    Modules(Vec<PortalRpcModule>),
+}
+
+impl FromStr for RpcApiSelection {
+    type Err = String;
+
+    /// Parses "all" or a comma-separated list of modules.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+        let modules = s
+            .split(',')
+            .map(str::trim)
+            .filter(|module| !module.is_empty())
+            .map(|module| {
+                PortalRpcModule::from_str(module).map_err(|_| {
+                    format!(
+                        "Unknown rpc module: {module}, expected one or more of {:?} or 'all'",
+                        PortalRpcModule::all_variants()
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if modules.is_empty() {
+            return Err("Expected one or more rpc modules, or 'all'".to_string());
+        }
+        Ok(Self::Modules(modules))
+    }
+}
+
+/// An overlay parameter override for one subnetwork, eg. "history.query-parallelism=5".
+#[derive(Debug, PartialEq, Clone)]
+pub struct OverlayConfigOverride {
+    pub network: String,
+    pub tuning: OverlayTuning,
+}
+
+impl FromStr for OverlayConfigOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("Invalid overlay-config arg {s}. Expected <network>.<param>=<value>");
+        let (key, value) = s.split_once('=').ok_or_else(invalid)?;
+        let (network, param) = key.split_once('.').ok_or_else(invalid)?;
+        let mut tuning = OverlayTuning::default();
+        tuning.set(param.trim(), value.trim())?;
+        Ok(Self {
+            network: network.trim().to_owned(),
+            tuning,
+        })
+    }
+}
+
 #[derive(Parser, Debug, PartialEq, Clone)]
 #[command(
     name = "trin",
@@ -56,12 +185,19 @@ impl FromStr for Web3TransportType {
     about = "Run an eth portal client"
 )]
 pub struct TrinConfig {
+    #[arg(
+        long = "config",
+        help = "Path to a TOML file with values for any of the other flags, keyed by flag name (eg. web3-transport = \"http\"). Flags given on the command line take precedence"
+    )]
+    pub config: Option<PathBuf>,
+
     #[arg(
         default_value = DEFAULT_WEB3_TRANSPORT,
         long = "web3-transport",
-        help = "select transport protocol to serve json-rpc endpoint"
+        help = "Comma-separated list of transport protocols to serve json-rpc endpoints over (http, ws, ipc)",
+        use_value_delimiter = true
     )]
-    pub web3_transport: Web3TransportType,
+    pub web3_transport: Vec<Web3TransportType>,
 
     #[arg(
         default_value = DEFAULT_WEB3_HTTP_ADDRESS,
@@ -70,6 +206,13 @@ pub struct TrinConfig {
     )]
     pub web3_http_address: Url,
 
+    #[arg(
+        default_value = DEFAULT_WEB3_WS_ADDRESS,
+        long = "web3-ws-address",
+        help = "address to accept json-rpc websocket connections"
+    )]
+    pub web3_ws_address: Url,
+
     #[arg(
         default_value = DEFAULT_WEB3_IPC_PATH,
         long = "web3-ipc-path",
@@ -77,6 +220,24 @@ pub struct TrinConfig {
     )]
     pub web3_ipc_path: PathBuf,
 
+    #[arg(
+        long = "http.api",
+        help = "Comma-separated list of rpc modules to serve over http, eg. eth,web3, or 'all'. Defaults to every module of the active networks, except admin"
+    )]
+    pub http_api: Option<RpcApiSelection>,
+
+    #[arg(
+        long = "ws.api",
+        help = "Comma-separated list of rpc modules to serve over ws, eg. eth,web3, or 'all'. Defaults to every module of the active networks, except admin"
+    )]
+    pub ws_api: Option<RpcApiSelection>,
+
+    #[arg(
+        long = "ipc.api",
+        help = "Comma-separated list of rpc modules to serve over ipc, eg. eth,web3, or 'all'. Defaults to every module of the active networks, except admin"
+    )]
+    pub ipc_api: Option<RpcApiSelection>,
+
     #[arg(
         default_value = DEFAULT_DISCOVERY_PORT,
         long = "discovery-port",
@@ -84,6 +245,14 @@ pub struct TrinConfig {
     )]
     pub discovery_port: u16,
 
+    #[arg(
+        default_value = DEFAULT_DISCOVERY_ADDRESS,
+        long = "discovery-address",
+        help = "Comma-separated IP address(es) to bind the UDP discovery socket to. Supply an IPv4 address, an IPv6 address, or one of each to listen dual-stack, ex: 0.0.0.0,::",
+        use_value_delimiter = true
+    )]
+    pub discovery_address: Vec<IpAddr>,
+
     #[arg(
         default_value = "default",
         long = "bootnodes",
@@ -105,13 +274,47 @@ pub struct TrinConfig {
     )]
     pub no_stun: bool,
 
+    #[arg(
+        default_value = DEFAULT_IPV6_STUN_SERVER,
+        long = "ipv6-stun-server",
+        help = "STUN server (host:port) used to determine the external address of an IPv6 discovery address. The host must resolve to an IPv6 address"
+    )]
+    pub ipv6_stun_server: String,
+
+    #[arg(
+        long = "no-ipv6-stun",
+        help = "Do not use STUN to determine the external address of an IPv6 discovery address. Leaves the ENR entry for the IPv6 address blank, unless an IPv6 --external-address is set"
+    )]
+    pub no_ipv6_stun: bool,
+
     #[arg(
         long = "unsafe-private-key",
         value_parser = check_private_key_length,
-        help = "Hex encoded 32 byte private key (with 0x prefix) (considered unsafe as it's stored in terminal history - keyfile support coming soon)"
+        help = "Hex encoded 32 byte private key (with 0x prefix) (considered unsafe as it's stored in terminal history - prefer --keystore)"
     )]
     pub private_key: Option<H256>,
 
+    #[arg(
+        long = "keystore",
+        conflicts_with = "private_key",
+        help = "Path to an encrypted keystore file holding the node private key, eg. one created with `trin account new`"
+    )]
+    pub keystore: Option<PathBuf>,
+
+    #[arg(
+        long = "password-file",
+        global = true,
+        help = "Path to a file containing the keystore password. If not supplied, the TRIN_KEYSTORE_PASSWORD environment variable is used"
+    )]
+    pub password_file: Option<PathBuf>,
+
+    #[arg(
+        long = "trusted-block-root",
+        value_parser = check_trusted_block_root_length,
+        help = "Hex encoded beacon block root (with 0x prefix) to bootstrap the beacon light client from. The light client follows the head of the chain for eth_subscribe(\"newHeads\"), and only runs when this is set"
+    )]
+    pub trusted_block_root: Option<H256>,
+
     #[arg(
     long = "networks",
         help = "Comma-separated list of which portal subnetworks to activate",
@@ -120,6 +323,55 @@ pub struct TrinConfig {
     )]
     pub networks: Vec<String>,
 
+    #[arg(
+        long = "overlay-config",
+        help = "Comma-separated list of overlay parameters to set for a subnetwork, as <network>.<param>=<value>, eg. history.query-parallelism=5. Params: query-parallelism, query-timeout-ms, query-peer-timeout-ms, query-num-results, max-incoming-per-bucket, findnodes-query-distances-per-peer, utp-max-packet-size, utp-max-conn-attempts, utp-idle-timeout-ms, utp-initial-timeout-ms, utp-target-delay-ms, utp-max-connections, gossip-num-closest-nodes, gossip-num-farther-nodes, gossip-min-interested-nodes, gossip-max-lookups",
+        use_value_delimiter = true
+    )]
+    pub overlay_config: Vec<OverlayConfigOverride>,
+
+    #[arg(
+        default_value = DEFAULT_MAX_PEER_REQUESTS_PER_SEC,
+        long = "max-peer-requests-per-sec",
+        help = "Maximum number of FindContent and Offer requests served per second to a single peer. Requests over the limit get an empty response. 0 disables the limit"
+    )]
+    pub max_peer_requests_per_sec: u32,
+
+    #[arg(
+        default_value = DEFAULT_MAX_REQUESTS_PER_SEC,
+        long = "max-requests-per-sec",
+        help = "Maximum number of FindContent and Offer requests served per second to all peers. 0 disables the limit"
+    )]
+    pub max_requests_per_sec: u32,
+
+    #[arg(
+        default_value = DEFAULT_MAX_PEER_UTP_STREAMS,
+        long = "max-peer-utp-streams",
+        help = "Maximum number of concurrent uTP streams opened for a single peer's requests, in each direction. 0 disables the limit"
+    )]
+    pub max_peer_utp_streams: usize,
+
+    #[arg(
+        default_value = DEFAULT_MAX_UTP_STREAMS,
+        long = "max-utp-streams",
+        help = "Maximum number of concurrent uTP streams opened for all peers' requests, in each direction. 0 disables the limit"
+    )]
+    pub max_utp_streams: usize,
+
+    #[arg(
+        default_value = DEFAULT_MAX_PEER_BYTES_PER_MIN,
+        long = "max-peer-bytes-per-min",
+        help = "Maximum number of content bytes served to or accepted from a single peer per minute. 0 disables the limit"
+    )]
+    pub max_peer_bytes_per_min: u64,
+
+    #[arg(
+        default_value = DEFAULT_MAX_BYTES_PER_MIN,
+        long = "max-bytes-per-min",
+        help = "Maximum number of content bytes served to or accepted from all peers per minute. 0 disables the limit"
+    )]
+    pub max_bytes_per_min: u64,
+
     /// Storage capacity specified in megabytes.
     #[arg(
         default_value(DEFAULT_STORAGE_CAPACITY_MB),
@@ -128,6 +380,14 @@ pub struct TrinConfig {
     )]
     pub mb: u32,
 
+    /// Cache capacity specified in megabytes.
+    #[arg(
+        default_value_t = DEFAULT_CACHE_CAPACITY_MB,
+        long = "cache-mb",
+        help = "Maximum number of megabytes of recently found content outside of the radius to keep in memory for local lookups, per subnetwork"
+    )]
+    pub cache_mb: u32,
+
     #[arg(
         long = "enable-metrics-with-url",
         help = "Enable prometheus metrics reporting (provide local IP/Port from which your Prometheus server is configured to fetch metrics)"
@@ -155,25 +415,59 @@ pub struct TrinConfig {
 impl Default for TrinConfig {
     fn default() -> Self {
         TrinConfig {
-            web3_transport: Web3TransportType::from_str(DEFAULT_WEB3_TRANSPORT)
-                .expect("Parsing static DEFAULT_WEB3_TRANSPORT to work"),
+            config: None,
+            web3_transport: vec![Web3TransportType::from_str(DEFAULT_WEB3_TRANSPORT)
+                .expect("Parsing static DEFAULT_WEB3_TRANSPORT to work")],
             web3_http_address: Url::parse(DEFAULT_WEB3_HTTP_ADDRESS)
                 .expect("Parsing static DEFAULT_WEB3_HTTP_ADDRESS to work"),
+            web3_ws_address: Url::parse(DEFAULT_WEB3_WS_ADDRESS)
+                .expect("Parsing static DEFAULT_WEB3_WS_ADDRESS to work"),
             web3_ipc_path: PathBuf::from(DEFAULT_WEB3_IPC_PATH),
+            http_api: None,
+            ws_api: None,
+            ipc_api: None,
             discovery_port: DEFAULT_DISCOVERY_PORT
                 .parse()
                 .expect("Parsing static DEFAULT_DISCOVERY_PORT to work"),
+            discovery_address: vec![DEFAULT_DISCOVERY_ADDRESS
+                .parse()
+                .expect("Parsing static DEFAULT_DISCOVERY_ADDRESS to work")],
             bootnodes: Bootnodes::Default,
             external_addr: None,
             no_stun: false,
+            ipv6_stun_server: DEFAULT_IPV6_STUN_SERVER.to_string(),
+            no_ipv6_stun: false,
             private_key: None,
+            keystore: None,
+            password_file: None,
+            trusted_block_root: None,
             networks: DEFAULT_SUBNETWORKS
                 .split(',')
                 .map(|n| n.to_string())
                 .collect(),
+            overlay_config: vec![],
+            max_peer_requests_per_sec: DEFAULT_MAX_PEER_REQUESTS_PER_SEC
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_PEER_REQUESTS_PER_SEC to work"),
+            max_requests_per_sec: DEFAULT_MAX_REQUESTS_PER_SEC
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_REQUESTS_PER_SEC to work"),
+            max_peer_utp_streams: DEFAULT_MAX_PEER_UTP_STREAMS
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_PEER_UTP_STREAMS to work"),
+            max_utp_streams: DEFAULT_MAX_UTP_STREAMS
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_UTP_STREAMS to work"),
+            max_peer_bytes_per_min: DEFAULT_MAX_PEER_BYTES_PER_MIN
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_PEER_BYTES_PER_MIN to work"),
+            max_bytes_per_min: DEFAULT_MAX_BYTES_PER_MIN
+                .parse()
+                .expect("Parsing static DEFAULT_MAX_BYTES_PER_MIN to work"),
             mb: DEFAULT_STORAGE_CAPACITY_MB
                 .parse()
                 .expect("Parsing static DEFAULT_STORAGE_CAPACITY_MB to work"),
+            cache_mb: DEFAULT_CACHE_CAPACITY_MB,
             enable_metrics_with_url: None,
             ephemeral: false,
             master_acc_path: PathBuf::from(DEFAULT_MASTER_ACC_PATH.to_string()),
@@ -183,6 +477,19 @@ impl Default for TrinConfig {
 }
 
 impl TrinConfig {
+    /// Returns the overlay parameters set for `network` with `--overlay-config`.
+    pub fn overlay_tuning(&self, network: &str) -> OverlayTuning {
+        let mut tuning = OverlayTuning::default();
+        for overlay_config in &self.overlay_config {
+            if overlay_config.network == network {
+                tuning
+                    .merge(&overlay_config.tuning)
+                    .expect("Overlay config overrides are validated when parsed");
+            }
+        }
+        tuning
+    }
+
     pub fn from_cli() -> Self {
         Self::new_from(env::args_os()).unwrap_or_else(|e| e.exit())
     }
@@ -191,7 +498,27 @@ impl TrinConfig {
         I: Iterator<Item = T>,
         T: Into<OsString> + Clone,
     {
-        let config = Self::try_parse_from(args)?;
+        let mut args: Vec<OsString> = args.map(Into::into).collect();
+        let cli_matches = Self::command().try_get_matches_from(&args)?;
+        let mut config = Self::from_arg_matches(&cli_matches)?;
+
+        // The config file is parsed on its own, then each of its flags is used unless the flag is
+        // given on the command line. A flag given on the command line replaces the value from the
+        // file, rather than adding to it, so each flag is still passed at most once.
+        if let Some(config_file) = &config.config {
+            let file_args = config_file_args(config_file)?;
+            let binary_name = args.first().cloned().unwrap_or_default();
+            Self::try_parse_from(
+                std::iter::once(binary_name).chain(file_args.iter().map(|(_, arg)| arg.clone())),
+            )?;
+            let cli_flags = command_line_flags(&cli_matches);
+            let file_args = file_args
+                .into_iter()
+                .filter(|(flag, _)| !cli_flags.contains(flag))
+                .map(|(_, arg)| arg);
+            args.splice(1..1, file_args);
+            config = Self::try_parse_from(&args)?;
+        }
 
         if let Some(TrinConfigCommands::CreateDashboard(dashboard_config)) = config.command {
             if let Err(err) = create_dashboard(dashboard_config) {
@@ -201,20 +528,195 @@ impl TrinConfig {
             std::process::exit(0);
         }
 
-        match config.web3_transport {
-            Web3TransportType::HTTP => match &config.web3_ipc_path.as_path().display().to_string()[..] {
-                DEFAULT_WEB3_IPC_PATH => {}
-                _ => return Err(Error::raw(ErrorKind::ArgumentConflict, "Must not supply an ipc path when using http protocol for json-rpc")),
-            },
-            Web3TransportType::IPC => match config.web3_http_address.as_str() {
-                DEFAULT_WEB3_HTTP_ADDRESS => {}
-                p => return Err(Error::raw(ErrorKind::ArgumentConflict,format!("Must not supply an http address when using ipc protocol for json-rpc (received: {p})"))),
+        // A custom address or path is only allowed for a transport that is served
+        if !config.web3_transport.contains(&Web3TransportType::IPC)
+            && config.web3_ipc_path.as_path().display().to_string() != DEFAULT_WEB3_IPC_PATH
+        {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "Must not supply an ipc path when not using ipc protocol for json-rpc",
+            ));
+        }
+        if !config.web3_transport.contains(&Web3TransportType::HTTP)
+            && config.web3_http_address.as_str() != DEFAULT_WEB3_HTTP_ADDRESS
+        {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "Must not supply an http address when not using http protocol for json-rpc (received: {})",
+                    config.web3_http_address
+                ),
+            ));
+        }
+        if !config.web3_transport.contains(&Web3TransportType::WS)
+            && config.web3_ws_address.as_str() != DEFAULT_WEB3_WS_ADDRESS
+        {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "Must not supply a ws address when not using ws protocol for json-rpc (received: {})",
+                    config.web3_ws_address
+                ),
+            ));
+        }
+        for (transport, api) in [
+            (Web3TransportType::HTTP, &config.http_api),
+            (Web3TransportType::WS, &config.ws_api),
+            (Web3TransportType::IPC, &config.ipc_api),
+        ] {
+            if api.is_some() && !config.web3_transport.contains(&transport) {
+                return Err(Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    format!("Must not supply --{transport}.api when not using {transport} protocol for json-rpc"),
+                ));
             }
         }
+        if let Some(overlay_config) = config
+            .overlay_config
+            .iter()
+            .find(|overlay_config| !config.networks.contains(&overlay_config.network))
+        {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "Must not supply --overlay-config for the {} network, which is not enabled with --networks",
+                    overlay_config.network
+                ),
+            ));
+        }
+        let ipv4_count = config
+            .discovery_address
+            .iter()
+            .filter(|ip| ip.is_ipv4())
+            .count();
+        let ipv6_count = config.discovery_address.len() - ipv4_count;
+        if ipv4_count > 1 || ipv6_count > 1 {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "Must supply at most one IPv4 and one IPv6 --discovery-address",
+            ));
+        }
+
+        if let Some(TrinConfigCommands::DumpConfig) = config.command {
+            let matches = Self::command().try_get_matches_from(&args)?;
+            print!("{}", effective_config_toml(&matches));
+            // exit program since dumping the config is all we do
+            std::process::exit(0);
+        }
         Ok(config)
     }
 }
 
+/// Flags that are left out of `trin dump-config`.
+const DUMP_CONFIG_SKIPPED_FLAGS: [&str; 4] = ["config", "help", "version", "unsafe-private-key"];
+
+/// Returns the long names of the flags given on the command line, as opposed to defaults.
+fn command_line_flags(matches: &ArgMatches) -> HashSet<String> {
+    TrinConfig::command()
+        .get_arguments()
+        .filter(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .filter_map(|arg| arg.get_long().map(str::to_owned))
+        .collect()
+}
+
+/// Converts the TOML config file at `path` into command line args, paired with the long name of
+/// their flag. Each key is the long name of a flag; arrays are passed as comma-separated values and
+/// boolean flags are set when `true`.
+fn config_file_args(path: &Path) -> Result<Vec<(String, OsString)>, clap::Error> {
+    let io_error = |err: String| {
+        Error::raw(
+            ErrorKind::Io,
+            format!("Unable to load config file {}: {err}", path.display()),
+        )
+    };
+    let content = fs::read_to_string(path).map_err(|err| io_error(err.to_string()))?;
+    let table: toml::Table = content
+        .parse()
+        .map_err(|err: toml::de::Error| io_error(err.to_string()))?;
+
+    let mut args = vec![];
+    for (key, value) in table {
+        if key == "config" {
+            return Err(io_error(
+                "a config file must not reference another config file".to_owned(),
+            ));
+        }
+        let value = match value {
+            toml::Value::Boolean(true) => {
+                let arg = OsString::from(format!("--{key}"));
+                args.push((key, arg));
+                continue;
+            }
+            toml::Value::Boolean(false) => continue,
+            toml::Value::Array(values) => values
+                .into_iter()
+                .map(|value| toml_value_to_arg(&key, value))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(","),
+            value => toml_value_to_arg(&key, value)?,
+        };
+        let arg = OsString::from(format!("--{key}={value}"));
+        args.push((key, arg));
+    }
+    Ok(args)
+}
+
+fn toml_value_to_arg(key: &str, value: toml::Value) -> Result<String, clap::Error> {
+    match value {
+        toml::Value::String(value) => Ok(value),
+        toml::Value::Integer(value) => Ok(value.to_string()),
+        toml::Value::Float(value) => Ok(value.to_string()),
+        value => Err(Error::raw(
+            ErrorKind::InvalidValue,
+            format!("Unsupported value for {key} in config file: {value}"),
+        )),
+    }
+}
+
+/// Renders the effective value of every flag, including defaults, in the config file format.
+fn effective_config_toml(matches: &ArgMatches) -> String {
+    let mut table = toml::Table::new();
+    for arg in TrinConfig::command().get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        if DUMP_CONFIG_SKIPPED_FLAGS.contains(&long) {
+            continue;
+        }
+        let id = arg.get_id().as_str();
+        let value = match arg.get_action() {
+            ArgAction::SetTrue => toml::Value::Boolean(matches.get_flag(id)),
+            _ => {
+                let Some(raw_values) = matches.get_raw(id) else {
+                    continue;
+                };
+                let mut values = raw_values
+                    .flat_map(|value| {
+                        value
+                            .to_string_lossy()
+                            .split(',')
+                            .map(str::to_owned)
+                            .collect::<Vec<_>>()
+                    })
+                    .map(|value| match value.parse::<i64>() {
+                        Ok(value) => toml::Value::Integer(value),
+                        Err(_) => toml::Value::String(value),
+                    });
+                if arg.get_value_delimiter().is_some() {
+                    toml::Value::Array(values.collect())
+                } else {
+                    match values.next() {
+                        Some(value) => value,
+                        None => continue,
+                    }
+                }
+            }
+        };
+        table.insert(long.to_owned(), value);
+    }
+    toml::to_string(&table).expect("Serializing a TOML table to work")
+}
+
 fn check_private_key_length(private_key: &str) -> Result<H256, String> {
     if private_key.len() == 66 {
         return H256::from_str(private_key).map_err(|err| format!("HexError: {}", err));
@@ -225,13 +727,28 @@ fn check_private_key_length(private_key: &str) -> Result<H256, String> {
     ))
 }
 
+fn check_trusted_block_root_length(block_root: &str) -> Result<H256, String> {
+    if block_root.len() == 66 {
+        return H256::from_str(block_root).map_err(|err| format!("HexError: {}", err));
+    }
+    Err(format!(
+        "Invalid trusted block root length: {}, expected 66 (0x-prefixed 32 byte hexstring)",
+        block_root.len()
+    ))
+}
+
 impl fmt::Display for TrinConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let web3_ipc_path_str = self.web3_ipc_path.as_path().display().to_string();
-        let json_rpc_url = match &self.web3_transport {
-            Web3TransportType::HTTP => self.web3_http_address.as_str(),
-            Web3TransportType::IPC => &web3_ipc_path_str[..],
-        };
+        let json_rpc_url = self
+            .web3_transport
+            .iter()
+            .map(|transport| match transport {
+                Web3TransportType::HTTP => self.web3_http_address.to_string(),
+                Web3TransportType::WS => self.web3_ws_address.to_string(),
+                Web3TransportType::IPC => self.web3_ipc_path.as_path().display().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
 
         write!(
             f,
@@ -245,6 +762,28 @@ impl fmt::Display for TrinConfig {
 #[allow(clippy::enum_variant_names)]
 pub enum TrinConfigCommands {
     CreateDashboard(DashboardConfig),
+    /// Print the effective configuration, including values from --config, as TOML
+    DumpConfig,
+    /// Manage encrypted keystores holding node private keys
+    #[command(subcommand)]
+    Account(AccountCommands),
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum AccountCommands {
+    /// Generate a new private key and store it in an encrypted keystore
+    New,
+    /// Encrypt an existing hex encoded private key into a keystore. Defaults to the plaintext
+    /// key that trin generated in its data directory
+    Import {
+        #[arg(long = "private-key-file")]
+        private_key_file: Option<PathBuf>,
+    },
+    /// Decrypt a keystore and print its hex encoded private key
+    Export {
+        /// Path to the keystore file
+        keystore: PathBuf,
+    },
 }
 
 #[derive(Args, Debug, Default, Clone, PartialEq)]
@@ -317,7 +856,7 @@ mod test {
     fn test_custom_http_args() {
         let expected_config = TrinConfig {
             web3_http_address: Url::parse("http://0.0.0.0:8080/").unwrap(),
-            web3_transport: Web3TransportType::HTTP,
+            web3_transport: vec![Web3TransportType::HTTP],
             ..Default::default()
         };
         let actual_config = TrinConfig::new_from(
@@ -343,7 +882,7 @@ mod test {
         let actual_config: TrinConfig = Default::default();
         let expected_config = TrinConfig {
             web3_http_address: Url::parse(DEFAULT_WEB3_HTTP_ADDRESS).unwrap(),
-            web3_transport: Web3TransportType::IPC,
+            web3_transport: vec![Web3TransportType::IPC],
             ..Default::default()
         };
         assert_eq!(actual_config.web3_transport, expected_config.web3_transport);
@@ -360,7 +899,7 @@ mod test {
         let expected_config = TrinConfig {
             web3_http_address: Url::parse(DEFAULT_WEB3_HTTP_ADDRESS).unwrap(),
             web3_ipc_path: PathBuf::from("/path/test.ipc"),
-            web3_transport: Web3TransportType::IPC,
+            web3_transport: vec![Web3TransportType::IPC],
             ..Default::default()
         };
         assert_eq!(actual_config.web3_transport, expected_config.web3_transport);
@@ -372,7 +911,7 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Must not supply an ipc path when using http")]
+    #[should_panic(expected = "Must not supply an ipc path when not using ipc")]
 
     fn test_http_protocol_rejects_custom_web3_ipc_path() {
         TrinConfig::new_from(
@@ -389,12 +928,112 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Must not supply an http address when using ipc")]
+    #[should_panic(expected = "Must not supply an http address when not using http")]
     fn test_ipc_protocol_rejects_custom_web3_http_address() {
         TrinConfig::new_from(["trin", "--web3-http-address", "http://127.0.0.1:1234/"].iter())
             .unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "Must not supply a ws address when not using ws")]
+    fn test_http_protocol_rejects_custom_web3_ws_address() {
+        TrinConfig::new_from(
+            [
+                "trin",
+                "--web3-transport",
+                "http",
+                "--web3-ws-address",
+                "ws://127.0.0.1:1234/",
+            ]
+            .iter(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_transport_api() {
+        let actual_config = TrinConfig::new_from(
+            [
+                "trin",
+                "--web3-transport",
+                "http,ipc",
+                "--http.api",
+                "eth,web3",
+                "--ipc.api",
+                "all",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            actual_config.http_api,
+            Some(RpcApiSelection::Modules(vec![
+                PortalRpcModule::Eth,
+                PortalRpcModule::Web3
+            ]))
+        );
+        assert_eq!(actual_config.ws_api, None);
+        assert_eq!(actual_config.ipc_api, Some(RpcApiSelection::All));
+    }
+
+    #[test]
+    #[should_panic(expected = "Must not supply --ws.api when not using ws")]
+    fn test_transport_api_rejects_unused_transport() {
+        TrinConfig::new_from(["trin", "--ws.api", "eth"].iter()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown rpc module: ethh")]
+    fn test_transport_api_rejects_unknown_module() {
+        TrinConfig::new_from(["trin", "--ipc.api", "ethh,web3"].iter()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected one or more rpc modules")]
+    fn test_transport_api_rejects_empty_selection() {
+        TrinConfig::new_from(["trin", "--ipc.api", ","].iter()).unwrap();
+    }
+
+    #[test]
+    fn test_transport_api_in_config_file_is_validated() {
+        let file = write_config_file(r#""ipc.api" = "eth,unknown""#);
+        let config_path = file.path().to_str().unwrap();
+        let err = TrinConfig::new_from(["trin", "--config", config_path].iter()).unwrap_err();
+        assert!(err.to_string().contains("Unknown rpc module: unknown"));
+    }
+
+    #[test]
+    fn test_multiple_transports() {
+        let actual_config = TrinConfig::new_from(
+            [
+                "trin",
+                "--web3-transport",
+                "http,ws,ipc",
+                "--web3-http-address",
+                "http://0.0.0.0:8080/",
+                "--web3-ws-address",
+                "ws://0.0.0.0:8081/",
+                "--web3-ipc-path",
+                "/path/test.ipc",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            actual_config.web3_transport,
+            vec![
+                Web3TransportType::HTTP,
+                Web3TransportType::WS,
+                Web3TransportType::IPC
+            ]
+        );
+        assert_eq!(
+            actual_config.web3_ws_address,
+            Url::parse("ws://0.0.0.0:8081/").unwrap()
+        );
+        assert_eq!(actual_config.web3_ipc_path, PathBuf::from("/path/test.ipc"));
+    }
+
     #[test]
     fn test_custom_discovery_port() {
         let expected_config = TrinConfig {
@@ -426,6 +1065,276 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_ipv6_stun_server() {
+        let config = TrinConfig::new_from(["trin"].iter()).unwrap();
+        assert_eq!(config.ipv6_stun_server, DEFAULT_IPV6_STUN_SERVER);
+        assert!(!config.no_ipv6_stun);
+
+        let config = TrinConfig::new_from(
+            [
+                "trin",
+                "--ipv6-stun-server",
+                "stun.example.org:3478",
+                "--no-stun",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.ipv6_stun_server, "stun.example.org:3478");
+
+        let config = TrinConfig::new_from(["trin", "--no-ipv6-stun"].iter()).unwrap();
+        assert!(config.no_ipv6_stun);
+    }
+
+    #[test]
+    fn test_discovery_address_dual_stack() {
+        let actual_config =
+            TrinConfig::new_from(["trin", "--discovery-address", "127.0.0.1,::1"].iter()).unwrap();
+        assert_eq!(
+            actual_config.discovery_address,
+            vec![
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(std::net::Ipv6Addr::LOCALHOST)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Must supply at most one IPv4 and one IPv6 --discovery-address")]
+    fn test_discovery_address_same_family_twice() {
+        TrinConfig::new_from(["trin", "--discovery-address", "127.0.0.1,0.0.0.0"].iter()).unwrap();
+    }
+
+    fn write_config_file(content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_config_file() {
+        let file = write_config_file(
+            r#"
+            web3-transport = ["http", "ipc"]
+            discovery-port = 9009
+            networks = ["history", "beacon"]
+            ephemeral = true
+            "#,
+        );
+        let actual_config = TrinConfig::new_from(
+            [
+                "trin",
+                "--config",
+                file.path().to_str().unwrap(),
+                "--discovery-port",
+                "9010",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            actual_config.web3_transport,
+            vec![Web3TransportType::HTTP, Web3TransportType::IPC]
+        );
+        // command line flags take precedence over the config file
+        assert_eq!(actual_config.discovery_port, 9010);
+        assert_eq!(actual_config.networks, vec!["history", "beacon"]);
+        assert!(actual_config.ephemeral);
+    }
+
+    #[test]
+    fn test_config_file_vec_flags_are_replaced() {
+        let file = write_config_file(
+            r#"
+            networks = ["history", "beacon"]
+            overlay-config = ["history.query-parallelism=5"]
+            "#,
+        );
+        let config_path = file.path().to_str().unwrap();
+
+        let config = TrinConfig::new_from(["trin", "--config", config_path].iter()).unwrap();
+        assert_eq!(config.networks, vec!["history", "beacon"]);
+        assert_eq!(config.overlay_config.len(), 1);
+
+        // The command line values replace the values from the file, rather than adding to them
+        let config = TrinConfig::new_from(
+            [
+                "trin",
+                "--config",
+                config_path,
+                "--networks",
+                "history",
+                "--overlay-config",
+                "history.query-timeout-ms=100",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.networks, vec!["history"]);
+        assert_eq!(
+            config.overlay_config,
+            vec!["history.query-timeout-ms=100"
+                .parse::<OverlayConfigOverride>()
+                .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_config_file_bool_flags() {
+        let file = write_config_file(
+            r#"
+            ephemeral = true
+            no-stun = false
+            "#,
+        );
+        let config_path = file.path().to_str().unwrap();
+
+        let config = TrinConfig::new_from(["trin", "--config", config_path].iter()).unwrap();
+        assert!(config.ephemeral);
+        assert!(!config.no_stun);
+
+        // A flag set in both places is only set once
+        let config = TrinConfig::new_from(
+            ["trin", "--config", config_path, "--ephemeral", "--no-stun"].iter(),
+        )
+        .unwrap();
+        assert!(config.ephemeral);
+        assert!(config.no_stun);
+    }
+
+    #[test]
+    fn test_repeated_flag_is_rejected() {
+        let file = write_config_file("discovery-port = 9009");
+        let config_path = file.path().to_str().unwrap();
+        assert!(TrinConfig::new_from(
+            [
+                "trin",
+                "--discovery-port",
+                "9010",
+                "--discovery-port",
+                "9011"
+            ]
+            .iter()
+        )
+        .is_err());
+        assert!(TrinConfig::new_from(
+            [
+                "trin",
+                "--config",
+                config_path,
+                "--discovery-port",
+                "9010",
+                "--discovery-port",
+                "9011",
+            ]
+            .iter()
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Must not supply an http address when not using http")]
+    fn test_config_file_is_validated() {
+        let file = write_config_file(r#"web3-http-address = "http://127.0.0.1:1234/""#);
+        TrinConfig::new_from(["trin", "--config", file.path().to_str().unwrap()].iter()).unwrap();
+    }
+
+    #[test]
+    fn test_config_file_unknown_flag() {
+        let file = write_config_file("not-a-flag = 1");
+        assert!(
+            TrinConfig::new_from(["trin", "--config", file.path().to_str().unwrap()].iter())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dump_config_roundtrip() {
+        let args = [
+            "trin",
+            "--web3-transport",
+            "http,ipc",
+            "--discovery-port",
+            "9009",
+            "--ephemeral",
+        ];
+        let matches = TrinConfig::command().try_get_matches_from(args).unwrap();
+        let file = write_config_file(&effective_config_toml(&matches));
+        let dumped_config =
+            TrinConfig::new_from(["trin", "--config", file.path().to_str().unwrap()].iter())
+                .unwrap();
+        let expected_config = TrinConfig::new_from(args.iter()).unwrap();
+        assert_eq!(
+            TrinConfig {
+                config: None,
+                ..dumped_config
+            },
+            expected_config
+        );
+    }
+
+    #[test]
+    fn test_overlay_config() {
+        let actual_config = TrinConfig::new_from(
+            [
+                "trin",
+                "--networks",
+                "history,beacon",
+                "--overlay-config",
+                "history.query-parallelism=5,history.query-timeout-ms=30000",
+                "--overlay-config",
+                "beacon.max-incoming-per-bucket=8,beacon.utp-max-packet-size=1200",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            actual_config.overlay_tuning(HISTORY_NETWORK),
+            OverlayTuning {
+                query_parallelism: Some(5),
+                query_timeout_ms: Some(30000),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            actual_config.overlay_tuning(BEACON_NETWORK),
+            OverlayTuning {
+                max_incoming_per_bucket: Some(8),
+                utp_max_packet_size: Some(1200),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            actual_config.overlay_tuning(STATE_NETWORK),
+            OverlayTuning::default()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Must not supply --overlay-config for the state network")]
+    fn test_overlay_config_inactive_network() {
+        TrinConfig::new_from(["trin", "--overlay-config", "state.query-parallelism=5"].iter())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_overlay_config_rejects_zero_query_params() {
+        for overlay_config in ["history.query-parallelism=0", "history.query-timeout-ms=0"] {
+            let err = TrinConfig::new_from(["trin", "--overlay-config", overlay_config].iter())
+                .unwrap_err();
+            assert!(err.to_string().contains("must be greater than 0"));
+        }
+    }
+
+    #[test]
+    fn test_overlay_config_unknown_param() {
+        assert!(
+            TrinConfig::new_from(["trin", "--overlay-config", "history.query-speed=5"].iter())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_custom_private_key() {
         let expected_config = TrinConfig {
@@ -444,6 +1353,69 @@ mod test {
         assert_eq!(actual_config.private_key, expected_config.private_key);
     }
 
+    #[test]
+    fn test_trusted_block_root() {
+        let config = TrinConfig::new_from(
+            [
+                "trin",
+                "--trusted-block-root",
+                "0x0202020202020202020202020202020202020202020202020202020202020202",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.trusted_block_root, Some(H256::from_slice(&[2; 32])));
+        assert_eq!(TrinConfig::default().trusted_block_root, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid trusted block root length")]
+    fn test_trusted_block_root_requires_32_bytes() {
+        TrinConfig::new_from(["trin", "--trusted-block-root", "0x0202"].iter()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be used with")]
+    fn test_keystore_conflicts_with_private_key() {
+        TrinConfig::new_from(
+            [
+                "trin",
+                "--keystore",
+                "keystore.json",
+                "--unsafe-private-key",
+                "0x0101010101010101010101010101010101010101010101010101010101010101",
+            ]
+            .iter(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_account_export() {
+        let actual_config = TrinConfig::new_from(
+            [
+                "trin",
+                "account",
+                "export",
+                "keystore.json",
+                "--password-file",
+                "password.txt",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            actual_config.command,
+            Some(TrinConfigCommands::Account(AccountCommands::Export {
+                keystore: PathBuf::from("keystore.json")
+            }))
+        );
+        assert_eq!(
+            actual_config.password_file,
+            Some(PathBuf::from("password.txt"))
+        );
+    }
+
     #[test]
     fn test_ephemeral() {
         let expected_config = TrinConfig {
@@ -532,7 +1504,7 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Invalid web3-transport arg. Expected either 'http' or 'ipc'")]
+    #[should_panic(expected = "Invalid web3-transport arg. Expected 'http', 'ws' or 'ipc'")]
     fn test_invalid_web3_transport_argument() {
         TrinConfig::new_from(["trin", "--web3-transport", "invalid"].iter()).unwrap();
     }
@@ -0,0 +1,166 @@
+use crate::types::content_key::error::ContentKeyError;
+use crate::types::content_key::overlay::OverlayContentKey;
+use crate::utils::bytes::{hex_decode, hex_encode, hex_encode_compact};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fmt;
+
+/// A content key in the canonical indices network.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+#[ssz(enum_behaviour = "union")]
+pub enum CanonicalIndicesContentKey {
+    /// The hash of the canonical block at a given height.
+    BlockNumber(BlockNumberKey),
+    /// The block hash and index of a transaction included in the canonical chain.
+    TransactionHash(TransactionHashKey),
+}
+
+/// Key used to identify the canonical block at a given height.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct BlockNumberKey {
+    /// Number of the block.
+    pub block_number: u64,
+}
+
+/// Key used to identify the location of a transaction in the canonical chain.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct TransactionHashKey {
+    /// Hash of the transaction.
+    pub tx_hash: [u8; 32],
+}
+
+impl From<&CanonicalIndicesContentKey> for Vec<u8> {
+    fn from(val: &CanonicalIndicesContentKey) -> Self {
+        val.as_ssz_bytes()
+    }
+}
+
+impl From<CanonicalIndicesContentKey> for Vec<u8> {
+    fn from(val: CanonicalIndicesContentKey) -> Self {
+        val.as_ssz_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for CanonicalIndicesContentKey {
+    type Error = ContentKeyError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        CanonicalIndicesContentKey::from_ssz_bytes(&value).map_err(|e| ContentKeyError::DecodeSsz {
+            decode_error: e,
+            input: hex_encode(value),
+        })
+    }
+}
+
+impl fmt::Display for CanonicalIndicesContentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::BlockNumber(key) => {
+                format!("BlockNumber {{ block_number: {} }}", key.block_number)
+            }
+            Self::TransactionHash(key) => format!(
+                "TransactionHash {{ tx_hash: {} }}",
+                hex_encode_compact(key.tx_hash)
+            ),
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl OverlayContentKey for CanonicalIndicesContentKey {
+    fn content_id(&self) -> [u8; 32] {
+        let mut sha256 = Sha256::new();
+        sha256.update(self.as_ssz_bytes());
+        sha256.finalize().into()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        match self {
+            CanonicalIndicesContentKey::BlockNumber(key) => {
+                bytes.push(0x00);
+                bytes.extend_from_slice(&key.block_number.as_ssz_bytes());
+            }
+            CanonicalIndicesContentKey::TransactionHash(key) => {
+                bytes.push(0x01);
+                bytes.extend_from_slice(&key.tx_hash);
+            }
+        }
+
+        bytes
+    }
+}
+
+impl Serialize for CanonicalIndicesContentKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for CanonicalIndicesContentKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = String::deserialize(deserializer)?.to_lowercase();
+
+        if !data.starts_with("0x") {
+            return Err(de::Error::custom(format!(
+                "Hex strings must start with 0x, but found {}",
+                &data[..2]
+            )));
+        }
+
+        let ssz_bytes = hex_decode(&data).map_err(de::Error::custom)?;
+
+        Self::from_ssz_bytes(&ssz_bytes)
+            .map_err(|e| ContentKeyError::DecodeSsz {
+                decode_error: e,
+                input: hex_encode(ssz_bytes),
+            })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn block_number() {
+        const KEY_STR: &str = "0x00f1c3ed0000000000";
+        let expected_content_key = hex_decode(KEY_STR).unwrap();
+
+        let key = CanonicalIndicesContentKey::BlockNumber(BlockNumberKey {
+            block_number: 15_582_193,
+        });
+
+        assert_eq!(key.to_bytes(), expected_content_key);
+        assert_eq!(key.to_string(), "BlockNumber { block_number: 15582193 }");
+        assert_eq!(key.to_hex(), KEY_STR);
+    }
+
+    #[test]
+    fn transaction_hash() {
+        const KEY_STR: &str =
+            "0x0188df016429689c079f3b2f6ad39fa052532c56795b733da78a91ebe6a713944b";
+        const TX_HASH: &str = "0x88df016429689c079f3b2f6ad39fa052532c56795b733da78a91ebe6a713944b";
+        let expected_content_key = hex_decode(KEY_STR).unwrap();
+
+        let key = CanonicalIndicesContentKey::TransactionHash(TransactionHashKey {
+            tx_hash: <[u8; 32]>::try_from(hex_decode(TX_HASH).unwrap()).unwrap(),
+        });
+
+        assert_eq!(key.to_bytes(), expected_content_key);
+        assert_eq!(key.to_string(), "TransactionHash { tx_hash: 0x88df..944b }");
+        assert_eq!(key.to_hex(), KEY_STR);
+    }
+}
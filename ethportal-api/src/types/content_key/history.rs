@@ -6,12 +6,18 @@ use ssz_derive::{Decode, Encode};
 use std::fmt;
 
 use crate::types::content_key::error::ContentKeyError;
-use crate::types::content_key::overlay::OverlayContentKey;
+use crate::types::content_key::overlay::{OverlayContentKey, DEFAULT_MAX_CONTENT_SIZE};
 use crate::utils::bytes::{hex_decode, hex_encode, hex_encode_compact};
 
 /// SSZ encoded overlay content key as bytes
 pub type RawContentKey = Vec<u8>;
 
+/// The maximum size of a block header with proof: an RLP encoded header of at most 2048 bytes,
+/// with room to spare for the largest proof.
+pub const MAX_HEADER_WITH_PROOF_SIZE: usize = 8 * 1024;
+/// The size of an epoch accumulator: 8192 header records of 64 bytes each.
+pub const EPOCH_ACCUMULATOR_SIZE: usize = 8192 * 64;
+
 /// A content key in the history overlay network.
 #[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
 #[ssz(enum_behaviour = "union")]
@@ -186,6 +192,16 @@ impl OverlayContentKey for HistoryContentKey {
 
         bytes
     }
+
+    fn max_content_size(&self) -> usize {
+        match self {
+            HistoryContentKey::BlockHeaderWithProof(_) => MAX_HEADER_WITH_PROOF_SIZE,
+            HistoryContentKey::EpochAccumulator(_) => EPOCH_ACCUMULATOR_SIZE,
+            HistoryContentKey::BlockBody(_) | HistoryContentKey::BlockReceipts(_) => {
+                DEFAULT_MAX_CONTENT_SIZE
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -386,4 +402,22 @@ mod test {
             content_key_json
         );
     }
+
+    #[test]
+    fn max_content_size_of_full_epoch_accumulator() {
+        use crate::types::execution::accumulator::{EpochAccumulator, HeaderRecord};
+
+        let header_record = HeaderRecord {
+            block_hash: tree_hash::Hash256::zero(),
+            total_difficulty: ethereum_types::U256::zero(),
+        };
+        let epoch_accumulator = EpochAccumulator::from(vec![header_record; 8192]);
+        let content_key = HistoryContentKey::EpochAccumulator(EpochAccumulatorKey {
+            epoch_hash: H256::zero(),
+        });
+        assert_eq!(
+            content_key.max_content_size(),
+            epoch_accumulator.as_ssz_bytes().len()
+        );
+    }
 }
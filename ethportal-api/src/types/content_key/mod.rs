@@ -1,5 +1,7 @@
 pub mod beacon;
+pub mod canonical_indices;
 pub mod error;
 pub mod history;
 pub mod overlay;
 pub mod state;
+pub mod transaction_gossip;
@@ -3,6 +3,9 @@ use crate::utils::bytes::{hex_encode, hex_encode_compact};
 use quickcheck::{Arbitrary, Gen};
 use std::fmt;
 
+/// The maximum size of a content value, in bytes, unless its content key sets a lower limit.
+pub const DEFAULT_MAX_CONTENT_SIZE: usize = 16 * 1024 * 1024;
+
 /// Types whose values represent keys to lookup content items in an overlay network.
 /// Keys are serializable.
 pub trait OverlayContentKey:
@@ -17,6 +20,12 @@ pub trait OverlayContentKey:
     fn to_hex(&self) -> String {
         hex_encode(self.to_bytes())
     }
+
+    /// Returns the maximum size, in bytes, of the content value referred to by the key. Larger
+    /// values received from peers are rejected.
+    fn max_content_size(&self) -> usize {
+        DEFAULT_MAX_CONTENT_SIZE
+    }
 }
 
 /// A content key type whose content id is the inner value. Allows for the construction
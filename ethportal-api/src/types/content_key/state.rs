@@ -1,6 +1,8 @@
+use crate::types::content_key::error::ContentKeyError;
 use crate::types::content_key::overlay::OverlayContentKey;
-use crate::utils::bytes::hex_encode_compact;
+use crate::utils::bytes::{hex_decode, hex_encode, hex_encode_compact};
 use ethereum_types::{U256, U512};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest as Sha2Digest, Sha256};
 use sha3::{Digest, Keccak256};
 use ssz::{Decode, Encode};
@@ -100,6 +102,40 @@ impl TryFrom<Vec<u8>> for StateContentKey {
     }
 }
 
+impl Serialize for StateContentKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for StateContentKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = String::deserialize(deserializer)?.to_lowercase();
+
+        if !data.starts_with("0x") {
+            return Err(de::Error::custom(format!(
+                "Hex strings must start with 0x, but found {}",
+                &data[..2]
+            )));
+        }
+
+        let ssz_bytes = hex_decode(&data).map_err(de::Error::custom)?;
+
+        Self::from_ssz_bytes(&ssz_bytes)
+            .map_err(|e| ContentKeyError::DecodeSsz {
+                decode_error: e,
+                input: hex_encode(ssz_bytes),
+            })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl fmt::Display for StateContentKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
@@ -364,4 +400,13 @@ mod test {
         assert_eq!(hex_decode(expected_content_key).unwrap(), encoded);
         assert_eq!(expected_content_id, key.content_id());
     }
+
+    #[test]
+    fn ser_de_contract_bytecode() {
+        let content_key_json = "\"0x04829bd824b016326a401d083b33d092293333a830d1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d\"";
+        let key: StateContentKey = serde_json::from_str(content_key_json).unwrap();
+
+        assert!(matches!(key, StateContentKey::ContractBytecode(_)));
+        assert_eq!(serde_json::to_string(&key).unwrap(), content_key_json);
+    }
 }
@@ -0,0 +1,150 @@
+use crate::types::content_key::error::ContentKeyError;
+use crate::types::content_key::overlay::OverlayContentKey;
+use crate::utils::bytes::{hex_decode, hex_encode, hex_encode_compact};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fmt;
+
+/// A content key in the transaction gossip network.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+#[ssz(enum_behaviour = "union")]
+pub enum TransactionGossipContentKey {
+    /// A signed transaction, waiting to be included in a block.
+    Transaction(TransactionKey),
+}
+
+/// Key used to identify a signed transaction.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct TransactionKey {
+    /// Hash of the signed transaction.
+    pub tx_hash: [u8; 32],
+}
+
+impl From<&TransactionGossipContentKey> for Vec<u8> {
+    fn from(val: &TransactionGossipContentKey) -> Self {
+        val.as_ssz_bytes()
+    }
+}
+
+impl From<TransactionGossipContentKey> for Vec<u8> {
+    fn from(val: TransactionGossipContentKey) -> Self {
+        val.as_ssz_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for TransactionGossipContentKey {
+    type Error = ContentKeyError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        TransactionGossipContentKey::from_ssz_bytes(&value).map_err(|e| {
+            ContentKeyError::DecodeSsz {
+                decode_error: e,
+                input: hex_encode(value),
+            }
+        })
+    }
+}
+
+impl fmt::Display for TransactionGossipContentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Transaction(key) => format!(
+                "Transaction {{ tx_hash: {} }}",
+                hex_encode_compact(key.tx_hash)
+            ),
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl OverlayContentKey for TransactionGossipContentKey {
+    fn content_id(&self) -> [u8; 32] {
+        let mut sha256 = Sha256::new();
+        sha256.update(self.as_ssz_bytes());
+        sha256.finalize().into()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        match self {
+            TransactionGossipContentKey::Transaction(key) => {
+                bytes.push(0x00);
+                bytes.extend_from_slice(&key.tx_hash);
+            }
+        }
+
+        bytes
+    }
+}
+
+impl Serialize for TransactionGossipContentKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionGossipContentKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = String::deserialize(deserializer)?.to_lowercase();
+
+        if !data.starts_with("0x") {
+            return Err(de::Error::custom(format!(
+                "Hex strings must start with 0x, but found {}",
+                &data[..2]
+            )));
+        }
+
+        let ssz_bytes = hex_decode(&data).map_err(de::Error::custom)?;
+
+        Self::from_ssz_bytes(&ssz_bytes)
+            .map_err(|e| ContentKeyError::DecodeSsz {
+                decode_error: e,
+                input: hex_encode(ssz_bytes),
+            })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    const TX_HASH: &str = "0x88df016429689c079f3b2f6ad39fa052532c56795b733da78a91ebe6a713944b";
+
+    #[test]
+    fn transaction() {
+        const KEY_STR: &str =
+            "0x0088df016429689c079f3b2f6ad39fa052532c56795b733da78a91ebe6a713944b";
+        let expected_content_key = hex_decode(KEY_STR).unwrap();
+
+        let key = TransactionGossipContentKey::Transaction(TransactionKey {
+            tx_hash: <[u8; 32]>::try_from(hex_decode(TX_HASH).unwrap()).unwrap(),
+        });
+
+        assert_eq!(key.to_bytes(), expected_content_key);
+        assert_eq!(key.to_string(), "Transaction { tx_hash: 0x88df..944b }");
+        assert_eq!(key.to_hex(), KEY_STR);
+    }
+
+    #[test]
+    fn ser_de_transaction() {
+        let key = TransactionGossipContentKey::Transaction(TransactionKey {
+            tx_hash: <[u8; 32]>::try_from(hex_decode(TX_HASH).unwrap()).unwrap(),
+        });
+        let json = serde_json::to_string(&key).unwrap();
+        let decoded: TransactionGossipContentKey = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, key);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::types::constants::CONTENT_ABSENT;
+use crate::types::content_value::ContentValue;
+use crate::utils::bytes::{hex_decode, hex_encode};
+use crate::ContentValueError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+
+/// The hash of the canonical block at the height in the content key.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct BlockHashIndex {
+    pub block_hash: [u8; 32],
+}
+
+/// The location, in the canonical chain, of the transaction in the content key.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct TransactionIndex {
+    /// Hash of the block that includes the transaction.
+    pub block_hash: [u8; 32],
+    /// Index of the transaction in the block body.
+    pub index: u64,
+}
+
+/// A content value for the canonical indices network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CanonicalIndicesContentValue {
+    BlockHash(BlockHashIndex),
+    TransactionIndex(TransactionIndex),
+}
+
+/// A canonical indices content value, or the absence of one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PossibleCanonicalIndicesContentValue {
+    ContentPresent(CanonicalIndicesContentValue),
+    ContentAbsent,
+}
+
+impl Serialize for PossibleCanonicalIndicesContentValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::ContentPresent(content) => content.serialize(serializer),
+            Self::ContentAbsent => serializer.serialize_str(CONTENT_ABSENT),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PossibleCanonicalIndicesContentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        if s.as_str() == CONTENT_ABSENT {
+            return Ok(Self::ContentAbsent);
+        }
+
+        let content_bytes = hex_decode(&s).map_err(serde::de::Error::custom)?;
+
+        CanonicalIndicesContentValue::decode(&content_bytes)
+            .map(Self::ContentPresent)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl ContentValue for CanonicalIndicesContentValue {
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::BlockHash(value) => value.as_ssz_bytes(),
+            Self::TransactionIndex(value) => value.as_ssz_bytes(),
+        }
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, ContentValueError> {
+        // Catch any attempt to construct a content value from "0x" improperly.
+        if buf == CONTENT_ABSENT.to_string().as_bytes() {
+            return Err(ContentValueError::DecodeAbsentContent);
+        }
+
+        // Both values are fixed size containers, so they can be told apart by their length.
+        if let Ok(value) = BlockHashIndex::from_ssz_bytes(buf) {
+            return Ok(Self::BlockHash(value));
+        }
+
+        if let Ok(value) = TransactionIndex::from_ssz_bytes(buf) {
+            return Ok(Self::TransactionIndex(value));
+        }
+
+        Err(ContentValueError::UnknownContent {
+            bytes: hex_encode(buf),
+            network: "canonical indices".to_string(),
+        })
+    }
+}
+
+impl Serialize for CanonicalIndicesContentValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex_encode(self.encode()))
+    }
+}
+
+impl<'de> Deserialize<'de> for CanonicalIndicesContentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let content_bytes = hex_decode(&s).map_err(serde::de::Error::custom)?;
+
+        Self::decode(&content_bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn block_hash_encode_decode() {
+        let value = CanonicalIndicesContentValue::BlockHash(BlockHashIndex {
+            block_hash: [1; 32],
+        });
+        let encoded = value.encode();
+
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(
+            CanonicalIndicesContentValue::decode(&encoded).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn transaction_index_encode_decode() {
+        let value = CanonicalIndicesContentValue::TransactionIndex(TransactionIndex {
+            block_hash: [1; 32],
+            index: 7,
+        });
+        let encoded = value.encode();
+
+        assert_eq!(encoded.len(), 40);
+        assert_eq!(
+            CanonicalIndicesContentValue::decode(&encoded).unwrap(),
+            value
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::types::constants::CONTENT_ABSENT;
+use crate::types::content_value::ContentValue;
+use crate::utils::bytes::{hex_decode, hex_encode};
+use crate::ContentValueError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A content value for the state network.
+///
+/// State content is not decoded yet, so the value is kept in its network encoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateContentValue(pub Vec<u8>);
+
+impl ContentValue for StateContentValue {
+    fn encode(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, ContentValueError> {
+        // Catch any attempt to construct a content value from "0x" improperly.
+        if buf == CONTENT_ABSENT.to_string().as_bytes() {
+            return Err(ContentValueError::DecodeAbsentContent);
+        }
+
+        Ok(Self(buf.to_vec()))
+    }
+}
+
+impl Serialize for StateContentValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex_encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for StateContentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let content_bytes = hex_decode(&s).map_err(serde::de::Error::custom)?;
+
+        Self::decode(&content_bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A state network content value, or its absence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PossibleStateContentValue {
+    ContentPresent(StateContentValue),
+    ContentAbsent,
+}
+
+impl Serialize for PossibleStateContentValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::ContentPresent(content) => content.serialize(serializer),
+            Self::ContentAbsent => serializer.serialize_str(CONTENT_ABSENT),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PossibleStateContentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        if s.as_str() == CONTENT_ABSENT {
+            return Ok(PossibleStateContentValue::ContentAbsent);
+        }
+
+        let content_bytes = hex_decode(&s).map_err(serde::de::Error::custom)?;
+        Ok(Self::ContentPresent(StateContentValue(content_bytes)))
+    }
+}
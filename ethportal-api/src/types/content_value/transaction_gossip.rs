@@ -0,0 +1,58 @@
+use crate::types::constants::CONTENT_ABSENT;
+use crate::types::content_value::ContentValue;
+use crate::types::execution::transaction::Transaction;
+use crate::utils::bytes::{hex_decode, hex_encode};
+use crate::ContentValueError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A content value for the transaction gossip network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionGossipContentValue {
+    /// A signed transaction, in its EIP-2718 network encoding.
+    Transaction(Transaction),
+}
+
+impl ContentValue for TransactionGossipContentValue {
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Transaction(tx) => tx.encode(),
+        }
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, ContentValueError> {
+        // Catch any attempt to construct a content value from "0x" improperly.
+        if buf == CONTENT_ABSENT.to_string().as_bytes() {
+            return Err(ContentValueError::DecodeAbsentContent);
+        }
+
+        if let Ok(tx) = Transaction::decode(buf) {
+            return Ok(Self::Transaction(tx));
+        }
+
+        Err(ContentValueError::UnknownContent {
+            bytes: hex_encode(buf),
+            network: "transaction gossip".to_string(),
+        })
+    }
+}
+
+impl Serialize for TransactionGossipContentValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex_encode(self.encode()))
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionGossipContentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let content_bytes = hex_decode(&s).map_err(serde::de::Error::custom)?;
+
+        Self::decode(&content_bytes).map_err(serde::de::Error::custom)
+    }
+}
@@ -99,9 +99,10 @@ pub fn generate_random_remote_enr() -> (CombinedKey, Enr) {
 
 #[cfg(test)]
 mod test {
-    use crate::generate_random_node_id;
     use crate::types::distance::{Metric, XorMetric};
+    use crate::{generate_random_node_id, generate_random_node_id_with_rng};
     use discv5::enr::NodeId;
+    use rand::{rngs::StdRng, SeedableRng};
     use test_log::test;
 
     #[test]
@@ -138,4 +139,19 @@ mod test {
 
         assert!(distance[0] > 127);
     }
+
+    #[test]
+    fn test_generate_random_node_id_with_rng_is_reproducible() {
+        let target_bucket_idx: u8 = 200;
+        let local_node_id = NodeId::random();
+        let node_id = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            generate_random_node_id_with_rng(&mut rng, target_bucket_idx, local_node_id)
+        };
+
+        assert_eq!(node_id(1), node_id(1));
+        assert_ne!(node_id(1), node_id(2));
+        let distance = XorMetric::distance(&node_id(1).raw(), &local_node_id.raw());
+        assert_eq!(distance.log2(), Some(target_bucket_idx as usize + 1));
+    }
 }
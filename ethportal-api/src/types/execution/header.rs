@@ -7,6 +7,7 @@ use ssz::{Encode, SszDecoderBuilder, SszEncoder};
 use ssz_derive::{Decode, Encode};
 
 use crate::types::bytes::ByteList;
+use crate::types::consensus::execution_payload::ExecutionPayloadHeaderCapella;
 use crate::utils::bytes::{hex_decode, hex_encode};
 
 const LONDON_BLOCK_NUMBER: u64 = 12965000;
@@ -197,6 +198,34 @@ impl PartialEq for Header {
     }
 }
 
+/// Rebuild the execution block header from the execution payload header of a beacon block.
+///
+/// Post-merge headers have no uncles, zero difficulty and a zero nonce, so everything else can be
+/// recovered from the payload header.
+impl From<&ExecutionPayloadHeaderCapella> for Header {
+    fn from(payload: &ExecutionPayloadHeaderCapella) -> Self {
+        Self {
+            parent_hash: payload.parent_hash,
+            uncles_hash: keccak_hash::keccak(rlp::EMPTY_LIST_RLP),
+            author: payload.fee_recipient.clone().into(),
+            state_root: payload.state_root,
+            transactions_root: payload.transactions_root,
+            receipts_root: payload.receipts_root,
+            logs_bloom: Bloom::from_slice(&payload.logs_bloom[..]),
+            difficulty: U256::zero(),
+            number: payload.block_number,
+            gas_limit: U256::from(payload.gas_limit),
+            gas_used: U256::from(payload.gas_used),
+            timestamp: payload.timestamp,
+            extra_data: payload.extra_data.to_vec(),
+            mix_hash: Some(payload.prev_randao),
+            nonce: Some(H64::zero()),
+            base_fee_per_gas: Some(payload.base_fee_per_gas),
+            withdrawals_root: Some(payload.withdrawals_root),
+        }
+    }
+}
+
 /// Convert the standard header into a reth-style header type for RPC.
 ///
 /// This allows us to easily prepare a header for an RPC response.
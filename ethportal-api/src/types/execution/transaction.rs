@@ -52,6 +52,99 @@ impl Transaction {
             }
         }
     }
+
+    /// Returns the hash that was signed by the sender of the transaction.
+    pub fn signature_hash(&self) -> H256 {
+        match self {
+            Self::Legacy(tx) => {
+                let mut stream = RlpStream::new();
+                match tx.chain_id() {
+                    // EIP-155 replay protected transaction
+                    Some(chain_id) => {
+                        stream.begin_list(9);
+                        tx.append_unsigned_fields(&mut stream);
+                        stream.append(&chain_id);
+                        stream.append(&0u8);
+                        stream.append(&0u8);
+                    }
+                    None => {
+                        stream.begin_list(6);
+                        tx.append_unsigned_fields(&mut stream);
+                    }
+                }
+                keccak_hash::keccak(stream.out())
+            }
+            Self::AccessList(tx) => {
+                let mut stream = RlpStream::new_list(8);
+                stream.append(&tx.chain_id);
+                stream.append(&tx.nonce);
+                stream.append(&tx.gas_price);
+                stream.append(&tx.gas_limit);
+                stream.append(&tx.to);
+                stream.append(&tx.value);
+                stream.append(&tx.data.to_vec());
+                stream.append(&tx.access_list);
+                keccak_hash::keccak([&[TransactionId::AccessList as u8], stream.as_raw()].concat())
+            }
+            Self::EIP1559(tx) => {
+                let mut stream = RlpStream::new_list(9);
+                stream.append(&tx.chain_id);
+                stream.append(&tx.nonce);
+                stream.append(&tx.max_priority_fee_per_gas);
+                stream.append(&tx.max_fee_per_gas);
+                stream.append(&tx.gas_limit);
+                stream.append(&tx.to);
+                stream.append(&tx.value);
+                stream.append(&tx.data.to_vec());
+                stream.append(&tx.access_list);
+                keccak_hash::keccak([&[TransactionId::EIP1559 as u8], stream.as_raw()].concat())
+            }
+        }
+    }
+
+    /// Returns the signature of the transaction as (recovery id, r, s).
+    pub fn signature(&self) -> Result<(u8, U256, U256), DecoderError> {
+        match self {
+            Self::Legacy(tx) => {
+                let v = tx.v.as_u64();
+                let recovery_id = match tx.chain_id() {
+                    Some(chain_id) => v - 35 - 2 * chain_id,
+                    None => v.wrapping_sub(27),
+                };
+                if recovery_id > 1 {
+                    return Err(DecoderError::Custom("Invalid signature v value"));
+                }
+                Ok((recovery_id as u8, tx.r, tx.s))
+            }
+            Self::AccessList(tx) => Ok((y_parity(tx.y_parity)?, tx.r, tx.s)),
+            Self::EIP1559(tx) => Ok((y_parity(tx.y_parity)?, tx.r, tx.s)),
+        }
+    }
+
+    /// Returns the chain id the transaction is bound to, if any.
+    pub fn chain_id(&self) -> Option<U256> {
+        match self {
+            Self::Legacy(tx) => tx.chain_id().map(U256::from),
+            Self::AccessList(tx) => Some(tx.chain_id),
+            Self::EIP1559(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn gas_limit(&self) -> U256 {
+        match self {
+            Self::Legacy(tx) => tx.gas,
+            Self::AccessList(tx) => tx.gas_limit,
+            Self::EIP1559(tx) => tx.gas_limit,
+        }
+    }
+}
+
+fn y_parity(y_parity: U64) -> Result<u8, DecoderError> {
+    match y_parity.as_u64() {
+        0 => Ok(0),
+        1 => Ok(1),
+        _ => Err(DecoderError::Custom("Invalid signature y parity")),
+    }
 }
 
 impl<'de> Deserialize<'de> for Transaction {
@@ -103,6 +196,27 @@ pub struct LegacyTransaction {
     pub s: U256,
 }
 
+impl LegacyTransaction {
+    /// Returns the chain id for EIP-155 replay protected transactions.
+    pub fn chain_id(&self) -> Option<u64> {
+        let v = self.v.as_u64();
+        if v >= 35 {
+            Some((v - 35) / 2)
+        } else {
+            None
+        }
+    }
+
+    fn append_unsigned_fields(&self, stream: &mut RlpStream) {
+        stream.append(&self.nonce);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        stream.append(&self.to);
+        stream.append(&self.value);
+        stream.append(&self.data.to_vec());
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct LegacyTransactionHelper {
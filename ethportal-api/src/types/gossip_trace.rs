@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::node_id::NodeId;
+use super::query_trace::ContentTransfer;
+
+/// Keeps track of the peers that content was gossiped to, and the outcome of each offer.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct GossipTrace {
+    /// Map of each gossiped content key (hex encoded) to the peers selected to receive it.
+    /// Content keys that no peer was interested in map to an empty list.
+    pub recipients: HashMap<String, Vec<NodeId>>,
+    /// Map of a peer's ID to the `OfferTrace` of the offer sent to it.
+    pub offers: HashMap<NodeId, OfferTrace>,
+}
+
+impl GossipTrace {
+    /// Returns the number of content items that were accepted by, and successfully transferred
+    /// to, the recipients.
+    pub fn num_stored(&self) -> usize {
+        self.offers.values().map(OfferTrace::num_stored).sum()
+    }
+}
+
+/// The outcome of offering a single content item to a peer.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OfferOutcome {
+    /// The peer accepted the content, and it was successfully transferred.
+    Transferred,
+    /// The peer declined the content.
+    Declined,
+    /// The offer failed, or the accepted content could not be transferred.
+    Failed { error: String },
+}
+
+/// Keeps track of the outcome of a single offer.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct OfferTrace {
+    /// The offered content keys (hex encoded), in the order they were offered.
+    pub content_keys: Vec<String>,
+    /// Whether each offered content key was accepted, in the order they were offered.
+    /// None if no ACCEPT was received.
+    pub accepted: Option<Vec<bool>>,
+    /// The error of the OFFER request, if it failed.
+    pub error: Option<String>,
+    /// The uTP transfer of the accepted content. None if no content was accepted, or the
+    /// transfer was never started.
+    pub transfer: Option<ContentTransfer>,
+}
+
+impl OfferTrace {
+    pub fn new(content_keys: Vec<String>) -> Self {
+        Self {
+            content_keys,
+            accepted: None,
+            error: None,
+            transfer: None,
+        }
+    }
+
+    /// Returns the content keys that were accepted by the peer.
+    pub fn accepted_content_keys(&self) -> Vec<&String> {
+        match &self.accepted {
+            Some(accepted) => self
+                .content_keys
+                .iter()
+                .zip(accepted)
+                .filter(|(_, is_accepted)| **is_accepted)
+                .map(|(content_key, _)| content_key)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns the number of content items that were accepted by, and successfully transferred
+    /// to, the peer.
+    pub fn num_stored(&self) -> usize {
+        match &self.transfer {
+            Some(transfer) if transfer.error.is_none() => self.accepted_content_keys().len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns the outcome of offering each content key, in the order they were offered.
+    pub fn outcomes(&self) -> Vec<(String, OfferOutcome)> {
+        self.content_keys
+            .iter()
+            .enumerate()
+            .map(|(index, content_key)| {
+                let outcome = match &self.accepted {
+                    Some(accepted) if !accepted.get(index).copied().unwrap_or(false) => {
+                        OfferOutcome::Declined
+                    }
+                    Some(_) => match &self.transfer {
+                        Some(transfer) => match &transfer.error {
+                            Some(error) => OfferOutcome::Failed {
+                                error: error.clone(),
+                            },
+                            None => OfferOutcome::Transferred,
+                        },
+                        None => OfferOutcome::Failed {
+                            error: "Accepted content was not transferred".to_owned(),
+                        },
+                    },
+                    None => OfferOutcome::Failed {
+                        error: self
+                            .error
+                            .clone()
+                            .unwrap_or_else(|| "No ACCEPT was received".to_owned()),
+                    },
+                };
+                (content_key.clone(), outcome)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepted_content_keys() {
+        let mut trace = OfferTrace::new(vec!["0x01".to_owned(), "0x02".to_owned()]);
+        assert!(trace.accepted_content_keys().is_empty());
+
+        // Accept bitlists are padded to a byte, so may be longer than the offered keys.
+        trace.accepted = Some(vec![false, true, false, false, false, false, false, false]);
+        assert_eq!(trace.accepted_content_keys(), vec!["0x02"]);
+    }
+
+    #[test]
+    fn test_num_stored() {
+        let mut trace = OfferTrace::new(vec!["0x01".to_owned(), "0x02".to_owned()]);
+        trace.accepted = Some(vec![true, true]);
+        assert_eq!(trace.num_stored(), 0);
+
+        let mut transfer = ContentTransfer {
+            node: NodeId([0x01; 32]),
+            size_bytes: 64,
+            duration_millis: 10,
+            error: Some("connection reset".to_owned()),
+        };
+        trace.transfer = Some(transfer.clone());
+        assert_eq!(trace.num_stored(), 0);
+
+        transfer.error = None;
+        trace.transfer = Some(transfer);
+        assert_eq!(trace.num_stored(), 2);
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let mut trace = OfferTrace::new(vec!["0x01".to_owned(), "0x02".to_owned()]);
+        trace.error = Some("Request timed out".to_owned());
+        let failed = OfferOutcome::Failed {
+            error: "Request timed out".to_owned(),
+        };
+        assert_eq!(
+            trace.outcomes(),
+            vec![
+                ("0x01".to_owned(), failed.clone()),
+                ("0x02".to_owned(), failed)
+            ]
+        );
+
+        trace.error = None;
+        trace.accepted = Some(vec![false, true, false, false, false, false, false, false]);
+        trace.transfer = Some(ContentTransfer {
+            node: NodeId([0x01; 32]),
+            size_bytes: 64,
+            duration_millis: 10,
+            error: None,
+        });
+        assert_eq!(
+            trace.outcomes(),
+            vec![
+                ("0x01".to_owned(), OfferOutcome::Declined),
+                ("0x02".to_owned(), OfferOutcome::Transferred)
+            ]
+        );
+    }
+}
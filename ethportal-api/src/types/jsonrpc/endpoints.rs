@@ -1,6 +1,11 @@
 use crate::types::enr::Enr;
-use crate::{BeaconContentKey, HistoryContentKey};
-use crate::{BeaconContentValue, HistoryContentValue};
+use crate::{
+    BeaconContentKey, CanonicalIndicesContentKey, HistoryContentKey, TransactionGossipContentKey,
+};
+use crate::{
+    BeaconContentValue, CanonicalIndicesContentValue, HistoryContentValue,
+    TransactionGossipContentValue,
+};
 use discv5::enr::NodeId;
 
 /// Discv5 JSON-RPC endpoints. Start with "discv5_" prefix
@@ -45,14 +50,22 @@ pub enum HistoryEndpoint {
     LookupEnr(NodeId),
     /// params: [content_key, content_value]
     Gossip(HistoryContentKey, HistoryContentValue),
+    /// params: [content_key, content_value]
+    TraceGossip(HistoryContentKey, HistoryContentValue),
     /// params: [enr, content_key]
     Offer(Enr, HistoryContentKey, Option<HistoryContentValue>),
+    /// params: [enr, content_key, content_value]
+    TraceOffer(Enr, HistoryContentKey, Option<HistoryContentValue>),
+    /// params: [enr, [[content_key, content_value], ...]]
+    OfferMany(Enr, Vec<(HistoryContentKey, HistoryContentValue)>),
     /// params: [enr]
     Ping(Enr),
     /// params: content_key
     RecursiveFindContent(HistoryContentKey),
     /// params: content_key
     TraceRecursiveFindContent(HistoryContentKey),
+    /// params: [limit]
+    RecentQueryTraces(Option<u64>),
     /// params: [content_key, content_value]
     Store(HistoryContentKey, HistoryContentValue),
     /// params: None
@@ -62,6 +75,8 @@ pub enum HistoryEndpoint {
     PaginateLocalContentKeys(u64, u64),
     /// params: [node_id]
     RecursiveFindNodes(NodeId),
+    /// params: [node_id]
+    TraceRecursiveFindNodes(NodeId),
 }
 
 /// Beacon network JSON-RPC endpoints. Start with "portal_beacon" prefix
@@ -85,14 +100,20 @@ pub enum BeaconEndpoint {
     LookupEnr(NodeId),
     /// params: [content_key, content_value]
     Gossip(BeaconContentKey, BeaconContentValue),
+    /// params: [content_key, content_value]
+    TraceGossip(BeaconContentKey, BeaconContentValue),
     /// params: [enr, content_key]
     Offer(Enr, BeaconContentKey, Option<BeaconContentValue>),
+    /// params: [enr, content_key, content_value]
+    TraceOffer(Enr, BeaconContentKey, Option<BeaconContentValue>),
     /// params: enr
     Ping(Enr),
     /// params: content_key
     RecursiveFindContent(BeaconContentKey),
     /// params: content_key
     TraceRecursiveFindContent(BeaconContentKey),
+    /// params: [limit]
+    RecentQueryTraces(Option<u64>),
     /// params: [content_key, content_value]
     Store(BeaconContentKey, BeaconContentValue),
     /// params: None
@@ -101,4 +122,32 @@ pub enum BeaconEndpoint {
     PaginateLocalContentKeys(u64, u64),
     /// params: [node_id]
     RecursiveFindNodes(NodeId),
+    /// params: [node_id]
+    TraceRecursiveFindNodes(NodeId),
+}
+
+/// Transaction gossip network JSON-RPC endpoints. Start with "portal_transactionGossip" prefix
+#[derive(Debug, PartialEq, Clone)]
+pub enum TransactionGossipEndpoint {
+    /// params: None
+    DataRadius,
+    /// params: content_key
+    LocalContent(TransactionGossipContentKey),
+    /// params: [content_key, content_value]
+    Gossip(TransactionGossipContentKey, TransactionGossipContentValue),
+}
+
+/// Canonical indices network JSON-RPC endpoints. Start with "portal_canonicalIndices" prefix
+#[derive(Debug, PartialEq, Clone)]
+pub enum CanonicalIndicesEndpoint {
+    /// params: None
+    DataRadius,
+    /// params: content_key
+    LocalContent(CanonicalIndicesContentKey),
+    /// params: [content_key, content_value]
+    Gossip(CanonicalIndicesContentKey, CanonicalIndicesContentValue),
+    /// params: content_key
+    RecursiveFindContent(CanonicalIndicesContentKey),
+    /// params: [content_key, content_value]
+    Store(CanonicalIndicesContentKey, CanonicalIndicesContentValue),
 }
@@ -3,7 +3,10 @@ use serde_json::Value;
 use tokio::sync::mpsc;
 use validator::{Validate, ValidationError};
 
-use super::endpoints::{BeaconEndpoint, HistoryEndpoint, StateEndpoint};
+use super::endpoints::{
+    BeaconEndpoint, CanonicalIndicesEndpoint, HistoryEndpoint, StateEndpoint,
+    TransactionGossipEndpoint,
+};
 use super::params::Params;
 
 type Responder<T, E> = mpsc::UnboundedSender<Result<T, E>>;
@@ -61,6 +64,20 @@ pub struct BeaconJsonRpcRequest {
     pub resp: Responder<Value, String>,
 }
 
+/// Transaction gossip network JSON-RPC request
+#[derive(Debug)]
+pub struct TransactionGossipJsonRpcRequest {
+    pub endpoint: TransactionGossipEndpoint,
+    pub resp: Responder<Value, String>,
+}
+
+/// Canonical indices network JSON-RPC request
+#[derive(Debug)]
+pub struct CanonicalIndicesJsonRpcRequest {
+    pub endpoint: CanonicalIndicesEndpoint,
+    pub resp: Responder<Value, String>,
+}
+
 fn default_params() -> Params {
     Params::None
 }
@@ -9,6 +9,7 @@ pub mod discv5;
 pub mod distance;
 pub mod enr;
 pub mod execution;
+pub mod gossip_trace;
 pub mod jsonrpc;
 pub mod node_id;
 pub mod portal;
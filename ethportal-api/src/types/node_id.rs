@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use stremio_serde_hex::{SerHex, StrictPfx};
 
 use discv5::enr::NodeId as EnrNodeId;
+use rand::Rng;
 
 use super::distance::{Metric, XorMetric};
 
@@ -13,8 +14,19 @@ type RawNodeId = [u8; 32];
 /// Then we XOR the result distance with the local NodeId to get the random target NodeId
 // TODO: We should be able to make this generic over a `Metric`.
 pub fn generate_random_node_id(target_bucket_idx: u8, local_node_id: EnrNodeId) -> EnrNodeId {
+    generate_random_node_id_with_rng(&mut rand::thread_rng(), target_bucket_idx, local_node_id)
+}
+
+/// Generate random NodeId based on bucket index target and a local node id, drawing the random
+/// distance from `rng`, so that a seeded `rng` gives reproducible node ids.
+pub fn generate_random_node_id_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    target_bucket_idx: u8,
+    local_node_id: EnrNodeId,
+) -> EnrNodeId {
     let distance_leading_zeroes = 255 - target_bucket_idx;
-    let random_distance = crate::utils::bytes::random_32byte_array(distance_leading_zeroes);
+    let random_distance =
+        crate::utils::bytes::random_32byte_array_with_rng(rng, distance_leading_zeroes);
 
     let raw_node_id = XorMetric::distance(&local_node_id.raw(), &random_distance);
 
@@ -1,6 +1,9 @@
 use crate::types::enr::Enr;
-use crate::HistoryContentKey;
-use crate::PossibleHistoryContentValue;
+use crate::types::node_id::NodeId;
+use crate::{
+    BeaconContentKey, HistoryContentKey, PossibleBeaconContentValue, PossibleHistoryContentValue,
+    PossibleStateContentValue, StateContentKey,
+};
 use serde::{Deserialize, Serialize};
 use ssz_types::{typenum, BitList};
 
@@ -30,18 +33,22 @@ pub type FindNodesInfo = Vec<Enr>;
 /// Response for FindContent & RecursiveFindContent endpoints
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
-pub enum ContentInfo {
+pub enum ContentInfo<TContentValue> {
     #[serde(rename_all = "camelCase")]
     ConnectionId { connection_id: u16 },
     #[serde(rename_all = "camelCase")]
     Content {
-        content: PossibleHistoryContentValue,
+        content: TContentValue,
         utp_transfer: bool,
     },
     #[serde(rename_all = "camelCase")]
     Enrs { enrs: Vec<Enr> },
 }
 
+pub type HistoryContentInfo = ContentInfo<PossibleHistoryContentValue>;
+pub type BeaconContentInfo = ContentInfo<PossibleBeaconContentValue>;
+pub type StateContentInfo = ContentInfo<PossibleStateContentValue>;
+
 /// Response for Offer endpoint
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -55,16 +62,352 @@ pub struct AcceptInfo {
 /// represents the content info, using None for absent content.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct TraceContentInfo {
-    pub content: PossibleHistoryContentValue,
+pub struct TraceContentInfo<TContentValue> {
+    pub content: TContentValue,
     pub utp_transfer: bool,
     pub trace: QueryTrace,
 }
 
+pub type HistoryTraceContentInfo = TraceContentInfo<PossibleHistoryContentValue>;
+pub type BeaconTraceContentInfo = TraceContentInfo<PossibleBeaconContentValue>;
+pub type StateTraceContentInfo = TraceContentInfo<PossibleStateContentValue>;
+
+/// Response for TraceRecursiveFindNodes endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceNodesInfo {
+    pub nodes: Vec<Enr>,
+    pub trace: QueryTrace,
+}
+
 /// Response for PaginateLocalContentKeys endpoint
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct PaginateLocalContentInfo {
-    pub content_keys: Vec<HistoryContentKey>,
+pub struct PaginateLocalContentInfo<TContentKey> {
+    pub content_keys: Vec<TContentKey>,
     pub total_entries: u64,
 }
+
+pub type HistoryPaginateLocalContentInfo = PaginateLocalContentInfo<HistoryContentKey>;
+pub type BeaconPaginateLocalContentInfo = PaginateLocalContentInfo<BeaconContentKey>;
+pub type StatePaginateLocalContentInfo = PaginateLocalContentInfo<StateContentKey>;
+
+/// Notification sent to `portal_subscribe` subscribers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortalEvent {
+    /// Subnetwork that produced the event, eg. "history"
+    pub network: String,
+    /// Milliseconds since the Unix epoch at which the event was created, if known
+    pub timestamp: Option<i64>,
+    #[serde(flatten)]
+    pub event: PortalEventKind,
+}
+
+/// The overlay events that can be streamed to `portal_subscribe` subscribers.
+///
+/// Content keys and values are hex encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PortalEventKind {
+    LightClientOptimisticUpdate,
+    LightClientFinalityUpdate,
+    #[serde(rename_all = "camelCase")]
+    ContentReceived {
+        content_key: String,
+        content_value: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    OfferAccepted {
+        source: NodeId,
+        content_keys: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    NodeAdded {
+        node_id: NodeId,
+    },
+}
+
+/// Result of pruning a subnetwork's content store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneInfo {
+    /// Number of content items removed from the store.
+    pub pruned_items: usize,
+    /// Data radius of the store after pruning.
+    pub radius: DataRadius,
+}
+
+/// Storage, message and uTP metrics of a subnetwork, as reported in the periodic status logs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubnetworkSummary {
+    pub storage: String,
+    pub messages: String,
+    pub utp: String,
+}
+
+/// The maximum size of a uTP packet, which is sent as the payload of a Discv5 talk request, in
+/// bytes. This is the largest talk request payload that fits in a 1280 byte Discv5 packet.
+pub const MAX_UTP_PACKET_SIZE: u16 = 1171;
+
+/// Tunable overlay parameters of a subnetwork.
+///
+/// When applied to an overlay, only the parameters that are set are changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayTuning {
+    /// Number of peers to request data from in parallel for a single query.
+    pub query_parallelism: Option<usize>,
+    /// Timeout of a whole query, in milliseconds.
+    pub query_timeout_ms: Option<u64>,
+    /// Timeout after which a peer in an ongoing query is marked unresponsive, in milliseconds.
+    pub query_peer_timeout_ms: Option<u64>,
+    /// Number of new peers to discover before considering a FINDNODES query complete.
+    pub query_num_results: Option<usize>,
+    /// Maximum number of incoming connections in a routing table bucket.
+    pub max_incoming_per_bucket: Option<usize>,
+    /// The number of buckets requested from each peer in a FINDNODES query.
+    pub findnodes_query_distances_per_peer: Option<usize>,
+    /// Maximum size of a uTP packet, in bytes.
+    pub utp_max_packet_size: Option<u16>,
+    /// Number of attempts to establish a uTP connection before giving up.
+    pub utp_max_conn_attempts: Option<usize>,
+    /// Timeout after which an idle uTP connection is closed, in milliseconds.
+    pub utp_idle_timeout_ms: Option<u64>,
+    /// Initial retransmission timeout of a uTP connection, in milliseconds.
+    pub utp_initial_timeout_ms: Option<u64>,
+    /// Queuing delay that uTP congestion control aims for, in milliseconds. The send window grows
+    /// while the measured delay is below this target, so it is raised on high-latency links.
+    pub utp_target_delay_ms: Option<u64>,
+    /// Maximum number of concurrent uTP connections, inbound and outbound. 0 disables the limit.
+    pub utp_max_connections: Option<usize>,
+    /// Number of the interested peers closest to the content that are offered gossiped content.
+    pub gossip_num_closest_nodes: Option<usize>,
+    /// Number of randomly selected, farther interested peers that are offered gossiped content.
+    pub gossip_num_farther_nodes: Option<usize>,
+    /// If fewer peers in the routing table are interested in gossiped content, the nodes closest
+    /// to the content are looked up before it is offered.
+    pub gossip_min_interested_nodes: Option<usize>,
+    /// Maximum number of those lookups for a single batch of gossiped content.
+    pub gossip_max_lookups: Option<usize>,
+}
+
+impl OverlayTuning {
+    /// Names of the parameters, as accepted by [`OverlayTuning::set`].
+    pub const PARAMS: [&'static str; 16] = [
+        "query-parallelism",
+        "query-timeout-ms",
+        "query-peer-timeout-ms",
+        "query-num-results",
+        "max-incoming-per-bucket",
+        "findnodes-query-distances-per-peer",
+        "utp-max-packet-size",
+        "utp-max-conn-attempts",
+        "utp-idle-timeout-ms",
+        "utp-initial-timeout-ms",
+        "utp-target-delay-ms",
+        "utp-max-connections",
+        "gossip-num-closest-nodes",
+        "gossip-num-farther-nodes",
+        "gossip-min-interested-nodes",
+        "gossip-max-lookups",
+    ];
+
+    /// Sets the parameter named `param`, eg. "query-parallelism", from its string value.
+    pub fn set(&mut self, param: &str, value: &str) -> Result<(), String> {
+        let parse_err = |err: std::num::ParseIntError| format!("Invalid value for {param}: {err}");
+        match param {
+            "query-parallelism" => self.query_parallelism = Some(value.parse().map_err(parse_err)?),
+            "query-timeout-ms" => self.query_timeout_ms = Some(value.parse().map_err(parse_err)?),
+            "query-peer-timeout-ms" => {
+                self.query_peer_timeout_ms = Some(value.parse().map_err(parse_err)?)
+            }
+            "query-num-results" => self.query_num_results = Some(value.parse().map_err(parse_err)?),
+            "max-incoming-per-bucket" => {
+                self.max_incoming_per_bucket = Some(value.parse().map_err(parse_err)?)
+            }
+            "findnodes-query-distances-per-peer" => {
+                self.findnodes_query_distances_per_peer = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-max-packet-size" => {
+                self.utp_max_packet_size = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-max-conn-attempts" => {
+                self.utp_max_conn_attempts = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-idle-timeout-ms" => {
+                self.utp_idle_timeout_ms = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-initial-timeout-ms" => {
+                self.utp_initial_timeout_ms = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-target-delay-ms" => {
+                self.utp_target_delay_ms = Some(value.parse().map_err(parse_err)?)
+            }
+            "utp-max-connections" => {
+                self.utp_max_connections = Some(value.parse().map_err(parse_err)?)
+            }
+            "gossip-num-closest-nodes" => {
+                self.gossip_num_closest_nodes = Some(value.parse().map_err(parse_err)?)
+            }
+            "gossip-num-farther-nodes" => {
+                self.gossip_num_farther_nodes = Some(value.parse().map_err(parse_err)?)
+            }
+            "gossip-min-interested-nodes" => {
+                self.gossip_min_interested_nodes = Some(value.parse().map_err(parse_err)?)
+            }
+            "gossip-max-lookups" => {
+                self.gossip_max_lookups = Some(value.parse().map_err(parse_err)?)
+            }
+            _ => {
+                return Err(format!(
+                    "Unknown overlay parameter {param}, expected one of {:?}",
+                    Self::PARAMS
+                ))
+            }
+        }
+        self.validate()
+    }
+
+    /// Checks that the parameters that are set can be applied to an overlay. A query without
+    /// parallelism never sends a request, a query without a timeout fails immediately, and a uTP
+    /// packet must carry data and fit in a Discv5 packet.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.query_parallelism == Some(0) {
+            return Err("query-parallelism must be greater than 0".to_string());
+        }
+        if self.query_timeout_ms == Some(0) {
+            return Err("query-timeout-ms must be greater than 0".to_string());
+        }
+        if let Some(max_packet_size) = self.utp_max_packet_size {
+            if max_packet_size == 0 || max_packet_size > MAX_UTP_PACKET_SIZE {
+                return Err(format!(
+                    "utp-max-packet-size must be between 1 and {MAX_UTP_PACKET_SIZE}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Overrides the parameters of `self` that are set in `other`. Fails, without changing `self`,
+    /// if `other` is invalid.
+    pub fn merge(&mut self, other: &OverlayTuning) -> Result<(), String> {
+        other.validate()?;
+        self.query_parallelism = other.query_parallelism.or(self.query_parallelism);
+        self.query_timeout_ms = other.query_timeout_ms.or(self.query_timeout_ms);
+        self.query_peer_timeout_ms = other.query_peer_timeout_ms.or(self.query_peer_timeout_ms);
+        self.query_num_results = other.query_num_results.or(self.query_num_results);
+        self.max_incoming_per_bucket = other
+            .max_incoming_per_bucket
+            .or(self.max_incoming_per_bucket);
+        self.findnodes_query_distances_per_peer = other
+            .findnodes_query_distances_per_peer
+            .or(self.findnodes_query_distances_per_peer);
+        self.utp_max_packet_size = other.utp_max_packet_size.or(self.utp_max_packet_size);
+        self.utp_max_conn_attempts = other.utp_max_conn_attempts.or(self.utp_max_conn_attempts);
+        self.utp_idle_timeout_ms = other.utp_idle_timeout_ms.or(self.utp_idle_timeout_ms);
+        self.utp_initial_timeout_ms = other.utp_initial_timeout_ms.or(self.utp_initial_timeout_ms);
+        self.utp_target_delay_ms = other.utp_target_delay_ms.or(self.utp_target_delay_ms);
+        self.utp_max_connections = other.utp_max_connections.or(self.utp_max_connections);
+        self.gossip_num_closest_nodes = other
+            .gossip_num_closest_nodes
+            .or(self.gossip_num_closest_nodes);
+        self.gossip_num_farther_nodes = other
+            .gossip_num_farther_nodes
+            .or(self.gossip_num_farther_nodes);
+        self.gossip_min_interested_nodes = other
+            .gossip_min_interested_nodes
+            .or(self.gossip_min_interested_nodes);
+        self.gossip_max_lookups = other.gossip_max_lookups.or(self.gossip_max_lookups);
+        Ok(())
+    }
+
+    /// Returns `true` if any of the uTP connection parameters set in `self` differ from those in
+    /// `current`.
+    pub fn changes_utp_params(&self, current: &OverlayTuning) -> bool {
+        fn changes<T: PartialEq>(new: Option<T>, current: Option<T>) -> bool {
+            new.is_some() && new != current
+        }
+        changes(self.utp_max_packet_size, current.utp_max_packet_size)
+            || changes(self.utp_max_conn_attempts, current.utp_max_conn_attempts)
+            || changes(self.utp_idle_timeout_ms, current.utp_idle_timeout_ms)
+            || changes(self.utp_initial_timeout_ms, current.utp_initial_timeout_ms)
+            || changes(self.utp_target_delay_ms, current.utp_target_delay_ms)
+            || changes(self.utp_max_connections, current.utp_max_connections)
+    }
+
+    /// Returns `true` if any of the gossip parameters set in `self` differ from those in
+    /// `current`.
+    pub fn changes_gossip_params(&self, current: &OverlayTuning) -> bool {
+        fn changes<T: PartialEq>(new: Option<T>, current: Option<T>) -> bool {
+            new.is_some() && new != current
+        }
+        changes(
+            self.gossip_num_closest_nodes,
+            current.gossip_num_closest_nodes,
+        ) || changes(
+            self.gossip_num_farther_nodes,
+            current.gossip_num_farther_nodes,
+        ) || changes(
+            self.gossip_min_interested_nodes,
+            current.gossip_min_interested_nodes,
+        ) || changes(self.gossip_max_lookups, current.gossip_max_lookups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_tuning_rejects_zero_query_params() {
+        let mut tuning = OverlayTuning::default();
+        assert!(tuning.set("query-parallelism", "0").is_err());
+        assert!(tuning.set("query-timeout-ms", "0").is_err());
+        tuning.set("query-parallelism", "2").unwrap();
+
+        let invalid = OverlayTuning {
+            query_timeout_ms: Some(0),
+            ..Default::default()
+        };
+        let mut merged = tuning.clone();
+        assert!(merged.merge(&invalid).is_err());
+        assert_eq!(merged, tuning);
+    }
+
+    #[test]
+    fn overlay_tuning_rejects_invalid_utp_packet_size() {
+        let mut tuning = OverlayTuning::default();
+        assert!(tuning.set("utp-max-packet-size", "0").is_err());
+        assert!(tuning
+            .set(
+                "utp-max-packet-size",
+                &(MAX_UTP_PACKET_SIZE + 1).to_string()
+            )
+            .is_err());
+        tuning
+            .set("utp-max-packet-size", &MAX_UTP_PACKET_SIZE.to_string())
+            .unwrap();
+        assert_eq!(tuning.utp_max_packet_size, Some(MAX_UTP_PACKET_SIZE));
+    }
+
+    #[test]
+    fn overlay_tuning_sets_gossip_params() {
+        let mut tuning = OverlayTuning::default();
+        tuning.set("gossip-num-closest-nodes", "2").unwrap();
+        tuning.set("gossip-max-lookups", "1").unwrap();
+        assert_eq!(tuning.gossip_num_closest_nodes, Some(2));
+        assert_eq!(tuning.gossip_max_lookups, Some(1));
+
+        let current = OverlayTuning {
+            gossip_num_closest_nodes: Some(2),
+            gossip_max_lookups: Some(4),
+            ..Default::default()
+        };
+        assert!(tuning.changes_gossip_params(&current));
+        assert!(!tuning.changes_utp_params(&current));
+        tuning.gossip_max_lookups = Some(4);
+        assert!(!tuning.changes_gossip_params(&current));
+    }
+}
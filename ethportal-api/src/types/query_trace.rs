@@ -1,7 +1,7 @@
 use super::enr::Enr;
 use super::node_id::NodeId;
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +19,24 @@ pub struct QueryTrace {
     /// Map of a node's ID to its corresponding `QueryResponse`
     pub responses: HashMap<NodeId, QueryResponse>,
     pub node_metadata: HashMap<NodeId, NodeInfo>,
+    /// Map of a node's ID to the `QueryRequest` sent to it.
+    #[serde(default)]
+    pub requests: HashMap<NodeId, QueryRequest>,
+    /// Nodes that were learned of during the query, but never sent a request.
+    #[serde(default)]
+    pub uncontacted: Vec<NodeId>,
+    /// The uTP transfer of the content, if the content was offered over uTP.
+    #[serde(default)]
+    pub transfer: Option<ContentTransfer>,
+    /// The result of validating the received content.
+    #[serde(default)]
+    pub validation: Option<ContentValidation>,
+    /// Why the query ended. None while the query is in progress.
+    #[serde(default)]
+    pub termination: Option<QueryTermination>,
+    /// Milliseconds since query started, at which the query ended.
+    #[serde(default)]
+    pub finished_at_millis: Option<u64>,
     started_at: SystemTime,
     target_id: ContentId,
 }
@@ -30,6 +48,12 @@ impl QueryTrace {
             origin: local_enr.into(),
             responses: HashMap::new(),
             node_metadata: HashMap::new(),
+            requests: HashMap::new(),
+            uncontacted: vec![],
+            transfer: None,
+            validation: None,
+            termination: None,
+            finished_at_millis: None,
             started_at: SystemTime::now(),
             target_id,
         }
@@ -60,6 +84,7 @@ impl QueryTrace {
             .responded_with
             .append(&mut responded_with_ids);
         self.add_node_metadata(enr, true);
+        self.end_request(&node_id, RequestOutcome::Responded);
     }
 
     /// Mark the node that responded with the content, and when it was received.
@@ -75,6 +100,91 @@ impl QueryTrace {
             },
         );
         self.add_node_metadata(enr, true);
+        self.end_request(&node_id, RequestOutcome::Responded);
+    }
+
+    /// Records that a request was sent to `enr` on behalf of the query.
+    pub fn node_request_started(&mut self, enr: &Enr) {
+        let timestamp_u64 = QueryTrace::timestamp_millis_u64(self.started_at);
+        self.requests.insert(
+            enr.into(),
+            QueryRequest {
+                started_at_millis: timestamp_u64,
+                ended_at_millis: None,
+                outcome: RequestOutcome::Pending,
+            },
+        );
+        self.add_node_metadata(enr, false);
+    }
+
+    /// Records that the request sent to `node_id` failed with an error of the given kind.
+    pub fn node_request_failed(&mut self, node_id: &NodeId, error_kind: &str, message: String) {
+        self.end_request(
+            node_id,
+            RequestOutcome::Failed {
+                error_kind: error_kind.to_owned(),
+                message,
+            },
+        );
+    }
+
+    /// Records the uTP transfer of the content from `node_id`.
+    /// `error` is None if the transfer completed.
+    pub fn content_transferred(
+        &mut self,
+        node_id: NodeId,
+        size_bytes: usize,
+        duration: Duration,
+        error: Option<String>,
+    ) {
+        self.transfer = Some(ContentTransfer {
+            node: node_id,
+            size_bytes: size_bytes as u64,
+            duration_millis: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            error,
+        });
+    }
+
+    /// Records the result of validating the content received from `node_id`.
+    pub fn content_validated(&mut self, node_id: NodeId, result: Result<(), String>) {
+        self.validation = Some(ContentValidation {
+            node: node_id,
+            valid: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    /// Marks the query as ended. Requests still awaiting a response are marked as cancelled,
+    /// and every node that was learned of but never contacted is recorded.
+    pub fn finish(&mut self, termination: QueryTermination) {
+        let timestamp_u64 = QueryTrace::timestamp_millis_u64(self.started_at);
+        for request in self.requests.values_mut() {
+            if request.outcome == RequestOutcome::Pending {
+                request.ended_at_millis = Some(timestamp_u64);
+                request.outcome = RequestOutcome::Cancelled;
+            }
+        }
+        let mut uncontacted: Vec<NodeId> = self
+            .node_metadata
+            .keys()
+            .filter(|node_id| **node_id != self.origin && !self.requests.contains_key(node_id))
+            .copied()
+            .collect();
+        uncontacted.sort_by_key(|node_id| node_id.0);
+        self.uncontacted = uncontacted;
+        self.termination = Some(termination);
+        self.finished_at_millis = Some(timestamp_u64);
+    }
+
+    /// Sets the outcome of a pending request to `node_id`, if there is one.
+    fn end_request(&mut self, node_id: &NodeId, outcome: RequestOutcome) {
+        let timestamp_u64 = QueryTrace::timestamp_millis_u64(self.started_at);
+        if let Some(request) = self.requests.get_mut(node_id) {
+            if request.outcome == RequestOutcome::Pending {
+                request.ended_at_millis = Some(timestamp_u64);
+                request.outcome = outcome;
+            }
+        }
     }
 
     /// Returns milliseconds since the time provided.
@@ -110,11 +220,12 @@ impl QueryTrace {
     fn get_node_metadata(enr: &Enr, target: &ContentId) -> NodeInfo {
         let node_id = enr.node_id();
         let node_id_raw = node_id.raw();
-        let ip = match enr.ip4() {
-            Some(ip) => ip.to_string(),
-            None => "".to_owned(),
+        let ip = match (enr.ip4(), enr.ip6()) {
+            (Some(ip), _) => ip.to_string(),
+            (None, Some(ip)) => ip.to_string(),
+            (None, None) => "".to_owned(),
         };
-        let port = enr.udp4().unwrap_or(0).to_string();
+        let port = enr.udp4().or_else(|| enr.udp6()).unwrap_or(0).to_string();
         let distance = XorMetric::distance(&node_id_raw, &target.0);
         let distance_log2 = distance.log2().unwrap_or(0);
         let distance: u64 = distance.0[3];
@@ -139,6 +250,72 @@ pub struct QueryResponse {
     pub responded_with: Vec<NodeId>,
 }
 
+/// Represents a request sent to a single node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryRequest {
+    /// Milliseconds since query started, at which the request was sent.
+    pub started_at_millis: u64,
+    /// Milliseconds since query started, at which the request ended. None while pending.
+    pub ended_at_millis: Option<u64>,
+    pub outcome: RequestOutcome,
+}
+
+/// The outcome of a request sent to a single node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RequestOutcome {
+    /// The request has not ended yet.
+    Pending,
+    /// The node responded with ENRs or content.
+    Responded,
+    /// The request failed. `error_kind` is the kind of the overlay request error.
+    Failed { error_kind: String, message: String },
+    /// The query ended before the node responded.
+    Cancelled,
+}
+
+/// Represents the uTP transfer of the content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentTransfer {
+    /// The node that sent the content.
+    pub node: NodeId,
+    pub size_bytes: u64,
+    pub duration_millis: u64,
+    /// None if the transfer completed.
+    pub error: Option<String>,
+}
+
+/// Represents the result of validating the received content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentValidation {
+    /// The node that sent the content.
+    pub node: NodeId,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// The reason a query ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryTermination {
+    /// Valid content was received.
+    ContentFound,
+    /// All of the closest nodes were contacted without finding the content.
+    NotFound,
+    /// A find nodes query received responses from the closest nodes to the target.
+    Converged,
+    /// The query did not finish within the query timeout.
+    TimedOut,
+    /// There were no nodes to query.
+    NoPeers,
+    /// The node offering the content over uTP is not known.
+    UnknownPeer,
+    /// The uTP transfer of the content failed.
+    TransferFailed,
+    /// The received content failed validation.
+    ValidationFailed,
+}
+
 /// Represents additional info for a given node.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NodeInfo {
@@ -209,4 +386,105 @@ mod tests {
         let local_data = tracer.node_metadata.get(local_node_id).unwrap();
         assert_eq!(local_data.enr, local_enr);
     }
+
+    #[test]
+    fn test_query_trace_requests() {
+        let (_, local_enr) = generate_random_remote_enr();
+        let local_node_id: NodeId = local_enr.node_id().into();
+
+        let mut tracer = QueryTrace::new(&local_enr, local_enr.node_id().into());
+        let (_, enr_a) = generate_random_remote_enr();
+        let node_id_a: NodeId = enr_a.node_id().into();
+        let (_, enr_b) = generate_random_remote_enr();
+        let node_id_b: NodeId = enr_b.node_id().into();
+        let (_, enr_c) = generate_random_remote_enr();
+        let node_id_c: NodeId = enr_c.node_id().into();
+        let (_, enr_d) = generate_random_remote_enr();
+        let node_id_d: NodeId = enr_d.node_id().into();
+
+        tracer.node_responded_with(&local_enr, vec![&enr_a, &enr_b, &enr_c, &enr_d]);
+        tracer.node_request_started(&enr_a);
+        tracer.node_request_started(&enr_b);
+        tracer.node_request_started(&enr_c);
+
+        tracer.node_responded_with(&enr_a, vec![]);
+        tracer.node_request_failed(&node_id_b, "timeout", "The request timed out".to_owned());
+        tracer.finish(QueryTermination::NotFound);
+
+        let a_request = tracer.requests.get(&node_id_a).unwrap();
+        assert_eq!(a_request.outcome, RequestOutcome::Responded);
+        assert!(a_request.ended_at_millis.is_some());
+        let b_request = tracer.requests.get(&node_id_b).unwrap();
+        assert_eq!(
+            b_request.outcome,
+            RequestOutcome::Failed {
+                error_kind: "timeout".to_owned(),
+                message: "The request timed out".to_owned(),
+            }
+        );
+        // The query ended before c responded.
+        let c_request = tracer.requests.get(&node_id_c).unwrap();
+        assert_eq!(c_request.outcome, RequestOutcome::Cancelled);
+        assert!(c_request.ended_at_millis.is_some());
+
+        // d was never contacted, and the origin does not count as uncontacted.
+        assert!(!tracer.requests.contains_key(&node_id_d));
+        assert_eq!(tracer.uncontacted, vec![node_id_d]);
+        assert!(!tracer.uncontacted.contains(&local_node_id));
+        assert_eq!(tracer.termination, Some(QueryTermination::NotFound));
+        assert!(tracer.finished_at_millis.is_some());
+    }
+
+    #[test]
+    fn test_query_trace_transfer_and_validation() {
+        let (_, local_enr) = generate_random_remote_enr();
+        let mut tracer = QueryTrace::new(&local_enr, local_enr.node_id().into());
+        let (_, enr_a) = generate_random_remote_enr();
+        let node_id_a: NodeId = enr_a.node_id().into();
+
+        tracer.node_request_started(&enr_a);
+        tracer.node_responded_with_content(&enr_a);
+        tracer.content_transferred(node_id_a, 4096, Duration::from_millis(250), None);
+        tracer.content_validated(node_id_a, Err("invalid header".to_owned()));
+        tracer.finish(QueryTermination::ValidationFailed);
+
+        assert_eq!(
+            tracer.requests.get(&node_id_a).unwrap().outcome,
+            RequestOutcome::Responded
+        );
+        assert_eq!(
+            tracer.transfer,
+            Some(ContentTransfer {
+                node: node_id_a,
+                size_bytes: 4096,
+                duration_millis: 250,
+                error: None,
+            })
+        );
+        assert_eq!(
+            tracer.validation,
+            Some(ContentValidation {
+                node: node_id_a,
+                valid: false,
+                error: Some("invalid header".to_owned()),
+            })
+        );
+
+        // Traces serialized before these fields existed can still be read.
+        let mut value = serde_json::to_value(&tracer).unwrap();
+        let object = value.as_object_mut().unwrap();
+        for field in [
+            "requests",
+            "uncontacted",
+            "transfer",
+            "validation",
+            "termination",
+            "finished_at_millis",
+        ] {
+            object.remove(field);
+        }
+        let decoded: QueryTrace = serde_json::from_value(value).unwrap();
+        assert!(decoded.requests.is_empty());
+        assert_eq!(decoded.termination, None);
+    }
 }
@@ -57,3 +57,9 @@ impl Encode for H160 {
         20
     }
 }
+
+impl From<H160> for H160Type {
+    fn from(value: H160) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,47 @@
+use tracing::error;
+
+use trin_utils::log::init_tracing_logger;
+
+/// Runs declarative peertest scenarios.
+///
+/// Usage: `peertest-scenario <PATH>...`, where each path is a scenario file or a directory of
+/// scenario files.
+#[cfg(unix)]
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> anyhow::Result<()> {
+    use ethportal_peertest::scenario_file::{run_scenario, scenario_paths, ScenarioFile};
+    use std::path::PathBuf;
+    use tracing::info;
+
+    init_tracing_logger();
+    let paths: Vec<PathBuf> = std::env::args().skip(1).map(PathBuf::from).collect();
+    if paths.is_empty() {
+        anyhow::bail!("Usage: peertest-scenario <PATH>...");
+    }
+
+    let mut failed = vec![];
+    for path in scenario_paths(&paths)? {
+        let result = match ScenarioFile::from_path(&path) {
+            Ok(scenario) => run_scenario(&scenario).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => info!(path = %path.display(), "Scenario passed"),
+            Err(err) => {
+                error!(path = %path.display(), %err, "Scenario failed");
+                failed.push(path);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("{} scenario(s) failed: {failed:?}", failed.len());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() -> anyhow::Result<()> {
+    init_tracing_logger();
+    error!("Peertest scenarios are only supported on unix");
+    Ok(())
+}
@@ -1,11 +1,13 @@
 #![cfg(unix)]
 pub mod constants;
+pub mod scenario_file;
 pub mod scenarios;
 pub mod utils;
 
-use std::net::{IpAddr, Ipv4Addr};
-use std::path::PathBuf;
-use std::{thread, time};
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::path::Path;
+
+use anyhow::anyhow;
 
 use ethportal_api::types::cli::TrinConfig;
 use ethportal_api::types::enr::Enr;
@@ -14,6 +16,8 @@ use ethportal_api::Discv5ApiClient;
 use futures::future;
 use jsonrpsee::async_client::Client;
 use rpc::RpcServerHandle;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
 
 pub struct PeertestNode {
     pub enr: Enr,
@@ -24,6 +28,8 @@ pub struct PeertestNode {
 pub struct Peertest {
     pub bootnode: PeertestNode,
     pub nodes: Vec<PeertestNode>,
+    /// Holds the IPC sockets of the nodes, and is deleted on drop.
+    _ipc_dir: TempDir,
 }
 
 impl Peertest {
@@ -37,94 +43,109 @@ impl Peertest {
 
 async fn launch_node(trin_config: TrinConfig) -> anyhow::Result<PeertestNode> {
     let web3_ipc_path = trin_config.web3_ipc_path.clone();
-    let rpc_handle = trin::run_trin(trin_config).await.unwrap();
+    let rpc_handle = trin::run_trin(trin_config)
+        .await
+        .map_err(|err| anyhow!("Unable to launch peertest node: {err}"))?;
 
     // Short sleep to make sure all peertest nodes can connect
-    thread::sleep(time::Duration::from_secs(2));
+    sleep(Duration::from_secs(2)).await;
     let ipc_client = reth_ipc::client::IpcClientBuilder::default()
         .build(web3_ipc_path)
-        .await
-        .unwrap();
+        .await?;
 
     Ok(PeertestNode {
-        enr: ipc_client.node_info().await.unwrap().enr,
+        enr: ipc_client.node_info().await?.enr,
         ipc_client,
         rpc_handle,
     })
 }
 
-fn generate_trin_config(id: u16, bootnode_enr: Option<&Enr>) -> TrinConfig {
-    let discovery_port: u16 = 9000 + id;
+/// Returns `count` UDP ports that are free on localhost.
+///
+/// The ports are reserved until all of them are picked, so that they are distinct. Another
+/// process may still bind one of them before the node that it is picked for does.
+fn free_udp_ports(count: usize) -> anyhow::Result<Vec<u16>> {
+    let sockets = (0..count)
+        .map(|_| UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)))
+        .collect::<Result<Vec<_>, _>>()?;
+    sockets
+        .iter()
+        .map(|socket| Ok(socket.local_addr()?.port()))
+        .collect()
+}
+
+fn generate_trin_config(
+    id: u16,
+    bootnode_enr: Option<&Enr>,
+    networks: &str,
+    discovery_port: u16,
+    ipc_dir: &Path,
+) -> anyhow::Result<TrinConfig> {
     let discovery_port: String = discovery_port.to_string();
-    let web3_ipc_path = PathBuf::from(format!("/tmp/ethportal-peertest-buddy-{id}.ipc"));
+    let web3_ipc_path = ipc_dir.join(format!("ethportal-peertest-buddy-{id}.ipc"));
     // This specific private key scheme is chosen to enforce that the first peer node will be in
     // the 256 kbucket of the bootnode, to ensure consistent `FindNodes` tests.
     let mut private_key = vec![id as u8; 3];
     private_key.append(&mut vec![0u8; 29]);
     let private_key = hex_encode(private_key);
-    match bootnode_enr {
+    let (ip_addr, bootnodes) = match bootnode_enr {
         Some(enr) => {
-            let external_addr = format!(
-                "{}:{}",
-                enr.ip4().expect("bootnode must have IP"),
-                discovery_port
-            );
-            let enr_base64 = enr.to_base64();
-            let web3_ipc_path_str = web3_ipc_path.as_path().display().to_string();
-            let trin_config_args = vec![
-                "trin",
-                "--networks",
-                "history,beacon",
-                "--external-address",
-                external_addr.as_str(),
-                "--bootnodes",
-                enr_base64.as_str(),
-                "--discovery-port",
-                discovery_port.as_str(),
-                "--web3-ipc-path",
-                &web3_ipc_path_str[..],
-                "--unsafe-private-key",
-                private_key.as_str(),
-                "--ephemeral",
-            ];
-            TrinConfig::new_from(trin_config_args.iter()).unwrap()
+            let ip_addr = enr
+                .ip4()
+                .ok_or_else(|| anyhow!("Bootnode ENR must have an IPv4 address"))?;
+            (IpAddr::V4(ip_addr), enr.to_base64())
         }
-        None => {
-            let ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
-            let external_addr = format!("{ip_addr}:{discovery_port}");
-            let web3_ipc_path_str = web3_ipc_path.as_path().display().to_string();
-            let trin_config_args = vec![
-                "trin",
-                "--networks",
-                "history,beacon",
-                "--external-address",
-                external_addr.as_str(),
-                "--bootnodes",
-                "none",
-                "--discovery-port",
-                discovery_port.as_str(),
-                "--web3-ipc-path",
-                &web3_ipc_path_str[..],
-                "--unsafe-private-key",
-                private_key.as_str(),
-                "--ephemeral",
-            ];
-            TrinConfig::new_from(trin_config_args.iter()).unwrap()
-        }
-    }
+        None => (IpAddr::V4(Ipv4Addr::LOCALHOST), "none".to_owned()),
+    };
+    let external_addr = format!("{ip_addr}:{discovery_port}");
+    let web3_ipc_path_str = web3_ipc_path.as_path().display().to_string();
+    let trin_config_args = vec![
+        "trin",
+        "--networks",
+        networks,
+        "--external-address",
+        external_addr.as_str(),
+        "--bootnodes",
+        bootnodes.as_str(),
+        "--discovery-port",
+        discovery_port.as_str(),
+        "--web3-ipc-path",
+        &web3_ipc_path_str[..],
+        "--unsafe-private-key",
+        private_key.as_str(),
+        "--ephemeral",
+    ];
+    TrinConfig::new_from(trin_config_args.iter()).map_err(|err| anyhow!(err))
 }
 
-pub async fn launch_peertest_nodes(count: u16) -> Peertest {
+pub async fn launch_peertest_nodes(count: u16) -> anyhow::Result<Peertest> {
+    launch_peertest_nodes_with_networks(count, "history,beacon").await
+}
+
+/// Launches `count` peertest nodes, including the bootnode, running the comma-separated
+/// `networks`. The nodes listen on free ports, and serve JSON-RPC over IPC sockets in a
+/// temporary directory.
+pub async fn launch_peertest_nodes_with_networks(
+    count: u16,
+    networks: &str,
+) -> anyhow::Result<Peertest> {
+    let ipc_dir = tempfile::tempdir()?;
+    let ports = free_udp_ports(count as usize)?;
     // Bootnode uses a peertest id of 1
-    let bootnode_config = generate_trin_config(1, None);
-    let bootnode = launch_node(bootnode_config).await.unwrap();
+    let bootnode_config = generate_trin_config(1, None, networks, ports[0], ipc_dir.path())?;
+    let bootnode = launch_node(bootnode_config).await?;
     let bootnode_enr = &bootnode.enr;
     // All other peertest node ids begin at 2, and increment from there
-    let nodes = future::try_join_all((2..count + 1).map(|id| {
-        let node_config = generate_trin_config(id, Some(bootnode_enr));
-        launch_node(node_config)
-    }))
-    .await
-    .unwrap();
-    Peertest { bootnode, nodes }
+    let node_configs = (2..count + 1)
+        .zip(ports[1..].iter())
+        .map(|(id, port)| {
+            generate_trin_config(id, Some(bootnode_enr), networks, *port, ipc_dir.path())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let nodes = future::try_join_all(node_configs.into_iter().map(launch_node)).await?;
+    Ok(Peertest {
+        bootnode,
+        nodes,
+        _ipc_dir: ipc_dir,
+    })
 }
@@ -0,0 +1,772 @@
+//! Declarative peertest scenarios.
+//!
+//! A scenario file describes a test network (how many trin nodes to launch, which subnetworks they
+//! run and any external clients to join), the content each node stores before the test starts,
+//! and a list of JSON-RPC calls to issue together with their expected results. Scenario files are
+//! written in YAML (`.yaml` / `.yml`) or TOML (`.toml`), see `ethportal-peertest/scenarios` for
+//! examples.
+//!
+//! Nodes are referred to by index: the bootnode is node `0`, the other trin nodes follow, and
+//! external nodes come last, in the order they are listed. A string parameter or expected value
+//! of the form `$enr:<index>` or `$node_id:<index>` is replaced with the ENR or node ID of that
+//! node.
+//!
+//! Content keys and values can refer to the fixtures shared with the other peertests, as
+//! `$fixture:<name>.key` and `$fixture:<name>.value`, eg. `$fixture:header_with_proof_1000010.key`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, ensure};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{
+    process::Child,
+    time::{sleep, Duration},
+};
+use tracing::info;
+
+use crate::{
+    constants::{
+        BLOCK_BODY_CONTENT_KEY, BLOCK_BODY_CONTENT_VALUE, HEADER_WITH_PROOF_CONTENT_KEY,
+        HEADER_WITH_PROOF_CONTENT_VALUE, HISTORY_CONTENT_KEY, HISTORY_CONTENT_VALUE,
+        RECEIPTS_CONTENT_KEY, RECEIPTS_CONTENT_VALUE,
+    },
+    launch_peertest_nodes_with_networks, Peertest,
+};
+use ethportal_api::jsonrpsee::{
+    async_client::Client,
+    core::{client::ClientT, params::ArrayParams},
+    http_client::{HttpClient, HttpClientBuilder},
+};
+use ethportal_api::types::enr::Enr;
+use ethportal_api::utils::bytes::hex_encode;
+use portal_bridge::{
+    cli::{BridgeConfig, ClientType},
+    mode::BridgeMode,
+    types::NetworkKind,
+};
+
+/// Delay between the attempts of a step that is retried.
+const DEFAULT_RETRY_INTERVAL_MS: u64 = 500;
+/// Number of times to poll an external client until its JSON-RPC server is up.
+const EXTERNAL_NODE_STARTUP_ATTEMPTS: u32 = 30;
+
+/// A test network and the JSON-RPC calls to run against it.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioFile {
+    /// Name of the scenario, used in logs and errors.
+    pub name: String,
+    /// Number of trin nodes to launch, including the bootnode.
+    #[serde(default = "default_node_count")]
+    pub nodes: u16,
+    /// Subnetworks run by every trin node.
+    #[serde(default = "default_networks")]
+    pub networks: Vec<String>,
+    /// Clients launched from an executable, e.g. fluffy, that join the test network.
+    #[serde(default)]
+    pub external_nodes: Vec<ExternalNode>,
+    /// Content stored by nodes before the first step.
+    #[serde(default)]
+    pub content: Vec<StoredContent>,
+    /// JSON-RPC calls to issue, in order.
+    pub steps: Vec<Step>,
+}
+
+/// A client that is launched from an executable with the portal-bridge client handles.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalNode {
+    /// The client type, `fluffy` or `trin`.
+    pub client: String,
+    /// Path to the client executable.
+    pub executable_path: PathBuf,
+    /// Port of the JSON-RPC HTTP server of the client.
+    pub rpc_port: u16,
+    /// Discovery port of the client.
+    pub udp_port: u16,
+    /// Private key of the client, random if unset.
+    #[serde(default)]
+    pub private_key: Option<String>,
+}
+
+/// A content item stored by a node before the first step.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoredContent {
+    /// Index of the node that stores the content.
+    pub node: usize,
+    /// Subnetwork of the content.
+    pub network: String,
+    pub content_key: Value,
+    pub content_value: Value,
+}
+
+/// A JSON-RPC call and its expected outcome.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Index of the node that the call is sent to.
+    #[serde(default)]
+    pub node: usize,
+    /// JSON-RPC method, e.g. `portal_historyPing`.
+    pub method: String,
+    #[serde(default)]
+    pub params: Vec<Value>,
+    #[serde(default)]
+    pub expect: Expectation,
+    /// Number of times to repeat the call while it does not meet its expectation, to wait for
+    /// eventually consistent results such as gossiped content.
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_retry_interval_ms")]
+    pub retry_interval_ms: u64,
+}
+
+/// The expected outcome of a step. By default the call only has to succeed.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    /// Result that the call must return.
+    #[serde(default)]
+    pub result: Option<Value>,
+    /// Fields that the result must include. Objects may have fields beyond the listed ones,
+    /// any other value must be equal.
+    #[serde(default)]
+    pub includes: Option<Value>,
+    /// Whether the call must fail.
+    #[serde(default)]
+    pub error: bool,
+}
+
+fn default_node_count() -> u16 {
+    2
+}
+
+fn default_networks() -> Vec<String> {
+    vec!["history".to_owned()]
+}
+
+fn default_retry_interval_ms() -> u64 {
+    DEFAULT_RETRY_INTERVAL_MS
+}
+
+impl ScenarioFile {
+    /// Reads a scenario from a YAML or TOML file, depending on its extension.
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let scenario: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)?,
+            Some("toml") => toml::from_str(&contents)?,
+            _ => bail!("Unsupported scenario file extension: {}", path.display()),
+        };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Returns the total number of nodes of the test network.
+    pub fn node_count(&self) -> usize {
+        self.nodes as usize + self.external_nodes.len()
+    }
+
+    /// Checks that the scenario only refers to nodes, subnetworks and fixtures that exist.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.nodes > 0,
+            "Scenario must launch at least one trin node"
+        );
+        for network in self.networks.iter() {
+            NetworkKind::from_str(network).map_err(|err| anyhow!(err))?;
+        }
+        for external_node in self.external_nodes.iter() {
+            ClientType::from_str(&external_node.client).map_err(|err| anyhow!(err))?;
+        }
+        for content in self.content.iter() {
+            ensure!(
+                self.networks.contains(&content.network),
+                "Content stored in subnetwork {} that is not run",
+                content.network
+            );
+            self.check_node(content.node)?;
+            ensure!(
+                content.node < self.nodes as usize,
+                "Content stored by external node {}, which runs without storage",
+                content.node
+            );
+            self.check_placeholders(&content.content_key)?;
+            self.check_placeholders(&content.content_value)?;
+        }
+        for step in self.steps.iter() {
+            self.check_node(step.node)?;
+            for param in step.params.iter() {
+                self.check_placeholders(param)?;
+            }
+            for expected in [&step.expect.result, &step.expect.includes]
+                .into_iter()
+                .flatten()
+            {
+                self.check_placeholders(expected)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the placeholders in `value` refer to nodes and fixtures that exist.
+    fn check_placeholders(&self, value: &Value) -> anyhow::Result<()> {
+        match value {
+            Value::String(string) => match Placeholder::parse(string)? {
+                Some(Placeholder::Enr(node)) | Some(Placeholder::NodeId(node)) => {
+                    self.check_node(node)
+                }
+                Some(Placeholder::Fixture(_)) | None => Ok(()),
+            },
+            Value::Array(values) => values
+                .iter()
+                .try_for_each(|value| self.check_placeholders(value)),
+            Value::Object(map) => map
+                .values()
+                .try_for_each(|value| self.check_placeholders(value)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_node(&self, node: usize) -> anyhow::Result<()> {
+        ensure!(
+            node < self.node_count(),
+            "Scenario refers to node {node}, but only has {} nodes",
+            self.node_count()
+        );
+        Ok(())
+    }
+}
+
+/// Expands the directories in `paths` into the scenario files they contain, sorted by name.
+pub fn scenario_paths(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut scenario_paths = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let mut dir_paths: Vec<PathBuf> = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<_, _>>()?;
+            dir_paths.retain(|path| is_scenario_file(path));
+            dir_paths.sort();
+            scenario_paths.extend(dir_paths);
+        } else {
+            scenario_paths.push(path.clone());
+        }
+    }
+    Ok(scenario_paths)
+}
+
+fn is_scenario_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml") | Some("yml") | Some("toml")
+    )
+}
+
+/// JSON-RPC client of a node of the test network.
+enum NodeClient<'a> {
+    Ipc(&'a Client),
+    Http(HttpClient),
+}
+
+impl NodeClient<'_> {
+    async fn request(&self, method: &str, params: Vec<Value>) -> anyhow::Result<Value> {
+        let mut rpc_params = ArrayParams::new();
+        for param in params {
+            rpc_params.insert(param)?;
+        }
+        let result = match self {
+            NodeClient::Ipc(client) => client.request::<Value, _>(method, rpc_params).await?,
+            NodeClient::Http(client) => client.request::<Value, _>(method, rpc_params).await?,
+        };
+        Ok(result)
+    }
+}
+
+/// A node of the test network.
+struct ScenarioNode<'a> {
+    enr: Enr,
+    client: NodeClient<'a>,
+}
+
+/// Launches the test network of `scenario`, runs its steps and shuts the network down.
+pub async fn run_scenario(scenario: &ScenarioFile) -> anyhow::Result<()> {
+    scenario.validate()?;
+    info!(scenario = %scenario.name, "Running peertest scenario");
+    let peertest =
+        launch_peertest_nodes_with_networks(scenario.nodes, &scenario.networks.join(",")).await?;
+    // External clients are killed when their handles are dropped.
+    let mut handles = vec![];
+    let result = run_steps(scenario, &peertest, &mut handles).await;
+    peertest.exit_all_nodes();
+    result.map_err(|err| anyhow!("Scenario {} failed: {err}", scenario.name))
+}
+
+async fn run_steps(
+    scenario: &ScenarioFile,
+    peertest: &Peertest,
+    handles: &mut Vec<Child>,
+) -> anyhow::Result<()> {
+    let mut nodes: Vec<ScenarioNode> = std::iter::once(&peertest.bootnode)
+        .chain(peertest.nodes.iter())
+        .map(|node| ScenarioNode {
+            enr: node.enr.clone(),
+            client: NodeClient::Ipc(&node.ipc_client),
+        })
+        .collect();
+    for external_node in scenario.external_nodes.iter() {
+        let (handle, node) = launch_external_node(external_node, &scenario.networks).await?;
+        handles.push(handle);
+        // Join the external node to the test network through the bootnode.
+        for network in scenario.networks.iter() {
+            nodes[0]
+                .client
+                .request(
+                    &format!("portal_{network}Ping"),
+                    vec![Value::String(node.enr.to_base64())],
+                )
+                .await
+                .map_err(|err| anyhow!("Unable to ping external node: {err}"))?;
+        }
+        nodes.push(node);
+    }
+
+    let enrs: Vec<Enr> = nodes.iter().map(|node| node.enr.clone()).collect();
+    for content in scenario.content.iter() {
+        let result = nodes[content.node]
+            .client
+            .request(
+                &format!("portal_{}Store", content.network),
+                vec![
+                    substitute(&content.content_key, &enrs)?,
+                    substitute(&content.content_value, &enrs)?,
+                ],
+            )
+            .await?;
+        ensure!(
+            result == Value::Bool(true),
+            "Node {} did not store content {}",
+            content.node,
+            content.content_key
+        );
+    }
+
+    for (index, step) in scenario.steps.iter().enumerate() {
+        info!(step = index, method = %step.method, node = step.node, "Running scenario step");
+        let params: Vec<Value> = step
+            .params
+            .iter()
+            .map(|param| substitute(param, &enrs))
+            .collect::<anyhow::Result<_>>()?;
+        let expected = Expectation {
+            result: step
+                .expect
+                .result
+                .as_ref()
+                .map(|result| substitute(result, &enrs))
+                .transpose()?,
+            includes: step
+                .expect
+                .includes
+                .as_ref()
+                .map(|includes| substitute(includes, &enrs))
+                .transpose()?,
+            error: step.expect.error,
+        };
+        let mut attempt = 0;
+        loop {
+            let outcome = nodes[step.node]
+                .client
+                .request(&step.method, params.clone())
+                .await;
+            match check_outcome(outcome, &expected) {
+                Ok(()) => break,
+                Err(_) if attempt < step.retries => {
+                    attempt += 1;
+                    sleep(Duration::from_millis(step.retry_interval_ms)).await;
+                }
+                Err(err) => bail!("Step {index} ({}) failed: {err}", step.method),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Launches an external client and waits for its JSON-RPC server to come up.
+async fn launch_external_node(
+    external_node: &ExternalNode,
+    networks: &[String],
+) -> anyhow::Result<(Child, ScenarioNode<'static>)> {
+    let client_type = ClientType::from_str(&external_node.client).map_err(|err| anyhow!(err))?;
+    let network = networks
+        .iter()
+        .map(|network| NetworkKind::from_str(network).map_err(|err| anyhow!(err)))
+        .collect::<anyhow::Result<_>>()?;
+    let bridge_config = BridgeConfig {
+        node_count: 1,
+        executable_path: Some(external_node.executable_path.clone()),
+        mode: BridgeMode::Latest,
+        epoch_acc_path: PathBuf::new(),
+        network,
+        metrics_url: None,
+        client_type: client_type.clone(),
+    };
+    let private_key = external_node
+        .private_key
+        .clone()
+        .unwrap_or_else(|| hex_encode(rand::random::<[u8; 32]>()));
+    let handle = client_type
+        .build_handle(
+            private_key,
+            external_node.rpc_port,
+            external_node.udp_port,
+            bridge_config,
+        )?
+        .ok_or_else(|| anyhow!("{} is not an external client", external_node.client))?;
+
+    let client = HttpClientBuilder::default()
+        .build(format!("http://127.0.0.1:{}", external_node.rpc_port))?;
+    let client = NodeClient::Http(client);
+    let mut attempt = 0;
+    let node_info = loop {
+        match client.request("discv5_nodeInfo", vec![]).await {
+            Ok(node_info) => break node_info,
+            Err(err) if attempt >= EXTERNAL_NODE_STARTUP_ATTEMPTS => {
+                bail!(
+                    "External {} node did not start: {err}",
+                    external_node.client
+                )
+            }
+            Err(_) => {
+                attempt += 1;
+                sleep(Duration::from_secs(1)).await;
+            }
+        }
+    };
+    let enr = node_info
+        .get("enr")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("External node info has no ENR"))?;
+    let enr = Enr::from_str(enr).map_err(|err| anyhow!("Invalid external node ENR: {err}"))?;
+    Ok((handle, ScenarioNode { enr, client }))
+}
+
+/// A placeholder in a parameter or expected value of a scenario.
+#[derive(Debug, PartialEq)]
+enum Placeholder {
+    /// `$enr:<index>`, the ENR of a node.
+    Enr(usize),
+    /// `$node_id:<index>`, the node ID of a node.
+    NodeId(usize),
+    /// `$fixture:<name>.key` or `$fixture:<name>.value`, a shared content fixture.
+    Fixture(&'static str),
+}
+
+impl Placeholder {
+    /// Parses the placeholder in `string`. Returns `None` if `string` is not a placeholder.
+    fn parse(string: &str) -> anyhow::Result<Option<Self>> {
+        let node = |index: &str| -> anyhow::Result<usize> {
+            index
+                .parse()
+                .map_err(|err| anyhow!("Invalid node index in placeholder {string}: {err}"))
+        };
+        Ok(if let Some(index) = string.strip_prefix("$enr:") {
+            Some(Placeholder::Enr(node(index)?))
+        } else if let Some(index) = string.strip_prefix("$node_id:") {
+            Some(Placeholder::NodeId(node(index)?))
+        } else if let Some(name) = string.strip_prefix("$fixture:") {
+            let fixture = fixture(name)
+                .ok_or_else(|| anyhow!("Placeholder refers to unknown fixture {name}"))?;
+            Some(Placeholder::Fixture(fixture))
+        } else {
+            None
+        })
+    }
+}
+
+/// Returns the content key or value of the shared fixture `name`, from `constants`.
+fn fixture(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "header_with_proof_1000010.key" => HISTORY_CONTENT_KEY,
+        "header_with_proof_1000010.value" => HISTORY_CONTENT_VALUE,
+        "header_with_proof_14764013.key" => HEADER_WITH_PROOF_CONTENT_KEY,
+        "header_with_proof_14764013.value" => HEADER_WITH_PROOF_CONTENT_VALUE,
+        "block_body_14764013.key" => BLOCK_BODY_CONTENT_KEY,
+        "block_body_14764013.value" => BLOCK_BODY_CONTENT_VALUE,
+        "receipts_14764013.key" => RECEIPTS_CONTENT_KEY,
+        "receipts_14764013.value" => RECEIPTS_CONTENT_VALUE,
+        _ => return None,
+    })
+}
+
+/// Replaces the placeholders in `value`, with the nodes of the test network in `enrs`.
+fn substitute(value: &Value, enrs: &[Enr]) -> anyhow::Result<Value> {
+    let enr = |index: usize| -> anyhow::Result<&Enr> {
+        enrs.get(index)
+            .ok_or_else(|| anyhow!("Placeholder refers to unknown node {index}"))
+    };
+    Ok(match value {
+        Value::String(string) => match Placeholder::parse(string)? {
+            Some(Placeholder::Enr(index)) => Value::String(enr(index)?.to_base64()),
+            Some(Placeholder::NodeId(index)) => {
+                Value::String(hex_encode(enr(index)?.node_id().raw()))
+            }
+            Some(Placeholder::Fixture(fixture)) => Value::String(fixture.to_owned()),
+            None => value.clone(),
+        },
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| substitute(value, enrs))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), substitute(value, enrs)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+/// Checks the outcome of a call against the expectation of its step.
+fn check_outcome(outcome: anyhow::Result<Value>, expect: &Expectation) -> anyhow::Result<()> {
+    let result = match outcome {
+        Ok(result) if expect.error => bail!("Expected an error, got {result}"),
+        Ok(result) => result,
+        Err(_) if expect.error => return Ok(()),
+        Err(err) => bail!("Call failed: {err}"),
+    };
+    if let Some(expected) = &expect.result {
+        ensure!(
+            &result == expected,
+            "Expected result {expected}, got {result}"
+        );
+    }
+    if let Some(expected) = &expect.includes {
+        ensure!(
+            includes(&result, expected),
+            "Expected result including {expected}, got {result}"
+        );
+    }
+    Ok(())
+}
+
+/// Returns `true` if `value` has all the fields of `expected`.
+fn includes(value: &Value, expected: &Value) -> bool {
+    match (value, expected) {
+        (Value::Object(fields), Value::Object(expected_fields)) => {
+            expected_fields.iter().all(|(key, expected)| {
+                fields
+                    .get(key)
+                    .map_or(false, |value| includes(value, expected))
+            })
+        }
+        (value, expected) => value == expected,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use ethportal_api::types::enr::generate_random_remote_enr;
+    use serde_json::json;
+
+    const SCENARIO: &str = r#"
+name: test
+nodes: 2
+external_nodes:
+  - client: fluffy
+    executable_path: /usr/local/bin/fluffy
+    rpc_port: 8546
+    udp_port: 9101
+content:
+  - node: 0
+    network: history
+    content_key: "$fixture:header_with_proof_1000010.key"
+    content_value: "$fixture:header_with_proof_1000010.value"
+steps:
+  - node: 2
+    method: portal_historyPing
+    params: ["$enr:0"]
+"#;
+
+    fn scenario(contents: &str) -> ScenarioFile {
+        serde_yaml::from_str(contents).unwrap()
+    }
+
+    fn write_scenario(extension: &str, contents: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("scenario.{extension}"));
+        fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn parse_yaml_with_defaults() {
+        let (_dir, path) = write_scenario("yaml", SCENARIO);
+        let scenario = ScenarioFile::from_path(&path).unwrap();
+
+        assert_eq!(scenario.name, "test");
+        assert_eq!(scenario.networks, vec!["history"]);
+        assert_eq!(scenario.node_count(), 3);
+        assert_eq!(scenario.steps[0].node, 2);
+        assert_eq!(scenario.steps[0].retries, 0);
+        assert_eq!(
+            scenario.steps[0].retry_interval_ms,
+            DEFAULT_RETRY_INTERVAL_MS
+        );
+        assert!(!scenario.steps[0].expect.error);
+        assert_eq!(scenario.external_nodes[0].private_key, None);
+    }
+
+    #[test]
+    fn parse_toml() {
+        let (_dir, path) = write_scenario(
+            "toml",
+            r#"
+name = "test"
+networks = ["history", "beacon"]
+
+[[steps]]
+method = "portal_historyRoutingTableInfo"
+
+[steps.expect.includes]
+localNodeId = "$node_id:0"
+"#,
+        );
+        let scenario = ScenarioFile::from_path(&path).unwrap();
+
+        assert_eq!(scenario.nodes, 2);
+        assert_eq!(scenario.networks, vec!["history", "beacon"]);
+        assert_eq!(
+            scenario.steps[0].expect.includes,
+            Some(json!({"localNodeId": "$node_id:0"}))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_fields_and_extensions() {
+        let (_dir, path) = write_scenario("yaml", "name: test\nstep: []\n");
+        assert!(ScenarioFile::from_path(&path).is_err());
+
+        let (_dir, path) = write_scenario("json", SCENARIO);
+        assert!(ScenarioFile::from_path(&path).is_err());
+    }
+
+    #[test]
+    fn shipped_scenarios_are_valid() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let paths = scenario_paths(&[dir]).unwrap();
+        assert!(!paths.is_empty());
+        for path in paths {
+            ScenarioFile::from_path(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate() {
+        assert!(scenario(SCENARIO).validate().is_ok());
+
+        let invalid = [
+            // No trin node
+            SCENARIO.replace("nodes: 2", "nodes: 0"),
+            // Unknown subnetwork
+            SCENARIO.replace("name: test", "name: test\nnetworks: [history, mainnet]"),
+            // Content stored in a subnetwork that is not run
+            SCENARIO.replace("network: history", "network: beacon"),
+            // Content stored by an external node
+            SCENARIO.replace("  - node: 0\n    network", "  - node: 2\n    network"),
+            // Step sent to an unknown node
+            SCENARIO.replace("  - node: 2\n    method", "  - node: 3\n    method"),
+            // Placeholder of an unknown node
+            SCENARIO.replace("$enr:0", "$enr:3"),
+            // Unknown fixture
+            SCENARIO.replace("1000010.value", "1000011.value"),
+            // Unknown external client
+            SCENARIO.replace("client: fluffy", "client: ultralight"),
+        ];
+        for contents in invalid {
+            assert!(
+                scenario(&contents).validate().is_err(),
+                "Invalid scenario passed validation: {contents}"
+            );
+        }
+    }
+
+    #[test]
+    fn substitute_placeholders() {
+        let enrs = vec![
+            generate_random_remote_enr().1,
+            generate_random_remote_enr().1,
+        ];
+        let value = json!({
+            "enr": "$enr:1",
+            "nodeIds": ["$node_id:0", 7],
+            "content": "$fixture:header_with_proof_1000010.key",
+            "text": "enr:1",
+        });
+
+        assert_eq!(
+            substitute(&value, &enrs).unwrap(),
+            json!({
+                "enr": enrs[1].to_base64(),
+                "nodeIds": [hex_encode(enrs[0].node_id().raw()), 7],
+                "content": HISTORY_CONTENT_KEY,
+                "text": "enr:1",
+            })
+        );
+        assert!(substitute(&json!("$enr:2"), &enrs).is_err());
+        assert!(substitute(&json!("$node_id:x"), &enrs).is_err());
+        assert!(substitute(&json!(["$fixture:unknown.key"]), &enrs).is_err());
+    }
+
+    #[test]
+    fn includes_fields() {
+        let value = json!({"a": 1, "b": {"c": [1, 2], "d": "e"}});
+
+        assert!(includes(&value, &json!({})));
+        assert!(includes(&value, &json!({"a": 1})));
+        assert!(includes(&value, &json!({"b": {"d": "e"}})));
+        assert!(!includes(&value, &json!({"b": {"c": [1]}})));
+        assert!(!includes(&value, &json!({"f": 1})));
+        assert!(!includes(&value, &json!({"a": 2})));
+        assert!(includes(&json!([1, 2]), &json!([1, 2])));
+        assert!(!includes(&json!(1), &json!({"a": 1})));
+    }
+
+    #[test]
+    fn check_outcome_against_expectation() {
+        let success = Expectation::default();
+        assert!(check_outcome(Ok(json!(true)), &success).is_ok());
+        assert!(check_outcome(Err(anyhow!("failed")), &success).is_err());
+
+        let error = Expectation {
+            error: true,
+            ..Default::default()
+        };
+        assert!(check_outcome(Err(anyhow!("failed")), &error).is_ok());
+        assert!(check_outcome(Ok(json!(true)), &error).is_err());
+
+        let result = Expectation {
+            result: Some(json!({"a": 1})),
+            ..Default::default()
+        };
+        assert!(check_outcome(Ok(json!({"a": 1})), &result).is_ok());
+        assert!(check_outcome(Ok(json!({"a": 1, "b": 2})), &result).is_err());
+
+        let includes = Expectation {
+            includes: Some(json!({"a": 1})),
+            ..Default::default()
+        };
+        assert!(check_outcome(Ok(json!({"a": 1, "b": 2})), &includes).is_ok());
+        assert!(check_outcome(Ok(json!({"b": 2})), &includes).is_err());
+    }
+}
@@ -1,7 +1,9 @@
 use crate::constants::fixture_header_with_proof_1000010;
 use crate::utils::{wait_for_beacon_content, wait_for_history_content};
 use crate::Peertest;
+use ethereum_types::H256;
 use ethportal_api::jsonrpsee::http_client::HttpClient;
+use ethportal_api::types::enr::Enr;
 use ethportal_api::{
     BeaconContentKey, BeaconContentValue, PossibleBeaconContentValue, PossibleHistoryContentValue,
 };
@@ -10,7 +12,10 @@ use portal_bridge::bridge::Bridge;
 use portal_bridge::consensus_api::ConsensusApi;
 use portal_bridge::execution_api::ExecutionApi;
 use portal_bridge::mode::BridgeMode;
+use portal_bridge::native::NativeNode;
 use portal_bridge::pandaops::PandaOpsMiddleware;
+use portal_bridge::portal_clients::PortalClients;
+use portal_bridge::types::NetworkKind;
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
@@ -18,9 +23,19 @@ use trin_validation::accumulator::MasterAccumulator;
 use trin_validation::oracle::HeaderOracle;
 
 pub async fn test_history_bridge(peertest: &Peertest, target: &HttpClient) {
+    run_history_bridge(peertest, target, None).await;
+}
+
+pub async fn test_history_bridge_offer_many(peertest: &Peertest, target: &HttpClient) {
+    run_history_bridge(peertest, target, Some(peertest.bootnode.enr.clone())).await;
+}
+
+/// Runs the history bridge in test mode, gossiping the test assets, or offering them to
+/// `offer_enr`, and checks that the bootnode stored them.
+async fn run_history_bridge(peertest: &Peertest, target: &HttpClient, offer_enr: Option<Enr>) {
     let master_acc = MasterAccumulator::default();
     let header_oracle = HeaderOracle::new(master_acc);
-    let portal_clients = vec![target.clone()];
+    let portal_clients = PortalClients::Http(vec![target.clone()]);
     let epoch_acc_path = "validation_assets/epoch_acc.bin".into();
     let mode = BridgeMode::Test("./test_assets/portalnet/bridge_data.json".into());
     let pandaops_middleware = PandaOpsMiddleware::default();
@@ -33,6 +48,7 @@ pub async fn test_history_bridge(peertest: &Peertest, target: &HttpClient) {
         portal_clients,
         header_oracle,
         epoch_acc_path,
+        offer_enr,
     );
     bridge.launch().await;
     let (content_key, content_value) = fixture_header_with_proof_1000010();
@@ -48,8 +64,38 @@ pub async fn test_history_bridge(peertest: &Peertest, target: &HttpClient) {
     );
 }
 
+pub async fn test_native_history_gossip(peertest: &Peertest) {
+    // Use an uncommon port for the native node to avoid clashes.
+    let native_node = NativeNode::launch(H256::random(), 8998, &[NetworkKind::History])
+        .await
+        .unwrap();
+    let history = native_node.history.as_ref().unwrap();
+    history
+        .overlay
+        .add_enr(peertest.bootnode.enr.clone())
+        .unwrap();
+
+    let (content_key, content_value) = fixture_header_with_proof_1000010();
+    let stored = native_node
+        .gossip_history(content_key.clone(), content_value.clone())
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    // Check if the stored content value in bootnode's DB matches the gossiped
+    let response = wait_for_history_content(&peertest.bootnode.ipc_client, content_key).await;
+    let received_content_value = match response {
+        PossibleHistoryContentValue::ContentPresent(c) => c,
+        PossibleHistoryContentValue::ContentAbsent => panic!("Expected content to be found"),
+    };
+    assert_eq!(
+        content_value, received_content_value,
+        "The received content {received_content_value:?}, must match the expected {content_value:?}",
+    );
+}
+
 pub async fn test_beacon_bridge(peertest: &Peertest, target: &HttpClient) {
-    let portal_clients = Arc::new(vec![target.clone()]);
+    let portal_clients = Arc::new(PortalClients::Http(vec![target.clone()]));
     let mode = BridgeMode::Test("./test_assets/portalnet/beacon_bridge_data.yaml".into());
     // Wait for bootnode to start
     sleep(Duration::from_secs(1)).await;
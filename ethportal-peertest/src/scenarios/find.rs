@@ -1,7 +1,8 @@
 use crate::constants::fixture_header_with_proof;
 use crate::Peertest;
 use discv5::enr::NodeId;
-use ethportal_api::types::portal::{ContentInfo, TraceContentInfo};
+use ethportal_api::types::portal::{HistoryContentInfo, HistoryTraceContentInfo};
+use ethportal_api::types::query_trace::QueryTermination;
 use ethportal_api::utils::bytes::hex_decode;
 use ethportal_api::{HistoryNetworkApiClient, PossibleHistoryContentValue};
 use jsonrpsee::async_client::Client;
@@ -31,7 +32,7 @@ pub async fn test_find_content_return_enr(target: &Client, peertest: &Peertest)
         .find_content(peertest.bootnode.enr.clone(), content_key.clone())
         .await;
 
-    let enrs = if let Ok(ContentInfo::Enrs { enrs }) = result {
+    let enrs = if let Ok(HistoryContentInfo::Enrs { enrs }) = result {
         enrs
     } else {
         panic!("Error: Invalid response from FINDCONTENT request, expected ENRs got: {result:?}");
@@ -85,6 +86,27 @@ pub async fn test_recursive_find_nodes_random(peertest: &Peertest) {
     assert_eq!(result.len(), 2);
 }
 
+pub async fn test_trace_recursive_find_nodes_random(peertest: &Peertest) {
+    info!("Testing trace recursive find nodes random");
+    let mut bytes = [0u8; 32];
+    let random_node_id =
+        hex_decode("0xcac75e7e776d84fba55a3104bdccfd716537bca3ad8465113f67f04d62694183").unwrap();
+    bytes.copy_from_slice(&random_node_id);
+    let target_node_id = NodeId::from(bytes);
+    let result = peertest
+        .bootnode
+        .ipc_client
+        .trace_recursive_find_nodes(target_node_id)
+        .await
+        .unwrap();
+    assert_eq!(result.nodes.len(), 2);
+    let trace = result.trace;
+    assert_eq!(trace.origin, peertest.bootnode.enr.node_id().into());
+    assert_eq!(trace.termination, Some(QueryTermination::Converged));
+    // The bootnode requested nodes from its peer.
+    assert!(!trace.requests.is_empty());
+}
+
 pub async fn test_trace_recursive_find_content(peertest: &Peertest) {
     info!("Testing trace recursive find content");
     let (content_key, content_value) = fixture_header_with_proof();
@@ -97,7 +119,7 @@ pub async fn test_trace_recursive_find_content(peertest: &Peertest) {
 
     assert!(store_result);
 
-    let trace_content_info: TraceContentInfo = peertest.nodes[0]
+    let trace_content_info: HistoryTraceContentInfo = peertest.nodes[0]
         .ipc_client
         .trace_recursive_find_content(content_key)
         .await
@@ -163,7 +185,7 @@ pub async fn test_trace_recursive_find_content_local_db(peertest: &Peertest) {
 
     assert!(store_result);
 
-    let trace_content_info: TraceContentInfo = peertest
+    let trace_content_info: HistoryTraceContentInfo = peertest
         .bootnode
         .ipc_client
         .trace_recursive_find_content(content_key)
@@ -2,10 +2,16 @@ use std::str::FromStr;
 
 use tracing::info;
 
-use crate::{constants::fixture_header_with_proof, utils::wait_for_history_content, Peertest};
+use crate::{
+    constants::{fixture_header_with_proof, fixture_header_with_proof_1000010},
+    utils::wait_for_history_content,
+    Peertest,
+};
 use ethportal_api::{
-    jsonrpsee::async_client::Client, types::enr::Enr, utils::bytes::hex_encode,
-    HistoryNetworkApiClient, PossibleHistoryContentValue,
+    jsonrpsee::async_client::Client,
+    types::{enr::Enr, gossip_trace::OfferOutcome},
+    utils::bytes::hex_encode,
+    HistoryNetworkApiClient, OverlayContentKey, PossibleHistoryContentValue,
 };
 
 pub async fn test_unpopulated_offer(peertest: &Peertest, target: &Client) {
@@ -45,6 +51,73 @@ pub async fn test_unpopulated_offer(peertest: &Peertest, target: &Client) {
     );
 }
 
+pub async fn test_trace_populated_offer(peertest: &Peertest, target: &Client) {
+    info!("Testing traced Populated Offer/ACCEPT flow");
+
+    let (content_key, content_value) = fixture_header_with_proof();
+    let trace = target
+        .trace_offer(
+            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            content_key.clone(),
+            Some(content_value.clone()),
+        )
+        .await
+        .unwrap();
+
+    // Check that the bootnode accepted the offered content, and that it was transferred
+    assert_eq!(trace.error, None);
+    assert_eq!(
+        trace.accepted_content_keys(),
+        vec![&hex_encode(content_key.to_bytes())]
+    );
+    let transfer = trace
+        .transfer
+        .expect("Expected the accepted content to be transferred");
+    assert_eq!(transfer.node, peertest.bootnode.enr.node_id().into());
+    assert_eq!(transfer.error, None);
+    assert!(transfer.size_bytes > 0);
+
+    let response = wait_for_history_content(&peertest.bootnode.ipc_client, content_key).await;
+    assert_eq!(
+        response,
+        PossibleHistoryContentValue::ContentPresent(content_value)
+    );
+}
+
+pub async fn test_offer_many(peertest: &Peertest, target: &Client) {
+    info!("Testing OfferMany flow");
+
+    let content_items = vec![
+        fixture_header_with_proof(),
+        fixture_header_with_proof_1000010(),
+    ];
+    let outcomes = target
+        .offer_many(
+            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            content_items.clone(),
+        )
+        .await
+        .unwrap();
+
+    // Check that every offered content item was transferred to, and stored by, the bootnode,
+    // with the outcomes in the order of the content items
+    assert_eq!(outcomes.len(), content_items.len());
+    for ((content_key, content_value), outcome) in content_items.into_iter().zip(outcomes) {
+        assert_eq!(
+            outcome,
+            (
+                hex_encode(content_key.to_bytes()),
+                OfferOutcome::Transferred
+            )
+        );
+        let response = wait_for_history_content(&peertest.bootnode.ipc_client, content_key).await;
+        assert_eq!(
+            response,
+            PossibleHistoryContentValue::ContentPresent(content_value)
+        );
+    }
+}
+
 pub async fn test_populated_offer(peertest: &Peertest, target: &Client) {
     info!("Testing Populated Offer/ACCEPT flow");
 
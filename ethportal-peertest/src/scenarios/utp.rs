@@ -3,7 +3,7 @@ use crate::{
     Peertest,
 };
 use discv5::enr::NodeId;
-use ethportal_api::types::portal::{ContentInfo, TraceContentInfo};
+use ethportal_api::types::portal::{HistoryContentInfo, HistoryTraceContentInfo};
 use ethportal_api::{HistoryNetworkApiClient, PossibleHistoryContentValue};
 use tracing::info;
 
@@ -36,7 +36,7 @@ pub async fn test_recursive_utp(peertest: &Peertest) {
         .await
         .unwrap();
 
-    if let ContentInfo::Content {
+    if let HistoryContentInfo::Content {
         content,
         utp_transfer,
     } = content_info
@@ -75,7 +75,7 @@ pub async fn test_trace_recursive_utp(peertest: &Peertest) {
 
     assert!(store_result);
 
-    let trace_content_info: TraceContentInfo = peertest.nodes[0]
+    let trace_content_info: HistoryTraceContentInfo = peertest.nodes[0]
         .ipc_client
         .trace_recursive_find_content(content_key)
         .await
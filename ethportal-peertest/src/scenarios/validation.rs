@@ -5,7 +5,7 @@ use crate::{
 use ethereum_types::H256;
 use ethportal_api::types::content_key::history::BlockHeaderKey;
 use ethportal_api::types::enr::Enr;
-use ethportal_api::types::portal::ContentInfo;
+use ethportal_api::types::portal::HistoryContentInfo;
 use ethportal_api::{
     jsonrpsee::async_client::Client, HistoryContentKey, HistoryNetworkApiClient,
     PossibleHistoryContentValue,
@@ -38,7 +38,7 @@ pub async fn test_validate_pre_merge_header_with_proof(peertest: &Peertest, targ
         .unwrap();
 
     match result {
-        ContentInfo::Content {
+        HistoryContentInfo::Content {
             content,
             utp_transfer,
         } => {
@@ -112,7 +112,7 @@ pub async fn test_validate_pre_merge_block_body(peertest: &Peertest, target: &Cl
         .unwrap();
 
     match result {
-        ContentInfo::Content {
+        HistoryContentInfo::Content {
             content,
             utp_transfer,
         } => {
@@ -155,7 +155,7 @@ pub async fn test_validate_pre_merge_receipts(peertest: &Peertest, target: &Clie
         .unwrap();
 
     match result {
-        ContentInfo::Content {
+        HistoryContentInfo::Content {
             content,
             utp_transfer,
         } => {
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use eyre::{eyre, Result};
 
+use crate::consensus::rpc::nimbus_rpc::NimbusRpc;
 use crate::consensus::ConsensusLightClient;
 use log::{error, info, warn};
 use tokio::sync::RwLock;
@@ -285,7 +286,7 @@ impl<DB: Database> Client<DB> {
             // We fail fast here since the node is unrecoverable at this point
             let config = self.node.read().await.config.clone();
             let consensus = ConsensusLightClient::new(
-                &config.consensus_rpc,
+                NimbusRpc::new(&config.consensus_rpc),
                 checkpoint.as_bytes(),
                 config.clone(),
             )?;
@@ -329,7 +330,7 @@ impl<DB: Database> Client<DB> {
         // We fail fast here since the node is unrecoverable at this point
         let config = self.node.read().await.config.clone();
         let consensus = ConsensusLightClient::new(
-            &config.consensus_rpc,
+            NimbusRpc::new(&config.consensus_rpc),
             checkpoint.as_bytes(),
             config.clone(),
         )?;
@@ -44,21 +44,23 @@ pub struct ConsensusLightClient<R: ConsensusRpc> {
 #[derive(Debug, Default)]
 struct LightClientStore {
     finalized_header: BeaconBlockHeader,
+    /// Execution payload header of `finalized_header`, if it is from Capella or later.
+    finalized_execution: Option<ExecutionPayloadHeaderCapella>,
     current_sync_committee: SyncCommittee,
     next_sync_committee: Option<SyncCommittee>,
     optimistic_header: BeaconBlockHeader,
+    /// Execution payload header of `optimistic_header`, if it is from Capella or later.
+    optimistic_execution: Option<ExecutionPayloadHeaderCapella>,
     previous_max_active_participants: u64,
     current_max_active_participants: u64,
 }
 
 impl<R: ConsensusRpc> ConsensusLightClient<R> {
     pub fn new(
-        rpc: &str,
+        rpc: R,
         checkpoint_block_root: &[u8],
         config: Arc<Config>,
     ) -> Result<ConsensusLightClient<R>> {
-        let rpc = R::new(rpc);
-
         Ok(ConsensusLightClient {
             rpc,
             store: LightClientStore::default(),
@@ -86,6 +88,18 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         &self.store.finalized_header
     }
 
+    /// Returns the execution payload header of the optimistic header, once the light client
+    /// follows a Capella or later block.
+    pub fn get_execution_header(&self) -> Option<&ExecutionPayloadHeaderCapella> {
+        self.store.optimistic_execution.as_ref()
+    }
+
+    /// Returns the execution payload header of the finalized header, once the light client
+    /// follows a Capella or later block.
+    pub fn get_finalized_execution_header(&self) -> Option<&ExecutionPayloadHeaderCapella> {
+        self.store.finalized_execution.as_ref()
+    }
+
     pub async fn sync(&mut self) -> Result<()> {
         self.bootstrap().await?;
 
@@ -145,13 +159,21 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
     }
 
     async fn bootstrap(&mut self) -> Result<()> {
-        let mut bootstrap = self
+        let bootstrap = self
             .rpc
             .get_bootstrap(&self.initial_checkpoint)
             .await
             .map_err(|err| eyre!("could not fetch bootstrap: {err}"))?;
+        let (header, execution) = match &bootstrap {
+            LightClientBootstrap::Bellatrix(bootstrap) => (bootstrap.header.beacon.clone(), None),
+            LightClientBootstrap::Capella(bootstrap) => (
+                bootstrap.header.beacon.clone(),
+                Some(ExecutionHeaderWithProof::from(&bootstrap.header)),
+            ),
+        };
+        let mut current_sync_committee = bootstrap.current_sync_committee().clone();
 
-        let is_valid = self.is_valid_checkpoint(bootstrap.header.beacon.slot);
+        let is_valid = self.is_valid_checkpoint(header.slot);
 
         if !is_valid {
             if self.config.strict_checkpoint_age {
@@ -162,12 +184,12 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         }
 
         let committee_valid = is_current_committee_proof_valid(
-            &bootstrap.header.beacon,
-            &mut bootstrap.current_sync_committee,
-            &bootstrap.current_sync_committee_branch,
+            &header,
+            &mut current_sync_committee,
+            bootstrap.current_sync_committee_branch(),
         );
 
-        let header_hash = hex_encode(bootstrap.header.beacon.tree_hash_root());
+        let header_hash = hex_encode(header.tree_hash_root());
         let expected_hash = hex_encode(&self.initial_checkpoint);
         let header_valid = header_hash == expected_hash;
 
@@ -179,11 +201,20 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
             return Err(ConsensusError::InvalidCurrentSyncCommitteeProof.into());
         }
 
+        if let Some(execution) = &execution {
+            if !is_execution_proof_valid(&header, execution) {
+                return Err(ConsensusError::InvalidExecutionProof.into());
+            }
+        }
+        let execution = execution.map(|execution| execution.header);
+
         self.store = LightClientStore {
-            finalized_header: bootstrap.header.beacon.clone(),
-            current_sync_committee: bootstrap.current_sync_committee,
+            finalized_header: header.clone(),
+            finalized_execution: execution.clone(),
+            current_sync_committee,
             next_sync_committee: None,
-            optimistic_header: bootstrap.header.beacon,
+            optimistic_header: header,
+            optimistic_execution: execution,
             previous_max_active_participants: 0,
             current_max_active_participants: 0,
         };
@@ -243,6 +274,20 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
             }
         }
 
+        if let Some(execution) = &update.attested_execution {
+            if !is_execution_proof_valid(&update.attested_header, execution) {
+                return Err(ConsensusError::InvalidExecutionProof.into());
+            }
+        }
+
+        if let (Some(finalized_header), Some(execution)) =
+            (&update.finalized_header, &update.finalized_execution)
+        {
+            if !is_execution_proof_valid(finalized_header, execution) {
+                return Err(ConsensusError::InvalidExecutionProof.into());
+            }
+        }
+
         if update.next_sync_committee.is_some() && update.next_sync_committee_branch.is_some() {
             let is_valid = is_next_committee_proof_valid(
                 &update.attested_header,
@@ -278,17 +323,17 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         Ok(())
     }
 
-    fn verify_update(&self, update: &LightClientUpdateCapella) -> Result<()> {
+    fn verify_update(&self, update: &LightClientUpdate) -> Result<()> {
         let update = GenericUpdate::from(update);
         self.verify_generic_update(&update)
     }
 
-    fn verify_finality_update(&self, update: &LightClientFinalityUpdateCapella) -> Result<()> {
+    fn verify_finality_update(&self, update: &LightClientFinalityUpdate) -> Result<()> {
         let update = GenericUpdate::from(update);
         self.verify_generic_update(&update)
     }
 
-    fn verify_optimistic_update(&self, update: &LightClientOptimisticUpdateCapella) -> Result<()> {
+    fn verify_optimistic_update(&self, update: &LightClientOptimisticUpdate) -> Result<()> {
         let update = GenericUpdate::from(update);
         self.verify_generic_update(&update)
     }
@@ -306,6 +351,10 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
         if should_update_optimistic {
             self.store.optimistic_header = update.attested_header.clone();
+            self.store.optimistic_execution = update
+                .attested_execution
+                .as_ref()
+                .map(|execution| execution.header.clone());
             self.log_optimistic_update(update);
         }
 
@@ -348,6 +397,10 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
             if update_finalized_slot > self.store.finalized_header.slot {
                 self.store.finalized_header = update.finalized_header.as_ref().unwrap().clone();
+                self.store.finalized_execution = update
+                    .finalized_execution
+                    .as_ref()
+                    .map(|execution| execution.header.clone());
                 self.log_finality_update(update);
 
                 if self.store.finalized_header.slot % 32 == 0 {
@@ -357,17 +410,18 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
                 if self.store.finalized_header.slot > self.store.optimistic_header.slot {
                     self.store.optimistic_header = self.store.finalized_header.clone();
+                    self.store.optimistic_execution = self.store.finalized_execution.clone();
                 }
             }
         }
     }
 
-    fn apply_update(&mut self, update: &LightClientUpdateCapella) {
+    fn apply_update(&mut self, update: &LightClientUpdate) {
         let update = GenericUpdate::from(update);
         self.apply_generic_update(&update);
     }
 
-    fn apply_finality_update(&mut self, update: &LightClientFinalityUpdateCapella) {
+    fn apply_finality_update(&mut self, update: &LightClientFinalityUpdate) {
         let update = GenericUpdate::from(update);
         self.apply_generic_update(&update);
     }
@@ -389,7 +443,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         );
     }
 
-    fn apply_optimistic_update(&mut self, update: &LightClientOptimisticUpdateCapella) {
+    fn apply_optimistic_update(&mut self, update: &LightClientOptimisticUpdate) {
         let update = GenericUpdate::from(update);
         self.apply_generic_update(&update);
     }
@@ -581,6 +635,25 @@ fn is_current_committee_proof_valid(
     )
 }
 
+// The execution payload is field 9 of the Capella beacon block body, at generalized index 25
+fn is_execution_proof_valid(
+    header: &BeaconBlockHeader,
+    execution: &ExecutionHeaderWithProof,
+) -> bool {
+    let execution_branch = execution
+        .branch
+        .iter()
+        .map(|h| bytes_to_bytes32(h.as_bytes()))
+        .collect::<Vec<_>>();
+    is_merkle_proof_valid(
+        &header.body_root,
+        &execution.header,
+        &execution_branch,
+        4,
+        9,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -613,7 +686,8 @@ mod tests {
                 .unwrap();
 
         let mut client =
-            ConsensusLightClient::new("testdata/", &checkpoint, Arc::new(config)).unwrap();
+            ConsensusLightClient::new(MockRpc::new("testdata/"), &checkpoint, Arc::new(config))
+                .unwrap();
         client.bootstrap().await.unwrap();
         client
     }
@@ -642,7 +716,7 @@ mod tests {
             .await
             .unwrap();
 
-        updates[0].next_sync_committee.pubkeys[0] = PubKey::default();
+        updates[0].next_sync_committee_mut().pubkeys[0] = PubKey::default();
 
         let err = client.verify_update(&updates[0]).err().unwrap();
         assert_eq!(
@@ -662,7 +736,7 @@ mod tests {
             .unwrap();
 
         let mut update = updates[0].clone();
-        update.finalized_header.beacon = BeaconBlockHeader::default();
+        update.finalized_header_capella_mut().unwrap().beacon = BeaconBlockHeader::default();
 
         let err = client.verify_update(&update).err().unwrap();
         assert_eq!(
@@ -681,7 +755,7 @@ mod tests {
             .await
             .unwrap();
 
-        updates[0].sync_aggregate.sync_committee_signature = BlsSignature::default();
+        updates[0].sync_aggregate_mut().sync_committee_signature = BlsSignature::default();
 
         let err = client.verify_update(&updates[0]).err().unwrap();
         assert_eq!(
@@ -707,7 +781,7 @@ mod tests {
 
         let mut update = client.rpc.get_finality_update().await.unwrap();
 
-        update.finalized_header.beacon = BeaconBlockHeader::default();
+        update.finalized_header_capella_mut().unwrap().beacon = BeaconBlockHeader::default();
 
         let err = client.verify_finality_update(&update).err().unwrap();
         assert_eq!(
@@ -722,7 +796,7 @@ mod tests {
         client.sync().await.unwrap();
 
         let mut update = client.rpc.get_finality_update().await.unwrap();
-        update.sync_aggregate.sync_committee_signature = BlsSignature::default();
+        update.sync_aggregate_mut().sync_committee_signature = BlsSignature::default();
 
         let err = client.verify_finality_update(&update).err().unwrap();
         assert_eq!(
@@ -746,7 +820,7 @@ mod tests {
         client.sync().await.unwrap();
 
         let mut update = client.rpc.get_optimistic_update().await.unwrap();
-        update.sync_aggregate.sync_committee_signature = BlsSignature::default();
+        update.sync_aggregate_mut().sync_committee_signature = BlsSignature::default();
 
         let err = client.verify_optimistic_update(&update).err().unwrap();
         assert_eq!(
@@ -755,6 +829,25 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_verify_optimistic_invalid_execution() {
+        let mut client = get_client(false).await;
+        client.sync().await.unwrap();
+
+        let mut update = client.rpc.get_optimistic_update().await.unwrap();
+        update
+            .attested_header_capella_mut()
+            .unwrap()
+            .execution
+            .block_number += 1;
+
+        let err = client.verify_optimistic_update(&update).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            ConsensusError::InvalidExecutionProof.to_string()
+        );
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn test_verify_checkpoint_age_invalid() {
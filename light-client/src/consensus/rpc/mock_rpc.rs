@@ -2,8 +2,9 @@ use std::{fs::read_to_string, path::PathBuf};
 
 use super::ConsensusRpc;
 use crate::consensus::types::{
-    LightClientBootstrapCapella, LightClientFinalityUpdateCapella,
-    LightClientOptimisticUpdateCapella, LightClientUpdateCapella,
+    LightClientBootstrap, LightClientBootstrapCapella, LightClientFinalityUpdate,
+    LightClientFinalityUpdateCapella, LightClientOptimisticUpdate,
+    LightClientOptimisticUpdateCapella, LightClientUpdate, LightClientUpdateCapella,
 };
 use async_trait::async_trait;
 use eyre::Result;
@@ -12,32 +13,42 @@ pub struct MockRpc {
     testdata: PathBuf,
 }
 
-#[async_trait]
-impl ConsensusRpc for MockRpc {
-    fn new(path: &str) -> Self {
+impl MockRpc {
+    pub fn new(path: &str) -> Self {
         MockRpc {
             testdata: PathBuf::from(path),
         }
     }
+}
 
-    async fn get_bootstrap(&self, _block_root: &'_ [u8]) -> Result<LightClientBootstrapCapella> {
+/// The test data is from the Capella fork.
+#[async_trait]
+impl ConsensusRpc for MockRpc {
+    async fn get_bootstrap(&self, _block_root: &'_ [u8]) -> Result<LightClientBootstrap> {
         let bootstrap = read_to_string(self.testdata.join("bootstrap.json"))?;
-        Ok(serde_json::from_str(&bootstrap)?)
+        let bootstrap: LightClientBootstrapCapella = serde_json::from_str(&bootstrap)?;
+        Ok(LightClientBootstrap::Capella(bootstrap))
     }
 
-    async fn get_updates(&self, _period: u64, _count: u8) -> Result<Vec<LightClientUpdateCapella>> {
+    async fn get_updates(&self, _period: u64, _count: u8) -> Result<Vec<LightClientUpdate>> {
         let updates = read_to_string(self.testdata.join("updates.json"))?;
-        Ok(serde_json::from_str(&updates)?)
+        let updates: Vec<LightClientUpdateCapella> = serde_json::from_str(&updates)?;
+        Ok(updates
+            .into_iter()
+            .map(LightClientUpdate::Capella)
+            .collect())
     }
 
-    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdateCapella> {
+    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdate> {
         let finality = read_to_string(self.testdata.join("finality.json"))?;
-        Ok(serde_json::from_str(&finality)?)
+        let finality: LightClientFinalityUpdateCapella = serde_json::from_str(&finality)?;
+        Ok(LightClientFinalityUpdate::Capella(finality))
     }
 
-    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdateCapella> {
+    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdate> {
         let optimistic = read_to_string(self.testdata.join("optimistic.json"))?;
-        Ok(serde_json::from_str(&optimistic)?)
+        let optimistic: LightClientOptimisticUpdateCapella = serde_json::from_str(&optimistic)?;
+        Ok(LightClientOptimisticUpdate::Capella(optimistic))
     }
 
     async fn chain_id(&self) -> Result<u64> {
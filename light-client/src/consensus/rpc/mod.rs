@@ -2,19 +2,20 @@ pub mod mock_rpc;
 pub mod nimbus_rpc;
 
 use super::types::{
-    LightClientBootstrapCapella, LightClientFinalityUpdateCapella,
-    LightClientOptimisticUpdateCapella, LightClientUpdateCapella,
+    LightClientBootstrap, LightClientFinalityUpdate, LightClientOptimisticUpdate, LightClientUpdate,
 };
 use async_trait::async_trait;
 use eyre::Result;
 
 // implements https://github.com/ethereum/beacon-APIs/tree/master/apis/beacon/light_client
+//
+// Light client data is returned for the fork it was created in, so that the consensus client can
+// follow the chain across forks.
 #[async_trait]
 pub trait ConsensusRpc {
-    fn new(path: &str) -> Self;
-    async fn get_bootstrap(&self, block_root: &'_ [u8]) -> Result<LightClientBootstrapCapella>;
-    async fn get_updates(&self, period: u64, count: u8) -> Result<Vec<LightClientUpdateCapella>>;
-    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdateCapella>;
-    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdateCapella>;
+    async fn get_bootstrap(&self, block_root: &'_ [u8]) -> Result<LightClientBootstrap>;
+    async fn get_updates(&self, period: u64, count: u8) -> Result<Vec<LightClientUpdate>>;
+    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdate>;
+    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdate>;
     async fn chain_id(&self) -> Result<u64>;
 }
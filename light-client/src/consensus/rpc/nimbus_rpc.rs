@@ -6,8 +6,9 @@ use std::cmp;
 use super::ConsensusRpc;
 use crate::consensus::constants::MAX_REQUEST_LIGHT_CLIENT_UPDATES;
 use crate::consensus::types::{
-    LightClientBootstrapCapella, LightClientFinalityUpdateCapella,
-    LightClientOptimisticUpdateCapella, LightClientUpdateCapella,
+    LightClientBootstrap, LightClientBootstrapCapella, LightClientFinalityUpdate,
+    LightClientFinalityUpdateCapella, LightClientOptimisticUpdate,
+    LightClientOptimisticUpdateCapella, LightClientUpdate, LightClientUpdateCapella,
 };
 use crate::errors::RpcError;
 
@@ -16,15 +17,18 @@ pub struct NimbusRpc {
     rpc: String,
 }
 
-#[async_trait]
-impl ConsensusRpc for NimbusRpc {
-    fn new(rpc: &str) -> Self {
+impl NimbusRpc {
+    pub fn new(rpc: &str) -> Self {
         NimbusRpc {
             rpc: rpc.to_string(),
         }
     }
+}
 
-    async fn get_bootstrap(&self, block_root: &'_ [u8]) -> Result<LightClientBootstrapCapella> {
+/// The beacon node is expected to serve light client data from the Capella fork.
+#[async_trait]
+impl ConsensusRpc for NimbusRpc {
+    async fn get_bootstrap(&self, block_root: &'_ [u8]) -> Result<LightClientBootstrap> {
         let root_hex = hex::encode(block_root);
         let req = format!(
             "{}/eth/v1/beacon/light_client/bootstrap/0x{}",
@@ -41,10 +45,10 @@ impl ConsensusRpc for NimbusRpc {
             .await
             .map_err(|e| RpcError::new("bootstrap", e))?;
 
-        Ok(res.data)
+        Ok(LightClientBootstrap::Capella(res.data))
     }
 
-    async fn get_updates(&self, period: u64, count: u8) -> Result<Vec<LightClientUpdateCapella>> {
+    async fn get_updates(&self, period: u64, count: u8) -> Result<Vec<LightClientUpdate>> {
         let count = cmp::min(count, MAX_REQUEST_LIGHT_CLIENT_UPDATES);
         let req = format!(
             "{}/eth/v1/beacon/light_client/updates?start_period={}&count={}",
@@ -61,10 +65,13 @@ impl ConsensusRpc for NimbusRpc {
             .await
             .map_err(|e| RpcError::new("updates", e))?;
 
-        Ok(res.iter().map(|d| d.data.clone()).collect())
+        Ok(res
+            .iter()
+            .map(|d| LightClientUpdate::Capella(d.data.clone()))
+            .collect())
     }
 
-    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdateCapella> {
+    async fn get_finality_update(&self) -> Result<LightClientFinalityUpdate> {
         let req = format!("{}/eth/v1/beacon/light_client/finality_update", self.rpc);
         let res = reqwest::get(req)
             .await
@@ -73,10 +80,10 @@ impl ConsensusRpc for NimbusRpc {
             .await
             .map_err(|e| RpcError::new("finality_update", e))?;
 
-        Ok(res.data)
+        Ok(LightClientFinalityUpdate::Capella(res.data))
     }
 
-    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdateCapella> {
+    async fn get_optimistic_update(&self) -> Result<LightClientOptimisticUpdate> {
         let req = format!("{}/eth/v1/beacon/light_client/optimistic_update", self.rpc);
         let res = reqwest::get(req)
             .await
@@ -85,7 +92,7 @@ impl ConsensusRpc for NimbusRpc {
             .await
             .map_err(|e| RpcError::new("optimistic_update", e))?;
 
-        Ok(res.data)
+        Ok(LightClientOptimisticUpdate::Capella(res.data))
     }
 
     async fn chain_id(&self) -> Result<u64> {
@@ -1,36 +1,118 @@
 use ethereum_types::H256;
 pub use ethportal_api::consensus::body::SyncAggregate;
+pub use ethportal_api::consensus::execution_payload::ExecutionPayloadHeaderCapella;
 use ethportal_api::consensus::header::BeaconBlockHeader;
 pub use ethportal_api::consensus::sync_committee::SyncCommittee;
 use ethportal_api::light_client::bootstrap::CurrentSyncCommitteeProofLen;
-pub use ethportal_api::light_client::bootstrap::LightClientBootstrapCapella;
-pub use ethportal_api::light_client::finality_update::LightClientFinalityUpdateCapella;
+pub use ethportal_api::light_client::bootstrap::{
+    LightClientBootstrap, LightClientBootstrapBellatrix, LightClientBootstrapCapella,
+};
+pub use ethportal_api::light_client::finality_update::{
+    LightClientFinalityUpdate, LightClientFinalityUpdateBellatrix, LightClientFinalityUpdateCapella,
+};
+use ethportal_api::light_client::header::ExecutionBranchLen;
 pub use ethportal_api::light_client::header::LightClientHeaderCapella;
-pub use ethportal_api::light_client::optimistic_update::LightClientOptimisticUpdateCapella;
+pub use ethportal_api::light_client::optimistic_update::{
+    LightClientOptimisticUpdate, LightClientOptimisticUpdateBellatrix,
+    LightClientOptimisticUpdateCapella,
+};
 use ethportal_api::light_client::update::FinalizedRootProofLen;
-pub use ethportal_api::light_client::update::LightClientUpdateCapella;
+pub use ethportal_api::light_client::update::{
+    LightClientUpdate, LightClientUpdateBellatrix, LightClientUpdateCapella,
+};
 use eyre::Result;
 use ssz_types::FixedVector;
 
+/// The execution payload header of a light client header, with its merkle branch against the body
+/// root of the beacon block header. Light client headers only carry one since Capella.
+#[derive(Clone, Debug)]
+pub struct ExecutionHeaderWithProof {
+    pub header: ExecutionPayloadHeaderCapella,
+    pub branch: FixedVector<H256, ExecutionBranchLen>,
+}
+
+impl From<&LightClientHeaderCapella> for ExecutionHeaderWithProof {
+    fn from(header: &LightClientHeaderCapella) -> Self {
+        Self {
+            header: header.execution.clone(),
+            branch: header.execution_branch.clone(),
+        }
+    }
+}
+
 pub struct GenericUpdate {
     pub attested_header: BeaconBlockHeader,
+    pub attested_execution: Option<ExecutionHeaderWithProof>,
     pub sync_aggregate: SyncAggregate,
     pub signature_slot: u64,
     pub next_sync_committee: Option<SyncCommittee>,
     pub next_sync_committee_branch: Option<FixedVector<H256, CurrentSyncCommitteeProofLen>>,
     pub finalized_header: Option<BeaconBlockHeader>,
+    pub finalized_execution: Option<ExecutionHeaderWithProof>,
     pub finality_branch: Option<FixedVector<H256, FinalizedRootProofLen>>,
 }
 
+impl From<&LightClientUpdate> for GenericUpdate {
+    fn from(update: &LightClientUpdate) -> Self {
+        match update {
+            LightClientUpdate::Bellatrix(update) => Self::from(update),
+            LightClientUpdate::Capella(update) => Self::from(update),
+        }
+    }
+}
+
+impl From<&LightClientUpdateBellatrix> for GenericUpdate {
+    fn from(update: &LightClientUpdateBellatrix) -> Self {
+        Self {
+            attested_header: update.attested_header.beacon.clone(),
+            attested_execution: None,
+            sync_aggregate: update.sync_aggregate.clone(),
+            signature_slot: update.signature_slot,
+            next_sync_committee: Some(update.next_sync_committee.clone()),
+            next_sync_committee_branch: Some(update.next_sync_committee_branch.clone()),
+            finalized_header: Some(update.finalized_header.beacon.clone()),
+            finalized_execution: None,
+            finality_branch: Some(update.finality_branch.clone()),
+        }
+    }
+}
+
 impl From<&LightClientUpdateCapella> for GenericUpdate {
     fn from(update: &LightClientUpdateCapella) -> Self {
         Self {
             attested_header: update.attested_header.beacon.clone(),
+            attested_execution: Some((&update.attested_header).into()),
             sync_aggregate: update.sync_aggregate.clone(),
             signature_slot: update.signature_slot,
             next_sync_committee: Some(update.next_sync_committee.clone()),
             next_sync_committee_branch: Some(update.next_sync_committee_branch.clone()),
             finalized_header: Some(update.finalized_header.beacon.clone()),
+            finalized_execution: Some((&update.finalized_header).into()),
+            finality_branch: Some(update.finality_branch.clone()),
+        }
+    }
+}
+
+impl From<&LightClientFinalityUpdate> for GenericUpdate {
+    fn from(update: &LightClientFinalityUpdate) -> Self {
+        match update {
+            LightClientFinalityUpdate::Bellatrix(update) => Self::from(update),
+            LightClientFinalityUpdate::Capella(update) => Self::from(update),
+        }
+    }
+}
+
+impl From<&LightClientFinalityUpdateBellatrix> for GenericUpdate {
+    fn from(update: &LightClientFinalityUpdateBellatrix) -> Self {
+        Self {
+            attested_header: update.attested_header.beacon.clone(),
+            attested_execution: None,
+            sync_aggregate: update.sync_aggregate.clone(),
+            signature_slot: update.signature_slot,
+            next_sync_committee: None,
+            next_sync_committee_branch: None,
+            finalized_header: Some(update.finalized_header.beacon.clone()),
+            finalized_execution: None,
             finality_branch: Some(update.finality_branch.clone()),
         }
     }
@@ -40,25 +122,54 @@ impl From<&LightClientFinalityUpdateCapella> for GenericUpdate {
     fn from(update: &LightClientFinalityUpdateCapella) -> Self {
         Self {
             attested_header: update.attested_header.beacon.clone(),
+            attested_execution: Some((&update.attested_header).into()),
             sync_aggregate: update.sync_aggregate.clone(),
             signature_slot: update.signature_slot,
             next_sync_committee: None,
             next_sync_committee_branch: None,
             finalized_header: Some(update.finalized_header.beacon.clone()),
+            finalized_execution: Some((&update.finalized_header).into()),
             finality_branch: Some(update.finality_branch.clone()),
         }
     }
 }
 
+impl From<&LightClientOptimisticUpdate> for GenericUpdate {
+    fn from(update: &LightClientOptimisticUpdate) -> Self {
+        match update {
+            LightClientOptimisticUpdate::Bellatrix(update) => Self::from(update),
+            LightClientOptimisticUpdate::Capella(update) => Self::from(update),
+        }
+    }
+}
+
+impl From<&LightClientOptimisticUpdateBellatrix> for GenericUpdate {
+    fn from(update: &LightClientOptimisticUpdateBellatrix) -> Self {
+        Self {
+            attested_header: update.attested_header.beacon.clone(),
+            attested_execution: None,
+            sync_aggregate: update.sync_aggregate.clone(),
+            signature_slot: update.signature_slot,
+            next_sync_committee: None,
+            next_sync_committee_branch: None,
+            finalized_header: None,
+            finalized_execution: None,
+            finality_branch: None,
+        }
+    }
+}
+
 impl From<&LightClientOptimisticUpdateCapella> for GenericUpdate {
     fn from(update: &LightClientOptimisticUpdateCapella) -> Self {
         Self {
             attested_header: update.attested_header.beacon.clone(),
+            attested_execution: Some((&update.attested_header).into()),
             sync_aggregate: update.sync_aggregate.clone(),
             signature_slot: update.signature_slot,
             next_sync_committee: None,
             next_sync_committee_branch: None,
             finalized_header: None,
+            finalized_execution: None,
             finality_branch: None,
         }
     }
@@ -1,5 +1,6 @@
 use crate::types::Bytes32;
 use crate::utils::bytes32_to_node;
+use ethereum_types::H256;
 use ethportal_api::consensus::header::BeaconBlockHeader;
 use ethportal_api::consensus::signature::BlsSignature;
 use eyre::Result;
@@ -26,16 +27,33 @@ pub fn is_proof_valid<L: TreeHash>(
     branch: &[Bytes32],
     depth: usize,
     index: usize,
+) -> bool {
+    is_merkle_proof_valid(
+        &attested_header.state_root,
+        leaf_object,
+        branch,
+        depth,
+        index,
+    )
+}
+
+/// Checks the merkle branch of `leaf_object` against `root`, eg. the state root or the body root
+/// of a beacon block header.
+pub fn is_merkle_proof_valid<L: TreeHash>(
+    root: &H256,
+    leaf_object: &L,
+    branch: &[Bytes32],
+    depth: usize,
+    index: usize,
 ) -> bool {
     let res: Result<bool> = (move || {
         let leaf_hash = Node::from_bytes(<[u8; 32]>::from(leaf_object.tree_hash_root()));
-        let state_root = bytes32_to_node(
-            &Bytes32::try_from(attested_header.state_root.0.to_vec())
-                .expect("Unable to convert state root to bytes"),
+        let root = bytes32_to_node(
+            &Bytes32::try_from(root.0.to_vec()).expect("Unable to convert root to bytes"),
         )?;
         let branch = branch_to_nodes(branch.to_vec())?;
 
-        let is_valid = is_valid_merkle_branch(&leaf_hash, branch.iter(), depth, index, &state_root);
+        let is_valid = is_valid_merkle_branch(&leaf_hash, branch.iter(), depth, index, &root);
         Ok(is_valid)
     })();
 
@@ -21,8 +21,12 @@ impl Node {
         let consensus_rpc = &config.consensus_rpc;
         let checkpoint_hash = &config.checkpoint.as_ref().unwrap();
 
-        let consensus = ConsensusLightClient::new(consensus_rpc, checkpoint_hash, config.clone())
-            .map_err(NodeError::ConsensusClientCreationError)?;
+        let consensus = ConsensusLightClient::new(
+            NimbusRpc::new(consensus_rpc),
+            checkpoint_hash,
+            config.clone(),
+        )
+        .map_err(NodeError::ConsensusClientCreationError)?;
 
         Ok(Node {
             consensus,
@@ -18,7 +18,7 @@ async fn setup() -> ConsensusLightClient<MockRpc> {
     let checkpoint =
         hex::decode("c62aa0de55e6f21230fa63713715e1a6c13e73005e89f6389da271955d819bde").unwrap();
 
-    ConsensusLightClient::new("testdata/", &checkpoint, Arc::new(config)).unwrap()
+    ConsensusLightClient::new(MockRpc::new("testdata/"), &checkpoint, Arc::new(config)).unwrap()
 }
 
 #[tokio::test]
@@ -31,4 +31,10 @@ async fn test_sync() {
 
     let finalized_head = client.get_finalized_header();
     assert_eq!(finalized_head.slot, 7358656);
+
+    let execution_head = client.get_execution_header().unwrap();
+    assert_eq!(execution_head.block_number, 18170142);
+
+    let finalized_execution_head = client.get_finalized_execution_header().unwrap();
+    assert_eq!(finalized_execution_head.block_number, 18170072);
 }
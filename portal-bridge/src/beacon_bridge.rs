@@ -1,6 +1,7 @@
 use crate::consensus_api::ConsensusApi;
 use crate::constants::BEACON_GENESIS_TIME;
 use crate::mode::BridgeMode;
+use crate::portal_clients::PortalClients;
 use crate::utils::{
     duration_until_next_update, expected_current_slot, read_test_assets_from_file, TestAssets,
 };
@@ -16,11 +17,9 @@ use ethportal_api::types::content_value::beacon::{
     ForkVersionedLightClientUpdate, LightClientUpdatesByRange,
 };
 use ethportal_api::utils::bytes::hex_decode;
-use ethportal_api::BeaconNetworkApiClient;
 use ethportal_api::{
     BeaconContentKey, BeaconContentValue, LightClientBootstrapKey, LightClientUpdatesByRangeKey,
 };
-use jsonrpsee::http_client::HttpClient;
 use serde_json::Value;
 use ssz_types::VariableList;
 use std::cmp::Ordering;
@@ -37,11 +36,11 @@ use tokio::time::{interval, sleep, Duration, MissedTickBehavior};
 pub struct BeaconBridge {
     pub api: ConsensusApi,
     mode: BridgeMode,
-    portal_clients: Arc<Vec<HttpClient>>,
+    portal_clients: Arc<PortalClients>,
 }
 
 impl BeaconBridge {
-    pub fn new(api: ConsensusApi, mode: BridgeMode, portal_clients: Arc<Vec<HttpClient>>) -> Self {
+    pub fn new(api: ConsensusApi, mode: BridgeMode, portal_clients: Arc<PortalClients>) -> Self {
         Self {
             api,
             mode,
@@ -124,7 +123,7 @@ impl BeaconBridge {
     /// Returns the new current period and finalized block root
     async fn serve_latest(
         api: ConsensusApi,
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
         current_period: u64,
         finalized_block_root: String,
     ) -> (u64, String) {
@@ -189,7 +188,7 @@ impl BeaconBridge {
     /// Serve `LightClientBootstrap` data
     async fn serve_light_client_bootstrap(
         api: ConsensusApi,
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
         finalized_block_root: &str,
     ) -> anyhow::Result<String> {
         let response = api.get_beacon_block_root("finalized".to_owned()).await?;
@@ -228,7 +227,7 @@ impl BeaconBridge {
 
     async fn serve_light_client_update(
         api: ConsensusApi,
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
         current_period: u64,
     ) -> anyhow::Result<u64> {
         let now = SystemTime::now();
@@ -276,7 +275,7 @@ impl BeaconBridge {
 
     async fn serve_light_client_optimistic_update(
         api: ConsensusApi,
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
     ) -> anyhow::Result<()> {
         let data = api.get_lc_optimistic_update().await?;
         let update: Value = serde_json::from_str(&data)?;
@@ -297,7 +296,7 @@ impl BeaconBridge {
 
     async fn serve_light_client_finality_update(
         api: ConsensusApi,
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
     ) -> anyhow::Result<()> {
         let data = api.get_lc_finality_update().await?;
         let update: Value = serde_json::from_str(&data)?;
@@ -317,15 +316,12 @@ impl BeaconBridge {
 
     /// Gossip any given content key / value to the history network.
     async fn gossip_beacon_content(
-        portal_clients: Arc<Vec<HttpClient>>,
+        portal_clients: Arc<PortalClients>,
         content_key: BeaconContentKey,
         content_value: BeaconContentValue,
     ) -> anyhow::Result<()> {
-        for client in portal_clients.as_ref() {
-            client
-                .gossip(content_key.clone(), content_value.clone())
-                .await?;
-        }
-        Ok(())
+        portal_clients
+            .gossip_beacon(content_key, content_value)
+            .await
     }
 }
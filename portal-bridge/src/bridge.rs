@@ -1,9 +1,10 @@
 use crate::execution_api::ExecutionApi;
 use crate::full_header::FullHeader;
 use crate::mode::{BridgeMode, ModeType};
+use crate::portal_clients::PortalClients;
 use crate::utils::{read_test_assets_from_file, TestAssets};
 use anyhow::{anyhow, bail};
-use ethportal_api::jsonrpsee::http_client::HttpClient;
+use ethportal_api::types::enr::Enr;
 use ethportal_api::types::execution::accumulator::EpochAccumulator;
 use ethportal_api::types::execution::block_body::{
     BlockBody, BlockBodyLegacy, BlockBodyMerge, BlockBodyShanghai, MERGE_TIMESTAMP,
@@ -13,9 +14,9 @@ use ethportal_api::types::execution::header::{
     AccumulatorProof, BlockHeaderProof, Header, HeaderWithProof, SszNone,
 };
 use ethportal_api::types::execution::receipts::Receipts;
+use ethportal_api::types::gossip_trace::OfferOutcome;
 use ethportal_api::utils::bytes::hex_encode;
 use ethportal_api::HistoryContentValue;
-use ethportal_api::HistoryNetworkApiClient;
 use ethportal_api::{
     BlockBodyKey, BlockHeaderKey, BlockReceiptsKey, EpochAccumulatorKey, HistoryContentKey,
 };
@@ -44,19 +45,22 @@ const FUTURES_BUFFER_SIZE: usize = 32;
 
 pub struct Bridge {
     pub mode: BridgeMode,
-    pub portal_clients: Vec<HttpClient>,
+    pub portal_clients: PortalClients,
     pub execution_api: ExecutionApi,
     pub header_oracle: HeaderOracle,
     pub epoch_acc_path: PathBuf,
+    /// In test mode, the peer that the content is offered to directly, rather than gossiped.
+    pub offer_enr: Option<Enr>,
 }
 
 impl Bridge {
     pub fn new(
         mode: BridgeMode,
         execution_api: ExecutionApi,
-        portal_clients: Vec<HttpClient>,
+        portal_clients: PortalClients,
         header_oracle: HeaderOracle,
         epoch_acc_path: PathBuf,
+        offer_enr: Option<Enr>,
     ) -> Self {
         Self {
             mode,
@@ -64,6 +68,7 @@ impl Bridge {
             execution_api,
             header_oracle,
             epoch_acc_path,
+            offer_enr,
         }
     }
 }
@@ -85,6 +90,30 @@ impl Bridge {
             .into_history_assets()
             .expect("Error parsing history test assets.");
 
+        if let Some(enr) = self.offer_enr.clone() {
+            // Headers are offered first, since they are needed to validate the other content.
+            let (headers, others): (Vec<_>, Vec<_>) = assets
+                .0
+                .into_iter()
+                .map(|asset| (asset.content_key, asset.content_value))
+                .partition(|(content_key, _)| {
+                    matches!(content_key, HistoryContentKey::BlockHeaderWithProof(_))
+                });
+            for content_items in [headers, others] {
+                let outcomes = self
+                    .portal_clients
+                    .offer_history_many(enr.clone(), content_items)
+                    .await
+                    .expect("Error offering test assets.");
+                for (content_key, outcome) in outcomes {
+                    if outcome != OfferOutcome::Transferred {
+                        warn!(content.key = %content_key, ?outcome, "Test asset was not transferred");
+                    }
+                }
+            }
+            return;
+        }
+
         for asset in assets.0.into_iter() {
             Bridge::gossip_content(
                 &self.portal_clients,
@@ -240,7 +269,7 @@ impl Bridge {
         height: u64,
         epoch_acc: Option<Arc<EpochAccumulator>>,
         gossip_stats: Arc<Mutex<GossipStats>>,
-        portal_clients: Vec<HttpClient>,
+        portal_clients: PortalClients,
     ) -> anyhow::Result<()> {
         debug!("Serving block: {height}");
         let mut full_header = self.execution_api.get_header(height).await?;
@@ -263,7 +292,7 @@ impl Bridge {
 
     async fn gossip_header(
         full_header: &FullHeader,
-        portal_clients: &Vec<HttpClient>,
+        portal_clients: &PortalClients,
         gossip_stats: &Arc<Mutex<GossipStats>>,
     ) -> anyhow::Result<()> {
         debug!("Serving header: {}", full_header.header.number);
@@ -344,7 +373,7 @@ impl Bridge {
     async fn construct_and_gossip_receipt(
         &self,
         full_header: &FullHeader,
-        portal_clients: &Vec<HttpClient>,
+        portal_clients: &PortalClients,
         gossip_stats: &Arc<Mutex<GossipStats>>,
     ) -> anyhow::Result<()> {
         debug!("Serving receipt: {:?}", full_header.header.number);
@@ -386,7 +415,7 @@ impl Bridge {
     async fn construct_and_gossip_block_body(
         &self,
         full_header: &FullHeader,
-        portal_clients: &Vec<HttpClient>,
+        portal_clients: &PortalClients,
         gossip_stats: &Arc<Mutex<GossipStats>>,
     ) -> anyhow::Result<()> {
         let txs = full_header.txs.clone();
@@ -445,16 +474,13 @@ impl Bridge {
 
     /// Gossip any given content key / value to the history network.
     async fn gossip_content(
-        portal_clients: &Vec<HttpClient>,
+        portal_clients: &PortalClients,
         content_key: HistoryContentKey,
         content_value: HistoryContentValue,
     ) -> anyhow::Result<()> {
-        for client in portal_clients {
-            client
-                .gossip(content_key.clone(), content_value.clone())
-                .await?;
-        }
-        Ok(())
+        portal_clients
+            .gossip_history(content_key, content_value)
+            .await
     }
 }
 
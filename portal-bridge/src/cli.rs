@@ -2,6 +2,7 @@ use crate::client_handles::{fluffy_handle, trin_handle};
 use crate::mode::BridgeMode;
 use crate::types::NetworkKind;
 use clap::{Parser, Subcommand};
+use ethportal_api::types::enr::Enr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use tokio::process::Child;
@@ -26,8 +27,11 @@ pub struct BridgeConfig {
     )]
     pub node_count: u8,
 
-    #[arg(long, help = "path to portalnet client executable")]
-    pub executable_path: PathBuf,
+    #[arg(
+        long,
+        help = "path to portalnet client executable, not required for native clients"
+    )]
+    pub executable_path: Option<PathBuf>,
 
     #[arg(
         long,
@@ -53,6 +57,12 @@ pub struct BridgeConfig {
     #[arg(long, help = "Url for metrics reporting")]
     pub metrics_url: Option<Url>,
 
+    #[arg(
+        long = "offer-enr",
+        help = "In test mode, offer the history content directly to this peer, in batches, rather than gossiping it"
+    )]
+    pub offer_enr: Option<Enr>,
+
     #[command(subcommand)]
     pub client_type: ClientType,
 }
@@ -72,6 +82,8 @@ type ParseError = &'static str;
 pub enum ClientType {
     Fluffy,
     Trin,
+    /// Run the portal nodes inside the bridge process, instead of spawning external clients.
+    Native,
 }
 
 impl FromStr for ClientType {
@@ -81,22 +93,30 @@ impl FromStr for ClientType {
         match s {
             "fluffy" => Ok(ClientType::Fluffy),
             "trin" => Ok(ClientType::Trin),
+            "native" => Ok(ClientType::Native),
             _ => Err("Invalid client type"),
         }
     }
 }
 
 impl ClientType {
+    /// Spawns an external client process. Native clients run inside the bridge process, so there
+    /// is no process to spawn for them, and `None` is returned.
     pub fn build_handle(
         &self,
         private_key: String,
         rpc_port: u16,
         udp_port: u16,
         bridge_config: BridgeConfig,
-    ) -> anyhow::Result<Child> {
+    ) -> anyhow::Result<Option<Child>> {
         match self {
-            ClientType::Fluffy => fluffy_handle(private_key, rpc_port, udp_port, bridge_config),
-            ClientType::Trin => trin_handle(private_key, rpc_port, udp_port, bridge_config),
+            ClientType::Fluffy => {
+                fluffy_handle(private_key, rpc_port, udp_port, bridge_config).map(Some)
+            }
+            ClientType::Trin => {
+                trin_handle(private_key, rpc_port, udp_port, bridge_config).map(Some)
+            }
+            ClientType::Native => Ok(None),
         }
     }
 }
@@ -105,6 +125,7 @@ impl ClientType {
 mod test {
     use super::*;
     use crate::mode::ModeType;
+    use ethportal_api::types::enr::generate_random_remote_enr;
 
     #[test]
     fn test_default_bridge_config() {
@@ -126,7 +147,7 @@ mod test {
         assert_eq!(bridge_config.node_count, 1);
         assert_eq!(
             bridge_config.executable_path,
-            PathBuf::from(EXECUTABLE_PATH)
+            Some(PathBuf::from(EXECUTABLE_PATH))
         );
         assert_eq!(bridge_config.mode, BridgeMode::Latest);
         assert_eq!(bridge_config.epoch_acc_path, PathBuf::from(EPOCH_ACC_PATH));
@@ -157,7 +178,7 @@ mod test {
         assert_eq!(bridge_config.node_count, 1);
         assert_eq!(
             bridge_config.executable_path,
-            PathBuf::from(EXECUTABLE_PATH)
+            Some(PathBuf::from(EXECUTABLE_PATH))
         );
         assert_eq!(
             bridge_config.mode,
@@ -186,13 +207,50 @@ mod test {
         assert_eq!(bridge_config.node_count, 16);
         assert_eq!(
             bridge_config.executable_path,
-            PathBuf::from(EXECUTABLE_PATH)
+            Some(PathBuf::from(EXECUTABLE_PATH))
         );
         assert_eq!(bridge_config.mode, BridgeMode::Latest);
         assert_eq!(bridge_config.epoch_acc_path, PathBuf::from(EPOCH_ACC_PATH));
         assert_eq!(bridge_config.network, vec![NetworkKind::History]);
     }
 
+    #[test]
+    fn test_native_bridge_config_without_executable_path() {
+        const EPOCH_ACC_PATH: &str = "path/to/epoch/accumulator";
+        let bridge_config = BridgeConfig::parse_from([
+            "bridge",
+            "--epoch-accumulator-path",
+            EPOCH_ACC_PATH,
+            "--network",
+            "history,beacon",
+            "native",
+        ]);
+        assert_eq!(bridge_config.node_count, 1);
+        assert_eq!(bridge_config.executable_path, None);
+        assert_eq!(bridge_config.client_type, ClientType::Native);
+        assert_eq!(
+            bridge_config.network,
+            vec![NetworkKind::History, NetworkKind::Beacon]
+        );
+    }
+
+    #[test]
+    fn test_bridge_config_with_offer_enr() {
+        const EPOCH_ACC_PATH: &str = "path/to/epoch/accumulator";
+        let (_, enr) = generate_random_remote_enr();
+        let bridge_config = BridgeConfig::parse_from([
+            "bridge",
+            "--epoch-accumulator-path",
+            EPOCH_ACC_PATH,
+            "--mode",
+            "test:path/to/test_data.json",
+            "--offer-enr",
+            &enr.to_base64(),
+            "native",
+        ]);
+        assert_eq!(bridge_config.offer_enr, Some(enr));
+    }
+
     #[test]
     #[should_panic(
         expected = "Invalid network arg. Expected either 'beacon', 'history' or 'state'"
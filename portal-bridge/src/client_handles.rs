@@ -10,12 +10,16 @@ pub fn fluffy_handle(
     udp_port: u16,
     bridge_config: BridgeConfig,
 ) -> anyhow::Result<Child> {
-    let mut command = Command::new(bridge_config.executable_path);
+    let executable_path = match bridge_config.executable_path {
+        Some(executable_path) => executable_path,
+        None => bail!("Missing executable path for fluffy client"),
+    };
+    let mut command = Command::new(executable_path);
     let listen_all_ips = SocketAddr::new("0.0.0.0".parse().expect("to parse ip"), udp_port);
-    let ip = stun_for_external(&listen_all_ips).expect("to stun for external ip");
+    let ip = stun_for_external(&listen_all_ips, None).expect("to stun for external ip");
     command
         .kill_on_drop(true)
-        .arg("--storage-size:0")
+        .arg("--storage-capacity:0")
         .arg("--rpc")
         .arg(format!("--rpc-port:{rpc_port}"))
         .arg(format!("--udp-port:{udp_port}"))
@@ -47,7 +51,11 @@ pub fn trin_handle(
     udp_port: u16,
     bridge_config: BridgeConfig,
 ) -> anyhow::Result<Child> {
-    let mut command = Command::new(bridge_config.executable_path);
+    let executable_path = match bridge_config.executable_path {
+        Some(executable_path) => executable_path,
+        None => bail!("Missing executable path for trin client"),
+    };
+    let mut command = Command::new(executable_path);
     let networks = bridge_config
         .network
         .into_iter()
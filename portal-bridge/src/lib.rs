@@ -9,7 +9,9 @@ pub mod constants;
 pub mod execution_api;
 pub mod full_header;
 pub mod mode;
+pub mod native;
 pub mod pandaops;
+pub mod portal_clients;
 pub mod types;
 pub mod utils;
 
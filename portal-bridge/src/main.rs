@@ -2,10 +2,12 @@ use clap::Parser;
 use ethportal_api::jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use portal_bridge::beacon_bridge::BeaconBridge;
 use portal_bridge::bridge::Bridge;
-use portal_bridge::cli::BridgeConfig;
+use portal_bridge::cli::{BridgeConfig, ClientType};
 use portal_bridge::consensus_api::ConsensusApi;
 use portal_bridge::execution_api::ExecutionApi;
+use portal_bridge::native::NativeNodes;
 use portal_bridge::pandaops::PandaOpsMiddleware;
+use portal_bridge::portal_clients::PortalClients;
 use portal_bridge::types::NetworkKind;
 use portal_bridge::utils::generate_spaced_private_keys;
 use std::sync::Arc;
@@ -21,30 +23,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let bridge_config = BridgeConfig::parse();
     let private_keys = generate_spaced_private_keys(bridge_config.node_count);
     let mut handles = vec![];
-    let mut http_addresses = vec![];
-    for (i, key) in private_keys.into_iter().enumerate() {
-        let web3_http_port = 8545 + i;
-        let discovery_port = 9000 + i;
-        let handle = bridge_config.client_type.build_handle(
-            key,
-            web3_http_port as u16,
-            discovery_port as u16,
-            bridge_config.clone(),
-        );
-        let web3_http_address = format!("http://127.0.0.1:{}", web3_http_port);
-        http_addresses.push(web3_http_address);
-        handles.push(handle);
-    }
-    sleep(Duration::from_secs(5)).await;
+    let portal_clients = match bridge_config.client_type {
+        ClientType::Native => {
+            let nodes = NativeNodes::launch(private_keys, &bridge_config)
+                .await
+                .expect("Failed to launch native portal nodes");
+            Ok(PortalClients::Native(Arc::new(nodes)))
+        }
+        _ => {
+            let mut http_addresses = vec![];
+            for (i, key) in private_keys.into_iter().enumerate() {
+                let web3_http_port = 8545 + i;
+                let discovery_port = 9000 + i;
+                let handle = bridge_config
+                    .client_type
+                    .build_handle(
+                        key,
+                        web3_http_port as u16,
+                        discovery_port as u16,
+                        bridge_config.clone(),
+                    )
+                    .expect("Failed to launch portal client");
+                let web3_http_address = format!("http://127.0.0.1:{}", web3_http_port);
+                http_addresses.push(web3_http_address);
+                handles.extend(handle);
+            }
+            sleep(Duration::from_secs(5)).await;
 
-    let portal_clients: Result<Vec<HttpClient>, String> = http_addresses
-        .iter()
-        .map(|address| {
-            HttpClientBuilder::default()
-                .build(address)
-                .map_err(|e| e.to_string())
-        })
-        .collect();
+            http_addresses
+                .iter()
+                .map(|address| {
+                    HttpClientBuilder::default()
+                        .build(address)
+                        .map_err(|e| e.to_string())
+                })
+                .collect::<Result<Vec<HttpClient>, String>>()
+                .map(PortalClients::Http)
+        }
+    };
 
     let mut bridge_tasks = Vec::new();
 
@@ -80,6 +96,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 portal_clients.expect("Failed to create history JSON-RPC clients"),
                 header_oracle,
                 bridge_config.epoch_acc_path,
+                bridge_config.offer_enr,
             );
 
             bridge.launch().await;
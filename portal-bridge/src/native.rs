@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use ethereum_types::H256;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tracing::info;
+use utp_rs::socket::UtpSocket;
+
+use crate::cli::BridgeConfig;
+use crate::types::NetworkKind;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::gossip_trace::OfferOutcome;
+use ethportal_api::{
+    BeaconContentKey, BeaconContentValue, ContentValue, HistoryContentKey, HistoryContentValue,
+};
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, Discv5UdpSocket},
+    events::PortalnetEvents,
+    shutdown::ShutdownSignal,
+    storage::PortalStorageConfig,
+    types::messages::ProtocolId,
+    utils::db::{configure_node_data_dir, setup_temp_dir},
+};
+use trin_beacon::{network::BeaconNetwork, spawn_beacon_network};
+use trin_history::{network::HistoryNetwork, spawn_history_network};
+use trin_validation::{accumulator::MasterAccumulator, oracle::HeaderOracle};
+
+/// First UDP port used by the in-process portal nodes.
+const NATIVE_DISCOVERY_PORT: u16 = 9000;
+
+/// A portal node that runs inside the bridge process. The bridge drives its overlays directly,
+/// rather than going through the JSON-RPC interface of an external client.
+pub struct NativeNode {
+    pub discovery: Arc<Discovery>,
+    pub history: Option<Arc<HistoryNetwork>>,
+    pub beacon: Option<Arc<BeaconNetwork>>,
+    /// The data directory of the node, which is deleted when the node is dropped.
+    _data_dir: TempDir,
+}
+
+impl NativeNode {
+    /// Launches a portal node with the given private key, activating the selected subnetworks.
+    pub async fn launch(
+        private_key: H256,
+        discovery_port: u16,
+        networks: &[NetworkKind],
+    ) -> anyhow::Result<Self> {
+        if networks.contains(&NetworkKind::State) {
+            bail!("State network is not supported by native bridge nodes");
+        }
+
+        let data_dir = setup_temp_dir()?;
+        let (node_data_dir, private_key) =
+            configure_node_data_dir(data_dir.path().to_path_buf(), Some(private_key))?;
+
+        let portalnet_config = PortalnetConfig {
+            private_key,
+            listen_port: discovery_port,
+            ..Default::default()
+        };
+
+        let mut discovery = Discovery::new(portalnet_config.clone(), node_data_dir.clone())
+            .map_err(|err| anyhow!(err))?;
+        let talk_req_rx = discovery.start().await.map_err(|err| anyhow!(err))?;
+        let discovery = Arc::new(discovery);
+
+        let (utp_talk_reqs_tx, utp_talk_reqs_rx) = mpsc::unbounded_channel();
+        let discv5_utp_socket = Discv5UdpSocket::new(Arc::clone(&discovery), utp_talk_reqs_rx);
+        let utp_socket = Arc::new(UtpSocket::with_socket(discv5_utp_socket));
+
+        // Bridge nodes only inject content into the network, so they don't store any of it.
+        let storage_config =
+            PortalStorageConfig::new(0, node_data_dir, discovery.local_enr().node_id())?;
+        let header_oracle = HeaderOracle::new(MasterAccumulator::default());
+        let header_oracle = Arc::new(RwLock::new(header_oracle));
+        let shutdown_signal = ShutdownSignal::new();
+
+        let mut overlay_senders = HashMap::new();
+        let history = if networks.contains(&NetworkKind::History) {
+            let network = HistoryNetwork::new(
+                Arc::clone(&discovery),
+                Arc::clone(&utp_socket),
+                storage_config.clone(),
+                portalnet_config.clone(),
+                header_oracle.clone(),
+            )
+            .await?;
+            let network = Arc::new(network);
+            let (event_tx, event_rx) = mpsc::unbounded_channel();
+            spawn_history_network(
+                Arc::clone(&network),
+                portalnet_config.clone(),
+                event_rx,
+                shutdown_signal.clone(),
+            );
+            overlay_senders.insert(ProtocolId::History, event_tx);
+            Some(network)
+        } else {
+            None
+        };
+
+        let beacon = if networks.contains(&NetworkKind::Beacon) {
+            let network = BeaconNetwork::new(
+                Arc::clone(&discovery),
+                utp_socket,
+                storage_config,
+                portalnet_config.clone(),
+                header_oracle,
+            )
+            .await?;
+            let network = Arc::new(network);
+            let (event_tx, event_rx) = mpsc::unbounded_channel();
+            spawn_beacon_network(
+                Arc::clone(&network),
+                portalnet_config,
+                event_rx,
+                shutdown_signal,
+            );
+            overlay_senders.insert(ProtocolId::Beacon, event_tx);
+            Some(network)
+        } else {
+            None
+        };
+
+        tokio::spawn(async move {
+            let events = PortalnetEvents::new(talk_req_rx, overlay_senders, utp_talk_reqs_tx).await;
+            events.start().await;
+        });
+
+        Ok(Self {
+            discovery,
+            history,
+            beacon,
+            _data_dir: data_dir,
+        })
+    }
+
+    /// Offers history content to interested peers, returning the number of content items
+    /// transferred to them.
+    pub async fn gossip_history(
+        &self,
+        content_key: HistoryContentKey,
+        content_value: HistoryContentValue,
+    ) -> anyhow::Result<usize> {
+        let network = self
+            .history
+            .as_ref()
+            .ok_or_else(|| anyhow!("History network is not active on native node"))?;
+        Ok(network
+            .overlay
+            .propagate_gossip(vec![(content_key, content_value.encode())])
+            .await)
+    }
+
+    /// Offers many history content items to `enr`, returning the outcome of offering each content
+    /// item, in the order of `content_items`.
+    pub async fn offer_history_many(
+        &self,
+        enr: Enr,
+        content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+    ) -> anyhow::Result<Vec<(String, OfferOutcome)>> {
+        let network = self
+            .history
+            .as_ref()
+            .ok_or_else(|| anyhow!("History network is not active on native node"))?;
+        let content_items = content_items
+            .into_iter()
+            .map(|(content_key, content_value)| (content_key.into(), content_value.encode()))
+            .collect();
+        Ok(network.overlay.offer_many(enr, content_items).await)
+    }
+
+    /// Offers beacon content to interested peers, returning the number of content items
+    /// transferred to them.
+    pub async fn gossip_beacon(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: BeaconContentValue,
+    ) -> anyhow::Result<usize> {
+        let network = self
+            .beacon
+            .as_ref()
+            .ok_or_else(|| anyhow!("Beacon network is not active on native node"))?;
+        Ok(network
+            .overlay
+            .propagate_gossip(vec![(content_key, content_value.encode())])
+            .await)
+    }
+}
+
+/// The set of in-process portal nodes used by the bridge.
+pub struct NativeNodes {
+    pub nodes: Vec<NativeNode>,
+}
+
+impl NativeNodes {
+    /// Launches one in-process node per private key, on consecutive discovery ports.
+    pub async fn launch(
+        private_keys: Vec<String>,
+        bridge_config: &BridgeConfig,
+    ) -> anyhow::Result<Self> {
+        let mut nodes = vec![];
+        for (i, key) in private_keys.into_iter().enumerate() {
+            let private_key = H256::from_str(&key)?;
+            let discovery_port = NATIVE_DISCOVERY_PORT + i as u16;
+            let node =
+                NativeNode::launch(private_key, discovery_port, &bridge_config.network).await?;
+            info!(
+                enr = %node.discovery.local_enr(),
+                "Launched native bridge node",
+            );
+            nodes.push(node);
+        }
+        Ok(Self { nodes })
+    }
+
+    /// Gossips history content through every native node.
+    pub async fn gossip_history(
+        &self,
+        content_key: HistoryContentKey,
+        content_value: HistoryContentValue,
+    ) -> anyhow::Result<()> {
+        for node in self.nodes.iter() {
+            node.gossip_history(content_key.clone(), content_value.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Gossips beacon content through every native node.
+    pub async fn gossip_beacon(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: BeaconContentValue,
+    ) -> anyhow::Result<()> {
+        for node in self.nodes.iter() {
+            node.gossip_beacon(content_key.clone(), content_value.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ethportal_api::jsonrpsee::http_client::HttpClient;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::gossip_trace::OfferOutcome;
+use ethportal_api::{
+    BeaconContentKey, BeaconContentValue, BeaconNetworkApiClient, HistoryContentKey,
+    HistoryContentValue, HistoryNetworkApiClient,
+};
+
+use crate::native::NativeNodes;
+
+/// The portal nodes a bridge injects content through.
+#[derive(Clone)]
+pub enum PortalClients {
+    /// JSON-RPC clients connected to external trin / fluffy processes.
+    Http(Vec<HttpClient>),
+    /// Nodes running inside the bridge process.
+    Native(Arc<NativeNodes>),
+}
+
+impl PortalClients {
+    /// Gossip history content through every portal node.
+    pub async fn gossip_history(
+        &self,
+        content_key: HistoryContentKey,
+        content_value: HistoryContentValue,
+    ) -> anyhow::Result<()> {
+        match self {
+            PortalClients::Http(clients) => {
+                for client in clients {
+                    HistoryNetworkApiClient::gossip(
+                        client,
+                        content_key.clone(),
+                        content_value.clone(),
+                    )
+                    .await?;
+                }
+                Ok(())
+            }
+            PortalClients::Native(nodes) => nodes.gossip_history(content_key, content_value).await,
+        }
+    }
+
+    /// Offer many history content items to `enr` through the first portal node. Returns the
+    /// outcome of offering each content item, in the order of `content_items`.
+    pub async fn offer_history_many(
+        &self,
+        enr: Enr,
+        content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+    ) -> anyhow::Result<Vec<(String, OfferOutcome)>> {
+        match self {
+            PortalClients::Http(clients) => {
+                let client = clients
+                    .first()
+                    .ok_or_else(|| anyhow!("No portal clients to offer content through"))?;
+                Ok(HistoryNetworkApiClient::offer_many(client, enr, content_items).await?)
+            }
+            PortalClients::Native(nodes) => {
+                let node = nodes
+                    .nodes
+                    .first()
+                    .ok_or_else(|| anyhow!("No native nodes to offer content through"))?;
+                node.offer_history_many(enr, content_items).await
+            }
+        }
+    }
+
+    /// Gossip beacon content through every portal node.
+    pub async fn gossip_beacon(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: BeaconContentValue,
+    ) -> anyhow::Result<()> {
+        match self {
+            PortalClients::Http(clients) => {
+                for client in clients {
+                    BeaconNetworkApiClient::gossip(
+                        client,
+                        content_key.clone(),
+                        content_value.clone(),
+                    )
+                    .await?;
+                }
+                Ok(())
+            }
+            PortalClients::Native(nodes) => nodes.gossip_beacon(content_key, content_value).await,
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 use ethereum_types::H256;
 
 use ethportal_api::types::bootnodes::Bootnodes;
-use ethportal_api::types::cli::TrinConfig;
+use ethportal_api::types::cli::{TrinConfig, DEFAULT_IPV6_STUN_SERVER};
 use ethportal_api::types::distance::Distance;
+use ethportal_api::types::portal::OverlayTuning;
+
+use crate::rate_limit::RateLimitConfig;
 
 /// Capacity of the cache for observed `NodeAddress` values.
 /// Provides capacity for 32 full k-buckets. This capacity will be shared among all active portal
@@ -16,11 +20,23 @@ pub struct PortalnetConfig {
     pub external_addr: Option<SocketAddr>,
     pub private_key: H256,
     pub listen_port: u16,
+    /// IPv4 address to bind discovery to, if any.
+    pub listen_ipv4: Option<Ipv4Addr>,
+    /// IPv6 address to bind discovery to, if any. Setting both addresses listens dual-stack.
+    pub listen_ipv6: Option<Ipv6Addr>,
     pub bootnodes: Bootnodes,
     pub data_radius: Distance,
     pub internal_ip: bool,
     pub no_stun: bool,
+    /// STUN server used to determine the external address of an IPv6 listen address, if any.
+    pub ipv6_stun_server: Option<String>,
     pub node_addr_cache_capacity: usize,
+    /// Overlay parameters set for each subnetwork, keyed by subnetwork name.
+    pub overlay_tuning: HashMap<String, OverlayTuning>,
+    /// Limits on the resources spent serving inbound requests.
+    pub rate_limits: RateLimitConfig,
+    /// Beacon block root that the beacon light client is bootstrapped from, if any.
+    pub trusted_block_root: Option<H256>,
 }
 
 impl Default for PortalnetConfig {
@@ -29,11 +45,17 @@ impl Default for PortalnetConfig {
             external_addr: None,
             private_key: H256::random(),
             listen_port: 4242,
+            listen_ipv4: Some(Ipv4Addr::UNSPECIFIED),
+            listen_ipv6: None,
             bootnodes: Bootnodes::default(),
             data_radius: Distance::MAX,
             internal_ip: false,
             no_stun: false,
+            ipv6_stun_server: Some(DEFAULT_IPV6_STUN_SERVER.to_string()),
             node_addr_cache_capacity: NODE_ADDR_CACHE_CAPACITY,
+            overlay_tuning: HashMap::new(),
+            rate_limits: RateLimitConfig::default(),
+            trusted_block_root: None,
         }
     }
 }
@@ -44,9 +66,47 @@ impl PortalnetConfig {
             external_addr: trin_config.external_addr,
             private_key,
             listen_port: trin_config.discovery_port,
+            listen_ipv4: trin_config
+                .discovery_address
+                .iter()
+                .find_map(|ip| match ip {
+                    IpAddr::V4(ip) => Some(*ip),
+                    IpAddr::V6(_) => None,
+                }),
+            listen_ipv6: trin_config
+                .discovery_address
+                .iter()
+                .find_map(|ip| match ip {
+                    IpAddr::V4(_) => None,
+                    IpAddr::V6(ip) => Some(*ip),
+                }),
             no_stun: trin_config.no_stun,
+            ipv6_stun_server: (!trin_config.no_ipv6_stun)
+                .then(|| trin_config.ipv6_stun_server.clone()),
             bootnodes: trin_config.bootnodes.clone(),
+            overlay_tuning: trin_config
+                .networks
+                .iter()
+                .map(|network| (network.clone(), trin_config.overlay_tuning(network)))
+                .collect(),
+            rate_limits: RateLimitConfig {
+                max_peer_requests_per_sec: trin_config.max_peer_requests_per_sec,
+                max_requests_per_sec: trin_config.max_requests_per_sec,
+                max_peer_utp_streams: trin_config.max_peer_utp_streams,
+                max_utp_streams: trin_config.max_utp_streams,
+                max_peer_bytes_per_min: trin_config.max_peer_bytes_per_min,
+                max_bytes_per_min: trin_config.max_bytes_per_min,
+            },
+            trusted_block_root: trin_config.trusted_block_root,
             ..Default::default()
         }
     }
+
+    /// Returns the overlay parameters set for the subnetwork named `network`.
+    pub fn overlay_tuning(&self, network: &str) -> OverlayTuning {
+        self.overlay_tuning
+            .get(network)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
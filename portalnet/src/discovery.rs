@@ -1,7 +1,8 @@
 use std::hash::{Hash, Hasher};
-use std::net::Ipv4Addr;
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use std::{convert::TryFrom, fmt, fs, io, net::SocketAddr, sync::Arc};
 
 use anyhow::anyhow;
@@ -21,7 +22,9 @@ use utp_rs::{cid::ConnectionPeer, udp::AsyncUdpSocket};
 
 use super::config::PortalnetConfig;
 use super::types::messages::ProtocolId;
+use crate::rate_limit::RateLimiter;
 use crate::socket;
+use crate::utp::UtpReadLimits;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::utils::bytes::hex_encode;
 use ethportal_api::NodeInfo;
@@ -47,62 +50,107 @@ pub struct NodeAddress {
     pub socket_addr: SocketAddr,
 }
 
+/// Delivers TALKREQ messages in place of the discv5 service.
+///
+/// Used to run overlay networks over a transport other than UDP, such as the in-memory network of
+/// the simulator.
+#[async_trait]
+pub trait TalkReqTransport: Send + Sync {
+    /// Sends a TALKREQ message to `enr` and waits for its TALKRESP.
+    async fn send_talk_req(
+        &self,
+        enr: Enr,
+        protocol: ProtocolId,
+        request: ProtocolRequest,
+    ) -> Result<Vec<u8>, RequestError>;
+}
+
 /// Base Node Discovery Protocol v5 layer
 pub struct Discovery {
     /// The inner Discv5 service.
     discv5: Discv5,
     /// A cache of the latest observed `NodeAddress` for a node ID.
     node_addr_cache: Arc<RwLock<LruCache<NodeId, NodeAddress>>>,
+    /// Limits the resources spent serving inbound requests, shared by all overlay networks.
+    rate_limiter: Arc<RateLimiter>,
+    /// Limits the data peers send over inbound uTP connections, shared by the uTP socket and all
+    /// overlay networks.
+    utp_read_limits: UtpReadLimits,
+    /// Transport that replaces discv5 for outgoing TALKREQ messages, if set.
+    talk_req_transport: Option<Arc<dyn TalkReqTransport>>,
     /// Indicates if the Discv5 service has been started.
     pub started: bool,
-    /// The socket address that the Discv5 service listens on.
-    pub listen_socket: SocketAddr,
+    /// The socket addresses that the Discv5 service listens on, one per IP family.
+    pub listen_sockets: Vec<SocketAddr>,
 }
 
 impl fmt::Debug for Discovery {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Discovery: ( enr: {}, started: {}, listen_socket: {} )",
+            "Discovery: ( enr: {}, started: {}, listen_sockets: {:?} )",
             self.discv5.local_enr(),
             self.started,
-            self.listen_socket
+            self.listen_sockets
         )
     }
 }
 
 impl Discovery {
     pub fn new(portal_config: PortalnetConfig, node_data_dir: PathBuf) -> Result<Self, String> {
-        let listen_all_ips = SocketAddr::new(
-            "0.0.0.0"
-                .parse()
-                .expect("Parsing static socket address to work"),
-            portal_config.listen_port,
-        );
-
-        let (enr_address, enr_port) = if portal_config.no_stun {
-            (None, portal_config.listen_port)
-        } else {
-            let known_external = portal_config
-                .external_addr
-                .or_else(|| socket::stun_for_external(&listen_all_ips));
-
-            match known_external {
-                Some(socket) => (Some(socket.ip()), socket.port()),
-                None => (None, portal_config.listen_port),
-            }
+        let listen_config = match (portal_config.listen_ipv4, portal_config.listen_ipv6) {
+            (Some(ip), None) => ListenConfig::Ipv4 {
+                ip,
+                port: portal_config.listen_port,
+            },
+            (None, Some(ip)) => ListenConfig::Ipv6 {
+                ip,
+                port: portal_config.listen_port,
+            },
+            (Some(ipv4), Some(ipv6)) => ListenConfig::DualStack {
+                ipv4,
+                ipv4_port: portal_config.listen_port,
+                ipv6,
+                ipv6_port: portal_config.listen_port,
+            },
+            (None, None) => return Err("No discovery listen address configured".to_owned()),
         };
-
+        let listen_sockets: Vec<SocketAddr> = [
+            portal_config.listen_ipv4.map(IpAddr::V4),
+            portal_config.listen_ipv6.map(IpAddr::V6),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|ip| SocketAddr::new(ip, portal_config.listen_port))
+        .collect();
+
+        if let Some(external_addr) = portal_config.external_addr {
+            if !listen_sockets
+                .iter()
+                .any(|socket| socket.is_ipv4() == external_addr.is_ipv4())
+            {
+                warn!(
+                    %external_addr,
+                    "External address does not match the IP family of any discovery listen address, ignoring it"
+                );
+            }
+        }
         let enr_key =
             CombinedKey::secp256k1_from_bytes(portal_config.private_key.0.clone().as_mut_slice())
                 .map_err(|e| format!("Unable to create enr key: {:?}", e.to_string()))?;
 
         let mut enr = {
             let mut builder = EnrBuilder::new("v4");
-            if let Some(ip_address) = enr_address {
-                builder.ip(ip_address);
+            for listen_socket in listen_sockets.iter() {
+                let (enr_address, enr_port) = enr_socket(&portal_config, listen_socket);
+                if let Some(ip_address) = enr_address {
+                    builder.ip(ip_address);
+                }
+                match listen_socket {
+                    SocketAddr::V4(_) => builder.udp4(enr_port),
+                    SocketAddr::V6(_) => builder.udp6(enr_port),
+                };
             }
-            builder.udp4(enr_port);
 
             let trin_version = get_trin_version();
             // Use "t" as short-hand for "Trin" to save bytes in ENR.
@@ -139,11 +187,6 @@ impl Discovery {
             fs::write(trin_enr_path, enr.to_base64()).expect("Unable to write Trin Enr to file");
         }
 
-        let listen_config = ListenConfig::Ipv4 {
-            ip: Ipv4Addr::UNSPECIFIED,
-            port: portal_config.listen_port,
-        };
-
         let discv5_config = ConfigBuilder::new(listen_config).build();
         let discv5 = Discv5::new(enr, enr_key, discv5_config)
             .map_err(|e| format!("Failed to create discv5 instance: {e}"))?;
@@ -161,15 +204,25 @@ impl Discovery {
 
         let node_addr_cache = LruCache::new(portal_config.node_addr_cache_capacity);
         let node_addr_cache = Arc::new(RwLock::new(node_addr_cache));
+        let rate_limiter = Arc::new(RateLimiter::new(portal_config.rate_limits.clone()));
 
         Ok(Self {
             discv5,
             node_addr_cache,
+            rate_limiter,
+            utp_read_limits: UtpReadLimits::default(),
+            talk_req_transport: None,
             started: false,
-            listen_socket: listen_all_ips,
+            listen_sockets,
         })
     }
 
+    /// Sends outgoing TALKREQ messages over `transport` instead of discv5.
+    pub fn with_talk_req_transport(mut self, transport: Arc<dyn TalkReqTransport>) -> Self {
+        self.talk_req_transport = Some(transport);
+        self
+    }
+
     pub async fn start(&mut self) -> Result<mpsc::Receiver<TalkRequest>, String> {
         info!(
             enr.encoded = ?self.local_enr(),
@@ -245,7 +298,9 @@ impl Discovery {
                 .discv5
                 .local_enr()
                 .ip4()
-                .map_or(Some("None".to_owned()), |ip| Some(ip.to_string())),
+                .map(|ip| ip.to_string())
+                .or_else(|| self.discv5.local_enr().ip6().map(|ip| ip.to_string()))
+                .or(Some("None".to_owned())),
         })
     }
 
@@ -292,6 +347,34 @@ impl Discovery {
         self.discv5.add_enr(enr)
     }
 
+    /// Removes `node_id` from the discv5 routing table. Returns `true` if the node was present.
+    pub fn remove_node(&self, node_id: &NodeId) -> bool {
+        self.discv5.remove_node(node_id)
+    }
+
+    /// Bans `node_id` for `duration`, or permanently if `None`, and drops it from the discv5
+    /// routing table.
+    pub fn ban_node(&self, node_id: &NodeId, duration: Option<Duration>) {
+        self.discv5
+            .ban_node(node_id, duration.map(|duration| Instant::now() + duration));
+        self.discv5.remove_node(node_id);
+    }
+
+    /// Lifts a ban on `node_id`.
+    pub fn unban_node(&self, node_id: &NodeId) {
+        self.discv5.ban_node_remove(node_id);
+    }
+
+    /// Returns the rate limiter for inbound requests.
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    /// Returns the limits on the data peers send over inbound uTP connections.
+    pub fn utp_read_limits(&self) -> &UtpReadLimits {
+        &self.utp_read_limits
+    }
+
     /// Returns the cached `NodeAddress` or `None` if not cached.
     pub fn cached_node_addr(&self, node_id: &NodeId) -> Option<NodeAddress> {
         self.node_addr_cache.write().get(node_id).cloned()
@@ -312,6 +395,10 @@ impl Discovery {
         protocol: ProtocolId,
         request: ProtocolRequest,
     ) -> Result<Vec<u8>, RequestError> {
+        if let Some(transport) = &self.talk_req_transport {
+            return transport.send_talk_req(enr, protocol, request).await;
+        }
+
         // Send empty protocol id if unable to convert it to bytes
         let protocol = Vec::try_from(protocol).unwrap_or_default();
 
@@ -392,38 +479,72 @@ impl AsyncUdpSocket<UtpEnr> for Discv5UdpSocket {
     }
 
     async fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<(usize, UtpEnr)> {
-        match self.talk_reqs.recv().await {
-            Some(talk_req) => {
-                let src_node_id = talk_req.node_id();
-                let enr = match self.discv5.find_enr(src_node_id) {
-                    Some(enr) => UtpEnr(enr),
-                    None => {
-                        let enr = match self.discv5.cached_node_addr(src_node_id) {
-                            Some(node_addr) => Ok(node_addr.enr),
-                            None => {
-                                warn!(node_id = %src_node_id, "uTP packet from unknown source");
-                                Err(io::Error::new(
-                                    io::ErrorKind::Other,
-                                    "ENR not found for talk req destination",
-                                ))
-                            }
-                        }?;
-                        UtpEnr(enr)
-                    }
-                };
-                let packet = talk_req.body();
-                let n = std::cmp::min(buf.len(), packet.len());
-                buf[..n].copy_from_slice(&packet[..n]);
-
-                // respond with empty talk response
+        while let Some(talk_req) = self.talk_reqs.recv().await {
+            let src_node_id = talk_req.node_id();
+            // Packets of a connection that went over its limit never reach utp-rs.
+            if !self
+                .discv5
+                .utp_read_limits()
+                .admit(*src_node_id, talk_req.body())
+            {
                 if let Err(err) = talk_req.respond(vec![]) {
                     warn!(%err, "failed to respond to uTP talk request");
                 }
-
-                Ok((n, enr))
+                continue;
+            }
+            let enr = match self.discv5.find_enr(src_node_id) {
+                Some(enr) => UtpEnr(enr),
+                None => {
+                    let enr = match self.discv5.cached_node_addr(src_node_id) {
+                        Some(node_addr) => Ok(node_addr.enr),
+                        None => {
+                            warn!(node_id = %src_node_id, "uTP packet from unknown source");
+                            Err(io::Error::new(
+                                io::ErrorKind::Other,
+                                "ENR not found for talk req destination",
+                            ))
+                        }
+                    }?;
+                    UtpEnr(enr)
+                }
+            };
+            let packet = talk_req.body();
+            let n = std::cmp::min(buf.len(), packet.len());
+            buf[..n].copy_from_slice(&packet[..n]);
+
+            // respond with empty talk response
+            if let Err(err) = talk_req.respond(vec![]) {
+                warn!(%err, "failed to respond to uTP talk request");
             }
-            None => Err(io::Error::from(io::ErrorKind::NotConnected)),
+
+            return Ok((n, enr));
         }
+        Err(io::Error::from(io::ErrorKind::NotConnected))
+    }
+}
+
+/// Returns the IP address and UDP port to advertise in the ENR for a discovery listen socket.
+///
+/// A manually configured external address is used only for the listen socket of the same IP
+/// family; otherwise the address is resolved via STUN, unless STUN is disabled. Without an
+/// external address, the ENR only advertises the listen port.
+fn enr_socket(
+    portal_config: &PortalnetConfig,
+    listen_socket: &SocketAddr,
+) -> (Option<IpAddr>, u16) {
+    if portal_config.no_stun {
+        return (None, listen_socket.port());
+    }
+    let known_external = portal_config
+        .external_addr
+        .filter(|external_addr| external_addr.is_ipv4() == listen_socket.is_ipv4())
+        .or_else(|| {
+            socket::stun_for_external(listen_socket, portal_config.ipv6_stun_server.as_deref())
+        });
+
+    match known_external {
+        Some(socket) => (Some(socket.ip()), socket.port()),
+        None => (None, listen_socket.port()),
     }
 }
 
@@ -500,4 +621,89 @@ mod tests {
         assert_eq!(old_enr.seq(), 2);
         assert_eq!(discovery.local_enr(), old_enr);
     }
+
+    #[test]
+    fn test_dual_stack_enr() {
+        let trin_data_dir = configure_trin_data_dir(true).unwrap();
+        let (node_data_dir, private_key) = configure_node_data_dir(trin_data_dir, None).unwrap();
+
+        let portalnet_config = PortalnetConfig {
+            private_key,
+            bootnodes: Bootnodes::None,
+            listen_ipv4: Some(std::net::Ipv4Addr::UNSPECIFIED),
+            listen_ipv6: Some(std::net::Ipv6Addr::UNSPECIFIED),
+            no_stun: true,
+            ..Default::default()
+        };
+        let listen_port = portalnet_config.listen_port;
+
+        let discovery = Discovery::new(portalnet_config, node_data_dir).unwrap();
+        let enr = discovery.local_enr();
+        assert_eq!(enr.udp4(), Some(listen_port));
+        assert_eq!(enr.udp6(), Some(listen_port));
+        assert_eq!(discovery.listen_sockets.len(), 2);
+    }
+
+    #[test]
+    fn test_ipv6_only_enr() {
+        let trin_data_dir = configure_trin_data_dir(true).unwrap();
+        let (node_data_dir, private_key) = configure_node_data_dir(trin_data_dir, None).unwrap();
+
+        let external_addr: SocketAddr = "[2001:db8::1]:9009".parse().unwrap();
+        let portalnet_config = PortalnetConfig {
+            private_key,
+            bootnodes: Bootnodes::None,
+            listen_ipv4: None,
+            listen_ipv6: Some(std::net::Ipv6Addr::UNSPECIFIED),
+            external_addr: Some(external_addr),
+            ..Default::default()
+        };
+
+        let discovery = Discovery::new(portalnet_config, node_data_dir).unwrap();
+        let enr = discovery.local_enr();
+        assert_eq!(enr.ip4(), None);
+        assert_eq!(enr.udp4(), None);
+        assert_eq!(enr.ip6(), Some("2001:db8::1".parse().unwrap()));
+        assert_eq!(enr.udp6(), Some(9009));
+        assert_eq!(discovery.listen_sockets.len(), 1);
+    }
+
+    #[test]
+    fn test_enr_socket_fallback() {
+        let ipv4_external: SocketAddr = "203.0.113.1:9009".parse().unwrap();
+        let ipv6_external: SocketAddr = "[2001:db8::1]:9010".parse().unwrap();
+        let ipv4_listen: SocketAddr = "0.0.0.0:9000".parse().unwrap();
+        let ipv6_listen: SocketAddr = "[::]:9000".parse().unwrap();
+
+        // The external address is used for the listen socket of the same IP family
+        let config = PortalnetConfig {
+            external_addr: Some(ipv4_external),
+            ipv6_stun_server: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            enr_socket(&config, &ipv4_listen),
+            (Some(ipv4_external.ip()), 9009)
+        );
+        // An IPv6 listen socket falls back to STUN, and to the listen port without it
+        assert_eq!(enr_socket(&config, &ipv6_listen), (None, 9000));
+
+        let config = PortalnetConfig {
+            external_addr: Some(ipv6_external),
+            ipv6_stun_server: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            enr_socket(&config, &ipv6_listen),
+            (Some(ipv6_external.ip()), 9010)
+        );
+
+        // Disabling STUN leaves the address blank, even if an external address is set
+        let config = PortalnetConfig {
+            external_addr: Some(ipv6_external),
+            no_stun: true,
+            ..Default::default()
+        };
+        assert_eq!(enr_socket(&config, &ipv6_listen), (None, 9000));
+    }
 }
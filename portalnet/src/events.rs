@@ -1,23 +1,21 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use discv5::TalkRequest;
+use discv5::{enr::NodeId, TalkRequest};
 use tokio::sync::mpsc;
 use tracing::{error, warn};
 
 use super::types::messages::ProtocolId;
 use ethportal_api::utils::bytes::{hex_encode, hex_encode_upper};
+use ethportal_api::RawContentKey;
 
 /// Main handler for portal network events
 pub struct PortalnetEvents {
     /// Receive Discv5 talk requests.
     pub talk_req_receiver: mpsc::Receiver<TalkRequest>,
-    /// Send overlay `TalkReq` to history network
-    pub history_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
-    /// Send overlay `TalkReq` to state network
-    pub state_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
-    /// Send overlay `TalkReq` to beacon network
-    pub beacon_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
+    /// Send overlay `TalkReq` to the subnetwork registered for its protocol
+    pub overlay_senders: HashMap<ProtocolId, mpsc::UnboundedSender<TalkRequest>>,
     /// Send TalkReq events with "utp" protocol id to `UtpListener`
     pub utp_talk_reqs: mpsc::UnboundedSender<TalkRequest>,
 }
@@ -25,16 +23,12 @@ pub struct PortalnetEvents {
 impl PortalnetEvents {
     pub async fn new(
         talk_req_receiver: mpsc::Receiver<TalkRequest>,
-        history_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
-        state_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
-        beacon_overlay_sender: Option<mpsc::UnboundedSender<TalkRequest>>,
+        overlay_senders: HashMap<ProtocolId, mpsc::UnboundedSender<TalkRequest>>,
         utp_talk_reqs: mpsc::UnboundedSender<TalkRequest>,
     ) -> Self {
         Self {
             talk_req_receiver,
-            history_overlay_sender,
-            state_overlay_sender,
-            beacon_overlay_sender,
+            overlay_senders,
             utp_talk_reqs,
         }
     }
@@ -51,56 +45,29 @@ impl PortalnetEvents {
         let protocol_id = ProtocolId::from_str(&hex_encode_upper(request.protocol()));
 
         match protocol_id {
-            Ok(protocol) => match protocol {
-                ProtocolId::History => {
-                    match &self.history_overlay_sender {
-                        Some(tx) => {
-                            if let Err(err) = tx.send(request) {
-                                error!(
-                                    "Error sending discv5 talk request to history network: {err}"
-                                );
-                            }
-                        }
-                        None => error!("History event handler not initialized!"),
-                    };
+            Ok(ProtocolId::Utp) => {
+                if let Err(err) = self.utp_talk_reqs.send(request) {
+                    error!(%err, "Error forwarding talk request to uTP socket");
                 }
-                ProtocolId::Beacon => {
-                    match &self.beacon_overlay_sender {
-                        Some(tx) => {
-                            if let Err(err) = tx.send(request) {
-                                error!(
-                                    "Error sending discv5 talk request to beacon network: {err}"
-                                );
-                            }
-                        }
-                        None => error!("Beacon event handler not initialized!"),
-                    };
-                }
-                ProtocolId::State => {
-                    match &self.state_overlay_sender {
-                        Some(tx) => {
-                            if let Err(err) = tx.send(request) {
-                                error!("Error sending discv5 talk request to state network: {err}");
-                            }
-                        }
-                        None => error!("State event handler not initialized!"),
-                    };
-                }
-                ProtocolId::Utp => {
-                    if let Err(err) = self.utp_talk_reqs.send(request) {
-                        error!(%err, "Error forwarding talk request to uTP socket");
+            }
+            Ok(protocol) => match self.overlay_senders.get(&protocol) {
+                Some(tx) => {
+                    if let Err(err) = tx.send(request) {
+                        error!("Error sending discv5 talk request to {protocol} network: {err}");
                     }
                 }
-                _ => {
-                    warn!(
-                        "Received TalkRequest on unknown protocol from={} protocol={} body={}",
-                        request.node_id(),
-                        hex_encode_upper(request.protocol()),
-                        hex_encode(request.body()),
-                    );
-                }
+                None => warn!(
+                    "Received TalkRequest on inactive protocol from={} protocol={protocol} body={}",
+                    request.node_id(),
+                    hex_encode(request.body()),
+                ),
             },
-            Err(_) => warn!("Unable to decode protocol id"),
+            Err(_) => warn!(
+                "Received TalkRequest on unknown protocol from={} protocol={} body={}",
+                request.node_id(),
+                hex_encode_upper(request.protocol()),
+                hex_encode(request.body()),
+            ),
         }
     }
 }
@@ -110,6 +77,21 @@ impl PortalnetEvents {
 pub enum OverlayEvent {
     LightClientOptimisticUpdate,
     LightClientFinalityUpdate,
+    /// Content was received from a peer, either offered or found in a lookup, and it passed
+    /// validation.
+    ContentReceived {
+        content_key: RawContentKey,
+        content_value: Vec<u8>,
+    },
+    /// Some of the content keys offered by a peer were accepted.
+    OfferAccepted {
+        source: NodeId,
+        content_keys: Vec<RawContentKey>,
+    },
+    /// A node was added to the routing table.
+    NodeAdded {
+        node_id: NodeId,
+    },
 }
 
 /// Timestamp of an overlay event.
@@ -41,6 +41,8 @@ pub enum FindContentQueryResult<TNodeId> {
     Content {
         content: Vec<u8>,
         nodes_to_poke: Vec<TNodeId>,
+        // peer that returned the content
+        peer: TNodeId,
     },
     Utp {
         connection_id: u16,
@@ -323,6 +325,7 @@ where
                     FindContentQueryResult::Content {
                         content: val.content,
                         nodes_to_poke,
+                        peer: val.peer,
                     }
                 }
                 ContentAndPeer::Utp(val) => {
@@ -608,12 +611,14 @@ mod tests {
                 FindContentQueryResult::Content {
                     content,
                     nodes_to_poke,
+                    peer,
                 } => {
                     let nodes_to_poke =
                         nodes_to_poke.into_iter().map(Key::from).collect::<Vec<_>>();
                     assert!(sorted(&target_key, &nodes_to_poke));
 
                     let content_peer = content_peer.unwrap();
+                    assert_eq!(Key::from(peer), content_peer);
 
                     // The peer who returned the content should not be included in the poke nodes
                     assert!(!nodes_to_poke.contains(&content_peer));
@@ -1,3 +1,4 @@
 pub mod iterators;
 pub mod query_info;
 pub mod query_pool;
+pub mod recent_traces;
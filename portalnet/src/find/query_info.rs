@@ -18,9 +18,15 @@ pub struct QueryInfo<TContentKey> {
     /// Temporary ENRs used when trying to reach nodes.
     pub untrusted_enrs: SmallVec<[Enr; 16]>,
 
+    /// The trace of the query. Always present for content queries, and present for find node
+    /// queries if the trace was requested.
     pub trace: Option<QueryTrace>,
 }
 
+// ENRs, trace
+// The trace is only present if it was requested.
+pub type RecursiveFindNodesResult = (Vec<Enr>, Option<QueryTrace>);
+
 // Content, utp_transfer, trace
 // Content is Option<Vec<u8>> because it can be None if the content is not found
 // in a recursive find content query.
@@ -41,7 +47,7 @@ pub enum QueryType<TContentKey> {
         target: NodeId,
 
         /// A callback channel for the result of the query.
-        callback: Option<oneshot::Sender<Vec<Enr>>>,
+        callback: Option<oneshot::Sender<RecursiveFindNodesResult>>,
 
         /// The number of distances we request for each peer.
         distances_to_request: usize,
@@ -53,6 +59,13 @@ pub enum QueryType<TContentKey> {
 
         /// A callback channel for the result of the query.
         callback: Option<oneshot::Sender<RecursiveFindContentResult>>,
+
+        /// Whether the trace of the query is returned with the result.
+        is_trace: bool,
+
+        /// The callbacks of lookups for the same content that were started while this query was
+        /// in progress, and whether each of them asked for the trace of the query.
+        coalesced_callbacks: Vec<(oneshot::Sender<RecursiveFindContentResult>, bool)>,
     },
 }
 
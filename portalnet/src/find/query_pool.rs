@@ -84,11 +84,28 @@ where
         }
     }
 
+    /// Returns the duration after which a query times out.
+    pub fn query_timeout(&self) -> Duration {
+        self.query_timeout
+    }
+
+    /// Sets the duration after which a query times out, including the queries in the pool.
+    pub fn set_query_timeout(&mut self, query_timeout: Duration) {
+        self.query_timeout = query_timeout;
+    }
+
     /// Returns an iterator over the queries in the pool.
     pub fn iter(&self) -> impl Iterator<Item = &(QueryInfo<TContentKey>, TQuery)> {
         self.queries.values()
     }
 
+    /// Returns a mutable iterator over the queries in the pool, along with their IDs.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (QueryId, &mut (QueryInfo<TContentKey>, TQuery))> {
+        self.queries.iter_mut().map(|(id, query)| (*id, query))
+    }
+
     /// Adds a query to the pool.
     pub fn add_query(&mut self, query_info: QueryInfo<TContentKey>, query: TQuery) -> QueryId {
         let id = self.next_id;
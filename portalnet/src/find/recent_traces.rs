@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use ethportal_api::types::query_trace::QueryTrace;
+
+/// Number of completed query traces that are kept for inspection.
+pub const RECENT_QUERY_TRACES_CAPACITY: usize = 32;
+
+/// Traces of the most recently completed content queries, newest last.
+///
+/// Every content query is traced, whether or not the caller asked for the trace, so that lookups
+/// can be inspected after the fact.
+#[derive(Debug)]
+pub struct RecentQueryTraces {
+    traces: VecDeque<QueryTrace>,
+    capacity: usize,
+}
+
+impl Default for RecentQueryTraces {
+    fn default() -> Self {
+        Self::new(RECENT_QUERY_TRACES_CAPACITY)
+    }
+}
+
+impl RecentQueryTraces {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            traces: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds the trace of a completed query, evicting the oldest trace when full.
+    pub fn push(&mut self, trace: QueryTrace) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.traces.len() == self.capacity {
+            self.traces.pop_front();
+        }
+        self.traces.push_back(trace);
+    }
+
+    /// Returns up to `limit` of the most recently completed traces, newest first.
+    pub fn latest(&self, limit: usize) -> Vec<QueryTrace> {
+        self.traces.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use ethportal_api::types::enr::generate_random_remote_enr;
+
+    fn trace() -> QueryTrace {
+        let (_, enr) = generate_random_remote_enr();
+        QueryTrace::new(&enr, enr.node_id().into())
+    }
+
+    #[test]
+    fn evicts_oldest_trace() {
+        let mut traces = RecentQueryTraces::new(2);
+        let (first, second, third) = (trace(), trace(), trace());
+        traces.push(first);
+        traces.push(second.clone());
+        traces.push(third.clone());
+
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces.latest(10), vec![third.clone(), second]);
+        assert_eq!(traces.latest(1), vec![third]);
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use discv5::enr::NodeId;
+use lru::LruCache;
+use tokio::time::Instant;
+
+use ethportal_api::RawContentKey;
+
+/// Default number of the interested peers closest to the content that are offered the content.
+pub const DEFAULT_NUM_CLOSEST_NODES: usize = 4;
+/// Default number of randomly selected, farther interested peers that are offered the content.
+pub const DEFAULT_NUM_FARTHER_NODES: usize = 4;
+/// Default maximum number of lookups of the nodes closest to the content, for a single batch of
+/// gossiped content.
+pub const DEFAULT_MAX_GOSSIP_LOOKUPS: usize = 4;
+/// Default number of offer responses remembered by an `OfferHistory`.
+pub const DEFAULT_OFFER_HISTORY_CAPACITY: usize = 4096;
+/// Default time after which a declined offer is forgotten. A peer also declines content that it
+/// cannot accept at the moment, eg. when it is rate limited, so the content is offered again later.
+pub const DEFAULT_DECLINE_EXPIRY: Duration = Duration::from_secs(10 * 60);
+
+/// Configuration of how content is gossiped to interested peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GossipConfig {
+    /// Number of the interested peers closest to the content that are offered the content.
+    pub num_closest_nodes: usize,
+    /// Number of peers, randomly selected from the remaining interested peers, that are offered
+    /// the content.
+    pub num_farther_nodes: usize,
+    /// If fewer peers in the routing table are interested in the content, the nodes closest to
+    /// the content are looked up before the content is offered.
+    pub min_interested_nodes: usize,
+    /// Maximum number of those lookups for a single batch of gossiped content. The content that
+    /// the fewest peers are interested in is looked up first.
+    pub max_lookups: usize,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            num_closest_nodes: DEFAULT_NUM_CLOSEST_NODES,
+            num_farther_nodes: DEFAULT_NUM_FARTHER_NODES,
+            min_interested_nodes: DEFAULT_NUM_CLOSEST_NODES,
+            max_lookups: DEFAULT_MAX_GOSSIP_LOOKUPS,
+        }
+    }
+}
+
+/// Remembers which peers accepted or declined offered content, so that the content is not
+/// offered to them again. The least recently recorded responses are forgotten first, and declines
+/// are forgotten once they expire.
+#[derive(Debug)]
+pub struct OfferHistory {
+    /// Whether each offer was accepted, and when the response was recorded.
+    responses: LruCache<(RawContentKey, NodeId), (bool, Instant)>,
+    decline_expiry: Duration,
+}
+
+impl OfferHistory {
+    pub fn new(capacity: usize, decline_expiry: Duration) -> Self {
+        Self {
+            responses: LruCache::new(capacity),
+            decline_expiry,
+        }
+    }
+
+    /// Records whether `node_id` accepted `content_key`.
+    pub fn record(&mut self, content_key: RawContentKey, node_id: NodeId, accepted: bool) {
+        self.responses
+            .put((content_key, node_id), (accepted, Instant::now()));
+    }
+
+    /// Returns whether `node_id` accepted `content_key`, or `None` if it was never offered the
+    /// content, or declined it longer ago than the decline expiry.
+    pub fn response(&self, content_key: &RawContentKey, node_id: &NodeId) -> Option<bool> {
+        match self.responses.peek(&(content_key.clone(), *node_id)) {
+            Some((false, recorded_at)) if recorded_at.elapsed() >= self.decline_expiry => None,
+            Some((accepted, _)) => Some(*accepted),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if `node_id` already accepted or declined `content_key`.
+    pub fn was_offered(&self, content_key: &RawContentKey, node_id: &NodeId) -> bool {
+        self.response(content_key, node_id).is_some()
+    }
+}
+
+impl Default for OfferHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_OFFER_HISTORY_CAPACITY, DEFAULT_DECLINE_EXPIRY)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forgets_oldest_response() {
+        let mut history = OfferHistory::new(2, DEFAULT_DECLINE_EXPIRY);
+        let node_id = NodeId::random();
+        let content_keys: Vec<RawContentKey> = vec![vec![0x01], vec![0x02], vec![0x03]];
+
+        history.record(content_keys[0].clone(), node_id, true);
+        history.record(content_keys[1].clone(), node_id, false);
+        assert_eq!(history.response(&content_keys[0], &node_id), Some(true));
+        assert_eq!(history.response(&content_keys[1], &node_id), Some(false));
+        assert!(!history.was_offered(&content_keys[0], &NodeId::random()));
+
+        history.record(content_keys[2].clone(), node_id, true);
+        assert!(!history.was_offered(&content_keys[0], &node_id));
+        assert!(history.was_offered(&content_keys[2], &node_id));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn forgets_expired_decline() {
+        let mut history = OfferHistory::default();
+        let node_id = NodeId::random();
+        let content_keys: Vec<RawContentKey> = vec![vec![0x01], vec![0x02]];
+
+        history.record(content_keys[0].clone(), node_id, true);
+        history.record(content_keys[1].clone(), node_id, false);
+        tokio::time::advance(DEFAULT_DECLINE_EXPIRY).await;
+
+        assert_eq!(history.response(&content_keys[0], &node_id), Some(true));
+        assert!(!history.was_offered(&content_keys[1], &node_id));
+    }
+}
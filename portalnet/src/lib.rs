@@ -4,10 +4,19 @@ pub mod config;
 pub mod discovery;
 pub mod events;
 pub mod find;
+pub mod gossip;
 pub mod metrics;
 pub mod overlay;
 mod overlay_service;
+pub mod peer_score;
+pub mod rate_limit;
+pub mod routing_table;
+pub mod shutdown;
+#[cfg(any(test, feature = "simulator"))]
+pub mod simulator;
 pub mod socket;
 pub mod storage;
+pub mod subnetwork;
 pub mod types;
 pub mod utils;
+pub mod utp;
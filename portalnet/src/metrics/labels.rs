@@ -1,3 +1,4 @@
+use crate::rate_limit::RateLimitExceeded;
 use crate::types::messages::{ProtocolId, Request, Response};
 
 pub type MetricLabel = &'static str;
@@ -57,6 +58,19 @@ impl From<UtpOutcomeLabel> for MetricLabel {
     }
 }
 
+impl From<RateLimitExceeded> for MetricLabel {
+    fn from(limit: RateLimitExceeded) -> Self {
+        match limit {
+            RateLimitExceeded::PeerRequests => "peer_requests",
+            RateLimitExceeded::Requests => "requests",
+            RateLimitExceeded::PeerUtpStreams => "peer_utp_streams",
+            RateLimitExceeded::UtpStreams => "utp_streams",
+            RateLimitExceeded::PeerBytes => "peer_bytes",
+            RateLimitExceeded::Bytes => "bytes",
+        }
+    }
+}
+
 impl From<&Request> for MessageLabel {
     fn from(request: &Request) -> Self {
         match request {
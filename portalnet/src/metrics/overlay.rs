@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use prometheus_exporter::{
     self,
     prometheus::{
-        opts, register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry,
+        exponential_buckets, histogram_opts, opts, register_histogram_vec_with_registry,
+        register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, HistogramVec,
         IntCounterVec, IntGaugeVec, Registry,
     },
 };
@@ -9,6 +12,7 @@ use prometheus_exporter::{
 use crate::metrics::labels::{
     MessageDirectionLabel, MessageLabel, UtpDirectionLabel, UtpOutcomeLabel,
 };
+use crate::rate_limit::RateLimitExceeded;
 use crate::types::messages::{Request, Response};
 
 /// Contains metrics reporters for use in the overlay network
@@ -20,7 +24,10 @@ pub struct OverlayMetrics {
     pub message_total: IntCounterVec,
     pub utp_outcome_total: IntCounterVec,
     pub utp_active_gauge: IntGaugeVec,
+    pub utp_transfer_bytes: HistogramVec,
+    pub utp_transfer_duration: HistogramVec,
     pub validation_total: IntCounterVec,
+    pub rate_limited_total: IntCounterVec,
 }
 
 impl OverlayMetrics {
@@ -49,6 +56,26 @@ impl OverlayMetrics {
             &["protocol", "direction"],
             registry
         )?;
+        let utp_transfer_bytes = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "trin_utp_transfer_bytes",
+                "size of the payload of successful utp transfers outbound and inbound",
+                // 256 bytes to 64 MiB
+                exponential_buckets(256.0, 4.0, 10)?
+            ),
+            &["protocol", "direction"],
+            registry
+        )?;
+        let utp_transfer_duration = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "trin_utp_transfer_duration_seconds",
+                "duration of successful utp transfers outbound and inbound, from connecting to closing the stream",
+                // 10 milliseconds to ~80 seconds
+                exponential_buckets(0.01, 2.0, 14)?
+            ),
+            &["protocol", "direction"],
+            registry
+        )?;
         let validation_total = register_int_counter_vec_with_registry!(
             opts!(
                 "trin_validation_total",
@@ -57,11 +84,22 @@ impl OverlayMetrics {
             &["protocol", "success"],
             registry
         )?;
+        let rate_limited_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_rate_limited_total",
+                "count all inbound requests rejected for going over a rate limit"
+            ),
+            &["protocol", "type", "limit"],
+            registry
+        )?;
         Ok(Self {
             message_total,
             utp_outcome_total,
             utp_active_gauge,
+            utp_transfer_bytes,
+            utp_transfer_duration,
             validation_total,
+            rate_limited_total,
         })
     }
 }
@@ -159,6 +197,25 @@ impl OverlayMetricsReporter {
             .dec();
     }
 
+    /// Records the size and duration of a successful uTP transfer. Retransmissions are not
+    /// recorded, because utp-rs does not expose them.
+    pub fn report_utp_transfer(
+        &self,
+        direction: UtpDirectionLabel,
+        size_bytes: usize,
+        duration: Duration,
+    ) {
+        let labels: [&str; 2] = [&self.protocol, direction.into()];
+        self.overlay_metrics
+            .utp_transfer_bytes
+            .with_label_values(&labels)
+            .observe(size_bytes as f64);
+        self.overlay_metrics
+            .utp_transfer_duration
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
     //
     // Validations
     //
@@ -181,6 +238,17 @@ impl OverlayMetricsReporter {
             .inc();
     }
 
+    //
+    // Rate limits
+    //
+    pub fn report_rate_limited(&self, message: MessageLabel, limit: RateLimitExceeded) {
+        let labels: [&str; 3] = [&self.protocol, message.into(), limit.into()];
+        self.overlay_metrics
+            .rate_limited_total
+            .with_label_values(&labels)
+            .inc();
+    }
+
     pub fn get_utp_summary(&self) -> String {
         let inbound_success =
             self.utp_outcome_total(UtpDirectionLabel::Inbound, UtpOutcomeLabel::Success);
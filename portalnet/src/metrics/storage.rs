@@ -2,8 +2,8 @@ use ethportal_api::types::distance::Distance;
 use prometheus_exporter::{
     self,
     prometheus::{
-        opts, register_gauge_vec_with_registry, register_int_gauge_vec_with_registry, GaugeVec,
-        IntGaugeVec, Registry,
+        opts, register_gauge_vec_with_registry, register_int_counter_vec_with_registry,
+        register_int_gauge_vec_with_registry, GaugeVec, IntCounterVec, IntGaugeVec, Registry,
     },
 };
 
@@ -15,6 +15,10 @@ pub struct StorageMetrics {
     pub storage_capacity_bytes: GaugeVec,
     pub radius_ratio: GaugeVec,
     pub entry_count: IntGaugeVec,
+    pub cache_usage_bytes: GaugeVec,
+    pub cache_entry_count: IntGaugeVec,
+    pub cache_lookups: IntCounterVec,
+    pub cache_evictions: IntCounterVec,
 }
 
 const BYTES_IN_MB_F64: f64 = 1000.0 * 1000.0;
@@ -58,12 +62,45 @@ impl StorageMetrics {
             &["protocol"],
             registry
         )?;
+        let cache_usage_bytes = register_gauge_vec_with_registry!(
+            opts!(
+                "trin_cache_usage_bytes",
+                "sum of size of individual content cached from outside the radius, in bytes"
+            ),
+            &["protocol"],
+            registry
+        )?;
+        let cache_entry_count = register_int_gauge_vec_with_registry!(
+            opts!("trin_cache_entry_count", "total number of cache entries"),
+            &["protocol"],
+            registry
+        )?;
+        let cache_lookups = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_cache_lookups_total",
+                "count all cache lookups, labeled by whether the content was cached"
+            ),
+            &["protocol", "result"],
+            registry
+        )?;
+        let cache_evictions = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_cache_evictions_total",
+                "count all cache entries evicted to make room for new content"
+            ),
+            &["protocol"],
+            registry
+        )?;
         Ok(Self {
             content_storage_usage_bytes,
             total_storage_usage_bytes,
             storage_capacity_bytes,
             radius_ratio,
             entry_count,
+            cache_usage_bytes,
+            cache_entry_count,
+            cache_lookups,
+            cache_evictions,
         })
     }
 }
@@ -135,6 +172,35 @@ impl StorageMetricsReporter {
             .dec();
     }
 
+    pub fn report_cache_usage(&self, entry_count: usize, bytes: u64) {
+        let entry_count: i64 = entry_count
+            .try_into()
+            .expect("Number of cache entries will be small enough to fit in i64");
+        self.storage_metrics
+            .cache_entry_count
+            .with_label_values(&[&self.protocol])
+            .set(entry_count);
+        self.storage_metrics
+            .cache_usage_bytes
+            .with_label_values(&[&self.protocol])
+            .set(bytes as f64);
+    }
+
+    pub fn report_cache_lookup(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.storage_metrics
+            .cache_lookups
+            .with_label_values(&[&self.protocol, result])
+            .inc();
+    }
+
+    pub fn report_cache_evictions(&self, count: usize) {
+        self.storage_metrics
+            .cache_evictions
+            .with_label_values(&[&self.protocol])
+            .inc_by(count as u64);
+    }
+
     pub fn get_summary(&self) -> String {
         let radius_percent = self
             .storage_metrics
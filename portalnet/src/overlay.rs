@@ -1,49 +1,63 @@
 use anyhow::anyhow;
+use async_trait::async_trait;
 use discv5::{
     enr::NodeId,
     kbucket::{
         Entry, FailureReason, Filter, InsertResult, KBucketsTable, Key, NodeStatus,
         MAX_NODES_PER_BUCKET,
     },
+    rpc::RequestId,
     ConnectionDirection, ConnectionState, TalkRequest,
 };
-use futures::channel::oneshot;
-use parking_lot::RwLock;
+use futures::{channel::oneshot, stream, StreamExt};
+use parking_lot::{Mutex, RwLock};
+use rand::{rngs::StdRng, SeedableRng};
 use ssz::Encode;
-use std::future::Future;
 use std::{
     collections::{BTreeMap, HashSet},
     fmt::{Debug, Display},
+    future::Future,
     marker::{PhantomData, Sync},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
-use tokio::sync::mpsc;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc::{self, UnboundedSender};
 use tracing::{debug, error, info, warn};
-use utp_rs::socket::UtpSocket;
+use utp_rs::{conn::ConnectionConfig, socket::UtpSocket};
 
 use crate::{
     discovery::{Discovery, UtpEnr},
-    find::query_info::{FindContentResult, RecursiveFindContentResult},
+    find::{
+        query_info::{FindContentResult, RecursiveFindContentResult, RecursiveFindNodesResult},
+        recent_traces::RecentQueryTraces,
+    },
+    gossip::{GossipConfig, OfferHistory},
     metrics::overlay::OverlayMetricsReporter,
     metrics::portalnet::PORTALNET_METRICS,
     overlay_service::{
-        OverlayCommand, OverlayRequest, OverlayRequestError, OverlayService, RequestDirection,
-        UTP_CONN_CFG,
+        OverlayCommand, OverlayRequest, OverlayRequestError, OverlayRng, OverlayService,
+        RequestDirection, EVENT_STREAM_CHANNEL_SIZE, UTP_CONN_CFG,
     },
-    storage::ContentStore,
+    peer_score::PeerScores,
+    storage::{ContentStore, PortalStorage},
+    subnetwork::SubnetworkAdmin,
     types::{
         messages::{
             Accept, Content, CustomPayload, FindContent, FindNodes, Message, Nodes, Offer, Ping,
-            Pong, PopulatedOffer, ProtocolId, Request, Response,
+            Pong, PopulatedOffer, ProtocolId, Request, Response, MAX_CONTENT_KEYS_PER_OFFER,
         },
         node::Node,
     },
+    utp::{read_to_eof_bounded, UtpConnections},
 };
 use ethportal_api::types::bootnodes::Bootnode;
 use ethportal_api::types::distance::{Distance, Metric};
 use ethportal_api::types::enr::Enr;
+use ethportal_api::types::gossip_trace::{GossipTrace, OfferOutcome, OfferTrace};
+use ethportal_api::types::portal::{OverlayTuning, PruneInfo, SubnetworkSummary};
+use ethportal_api::types::query_trace::{QueryTermination, QueryTrace};
 use ethportal_api::utils::bytes::hex_encode;
 use ethportal_api::OverlayContentKey;
 use ethportal_api::RawContentKey;
@@ -51,6 +65,10 @@ use trin_validation::validator::Validator;
 
 use crate::events::EventEnvelope;
 
+/// The maximum number of offers to a single peer that `OverlayProtocol::offer_many` keeps in
+/// flight at once.
+const MAX_CONCURRENT_OFFERS: usize = 4;
+
 /// Configuration parameters for the overlay network.
 #[derive(Clone)]
 pub struct OverlayConfig {
@@ -65,6 +83,18 @@ pub struct OverlayConfig {
     pub query_peer_timeout: Duration,
     pub query_num_results: usize,
     pub findnodes_query_distances_per_peer: usize,
+    /// Directory the routing table is persisted to, so that it survives restarts. The routing
+    /// table is not persisted if unset.
+    pub routing_table_dir: Option<PathBuf>,
+    /// How content is gossiped to interested peers.
+    pub gossip: GossipConfig,
+    /// Settings of the uTP connections that content is transferred over.
+    pub utp_conn_config: ConnectionConfig,
+    /// Maximum number of concurrent uTP connections, inbound and outbound. 0 disables the limit.
+    pub max_utp_connections: usize,
+    /// Seed of the overlay RNG, so that runs are reproducible. The RNG is seeded from the
+    /// operating system if unset.
+    pub rng_seed: Option<u64>,
 }
 
 impl Default for OverlayConfig {
@@ -81,11 +111,72 @@ impl Default for OverlayConfig {
             query_timeout: Duration::from_secs(60),
             query_num_results: MAX_NODES_PER_BUCKET,
             findnodes_query_distances_per_peer: 3,
+            routing_table_dir: None,
+            gossip: GossipConfig::default(),
+            utp_conn_config: *UTP_CONN_CFG,
+            max_utp_connections: 0,
+            rng_seed: None,
         }
     }
 }
 
-type BucketEntry = (NodeId, Enr, NodeStatus, Distance, Option<String>);
+impl OverlayConfig {
+    /// Overrides the parameters that are set in `tuning`.
+    pub fn with_tuning(mut self, tuning: &OverlayTuning) -> Self {
+        if let Some(query_parallelism) = tuning.query_parallelism {
+            self.query_parallelism = query_parallelism;
+        }
+        if let Some(query_timeout_ms) = tuning.query_timeout_ms {
+            self.query_timeout = Duration::from_millis(query_timeout_ms);
+        }
+        if let Some(query_peer_timeout_ms) = tuning.query_peer_timeout_ms {
+            self.query_peer_timeout = Duration::from_millis(query_peer_timeout_ms);
+        }
+        if let Some(query_num_results) = tuning.query_num_results {
+            self.query_num_results = query_num_results;
+        }
+        if let Some(max_incoming_per_bucket) = tuning.max_incoming_per_bucket {
+            self.max_incoming_per_bucket = max_incoming_per_bucket;
+        }
+        if let Some(findnodes_query_distances_per_peer) = tuning.findnodes_query_distances_per_peer
+        {
+            self.findnodes_query_distances_per_peer = findnodes_query_distances_per_peer;
+        }
+        if let Some(max_packet_size) = tuning.utp_max_packet_size {
+            self.utp_conn_config.max_packet_size = max_packet_size;
+        }
+        if let Some(max_conn_attempts) = tuning.utp_max_conn_attempts {
+            self.utp_conn_config.max_conn_attempts = max_conn_attempts;
+        }
+        if let Some(idle_timeout_ms) = tuning.utp_idle_timeout_ms {
+            self.utp_conn_config.max_idle_timeout = Duration::from_millis(idle_timeout_ms);
+        }
+        if let Some(initial_timeout_ms) = tuning.utp_initial_timeout_ms {
+            self.utp_conn_config.initial_timeout = Duration::from_millis(initial_timeout_ms);
+        }
+        if let Some(target_delay_ms) = tuning.utp_target_delay_ms {
+            self.utp_conn_config.target_delay = Duration::from_millis(target_delay_ms);
+        }
+        if let Some(max_utp_connections) = tuning.utp_max_connections {
+            self.max_utp_connections = max_utp_connections;
+        }
+        if let Some(num_closest_nodes) = tuning.gossip_num_closest_nodes {
+            self.gossip.num_closest_nodes = num_closest_nodes;
+        }
+        if let Some(num_farther_nodes) = tuning.gossip_num_farther_nodes {
+            self.gossip.num_farther_nodes = num_farther_nodes;
+        }
+        if let Some(min_interested_nodes) = tuning.gossip_min_interested_nodes {
+            self.gossip.min_interested_nodes = min_interested_nodes;
+        }
+        if let Some(max_lookups) = tuning.gossip_max_lookups {
+            self.gossip.max_lookups = max_lookups;
+        }
+        self
+    }
+}
+
+type BucketEntry = (NodeId, Enr, NodeStatus, Distance, Option<String>, i32);
 
 /// Overlay protocol is a layer on top of discv5 that handles all requests from the overlay networks
 /// (state, history etc.) and dispatch them to the discv5 protocol TalkReq. Each network should
@@ -105,6 +196,19 @@ pub struct OverlayProtocol<TContentKey, TMetric, TValidator, TStore> {
     pub command_tx: UnboundedSender<OverlayCommand<TContentKey>>,
     /// uTP socket.
     utp_socket: Arc<UtpSocket<UtpEnr>>,
+    /// Reputation of the peers of the overlay, maintained by the overlay service.
+    peer_scores: Arc<RwLock<PeerScores>>,
+    /// Traces of the most recently completed content queries, recorded by the overlay service.
+    recent_traces: Arc<RwLock<RecentQueryTraces>>,
+    /// How content is gossiped to interested peers.
+    gossip_config: GossipConfig,
+    /// Which peers accepted or declined gossiped content, shared with the overlay service.
+    offer_history: Arc<RwLock<OfferHistory>>,
+    /// The bootnodes of the overlay, shared with the overlay service. Nodes added at runtime with
+    /// [`OverlayProtocol::add_bootnode`] are included.
+    bootnodes: Arc<RwLock<Vec<Enr>>>,
+    /// The source of all randomness of the overlay, shared with the overlay service.
+    rng: OverlayRng,
     /// Declare the allowed content key types for a given overlay network.
     /// Use a phantom, because we don't store any keys in this struct.
     /// For example, this type is used when decoding a content key received over the network.
@@ -115,6 +219,12 @@ pub struct OverlayProtocol<TContentKey, TMetric, TValidator, TStore> {
     validator: Arc<TValidator>,
     /// Runtime telemetry metrics for the overlay network.
     metrics: OverlayMetricsReporter,
+    /// The channel that the overlay service emits events on.
+    event_stream: broadcast::Sender<EventEnvelope>,
+    /// Maximum number of incoming connections in a routing table bucket.
+    max_incoming_per_bucket: usize,
+    /// Settings and limit of the uTP connections, shared with the overlay service.
+    utp_connections: UtpConnections,
 }
 
 impl<
@@ -134,10 +244,11 @@ where
         protocol: ProtocolId,
         validator: Arc<TValidator>,
     ) -> Self {
+        let max_incoming_per_bucket = config.max_incoming_per_bucket;
         let kbuckets = Arc::new(RwLock::new(KBucketsTable::new(
             discovery.local_enr().node_id().into(),
             config.bucket_pending_timeout,
-            config.max_incoming_per_bucket,
+            max_incoming_per_bucket,
             config.table_filter,
             config.bucket_filter,
         )));
@@ -146,11 +257,24 @@ where
             overlay_metrics: PORTALNET_METRICS.overlay(),
             protocol: protocol.to_string(),
         };
+        let (event_stream, _) = broadcast::channel(EVENT_STREAM_CHANNEL_SIZE);
+        let peer_scores = Arc::new(RwLock::new(PeerScores::new()));
+        let recent_traces = Arc::new(RwLock::new(RecentQueryTraces::default()));
+        let gossip_config = config.gossip;
+        let offer_history = Arc::new(RwLock::new(OfferHistory::default()));
+        let utp_connections =
+            UtpConnections::new(config.utp_conn_config, config.max_utp_connections);
+        let bootnodes = Arc::new(RwLock::new(config.bootnode_enrs));
+        let rng = match config.rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let rng = Arc::new(Mutex::new(rng));
         let command_tx = OverlayService::<TContentKey, TMetric, TValidator, TStore>::spawn(
             Arc::clone(&discovery),
             Arc::clone(&store),
             Arc::clone(&kbuckets),
-            config.bootnode_enrs,
+            Arc::clone(&bootnodes),
             config.ping_queue_interval,
             protocol.clone(),
             Arc::clone(&utp_socket),
@@ -161,6 +285,14 @@ where
             config.query_parallelism,
             config.query_num_results,
             config.findnodes_query_distances_per_peer,
+            config.routing_table_dir,
+            event_stream.clone(),
+            Arc::clone(&peer_scores),
+            Arc::clone(&recent_traces),
+            gossip_config,
+            Arc::clone(&offer_history),
+            utp_connections.clone(),
+            Arc::clone(&rng),
         )
         .await;
 
@@ -171,10 +303,19 @@ where
             protocol,
             command_tx,
             utp_socket,
+            peer_scores,
+            recent_traces,
+            gossip_config,
+            offer_history,
+            bootnodes,
+            rng,
             phantom_content_key: PhantomData,
             phantom_metric: PhantomData,
             validator,
             metrics,
+            event_stream,
+            max_incoming_per_bucket,
+            utp_connections,
         }
     }
 
@@ -198,30 +339,69 @@ where
         &self,
         talk_request: &TalkRequest,
     ) -> Result<Response, OverlayRequestError> {
-        let request = match Message::try_from(Vec::<u8>::from(talk_request.body())) {
+        self.process_request_bytes(
+            talk_request.id().clone(),
+            *talk_request.node_id(),
+            talk_request.body(),
+        )
+        .await
+    }
+
+    /// Processes the encoded `body` of a request with `id` received from `source`.
+    ///
+    /// This is the transport-agnostic core of `process_one_request`, used where requests are not
+    /// delivered by discv5, e.g. by the in-memory network simulator.
+    pub async fn process_request_bytes(
+        &self,
+        id: RequestId,
+        source: NodeId,
+        body: &[u8],
+    ) -> Result<Response, OverlayRequestError> {
+        let request = match Message::try_from(body.to_vec()) {
             Ok(message) => match Request::try_from(message) {
                 Ok(request) => request,
                 Err(err) => return Err(OverlayRequestError::InvalidRequest(err.to_string())),
             },
             Err(_) => return Err(OverlayRequestError::DecodeError),
         };
-        let direction = RequestDirection::Incoming {
-            id: talk_request.id().clone(),
-            source: *talk_request.node_id(),
-        };
+        let direction = RequestDirection::Incoming { id, source };
 
         // Send the request and wait on the response.
         self.send_overlay_request(request, direction).await
     }
 
-    /// Propagate gossip accepted content via OFFER/ACCEPT, return number of peers propagated
-    pub fn propagate_gossip(&self, content: Vec<(TContentKey, Vec<u8>)>) -> usize {
+    /// Propagate gossip accepted content via OFFER/ACCEPT, and wait for the outcome of every
+    /// offer. Returns the number of content items successfully transferred to interested peers.
+    pub async fn propagate_gossip(&self, content: Vec<(TContentKey, Vec<u8>)>) -> usize {
         let kbuckets = Arc::clone(&self.kbuckets);
         crate::overlay_service::propagate_gossip_cross_thread(
             content,
             kbuckets,
             self.command_tx.clone(),
+            self.gossip_config,
+            Arc::clone(&self.offer_history),
+            Arc::clone(&self.rng),
+        )
+        .await
+    }
+
+    /// Propagate gossip and wait for the outcome of every offer.
+    /// Returns which peers were selected for each content key, which content keys each peer
+    /// accepted and the outcome of each uTP transfer.
+    pub async fn trace_propagate_gossip(
+        &self,
+        content: Vec<(TContentKey, Vec<u8>)>,
+    ) -> GossipTrace {
+        let kbuckets = Arc::clone(&self.kbuckets);
+        crate::overlay_service::trace_propagate_gossip_cross_thread(
+            content,
+            kbuckets,
+            self.command_tx.clone(),
+            self.gossip_config,
+            Arc::clone(&self.offer_history),
+            Arc::clone(&self.rng),
         )
+        .await
     }
 
     /// Returns a vector of all ENR node IDs of nodes currently contained in the routing table.
@@ -246,6 +426,7 @@ where
     ///     key: usize representing bucket index
     ///     value: Vec of tuples, each tuple represents a node
     pub fn bucket_entries(&self) -> BTreeMap<usize, Vec<BucketEntry>> {
+        let peer_scores = self.peer_scores.read();
         self.kbuckets
             .read()
             .buckets_iter()
@@ -276,6 +457,7 @@ where
                                 node.status,
                                 node.value.data_radius(),
                                 client_info,
+                                peer_scores.score(node.key.preimage()),
                             )
                         })
                         .collect(),
@@ -461,7 +643,9 @@ where
                     // Init uTP stream if `connection_id` is received
                     Content::ConnectionId(conn_id) => {
                         let conn_id = u16::from_be(conn_id);
-                        let content = self.init_find_content_stream(enr, conn_id).await?;
+                        let content = self
+                            .init_find_content_stream(enr, conn_id, content_key.max_content_size())
+                            .await?;
                         match self.validate_content(&content_key, &content).await {
                             Ok(_) => Ok((Content::Content(content), true)),
                             Err(msg) => Err(OverlayRequestError::FailedValidation(format!(
@@ -498,25 +682,28 @@ where
         }
     }
 
-    /// Initialize FindContent uTP stream with remote node
+    /// Initialize FindContent uTP stream with remote node, and read at most `max_size` bytes of
+    /// content from it.
     async fn init_find_content_stream(
         &self,
         enr: Enr,
         conn_id: u16,
+        max_size: usize,
     ) -> Result<Vec<u8>, OverlayRequestError> {
         let cid = utp_rs::cid::ConnectionId {
             recv: conn_id,
             send: conn_id.wrapping_add(1),
             peer: crate::discovery::UtpEnr(enr),
         };
+        let _utp_permit = self.utp_connections.acquire().await;
+        let read_limit = self.discovery.utp_read_limits().limit(&cid, max_size);
         let mut stream = self
             .utp_socket
-            .connect_with_cid(cid, *UTP_CONN_CFG)
+            .connect_with_cid(cid, self.utp_connections.config())
             .await
             .map_err(|err| OverlayRequestError::UtpError(format!("{err:?}")))?;
         let mut data = vec![];
-        stream
-            .read_to_eof(&mut data)
+        read_to_eof_bounded(&mut stream, &mut data, &read_limit)
             .await
             .map_err(|err| OverlayRequestError::UtpError(format!("{:?}", err)))?;
 
@@ -549,6 +736,57 @@ where
         }
     }
 
+    /// Offer the content associated with the given content keys, and wait for the uTP transfer
+    /// of the accepted content.
+    pub async fn trace_offer(&self, content_keys: Vec<RawContentKey>, enr: Enr) -> OfferTrace {
+        let request = Request::Offer(Offer { content_keys });
+        crate::overlay_service::trace_offer_cross_thread(request, enr, &self.command_tx).await
+    }
+
+    /// Offer content without storing it into db, and wait for the uTP transfer of the accepted
+    /// content.
+    pub async fn trace_populated_offer(
+        &self,
+        enr: Enr,
+        content_key: RawContentKey,
+        content_value: Vec<u8>,
+    ) -> OfferTrace {
+        let request = Request::PopulatedOffer(PopulatedOffer {
+            content_items: vec![(content_key, content_value)],
+        });
+        crate::overlay_service::trace_offer_cross_thread(request, enr, &self.command_tx).await
+    }
+
+    /// Offer many content items to `enr` without storing them into db. The items are split into
+    /// offers of at most `MAX_CONTENT_KEYS_PER_OFFER` keys, and at most `MAX_CONCURRENT_OFFERS`
+    /// of those offers are in flight at once.
+    /// Returns the outcome of offering each content item, with its hex encoded content key, in the
+    /// order of `content_items`.
+    pub async fn offer_many(
+        &self,
+        enr: Enr,
+        content_items: Vec<(RawContentKey, Vec<u8>)>,
+    ) -> Vec<(String, OfferOutcome)> {
+        let offers = content_items
+            .chunks(MAX_CONTENT_KEYS_PER_OFFER)
+            .map(|content_items| {
+                let request = Request::PopulatedOffer(PopulatedOffer {
+                    content_items: content_items.to_vec(),
+                });
+                crate::overlay_service::trace_offer_cross_thread(
+                    request,
+                    enr.clone(),
+                    &self.command_tx,
+                )
+            })
+            .collect::<Vec<_>>();
+        let traces: Vec<OfferTrace> = stream::iter(offers)
+            .buffered(MAX_CONCURRENT_OFFERS)
+            .collect()
+            .await;
+        traces.iter().flat_map(OfferTrace::outcomes).collect()
+    }
+
     /// Send Offer request without storing the content into db
     pub async fn send_populated_offer(
         &self,
@@ -574,8 +812,27 @@ where
     }
 
     pub async fn lookup_node(&self, target: NodeId) -> Vec<Enr> {
+        self.find_nodes(target, false).await.0
+    }
+
+    /// Performs a node lookup for `target`.
+    /// Returns the closest nodes to the target, along with the trace of the lookup.
+    pub async fn trace_lookup_node(&self, target: NodeId) -> (Vec<Enr>, QueryTrace) {
+        let (nodes, trace) = self.find_nodes(target, true).await;
+        let trace = trace.unwrap_or_else(|| {
+            // The lookup was answered without a query, from the local node.
+            let local_enr = self.local_enr();
+            let mut trace = QueryTrace::new(&local_enr, target.into());
+            trace.node_responded_with(&local_enr, nodes.iter().collect());
+            trace.finish(QueryTermination::Converged);
+            trace
+        });
+        (nodes, trace)
+    }
+
+    async fn find_nodes(&self, target: NodeId, is_trace: bool) -> RecursiveFindNodesResult {
         if target == self.local_enr().node_id() {
-            return vec![self.local_enr()];
+            return (vec![self.local_enr()], None);
         }
         let connected_peer = self
             .kbuckets
@@ -586,13 +843,13 @@ where
             .find(|node_id| node_id == &target);
         if let Some(entry) = connected_peer {
             match self.discovery.find_enr(&entry) {
-                Some(enr) => return vec![enr],
+                Some(enr) => return (vec![enr], None),
                 None => {
                     warn!(
                         protocol = %self.protocol,
                         "Error finding ENR for node expected to exist in local routing table",
                     );
-                    return vec![];
+                    return (vec![], None);
                 }
             }
         };
@@ -600,13 +857,14 @@ where
         if let Err(err) = self.command_tx.send(OverlayCommand::FindNodeQuery {
             target,
             callback: tx,
+            is_trace,
         }) {
             warn!(
                 protocol = %self.protocol,
                 error = %err,
                 "Error submitting FindNode query to service"
             );
-            return vec![];
+            return (vec![], None);
         }
         rx.await.unwrap_or_else(|err| {
             warn!(
@@ -614,10 +872,22 @@ where
                 error = %err,
                 "Error receiving FindNode query response"
             );
-            vec![]
+            (vec![], None)
         })
     }
 
+    /// Returns the traces of up to `limit` of the most recently completed content lookups,
+    /// newest first.
+    pub fn recent_traces(&self, limit: usize) -> Vec<QueryTrace> {
+        self.recent_traces.read().latest(limit)
+    }
+
+    /// Returns the content for `content_key` if it was recently found by a lookup, but not stored
+    /// because it is outside of the local node's radius.
+    pub fn cached_content(&self, content_key: &TContentKey) -> Option<Vec<u8>> {
+        self.store.read().get_cached(content_key)
+    }
+
     /// Performs a content lookup for `target`.
     /// Returns the target content along with the peers traversed during content lookup.
     pub async fn lookup_content(
@@ -683,7 +953,7 @@ where
         // The overlay ping via talkreq will trigger a session at the base layer, then
         // a session on the (overlay) portal network.
         let mut successfully_bonded_bootnode = false;
-        let enrs = self.discovery.table_entries_enr();
+        let enrs = self.bootnodes.read().clone();
         if enrs.is_empty() {
             info!(
                 protocol = %self.protocol,
@@ -726,6 +996,32 @@ where
         }
     }
 
+    /// Adds `enr` to the bootnodes of the overlay, and to the routing table, then pings it. The
+    /// bootnodes are pinged by [`OverlayProtocol::ping_bootnodes`], and are added back to the
+    /// routing table by a bucket refresh that finds it without connected peers.
+    pub async fn add_bootnode(&self, enr: Enr) -> anyhow::Result<()> {
+        {
+            let mut bootnodes = self.bootnodes.write();
+            if !bootnodes
+                .iter()
+                .any(|bootnode| bootnode.node_id() == enr.node_id())
+            {
+                bootnodes.push(enr.clone());
+            }
+        }
+        self.add_enr(enr.clone())
+            .map_err(|err| anyhow!("Unable to add node to routing table: {err}"))?;
+        self.send_ping(enr)
+            .await
+            .map_err(|err| anyhow!("Unable to ping node: {err}"))?;
+        Ok(())
+    }
+
+    /// Returns the bootnodes of the overlay.
+    pub fn bootnodes(&self) -> Vec<Enr> {
+        self.bootnodes.read().clone()
+    }
+
     pub fn get_message_summary(&self) -> String {
         self.metrics.get_message_summary()
     }
@@ -735,23 +1031,208 @@ where
     }
 
     /// Creates an event stream channel which can be polled to receive overlay events.
+    ///
+    /// Every call returns a new channel, which only receives the events emitted after it was
+    /// created. Events are dropped for a channel that falls behind, see
+    /// [`OverlayProtocol::subscribe_events`].
     pub fn event_stream(
         &self,
     ) -> impl Future<Output = anyhow::Result<mpsc::Receiver<EventEnvelope>>> + 'static {
-        let channel = self.command_tx.clone();
+        let mut events = self.subscribe_events();
 
         async move {
-            let (callback_send, callback_recv) = oneshot::channel();
+            let (event_tx, event_rx) = mpsc::channel(EVENT_STREAM_CHANNEL_SIZE);
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            if event_tx.send(event).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(skipped, "Overlay event stream is lagging, events dropped");
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+            Ok(event_rx)
+        }
+    }
 
-            let command = OverlayCommand::RequestEventStream(callback_send);
-            channel
-                .send(command)
-                .map_err(|_| anyhow!("The Overlay Service channel has been closed early."))?;
+    /// Subscribes to the overlay events emitted from now on. A subscriber that falls behind by
+    /// more than [`EVENT_STREAM_CHANNEL_SIZE`] events skips the oldest ones.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.event_stream.subscribe()
+    }
+
+    /// Returns the sender of the overlay event stream, for components that subscribe to it
+    /// repeatedly, such as the JSON-RPC server.
+    pub fn event_stream_sender(&self) -> broadcast::Sender<EventEnvelope> {
+        self.event_stream.clone()
+    }
 
-            callback_recv
-                .await
-                .map_err(|_| anyhow!("The Overlay Service callback channel has been closed early."))
+    /// Updates the query parameters of the running overlay that are set in `tuning`, and returns
+    /// the resulting parameters. The routing table bucket limits, the uTP connection parameters
+    /// and the gossip parameters can only be set at startup.
+    pub async fn tune(&self, tuning: OverlayTuning) -> anyhow::Result<OverlayTuning> {
+        tuning.validate().map_err(|err| anyhow!(err))?;
+        let utp_tuning = self.utp_tuning();
+        if tuning.changes_utp_params(&utp_tuning) {
+            return Err(anyhow!(
+                "uTP parameters can only be set at startup, with --overlay-config"
+            ));
+        }
+        if tuning.changes_gossip_params(&self.gossip_tuning()) {
+            return Err(anyhow!(
+                "Gossip parameters can only be set at startup, with --overlay-config"
+            ));
         }
+        if let Some(max_incoming_per_bucket) = tuning.max_incoming_per_bucket {
+            if max_incoming_per_bucket != self.max_incoming_per_bucket {
+                return Err(anyhow!(
+                    "max_incoming_per_bucket can only be set at startup, with --overlay-config"
+                ));
+            }
+        }
+        let (tx, rx) = oneshot::channel();
+        self.command_tx
+            .send(OverlayCommand::Tune {
+                tuning,
+                callback: tx,
+            })
+            .map_err(|err| anyhow!("Error submitting overlay parameters to service: {err}"))?;
+        let tuning = rx
+            .await
+            .map_err(|err| anyhow!("Error receiving overlay parameters from service: {err}"))?;
+        self.with_startup_params(tuning)
+    }
+
+    /// Returns the current overlay parameters, without changing them.
+    pub async fn tuning(&self) -> anyhow::Result<OverlayTuning> {
+        let (tx, rx) = oneshot::channel();
+        self.command_tx
+            .send(OverlayCommand::Tuning { callback: tx })
+            .map_err(|err| anyhow!("Error requesting overlay parameters from service: {err}"))?;
+        let tuning = rx
+            .await
+            .map_err(|err| anyhow!("Error receiving overlay parameters from service: {err}"))?;
+        self.with_startup_params(tuning)
+    }
+
+    /// Adds the parameters that are only set at startup to the query parameters of the service.
+    fn with_startup_params(&self, mut tuning: OverlayTuning) -> anyhow::Result<OverlayTuning> {
+        tuning.max_incoming_per_bucket = Some(self.max_incoming_per_bucket);
+        tuning
+            .merge(&self.utp_tuning())
+            .map_err(|err| anyhow!(err))?;
+        tuning
+            .merge(&self.gossip_tuning())
+            .map_err(|err| anyhow!(err))?;
+        Ok(tuning)
+    }
+
+    /// Stops the overlay service, and waits until it has persisted its routing table.
+    pub async fn shutdown(&self) {
+        let (tx, rx) = oneshot::channel();
+        if self
+            .command_tx
+            .send(OverlayCommand::Shutdown { callback: tx })
+            .is_ok()
+        {
+            let _ = rx.await;
+        }
+    }
+
+    /// Returns the uTP connection parameters of the overlay.
+    fn utp_tuning(&self) -> OverlayTuning {
+        let config = self.utp_connections.config();
+        OverlayTuning {
+            utp_max_packet_size: Some(config.max_packet_size),
+            utp_max_conn_attempts: Some(config.max_conn_attempts),
+            utp_idle_timeout_ms: Some(config.max_idle_timeout.as_millis() as u64),
+            utp_initial_timeout_ms: Some(config.initial_timeout.as_millis() as u64),
+            utp_target_delay_ms: Some(config.target_delay.as_millis() as u64),
+            utp_max_connections: Some(self.utp_connections.max_connections()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the gossip parameters of the overlay.
+    fn gossip_tuning(&self) -> OverlayTuning {
+        OverlayTuning {
+            gossip_num_closest_nodes: Some(self.gossip_config.num_closest_nodes),
+            gossip_num_farther_nodes: Some(self.gossip_config.num_farther_nodes),
+            gossip_min_interested_nodes: Some(self.gossip_config.min_interested_nodes),
+            gossip_max_lookups: Some(self.gossip_config.max_lookups),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl<TContentKey, TMetric, TValidator> SubnetworkAdmin
+    for OverlayProtocol<TContentKey, TMetric, TValidator, PortalStorage>
+where
+    TContentKey: 'static + OverlayContentKey + Send + Sync,
+    TMetric: Metric + Send + Sync,
+    TValidator: 'static + Validator<TContentKey> + Send + Sync,
+    <TContentKey as TryFrom<Vec<u8>>>::Error: Debug + Display + Send,
+{
+    async fn overlay_tuning(&self) -> anyhow::Result<OverlayTuning> {
+        self.tuning().await
+    }
+
+    async fn tune_overlay(&self, tuning: OverlayTuning) -> anyhow::Result<OverlayTuning> {
+        self.tune(tuning).await
+    }
+
+    fn set_storage_capacity(&self, storage_capacity_mb: u64) -> anyhow::Result<PruneInfo> {
+        self.store
+            .write()
+            .set_storage_capacity_mb(storage_capacity_mb);
+        SubnetworkAdmin::prune(self)
+    }
+
+    fn prune(&self) -> anyhow::Result<PruneInfo> {
+        // The write lock is taken per pruning step, so that lookups and stores are not blocked
+        // for the whole prune.
+        let mut pruned_items = 0;
+        loop {
+            let step = self.store.write().prune_once()?;
+            match step {
+                Some(true) => pruned_items += 1,
+                Some(false) => {}
+                None => break,
+            }
+        }
+        let store = self.store.read();
+        store.report_storage_usage()?;
+        Ok(PruneInfo {
+            pruned_items,
+            radius: *store.radius(),
+        })
+    }
+
+    async fn add_bootnode(&self, enr: Enr) -> anyhow::Result<()> {
+        OverlayProtocol::add_bootnode(self, enr).await
+    }
+
+    fn remove_node(&self, node_id: NodeId) -> bool {
+        self.delete_enr(node_id)
+    }
+
+    fn summary(&self) -> SubnetworkSummary {
+        SubnetworkSummary {
+            storage: self.store.read().get_summary_info(),
+            messages: self.get_message_summary(),
+            utp: self.get_utp_summary(),
+        }
+    }
+
+    async fn shutdown(&self) {
+        OverlayProtocol::shutdown(self).await
     }
 }
 
@@ -1,11 +1,14 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
     marker::{PhantomData, Sync},
-    str::FromStr,
-    sync::Arc,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::Poll,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
@@ -21,23 +24,30 @@ use discv5::{
 };
 use futures::{channel::oneshot, future::join_all, prelude::*};
 use lazy_static::lazy_static;
-use parking_lot::RwLock;
-use rand::seq::{IteratorRandom, SliceRandom};
+use parking_lot::{Mutex, RwLock};
+use rand::{
+    rngs::StdRng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
 use smallvec::SmallVec;
 use ssz::Encode;
 use ssz_types::BitList;
 use thiserror::Error;
 use tokio::{
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    sync::{
+        broadcast,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+    },
     task::JoinHandle,
 };
 use tracing::{debug, error, info, trace, warn};
-use utp_rs::{conn::ConnectionConfig, socket::UtpSocket, stream::UtpStream};
+use utp_rs::{cid::ConnectionId, conn::ConnectionConfig, socket::UtpSocket, stream::UtpStream};
 
 use crate::events::EventEnvelope;
 use crate::storage::ShouldWeStoreContent;
 use crate::{
-    discovery::Discovery,
+    discovery::{Discovery, UtpEnr},
     events::OverlayEvent,
     find::{
         iterators::{
@@ -45,13 +55,18 @@ use crate::{
             findnodes::FindNodeQuery,
             query::{Query, QueryConfig},
         },
-        query_info::{QueryInfo, QueryType, RecursiveFindContentResult},
+        query_info::{QueryInfo, QueryType, RecursiveFindContentResult, RecursiveFindNodesResult},
         query_pool::{QueryId, QueryPool, QueryPoolState, TargetKey},
+        recent_traces::RecentQueryTraces,
     },
+    gossip::{GossipConfig, OfferHistory},
     metrics::{
-        labels::{UtpDirectionLabel, UtpOutcomeLabel},
+        labels::{MessageLabel, UtpDirectionLabel, UtpOutcomeLabel},
         overlay::OverlayMetricsReporter,
     },
+    peer_score::{PeerScores, PEER_BAN_DURATION},
+    rate_limit::RateLimitExceeded,
+    routing_table::RoutingTableStore,
     storage::ContentStore,
     types::{
         messages::{
@@ -62,11 +77,14 @@ use crate::{
         node::Node,
     },
     utils::portal_wire,
+    utp::{read_to_eof_bounded, UtpConnections},
 };
-use ethportal_api::generate_random_node_id;
+use ethportal_api::generate_random_node_id_with_rng;
 use ethportal_api::types::distance::{Distance, Metric, XorMetric};
 use ethportal_api::types::enr::{Enr, SszEnr};
-use ethportal_api::types::query_trace::QueryTrace;
+use ethportal_api::types::gossip_trace::{GossipTrace, OfferTrace};
+use ethportal_api::types::portal::OverlayTuning;
+use ethportal_api::types::query_trace::{ContentTransfer, QueryTermination, QueryTrace};
 use ethportal_api::utils::bytes::{hex_encode, hex_encode_compact};
 use ethportal_api::OverlayContentKey;
 use ethportal_api::RawContentKey;
@@ -84,6 +102,13 @@ const EXPECTED_NON_EMPTY_BUCKETS: usize = 17;
 /// Bucket refresh lookup interval in seconds
 const BUCKET_REFRESH_INTERVAL_SECS: u64 = 60;
 
+/// Routing table persistence interval in seconds
+const ROUTING_TABLE_PERSIST_INTERVAL_SECS: u64 = 300;
+
+/// Number of events buffered for each event stream subscriber. The channel size needs to be large
+/// to handle many events; a subscriber that falls further behind misses the oldest events.
+pub const EVENT_STREAM_CHANNEL_SIZE: usize = 100;
+
 lazy_static! {
     /// The default configuration to use for uTP connections.
     pub static ref UTP_CONN_CFG: ConnectionConfig = ConnectionConfig { max_packet_size: 1024, ..Default::default()};
@@ -116,10 +141,31 @@ pub enum OverlayCommand<TContentKey> {
         /// The query target.
         target: NodeId,
         /// A callback channel to transmit the result of the query.
-        callback: oneshot::Sender<Vec<Enr>>,
+        callback: oneshot::Sender<RecursiveFindNodesResult>,
+        /// Whether to trace the query.
+        is_trace: bool,
+    },
+    /// Update the query parameters of the service.
+    ///
+    /// Only the parameters that are set in `tuning` are changed. The resulting parameters are
+    /// sent to the issuer over `callback`.
+    Tune {
+        tuning: OverlayTuning,
+        callback: oneshot::Sender<OverlayTuning>,
     },
-    /// Sets up an event stream where the overlay server will return various events.
-    RequestEventStream(oneshot::Sender<mpsc::Receiver<EventEnvelope>>),
+    /// Send the current query parameters of the service to the issuer over `callback`.
+    Tuning {
+        callback: oneshot::Sender<OverlayTuning>,
+    },
+    /// Penalize a peer for a response that turned out to be invalid after it was processed, eg.
+    /// content that failed validation.
+    PenalizePeer {
+        node_id: NodeId,
+        error: OverlayRequestError,
+    },
+    /// Persist the routing table and stop the service. The issuer is notified over `callback`
+    /// once the service has stopped.
+    Shutdown { callback: oneshot::Sender<()> },
 }
 
 /// An overlay request error.
@@ -176,6 +222,27 @@ pub enum OverlayRequestError {
     InvalidRemoteDiscv5Packet,
 }
 
+impl OverlayRequestError {
+    /// Returns a short, stable name for the kind of error, for use in query traces.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ChannelFailure(_) => "channel_failure",
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::InvalidResponse => "invalid_response",
+            Self::FailedValidation(_) => "failed_validation",
+            Self::EmptyResponse => "empty_response",
+            Self::DecodeError => "decode_error",
+            Self::Timeout => "timeout",
+            Self::Failure(_) => "failure",
+            Self::Discv5Error(_) => "discv5_error",
+            Self::AcceptError(_) => "accept_error",
+            Self::OfferError(_) => "offer_error",
+            Self::UtpError(_) => "utp_error",
+            Self::InvalidRemoteDiscv5Packet => "invalid_remote_discv5_packet",
+        }
+    }
+}
+
 impl From<discv5::RequestError> for OverlayRequestError {
     fn from(err: discv5::RequestError) -> Self {
         match err {
@@ -200,9 +267,20 @@ pub enum RequestDirection {
 // a separate identifier to track outgoing talk requests.
 type OverlayRequestId = u128;
 
+/// The source of overlay request identifiers. Identifiers only need to be unique, so they are
+/// counted rather than drawn at random, which keeps them reproducible.
+static NEXT_OVERLAY_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The random number generator of an overlay, shared by the overlay protocol and its service.
+/// Seeding it makes the routing table maintenance and gossip of the overlay reproducible.
+pub type OverlayRng = Arc<Mutex<StdRng>>;
+
 /// An overlay request response channel.
 type OverlayResponder = oneshot::Sender<Result<Response, OverlayRequestError>>;
 
+/// A channel for the outcome of the uTP transfer of content accepted in response to an OFFER.
+pub type UtpTransferResponder = oneshot::Sender<ContentTransfer>;
+
 /// A request to pass through the overlay.
 #[derive(Debug)]
 pub struct OverlayRequest {
@@ -218,6 +296,9 @@ pub struct OverlayRequest {
     /// ID of query that request's response will advance.
     /// Will be None for requests that are not associated with a query.
     pub query_id: Option<QueryId>,
+    /// An optional responder to send the outcome of the uTP transfer of the content accepted in
+    /// response to an OFFER request. It is dropped if no content is transferred.
+    pub utp_responder: Option<UtpTransferResponder>,
 }
 
 impl OverlayRequest {
@@ -229,11 +310,14 @@ impl OverlayRequest {
         query_id: Option<QueryId>,
     ) -> Self {
         OverlayRequest {
-            id: rand::random(),
+            id: NEXT_OVERLAY_REQUEST_ID
+                .fetch_add(1, Ordering::Relaxed)
+                .into(),
             request,
             direction,
             responder,
             query_id,
+            utp_responder: None,
         }
     }
 }
@@ -247,6 +331,8 @@ struct ActiveOutgoingRequest {
     pub request: Request,
     /// An optional QueryID for the query that this request is associated with.
     pub query_id: Option<QueryId>,
+    /// An optional responder for the outcome of the uTP transfer following an OFFER.
+    pub utp_responder: Option<UtpTransferResponder>,
 }
 
 /// A response for a particular overlay request.
@@ -297,7 +383,7 @@ pub struct OverlayService<TContentKey, TMetric, TValidator, TStore> {
     /// The sender half of a channel for responses to outgoing requests.
     response_tx: UnboundedSender<OverlayResponse>,
     /// uTP socket.
-    utp_socket: Arc<UtpSocket<crate::discovery::UtpEnr>>,
+    utp_socket: Arc<UtpSocket<UtpEnr>>,
     /// Phantom content key.
     phantom_content_key: PhantomData<TContentKey>,
     /// Phantom metric (distance function).
@@ -307,7 +393,57 @@ pub struct OverlayService<TContentKey, TMetric, TValidator, TStore> {
     /// Validator for overlay network content.
     validator: Arc<TValidator>,
     /// A channel that the overlay service emits events on.
-    event_stream: Option<mpsc::Sender<EventEnvelope>>,
+    event_stream: broadcast::Sender<EventEnvelope>,
+    /// Persists the routing table across restarts, if enabled.
+    routing_table_store: Option<RoutingTableStore>,
+    /// Reputation of the peers of the overlay.
+    peer_scores: Arc<RwLock<PeerScores>>,
+    /// Traces of the most recently completed content queries.
+    recent_traces: Arc<RwLock<RecentQueryTraces>>,
+    /// How content is gossiped to interested peers.
+    gossip_config: GossipConfig,
+    /// Which peers accepted or declined gossiped content.
+    offer_history: Arc<RwLock<OfferHistory>>,
+    /// Settings and limit of the uTP connections.
+    utp_connections: UtpConnections,
+    /// The bootnodes of the overlay, including those added at runtime.
+    bootnodes: Arc<RwLock<Vec<Enr>>>,
+    /// The source of all randomness of the service.
+    rng: OverlayRng,
+}
+
+/// Delivers the result of a find content query to its callers, and records the trace of the
+/// query with the recently completed queries.
+struct FindContentResponder {
+    /// The callbacks of the query, and whether each caller asked for the trace of the query.
+    callbacks: Vec<(oneshot::Sender<RecursiveFindContentResult>, bool)>,
+    trace: Option<QueryTrace>,
+    recent_traces: Arc<RwLock<RecentQueryTraces>>,
+}
+
+impl FindContentResponder {
+    fn trace_mut(&mut self) -> Option<&mut QueryTrace> {
+        self.trace.as_mut()
+    }
+
+    /// Ends the trace of the query with `termination`, and sends the result to the callers.
+    /// The trace is only returned to the callers that asked for it.
+    fn respond(self, content: Option<Vec<u8>>, utp_transfer: bool, termination: QueryTermination) {
+        let Self {
+            callbacks,
+            trace,
+            recent_traces,
+        } = self;
+        let trace = trace.map(|mut trace| {
+            trace.finish(termination);
+            recent_traces.write().push(trace.clone());
+            trace
+        });
+        for (callback, is_trace) in callbacks {
+            let trace = if is_trace { trace.clone() } else { None };
+            let _ = callback.send((content.clone(), utp_transfer, trace));
+        }
+    }
 }
 
 impl<
@@ -329,10 +465,10 @@ where
         discovery: Arc<Discovery>,
         store: Arc<RwLock<TStore>>,
         kbuckets: Arc<RwLock<KBucketsTable<NodeId, Node>>>,
-        bootnode_enrs: Vec<Enr>,
+        bootnodes: Arc<RwLock<Vec<Enr>>>,
         ping_queue_interval: Option<Duration>,
         protocol: ProtocolId,
-        utp_socket: Arc<UtpSocket<crate::discovery::UtpEnr>>,
+        utp_socket: Arc<UtpSocket<UtpEnr>>,
         metrics: OverlayMetricsReporter,
         validator: Arc<TValidator>,
         query_timeout: Duration,
@@ -340,6 +476,14 @@ where
         query_parallelism: usize,
         query_num_results: usize,
         findnodes_query_distances_per_peer: usize,
+        routing_table_dir: Option<PathBuf>,
+        event_stream: broadcast::Sender<EventEnvelope>,
+        peer_scores: Arc<RwLock<PeerScores>>,
+        recent_traces: Arc<RwLock<RecentQueryTraces>>,
+        gossip_config: GossipConfig,
+        offer_history: Arc<RwLock<OfferHistory>>,
+        utp_connections: UtpConnections,
+        rng: OverlayRng,
     ) -> UnboundedSender<OverlayCommand<TContentKey>>
     where
         <TContentKey as TryFrom<Vec<u8>>>::Error: Send,
@@ -357,6 +501,9 @@ where
 
         let (response_tx, response_rx) = mpsc::unbounded_channel();
 
+        let routing_table_store =
+            routing_table_dir.map(|dir| RoutingTableStore::new(&dir, &protocol));
+
         tokio::spawn(async move {
             let mut service = Self {
                 discovery,
@@ -380,11 +527,19 @@ where
                 phantom_metric: PhantomData,
                 metrics,
                 validator,
-                event_stream: None,
+                event_stream,
+                routing_table_store,
+                peer_scores,
+                recent_traces,
+                gossip_config,
+                offer_history,
+                utp_connections,
+                bootnodes,
+                rng,
             };
 
             info!(protocol = %overlay_protocol, "Starting overlay service");
-            service.initialize_routing_table(bootnode_enrs);
+            service.initialize_routing_table();
             service.start().await;
         });
 
@@ -435,17 +590,92 @@ where
         }
     }
 
+    /// Inserts the nodes persisted by a previous run into the routing table in a disconnected
+    /// state, and pings them to find out which of them are still online.
+    fn add_persisted_nodes(&mut self) {
+        let persisted_nodes = match self.routing_table_store.as_mut().map(|store| store.load()) {
+            Some(Ok(nodes)) => nodes,
+            Some(Err(err)) => {
+                warn!(protocol = %self.protocol, error = %err, "Error loading persisted routing table");
+                return;
+            }
+            None => return,
+        };
+        let local_node_id = self.local_enr().node_id();
+
+        let mut inserted = 0;
+        for persisted_node in persisted_nodes {
+            let node_id = persisted_node.enr.node_id();
+            if node_id == local_node_id {
+                continue;
+            }
+            let status = NodeStatus {
+                state: ConnectionState::Disconnected,
+                direction: ConnectionDirection::Outgoing,
+            };
+            let key = kbucket::Key::from(node_id);
+            let result =
+                self.kbuckets
+                    .write()
+                    .insert_or_update(&key, persisted_node.node(), status);
+            if let InsertResult::Failed(reason) = result {
+                debug!(
+                    protocol = %self.protocol,
+                    node = %node_id,
+                    error = ?reason,
+                    "Error inserting persisted node into routing table",
+                );
+                continue;
+            }
+            // Nodes that answer are queued for liveness checks once their pong is processed.
+            self.ping_node(&persisted_node.enr);
+            inserted += 1;
+        }
+        info!(protocol = %self.protocol, nodes = inserted, "Loaded persisted routing table");
+    }
+
+    /// Writes the routing table to the node data dir, if persistence is enabled.
+    async fn persist_routing_table(&mut self) {
+        let Some(store) = self.routing_table_store.as_mut() else {
+            return;
+        };
+        let entries: Vec<(Node, bool)> = self
+            .kbuckets
+            .write()
+            .iter()
+            .map(|entry| (entry.node.value.clone(), entry.status.is_connected()))
+            .collect();
+        let num_entries = entries.len();
+        match store.save(entries).await {
+            Ok(()) => {
+                trace!(protocol = %self.protocol, entries = num_entries, "Persisted routing table")
+            }
+            Err(err) => warn!(
+                protocol = %self.protocol,
+                path = %store.path().display(),
+                error = %err,
+                "Error persisting routing table",
+            ),
+        }
+    }
+
     /// Begins initial FINDNODES query to populate the routing table.
-    fn initialize_routing_table(&mut self, bootnodes: Vec<Enr>) {
+    fn initialize_routing_table(&mut self) {
+        self.add_persisted_nodes();
+        let bootnodes = self.bootnodes.read().clone();
         self.add_bootnodes(bootnodes);
         let local_node_id = self.local_enr().node_id();
 
         // Begin request for our local node ID.
-        self.init_find_nodes_query(&local_node_id, None);
+        self.init_find_nodes_query(&local_node_id, None, false);
 
         for bucket_index in (255 - EXPECTED_NON_EMPTY_BUCKETS as u8)..255 {
-            let target_node_id = generate_random_node_id(bucket_index, self.local_enr().into());
-            self.init_find_nodes_query(&target_node_id, None);
+            let target_node_id = generate_random_node_id_with_rng(
+                &mut *self.rng.lock(),
+                bucket_index,
+                self.local_enr().into(),
+            );
+            self.init_find_nodes_query(&target_node_id, None, false);
         }
     }
 
@@ -462,10 +692,17 @@ where
     /// information relevant to the overlay network.
     ///
     /// Bucket maintenance: Maintain the routing table (more info documented above function).
+    ///
+    /// Routing table persistence: Periodically, and when the service is shut down, write the
+    /// routing table to the node data dir so that it can be reloaded on the next start.
     async fn start(&mut self) {
         // Construct bucket refresh interval
         let mut bucket_refresh_interval =
             tokio::time::interval(Duration::from_secs(BUCKET_REFRESH_INTERVAL_SECS));
+        // Construct routing table persistence interval, skipping the immediate first tick
+        let persist_period = Duration::from_secs(ROUTING_TABLE_PERSIST_INTERVAL_SECS);
+        let mut persist_interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + persist_period, persist_period);
 
         loop {
             tokio::select! {
@@ -473,17 +710,29 @@ where
                     match command {
                         OverlayCommand::Request(request) => self.process_request(request),
                         OverlayCommand::FindContentQuery { target, callback, is_trace } => {
-                            if let Some(query_id) = self.init_find_content_query(target.clone(), Some(callback), is_trace) {
-                                trace!(
-                                    query.id = %query_id,
-                                    content.id = %hex_encode_compact(target.content_id()),
-                                    content.key = %target,
-                                    "FindContent query initialized"
-                                );
+                            match self.coalesce_find_content_query(&target, callback, is_trace) {
+                                Ok(query_id) => {
+                                    trace!(
+                                        query.id = %query_id,
+                                        content.id = %hex_encode_compact(target.content_id()),
+                                        content.key = %target,
+                                        "FindContent query coalesced with in-progress query"
+                                    );
+                                }
+                                Err(callback) => {
+                                    if let Some(query_id) = self.init_find_content_query(target.clone(), Some(callback), is_trace) {
+                                        trace!(
+                                            query.id = %query_id,
+                                            content.id = %hex_encode_compact(target.content_id()),
+                                            content.key = %target,
+                                            "FindContent query initialized"
+                                        );
+                                    }
+                                }
                             }
                         }
-                        OverlayCommand::FindNodeQuery { target, callback } => {
-                            if let Some(query_id) = self.init_find_nodes_query(&target, Some(callback)) {
+                        OverlayCommand::FindNodeQuery { target, callback, is_trace } => {
+                            if let Some(query_id) = self.init_find_nodes_query(&target, Some(callback), is_trace) {
                                 trace!(
                                     query.id = %query_id,
                                     node.id = %hex_encode_compact(target),
@@ -491,14 +740,21 @@ where
                                 );
                             }
                         }
-                        OverlayCommand::RequestEventStream(callback) => {
-                            // the channel size needs to be large to handle many events
-                            let channel_size = 100;
-                            let (event_stream, event_stream_recv) = mpsc::channel(channel_size);
-                            self.event_stream = Some(event_stream);
-                            if callback.send(event_stream_recv).is_err() {
-                                error!("Failed to return the event stream channel");
-                            }
+                        OverlayCommand::Tune { tuning, callback } => {
+                            self.apply_tuning(&tuning);
+                            let _ = callback.send(self.tuning());
+                        }
+                        OverlayCommand::Tuning { callback } => {
+                            let _ = callback.send(self.tuning());
+                        }
+                        OverlayCommand::PenalizePeer { node_id, error } => {
+                            self.penalize_peer(node_id, &error);
+                        }
+                        OverlayCommand::Shutdown { callback } => {
+                            self.persist_routing_table().await;
+                            info!(protocol = %self.protocol, "Overlay service stopped");
+                            let _ = callback.send(());
+                            return;
                         }
                     }
                 }
@@ -516,9 +772,14 @@ where
                         match response.response {
                             Ok(response) => {
                                 self.metrics.report_inbound_response(&response);
-                                self.process_response(response, request.destination, request.request, request.query_id)
+                                self.process_response(response, request.destination, request.request, request.query_id, request.utp_responder)
+                            }
+                            Err(error) => {
+                                if let Some(query_id) = request.query_id {
+                                    self.trace_query_request_failure(query_id, &request.request, &request.destination, &error);
+                                }
+                                self.process_request_failure(response.request_id, request.destination, error)
                             }
-                            Err(error) => self.process_request_failure(response.request_id, request.destination, error),
                         }
 
                     } else {
@@ -545,12 +806,31 @@ where
                     trace!(protocol = %self.protocol, "Routing table bucket refresh");
                     self.bucket_refresh_lookup();
                 }
+                _ = persist_interval.tick(), if self.routing_table_store.is_some() => {
+                    self.persist_routing_table().await;
+                }
             }
         }
     }
 
     /// Main bucket refresh lookup logic
     fn bucket_refresh_lookup(&mut self) {
+        // Without connected peers the lookup has no one to ask, so rejoin through the bootnodes.
+        let has_connected_peers = self
+            .kbuckets
+            .write()
+            .iter()
+            .any(|entry| entry.status.is_connected());
+        if !has_connected_peers {
+            let bootnodes = self.bootnodes.read().clone();
+            debug!(
+                protocol = %self.protocol,
+                bootnodes = bootnodes.len(),
+                "No connected peers, adding bootnodes to the routing table",
+            );
+            self.add_bootnodes(bootnodes);
+        }
+
         // Look at local routing table and select the largest 17 buckets.
         // We only need the 17 bits furthest from our own node ID, because the closest 239 bits of
         // buckets are going to be empty-ish.
@@ -560,12 +840,16 @@ where
             let buckets = &buckets[256 - EXPECTED_NON_EMPTY_BUCKETS..];
 
             // Randomly pick one of these buckets.
-            let target_bucket = buckets.choose(&mut rand::thread_rng());
+            let target_bucket = buckets.choose(&mut *self.rng.lock());
             match target_bucket {
                 Some(bucket) => {
                     trace!(protocol = %self.protocol, bucket = %bucket.0, "Refreshing routing table bucket");
                     match u8::try_from(bucket.0) {
-                        Ok(idx) => generate_random_node_id(idx, self.local_enr().into()),
+                        Ok(idx) => generate_random_node_id_with_rng(
+                            &mut *self.rng.lock(),
+                            idx,
+                            self.local_enr().into(),
+                        ),
                         Err(err) => {
                             error!(error = %err, "Error downcasting bucket index");
                             return;
@@ -579,7 +863,7 @@ where
             }
         };
 
-        self.init_find_nodes_query(&target_node_id, None);
+        self.init_find_nodes_query(&target_node_id, None, false);
     }
 
     /// Returns the local ENR of the node.
@@ -657,11 +941,19 @@ where
         &mut self,
         query_event: QueryEvent<FindNodeQuery<NodeId>, TContentKey>,
     ) {
+        let timed_out = matches!(query_event, QueryEvent::TimedOut(..));
         match query_event {
             // Send a FINDNODES on behalf of the query.
             QueryEvent::Waiting(query_id, node_id, request) => {
                 // Look up the node's ENR.
                 if let Some(enr) = self.find_enr(&node_id) {
+                    if let Some((query_info, _)) =
+                        self.find_node_query_pool.write().get_mut(query_id)
+                    {
+                        if let Some(trace) = &mut query_info.trace {
+                            trace.node_request_started(&enr);
+                        }
+                    }
                     let request = OverlayRequest::new(
                         request,
                         RequestDirection::Outgoing { destination: enr },
@@ -705,12 +997,21 @@ where
                         );
                     }
                 }
+                let trace = query_info.trace.map(|mut trace| {
+                    let termination = if timed_out {
+                        QueryTermination::TimedOut
+                    } else {
+                        QueryTermination::Converged
+                    };
+                    trace.finish(termination);
+                    trace
+                });
                 if let QueryType::FindNode {
                     callback: Some(callback),
                     ..
                 } = query_info.query_type
                 {
-                    if let Err(err) = callback.send(found_enrs.clone()) {
+                    if let Err(err) = callback.send((found_enrs.clone(), trace)) {
                         error!(
                             query.id = %query_id,
                             error = ?err,
@@ -733,9 +1034,17 @@ where
         &mut self,
         query_event: QueryEvent<FindContentQuery<NodeId>, TContentKey>,
     ) {
+        let timed_out = matches!(query_event, QueryEvent::TimedOut(..));
         match query_event {
             QueryEvent::Waiting(query_id, node_id, request) => {
                 if let Some(enr) = self.find_enr(&node_id) {
+                    if let Some((query_info, _)) =
+                        self.find_content_query_pool.write().get_mut(query_id)
+                    {
+                        if let Some(trace) = &mut query_info.trace {
+                            trace.node_request_started(&enr);
+                        }
+                    }
                     // If we find the node's ENR, then send the request on behalf of the
                     // query. No callback channel is necessary for the request, because the
                     // response will be incorporated into the query.
@@ -762,8 +1071,20 @@ where
             }
             QueryEvent::Finished(_, query_info, query)
             | QueryEvent::TimedOut(_, query_info, query) => {
-                let (callback, content_key) = match query_info.query_type {
-                    QueryType::FindContent { callback, target } => (callback, target),
+                let (callbacks, content_key) = match query_info.query_type {
+                    QueryType::FindContent {
+                        callback,
+                        target,
+                        is_trace,
+                        coalesced_callbacks,
+                    } => {
+                        let callbacks = callback
+                            .map(|callback| (callback, is_trace))
+                            .into_iter()
+                            .chain(coalesced_callbacks)
+                            .collect();
+                        (callbacks, target)
+                    }
                     _ => {
                         error!(
                             "Only FindContent queries trigger a Finished or TimedOut event, but this is a {:?}",
@@ -772,22 +1093,28 @@ where
                         return;
                     }
                 };
+                let mut responder = self.find_content_responder(callbacks, query_info.trace);
 
                 match query.into_result() {
                     FindContentQueryResult::ClosestNodes(_closest_nodes) => {
-                        if let Some(responder) = callback {
-                            let _ = responder.send((None, false, query_info.trace));
-                        }
+                        let termination = if timed_out {
+                            QueryTermination::TimedOut
+                        } else {
+                            QueryTermination::NotFound
+                        };
+                        responder.respond(None, false, termination);
                     }
                     FindContentQueryResult::Content {
                         content,
                         nodes_to_poke,
+                        peer,
                     } => {
                         let validator = self.validator.clone();
                         let store = self.store.clone();
                         let kbuckets = self.kbuckets.clone();
                         let command_tx = self.command_tx.clone();
                         let metrics = self.metrics.clone();
+                        let event_stream = self.event_stream.clone();
                         tokio::spawn(async move {
                             Self::process_received_content(
                                 kbuckets,
@@ -797,10 +1124,11 @@ where
                                 content.clone(),
                                 false,
                                 content_key,
-                                callback,
-                                query_info.trace,
+                                peer,
+                                responder,
                                 nodes_to_poke,
                                 metrics,
+                                event_stream,
                             )
                             .await;
                         });
@@ -816,25 +1144,30 @@ where
                             Some(enr) => enr,
                             _ => {
                                 warn!("Received uTP payload from unknown {peer}");
-                                if let Some(responder) = callback {
-                                    let _ = responder.send((None, true, query_info.trace));
-                                };
+                                responder.respond(None, true, QueryTermination::UnknownPeer);
                                 return;
                             }
                         };
                         let cid = utp_rs::cid::ConnectionId {
                             recv: connection_id,
                             send: connection_id.wrapping_add(1),
-                            peer: crate::discovery::UtpEnr(source),
+                            peer: UtpEnr(source),
                         };
                         let validator = self.validator.clone();
                         let store = self.store.clone();
                         let kbuckets = self.kbuckets.clone();
                         let command_tx = self.command_tx.clone();
+                        let event_stream = self.event_stream.clone();
+                        let utp_connections = self.utp_connections.clone();
+                        let utp_read_limits = self.discovery.utp_read_limits().clone();
                         tokio::spawn(async move {
+                            let _utp_permit = utp_connections.acquire().await;
+                            let read_limit =
+                                utp_read_limits.limit(&cid, content_key.max_content_size());
                             metrics.report_utp_active_inc(UtpDirectionLabel::Inbound);
+                            let transfer_start = Instant::now();
                             let mut stream = match utp
-                                .connect_with_cid(cid.clone(), *UTP_CONN_CFG)
+                                .connect_with_cid(cid.clone(), utp_connections.config())
                                 .await
                             {
                                 Ok(stream) => stream,
@@ -850,33 +1183,59 @@ where
                                         peer = ?cid.peer.client(),
                                         "Unable to establish uTP conn based on Content response",
                                     );
-                                    if let Some(responder) = callback {
-                                        let _ = responder.send((None, true, query_info.trace));
-                                    };
+                                    if let Some(trace) = responder.trace_mut() {
+                                        trace.content_transferred(
+                                            peer.into(),
+                                            0,
+                                            transfer_start.elapsed(),
+                                            Some(err.to_string()),
+                                        );
+                                    }
+                                    responder.respond(None, true, QueryTermination::TransferFailed);
                                     return;
                                 }
                             };
 
                             let mut data = vec![];
-                            if let Err(err) = stream.read_to_eof(&mut data).await {
+                            if let Err(err) =
+                                read_to_eof_bounded(&mut stream, &mut data, &read_limit).await
+                            {
                                 metrics.report_utp_outcome(
                                     UtpDirectionLabel::Inbound,
                                     UtpOutcomeLabel::FailedDataTx,
                                 );
                                 error!(%err, cid.send, cid.recv, peer = ?cid.peer.client(), "error reading data from uTP stream, while handling a FindContent request.");
-                                if let Some(responder) = callback {
-                                    let _ = responder.send((None, true, query_info.trace));
-                                };
+                                if let Some(trace) = responder.trace_mut() {
+                                    trace.content_transferred(
+                                        peer.into(),
+                                        data.len(),
+                                        transfer_start.elapsed(),
+                                        Some(err.to_string()),
+                                    );
+                                }
+                                responder.respond(None, true, QueryTermination::TransferFailed);
                                 return;
                             }
+                            if let Some(trace) = responder.trace_mut() {
+                                trace.content_transferred(
+                                    peer.into(),
+                                    data.len(),
+                                    transfer_start.elapsed(),
+                                    None,
+                                );
+                            }
 
                             // report utp tx as successful, even if we go on to fail to process the payload
                             metrics.report_utp_outcome(
                                 UtpDirectionLabel::Inbound,
                                 UtpOutcomeLabel::Success,
                             );
+                            metrics.report_utp_transfer(
+                                UtpDirectionLabel::Inbound,
+                                data.len(),
+                                transfer_start.elapsed(),
+                            );
 
-                            let trace = query_info.trace;
                             let metrics = metrics.clone();
                             Self::process_received_content(
                                 kbuckets,
@@ -886,10 +1245,11 @@ where
                                 data,
                                 true,
                                 content_key,
-                                callback,
-                                trace,
+                                peer,
+                                responder,
                                 nodes_to_poke,
                                 metrics,
+                                event_stream,
                             )
                             .await;
                         });
@@ -991,6 +1351,7 @@ where
                         responder: request.responder,
                         request: request.request.clone(),
                         query_id: request.query_id,
+                        utp_responder: request.utp_responder,
                     },
                 );
                 self.metrics.report_outbound_request(&request.request);
@@ -1087,6 +1448,12 @@ where
             "Handling FindContent message",
         );
 
+        let rate_limiter = self.discovery.rate_limiter();
+        if let Err(limit) = rate_limiter.check_request(source) {
+            self.report_rate_limited(source, MessageLabel::FindContent, limit);
+            return Ok(Content::Enrs(vec![]));
+        }
+
         let content_key = match (TContentKey::try_from)(request.content_key) {
             Ok(key) => key,
             Err(_) => {
@@ -1097,6 +1464,39 @@ where
         };
         match self.store.read().get(&content_key) {
             Ok(Some(content)) => {
+                let (utp_permit, utp_connection_permit) =
+                    if content.len() <= MAX_PORTAL_CONTENT_PAYLOAD_SIZE {
+                        (None, None)
+                    } else {
+                        let utp_permit = match rate_limiter
+                            .acquire_utp_stream(source, UtpDirectionLabel::Outbound)
+                        {
+                            Ok(permit) => permit,
+                            Err(limit) => {
+                                self.report_rate_limited(source, MessageLabel::FindContent, limit);
+                                return Ok(Content::Enrs(vec![]));
+                            }
+                        };
+                        // The peer is only given a connection ID if the connection can be
+                        // accepted right away.
+                        let utp_connection_permit = match self.utp_connections.try_acquire() {
+                            Ok(permit) => permit,
+                            Err(_) => {
+                                debug!(
+                                    protocol = %self.protocol,
+                                    request.source = %source,
+                                    "Too many uTP connections to serve FindContent"
+                                );
+                                return Ok(Content::Enrs(vec![]));
+                            }
+                        };
+                        (Some(utp_permit), utp_connection_permit)
+                    };
+                if let Err(limit) = rate_limiter.reserve_bytes(source, content.len() as u64) {
+                    self.report_rate_limited(source, MessageLabel::FindContent, limit);
+                    return Ok(Content::Enrs(vec![]));
+                }
+
                 if content.len() <= MAX_PORTAL_CONTENT_PAYLOAD_SIZE {
                     Ok(Content::Content(content))
                 } else {
@@ -1106,17 +1506,25 @@ where
                             "unable to find ENR for NodeId".to_string(),
                         )
                     })?;
-                    let enr = crate::discovery::UtpEnr(node_addr.enr);
-                    let cid = self.utp_socket.cid(enr, false);
+                    let enr = UtpEnr(node_addr.enr);
+                    let cid = self.inbound_utp_cid(enr);
                     let cid_send = cid.send;
 
                     // Wait for an incoming connection with the given CID. Then, write the data
                     // over the uTP stream.
                     let utp = Arc::clone(&self.utp_socket);
                     let metrics = self.metrics.clone();
+                    let utp_connections = self.utp_connections.clone();
                     tokio::spawn(async move {
+                        // Hold the stream permits until the transfer is over.
+                        let _utp_permit = utp_permit;
+                        let _utp_connection_permit = utp_connection_permit;
                         metrics.report_utp_active_inc(UtpDirectionLabel::Outbound);
-                        let stream = match utp.accept_with_cid(cid.clone(), *UTP_CONN_CFG).await {
+                        let transfer_start = Instant::now();
+                        let stream = match utp
+                            .accept_with_cid(cid.clone(), utp_connections.config())
+                            .await
+                        {
                             Ok(stream) => stream,
                             Err(err) => {
                                 metrics.report_utp_outcome(
@@ -1133,7 +1541,9 @@ where
                                 return;
                             }
                         };
-                        if let Err(err) = Self::send_utp_content(stream, &content, metrics).await {
+                        if let Err(err) =
+                            Self::send_utp_content(stream, &content, metrics, transfer_start).await
+                        {
                             warn!(
                                 %err,
                                 %cid.send,
@@ -1166,6 +1576,35 @@ where
         }
     }
 
+    /// Builds an `Accept` response that declines all `num_keys` offered content keys.
+    fn empty_accept(num_keys: usize) -> Result<Accept, OverlayRequestError> {
+        let content_keys = BitList::with_capacity(num_keys).map_err(|_| {
+            OverlayRequestError::AcceptError(
+                "Unable to initialize bitlist for requested keys.".to_owned(),
+            )
+        })?;
+        Ok(Accept {
+            connection_id: 0,
+            content_keys,
+        })
+    }
+
+    /// Reports a request that was declined for going over a rate limit.
+    fn report_rate_limited(
+        &self,
+        source: &NodeId,
+        message: MessageLabel,
+        limit: RateLimitExceeded,
+    ) {
+        debug!(
+            protocol = %self.protocol,
+            request.source = %source,
+            %limit,
+            "Declining request over rate limit",
+        );
+        self.metrics.report_rate_limited(message, limit);
+    }
+
     /// Attempts to build an `Accept` response for an `Offer` request.
     fn handle_offer(
         &self,
@@ -1180,6 +1619,12 @@ where
             "Handling Offer message",
         );
 
+        let rate_limiter = self.discovery.rate_limiter();
+        if let Err(limit) = rate_limiter.check_request(source) {
+            self.report_rate_limited(source, MessageLabel::Offer, limit);
+            return Self::empty_accept(request.content_keys.len());
+        }
+
         let mut requested_keys =
             BitList::with_capacity(request.content_keys.len()).map_err(|_| {
                 OverlayRequestError::AcceptError(
@@ -1226,26 +1671,90 @@ where
             });
         }
 
+        // The size of the offered content is only known once it is received, so only check that
+        // the bandwidth is not used up yet.
+        let utp_permit = match rate_limiter
+            .acquire_utp_stream(source, UtpDirectionLabel::Inbound)
+            .and_then(|permit| rate_limiter.reserve_bytes(source, 0).map(|_| permit))
+        {
+            Ok(permit) => permit,
+            Err(limit) => {
+                self.report_rate_limited(source, MessageLabel::Offer, limit);
+                return Self::empty_accept(content_keys.len());
+            }
+        };
+        // The peer is only given a connection ID if the connection can be accepted right away.
+        let utp_connection_permit = match self.utp_connections.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                debug!(
+                    protocol = %self.protocol,
+                    request.source = %source,
+                    "Too many uTP connections to accept Offer"
+                );
+                return Self::empty_accept(content_keys.len());
+            }
+        };
+
+        let accepted_keys: Vec<&TContentKey> = content_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| requested_keys.get(*i).unwrap_or(false))
+            .map(|(_, key)| key)
+            .collect();
+        // Each accepted content item is prefixed with its length in the uTP payload.
+        let max_payload_size = accepted_keys
+            .iter()
+            .map(|key| key.max_content_size() + portal_wire::MAX_VARINT_SIZE)
+            .sum();
+        let accepted_keys: Vec<RawContentKey> = accepted_keys
+            .into_iter()
+            .map(|key| key.to_bytes())
+            .collect();
+        self.send_event(OverlayEvent::OfferAccepted {
+            source: *source,
+            content_keys: accepted_keys,
+        });
+
         // Generate a connection ID for the uTP connection if there is data we would like to
         // accept.
         let node_addr = self.discovery.cached_node_addr(source).ok_or_else(|| {
             OverlayRequestError::AcceptError("unable to find ENR for NodeId".to_string())
         })?;
-        let enr = crate::discovery::UtpEnr(node_addr.enr);
-        let cid = self.utp_socket.cid(enr, false);
+        let enr = UtpEnr(node_addr.enr);
+        let cid = self.inbound_utp_cid(enr);
         let cid_send = cid.send;
+        // Limit the data before the peer learns the connection ID, so that every packet counts.
+        let read_limit = self
+            .discovery
+            .utp_read_limits()
+            .limit(&cid, max_payload_size);
         let validator = Arc::clone(&self.validator);
         let store = Arc::clone(&self.store);
         let kbuckets = Arc::clone(&self.kbuckets);
         let command_tx = self.command_tx.clone();
         let utp = Arc::clone(&self.utp_socket);
         let metrics = self.metrics.clone();
+        let event_stream = self.event_stream.clone();
+        let gossip_config = self.gossip_config;
+        let offer_history = Arc::clone(&self.offer_history);
+        let rng = Arc::clone(&self.rng);
+        let rate_limiter = Arc::clone(rate_limiter);
+        let utp_connections = self.utp_connections.clone();
+        let source = *source;
 
         tokio::spawn(async move {
+            // Hold the stream permits until the transfer is over.
+            let _utp_permit = utp_permit;
+            let _utp_connection_permit = utp_connection_permit;
             // Wait for an incoming connection with the given CID. Then, read the data from the uTP
             // stream.
             metrics.report_utp_active_inc(UtpDirectionLabel::Inbound);
-            let mut stream = match utp.accept_with_cid(cid.clone(), *UTP_CONN_CFG).await {
+            let transfer_start = Instant::now();
+            let mut stream = match utp
+                .accept_with_cid(cid.clone(), utp_connections.config())
+                .await
+            {
                 Ok(stream) => stream,
                 Err(err) => {
                     metrics.report_utp_outcome(
@@ -1258,7 +1767,7 @@ where
             };
 
             let mut data = vec![];
-            if let Err(err) = stream.read_to_eof(&mut data).await {
+            if let Err(err) = read_to_eof_bounded(&mut stream, &mut data, &read_limit).await {
                 metrics
                     .report_utp_outcome(UtpDirectionLabel::Inbound, UtpOutcomeLabel::FailedDataTx);
                 error!(%err, cid.send, cid.recv, peer = ?cid.peer.client(), "error reading data from uTP stream, while handling an Offer request.");
@@ -1267,6 +1776,12 @@ where
 
             // report utp tx as successful, even if we go on to fail to process the payload
             metrics.report_utp_outcome(UtpDirectionLabel::Inbound, UtpOutcomeLabel::Success);
+            metrics.report_utp_transfer(
+                UtpDirectionLabel::Inbound,
+                data.len(),
+                transfer_start.elapsed(),
+            );
+            rate_limiter.consume_bytes(&source, data.len() as u64);
 
             if let Err(err) = Self::process_accept_utp_payload(
                 validator,
@@ -1274,6 +1789,10 @@ where
                 metrics,
                 kbuckets,
                 command_tx,
+                event_stream,
+                gossip_config,
+                offer_history,
+                rng,
                 content_keys,
                 data,
             )
@@ -1291,6 +1810,18 @@ where
         Ok(accept)
     }
 
+    /// Generates the ID of a uTP connection that `peer` is asked to initiate. The socket avoids
+    /// the IDs of live connections, and the IDs of connections that are limited, but not yet
+    /// open, are skipped as well.
+    fn inbound_utp_cid(&self, peer: UtpEnr) -> ConnectionId<UtpEnr> {
+        loop {
+            let cid = self.utp_socket.cid(peer.clone(), false);
+            if !self.discovery.utp_read_limits().is_limited(&cid) {
+                return cid;
+            }
+        }
+    }
+
     /// Sends a TALK request via Discovery v5 to some destination node.
     fn send_talk_req(&self, request: Request, request_id: OverlayRequestId, destination: Enr) {
         let discovery = Arc::clone(&self.discovery);
@@ -1414,6 +1945,25 @@ where
         let _ = self.update_node_connection_state(node_id, ConnectionState::Disconnected);
         // Remove the node from the ping queue.
         self.peers_to_ping.remove(&node_id);
+        self.penalize_peer(node_id, &error);
+    }
+
+    /// Lowers the reputation of a peer for a failed or invalid response. Once the score of the
+    /// peer falls to the ban threshold, the peer is evicted from the routing table and banned
+    /// at the discv5 level.
+    fn penalize_peer(&mut self, node_id: NodeId, error: &OverlayRequestError) {
+        if !self.peer_scores.write().penalize(&node_id, error) {
+            return;
+        }
+        warn!(
+            protocol = %self.protocol,
+            peer = %node_id,
+            ban.duration = ?PEER_BAN_DURATION,
+            "Banning peer with low reputation",
+        );
+        self.kbuckets.write().remove(&kbucket::Key::from(node_id));
+        self.peers_to_ping.remove(&node_id);
+        self.discovery.ban_node(&node_id, Some(PEER_BAN_DURATION));
     }
 
     /// Processes a response to an outgoing request from some source node.
@@ -1423,6 +1973,7 @@ where
         source: Enr,
         request: Request,
         query_id: Option<QueryId>,
+        utp_responder: Option<UtpTransferResponder>,
     ) {
         // If the node is present in the routing table, but the node is not connected, then
         // use the existing entry's value and direction. Otherwise, build a new entry from
@@ -1465,12 +2016,22 @@ where
             }
         }
 
+        self.peer_scores.write().reward(&source.node_id());
+
         match response {
             Response::Pong(pong) => self.process_pong(pong, source),
-            Response::Nodes(nodes) => self.process_nodes(nodes, source, query_id),
+            Response::Nodes(nodes) => {
+                // The ENRs are still processed, the peer only loses reputation.
+                if let Request::FindNodes(find_nodes) = &request {
+                    if !Self::nodes_match_distances(&nodes, &source, &find_nodes.distances) {
+                        self.penalize_peer(source.node_id(), &OverlayRequestError::InvalidResponse);
+                    }
+                }
+                self.process_nodes(nodes, source, query_id)
+            }
             Response::Content(content) => self.process_content(content, source, query_id),
             Response::Accept(accept) => {
-                if let Err(err) = self.process_accept(accept, source, request) {
+                if let Err(err) = self.process_accept(accept, source, request, utp_responder) {
                     error!(response.error = %err, "Error processing ACCEPT message")
                 }
             }
@@ -1478,7 +2039,13 @@ where
     }
 
     // Process ACCEPT response
-    fn process_accept(&self, response: Accept, enr: Enr, offer: Request) -> anyhow::Result<Accept> {
+    fn process_accept(
+        &self,
+        response: Accept,
+        enr: Enr,
+        offer: Request,
+        utp_responder: Option<UtpTransferResponder>,
+    ) -> anyhow::Result<Accept> {
         // Check that a valid triggering request was sent
         match &offer {
             Request::Offer(_) => {}
@@ -1495,10 +2062,11 @@ where
 
         // Build a connection ID based on the response.
         let conn_id = u16::from_be(response.connection_id);
+        let peer = enr.node_id();
         let cid = utp_rs::cid::ConnectionId {
             recv: conn_id,
             send: conn_id.wrapping_add(1),
-            peer: crate::discovery::UtpEnr(enr),
+            peer: UtpEnr(enr),
         };
 
         let store = Arc::clone(&self.store);
@@ -1506,10 +2074,28 @@ where
 
         let utp = Arc::clone(&self.utp_socket);
         let metrics = self.metrics.clone();
+        let utp_connections = self.utp_connections.clone();
 
         tokio::spawn(async move {
+            let _utp_permit = utp_connections.acquire().await;
             metrics.report_utp_active_inc(UtpDirectionLabel::Outbound);
-            let stream = match utp.connect_with_cid(cid.clone(), *UTP_CONN_CFG).await {
+            let transfer_start = Instant::now();
+            // Reports the outcome of the transfer, if requested.
+            let report_transfer = |size_bytes: usize, error: Option<String>| {
+                if let Some(responder) = utp_responder {
+                    let _ = responder.send(ContentTransfer {
+                        node: peer.into(),
+                        size_bytes: size_bytes as u64,
+                        duration_millis: u64::try_from(transfer_start.elapsed().as_millis())
+                            .unwrap_or(u64::MAX),
+                        error,
+                    });
+                }
+            };
+            let stream = match utp
+                .connect_with_cid(cid.clone(), utp_connections.config())
+                .await
+            {
                 Ok(stream) => stream,
                 Err(err) => {
                     metrics.report_utp_outcome(
@@ -1523,6 +2109,7 @@ where
                         peer = ?cid.peer.client(),
                         "Unable to establish uTP conn based on Accept",
                     );
+                    report_transfer(0, Some(err.to_string()));
                     return;
                 }
             };
@@ -1555,6 +2142,7 @@ where
                         peer = ?cid.peer.client(),
                         "Error decoding previously offered content items"
                     );
+                    report_transfer(0, Some(err.to_string()));
                     return;
                 }
             };
@@ -1563,19 +2151,24 @@ where
                 Ok(payload) => payload,
                 Err(err) => {
                     warn!(%err, "Unable to build content payload");
+                    report_transfer(0, Some(err.to_string()));
                     return;
                 }
             };
 
             // send the content to the acceptor over a uTP stream
-            if let Err(err) = Self::send_utp_content(stream, &content_payload, metrics).await {
-                warn!(
-                    %err,
-                    %cid.send,
-                    %cid.recv,
-                    peer = ?cid.peer.client(),
-                    "Error sending content over uTP, in response to ACCEPT"
-                );
+            match Self::send_utp_content(stream, &content_payload, metrics, transfer_start).await {
+                Ok(()) => report_transfer(content_payload.len(), None),
+                Err(err) => {
+                    warn!(
+                        %err,
+                        %cid.send,
+                        %cid.recv,
+                        peer = ?cid.peer.client(),
+                        "Error sending content over uTP, in response to ACCEPT"
+                    );
+                    report_transfer(content_payload.len(), Some(err.to_string()));
+                }
             }
         });
 
@@ -1583,12 +2176,17 @@ where
     }
 
     /// Process accepted uTP payload of the OFFER/ACCEPT stream
+    #[allow(clippy::too_many_arguments)]
     async fn process_accept_utp_payload(
         validator: Arc<TValidator>,
         store: Arc<RwLock<TStore>>,
         metrics: OverlayMetricsReporter,
         kbuckets: Arc<RwLock<KBucketsTable<NodeId, Node>>>,
         command_tx: UnboundedSender<OverlayCommand<TContentKey>>,
+        event_stream: broadcast::Sender<EventEnvelope>,
+        gossip_config: GossipConfig,
+        offer_history: Arc<RwLock<OfferHistory>>,
+        rng: OverlayRng,
         content_keys: Vec<TContentKey>,
         payload: Vec<u8>,
     ) -> anyhow::Result<()> {
@@ -1602,6 +2200,18 @@ where
                 "Content keys len {keys_len} doesn't match content values len {vals_len}."
             ));
         }
+        if let Some((key, content_value)) = content_keys
+            .iter()
+            .zip(&content_values)
+            .find(|(key, content_value)| content_value.len() > key.max_content_size())
+        {
+            return Err(anyhow!(
+                "Content value of {} bytes for {} exceeds the max size of {} bytes",
+                content_value.len(),
+                key.to_hex(),
+                key.max_content_size()
+            ));
+        }
 
         let handles: Vec<JoinHandle<_>> = content_keys
             .into_iter()
@@ -1615,6 +2225,7 @@ where
                 let validator = Arc::clone(&validator);
                 let store = Arc::clone(&store);
                 let metrics = metrics.clone();
+                let event_stream = event_stream.clone();
                 tokio::spawn(async move {
                     // Validated received content
                     if let Err(err) = validator
@@ -1631,6 +2242,15 @@ where
                         return None;
                     }
                     metrics.report_validation(true);
+                    if event_stream.receiver_count() > 0 {
+                        Self::send_event_to(
+                            &event_stream,
+                            OverlayEvent::ContentReceived {
+                                content_key: key.to_bytes(),
+                                content_value: content_value.to_vec(),
+                            },
+                        );
+                    }
 
                     // Check if data should be stored, and store if it is within our radius and not already stored.
                     let key_desired = store.read().is_key_within_radius_and_unavailable(&key);
@@ -1680,15 +2300,23 @@ where
             .map(|(k, _)| hex_encode_compact(k.content_id()))
             .collect();
         debug!(ids = ?validated_ids, "propagating validated content");
-        propagate_gossip_cross_thread(validated_content, kbuckets, command_tx.clone());
+        tokio::spawn(propagate_gossip_cross_thread(
+            validated_content,
+            kbuckets,
+            command_tx,
+            gossip_config,
+            offer_history,
+            rng,
+        ));
 
         Ok(())
     }
 
     async fn send_utp_content(
-        mut stream: UtpStream<crate::discovery::UtpEnr>,
+        mut stream: UtpStream<UtpEnr>,
         content: &[u8],
         metrics: OverlayMetricsReporter,
+        transfer_start: Instant,
     ) -> anyhow::Result<()> {
         match stream.write(content).await {
             Ok(write_size) => {
@@ -1717,6 +2345,11 @@ where
             return Err(anyhow!("Error closing uTP connection: {err}"));
         };
         metrics.report_utp_outcome(UtpDirectionLabel::Outbound, UtpOutcomeLabel::Success);
+        metrics.report_utp_transfer(
+            UtpDirectionLabel::Outbound,
+            content.len(),
+            transfer_start.elapsed(),
+        );
         Ok(())
     }
 
@@ -1769,6 +2402,20 @@ where
         };
     }
 
+    /// Returns whether every ENR of a Nodes response is at one of the requested distances from the
+    /// responding node.
+    fn nodes_match_distances(nodes: &Nodes, source: &Enr, distances: &[u16]) -> bool {
+        let source_id = source.node_id().raw();
+        nodes.enrs.iter().all(|ssz_enr| {
+            let distance = TMetric::distance(&source_id, &ssz_enr.0.node_id().raw())
+                .log2()
+                .unwrap_or(0);
+            distances
+                .iter()
+                .any(|requested| usize::from(*requested) == distance)
+        })
+    }
+
     /// Processes a Nodes response.
     fn process_nodes(&mut self, nodes: Nodes, source: Enr, query_id: Option<QueryId>) {
         trace!(
@@ -1829,10 +2476,11 @@ where
         content: Vec<u8>,
         utp_transfer: bool,
         content_key: TContentKey,
-        responder: Option<oneshot::Sender<RecursiveFindContentResult>>,
-        trace: Option<QueryTrace>,
+        source: NodeId,
+        mut responder: FindContentResponder,
         nodes_to_poke: Vec<NodeId>,
         metrics: OverlayMetricsReporter,
+        event_stream: broadcast::Sender<EventEnvelope>,
     ) {
         let mut content = content;
         // Operate under assumption that all content in the store is valid
@@ -1851,19 +2499,47 @@ where
                     content.key = %content_key,
                     "Error validating content"
                 );
-                if let Some(responder) = responder {
-                    let _ = responder.send((None, utp_transfer, trace));
+                if let Some(trace) = responder.trace_mut() {
+                    trace.content_validated(source.into(), Err(err.to_string()));
+                }
+                // Only content that is provably invalid is the fault of the peer. Content that
+                // can't be validated yet, e.g. while the chain is syncing, may well be valid.
+                if err.is_invalid() {
+                    let _ = command_tx.send(OverlayCommand::PenalizePeer {
+                        node_id: source,
+                        error: OverlayRequestError::FailedValidation(err.to_string()),
+                    });
                 }
+                responder.respond(None, utp_transfer, QueryTermination::ValidationFailed);
                 return;
             };
             metrics.report_validation(true);
+            if let Some(trace) = responder.trace_mut() {
+                trace.content_validated(source.into(), Ok(()));
+            }
+            if event_stream.receiver_count() > 0 {
+                Self::send_event_to(
+                    &event_stream,
+                    OverlayEvent::ContentReceived {
+                        content_key: content_key.to_bytes(),
+                        content_value: content.clone(),
+                    },
+                );
+            }
 
             // skip storing if the content is already stored
             // or if there's an error reading the store
-            let should_store = match store
+            let should_store = store
                 .read()
-                .is_key_within_radius_and_unavailable(&content_key)
-            {
+                .is_key_within_radius_and_unavailable(&content_key);
+            let should_store = match should_store {
+                Ok(ShouldWeStoreContent::NotWithinRadius) => {
+                    // Cache the content, in case it is looked up again soon.
+                    store
+                        .write()
+                        .put_cached(content_key.clone(), content.clone());
+                    false
+                }
                 Ok(val) => matches!(val, ShouldWeStoreContent::Store),
                 Err(msg) => {
                     error!("Unable to read store: {}", msg);
@@ -1881,9 +2557,11 @@ where
                 }
             }
         }
-        if let Some(responder) = responder {
-            let _ = responder.send((Some(content.clone()), utp_transfer, trace));
-        }
+        responder.respond(
+            Some(content.clone()),
+            utp_transfer,
+            QueryTermination::ContentFound,
+        );
         Self::poke_content(kbuckets, command_tx, content_key, content, nodes_to_poke);
     }
 
@@ -1945,6 +2623,10 @@ where
                     InsertResult::Inserted => {
                         debug!(inserted = %node_id, "Inserted discovered node into routing table");
                         self.peers_to_ping.insert(node_id);
+                        Self::send_event_to(
+                            &self.event_stream,
+                            OverlayEvent::NodeAdded { node_id },
+                        );
                     }
                     InsertResult::Pending { disconnected } => {
                         // The disconnected node is the least-recently connected entry that is
@@ -2018,6 +2700,7 @@ where
         // If so, advance the query with the returned data.
         let local_node_id = self.local_enr().node_id();
         if let Some((query_info, query)) = self.find_node_query_pool.write().get_mut(query_id) {
+            let mut new_enrs: Vec<&Enr> = vec![];
             for enr_ref in enrs.iter() {
                 if !query_info
                     .untrusted_enrs
@@ -2025,8 +2708,12 @@ where
                     .any(|enr| enr.node_id() == enr_ref.node_id() && enr.node_id() != local_node_id)
                 {
                     query_info.untrusted_enrs.push(enr_ref.clone());
+                    new_enrs.push(enr_ref);
                 }
             }
+            if let Some(trace) = &mut query_info.trace {
+                trace.node_responded_with(&source, new_enrs);
+            }
             query.on_success(
                 &source.node_id(),
                 enrs.iter().map(|enr| enr.into()).collect(),
@@ -2173,6 +2860,7 @@ where
                 );
 
                 self.peers_to_ping.insert(node_id);
+                self.send_event(OverlayEvent::NodeAdded { node_id });
             }
             InsertResult::Pending { disconnected } => {
                 // The disconnected node is the least-recently connected entry that is
@@ -2336,11 +3024,60 @@ where
             .collect()
     }
 
+    /// Applies the query parameters that are set in `tuning`. Queries that are already running
+    /// keep their parallelism and peer timeout, but are subject to the new query timeout.
+    fn apply_tuning(&mut self, tuning: &OverlayTuning) {
+        if let Some(query_parallelism) = tuning.query_parallelism {
+            self.query_parallelism = query_parallelism;
+        }
+        if let Some(query_timeout_ms) = tuning.query_timeout_ms {
+            let query_timeout = Duration::from_millis(query_timeout_ms);
+            self.find_node_query_pool
+                .write()
+                .set_query_timeout(query_timeout);
+            self.find_content_query_pool
+                .write()
+                .set_query_timeout(query_timeout);
+        }
+        if let Some(query_peer_timeout_ms) = tuning.query_peer_timeout_ms {
+            self.query_peer_timeout = Duration::from_millis(query_peer_timeout_ms);
+        }
+        if let Some(query_num_results) = tuning.query_num_results {
+            self.query_num_results = query_num_results;
+        }
+        if let Some(findnodes_query_distances_per_peer) = tuning.findnodes_query_distances_per_peer
+        {
+            self.findnodes_query_distances_per_peer = findnodes_query_distances_per_peer;
+        }
+        info!(protocol = %self.protocol, tuning = ?self.tuning(), "Updated overlay parameters");
+    }
+
+    /// Returns the current query parameters.
+    fn tuning(&self) -> OverlayTuning {
+        OverlayTuning {
+            query_parallelism: Some(self.query_parallelism),
+            query_timeout_ms: Some(
+                self.find_node_query_pool.read().query_timeout().as_millis() as u64
+            ),
+            query_peer_timeout_ms: Some(self.query_peer_timeout.as_millis() as u64),
+            query_num_results: Some(self.query_num_results),
+            max_incoming_per_bucket: None,
+            findnodes_query_distances_per_peer: Some(self.findnodes_query_distances_per_peer),
+            utp_max_packet_size: None,
+            utp_max_conn_attempts: None,
+            utp_idle_timeout_ms: None,
+            utp_initial_timeout_ms: None,
+            utp_target_delay_ms: None,
+            utp_max_connections: None,
+        }
+    }
+
     /// Starts a FindNode query to find nodes with IDs closest to `target`.
     fn init_find_nodes_query(
         &mut self,
         target: &NodeId,
-        callback: Option<oneshot::Sender<Vec<Enr>>>,
+        callback: Option<oneshot::Sender<RecursiveFindNodesResult>>,
+        is_trace: bool,
     ) -> Option<QueryId> {
         let target_key = Key::from(*target);
 
@@ -2368,6 +3105,13 @@ where
             peer_timeout: self.query_peer_timeout,
         };
 
+        let trace = is_trace.then(|| {
+            let local_enr = self.local_enr();
+            let mut trace = QueryTrace::new(&local_enr, (*target).into());
+            trace.node_responded_with(&local_enr, closest_enrs.iter().collect());
+            trace
+        });
+
         let query_info = QueryInfo {
             query_type: QueryType::FindNode {
                 target: *target,
@@ -2375,7 +3119,7 @@ where
                 callback,
             },
             untrusted_enrs: SmallVec::from_vec(closest_enrs),
-            trace: None,
+            trace,
         };
 
         let known_closest_peers: Vec<Key<NodeId>> = query_info
@@ -2433,9 +3177,16 @@ where
         if closest_enrs.is_empty() {
             // If there are no nodes whatsoever in the routing table the query cannot proceed.
             warn!("No nodes in routing table, query cannot proceed.");
-            if let Some(callback) = callback {
-                let _ = callback.send((None, false, None));
-            }
+            let trace = QueryTrace::new(&self.local_enr(), target_node_id.into());
+            let callbacks = callback
+                .map(|callback| (callback, is_trace))
+                .into_iter()
+                .collect();
+            self.find_content_responder(callbacks, Some(trace)).respond(
+                None,
+                false,
+                QueryTermination::NoPeers,
+            );
             return None;
         }
 
@@ -2445,21 +3196,20 @@ where
             .map(|enr| Key::from(enr.node_id()))
             .collect();
 
-        let trace: Option<QueryTrace> = {
-            if is_trace {
-                let mut trace = QueryTrace::new(&self.local_enr(), target_node_id.into());
-                let local_enr = self.local_enr();
-                trace.node_responded_with(&local_enr, closest_enrs.iter().collect());
-                Some(trace)
-            } else {
-                None
-            }
-        };
+        // Content queries are always traced, so that they can be inspected once completed.
+        let local_enr = self.local_enr();
+        let mut trace = QueryTrace::new(&local_enr, target_node_id.into());
+        trace.node_responded_with(&local_enr, closest_enrs.iter().collect());
 
         let query_info = QueryInfo {
-            query_type: QueryType::FindContent { target, callback },
+            query_type: QueryType::FindContent {
+                target,
+                callback,
+                is_trace,
+                coalesced_callbacks: vec![],
+            },
             untrusted_enrs: SmallVec::from_vec(closest_enrs),
-            trace,
+            trace: Some(trace),
         };
 
         let query = FindContentQuery::with_config(query_config, target_key, closest_nodes);
@@ -2470,15 +3220,89 @@ where
         )
     }
 
-    /// Returns an ENR if one is known for the given NodeId.
-    pub fn find_enr(&self, node_id: &NodeId) -> Option<Enr> {
-        // Check whether we know this node id in our routing table.
-        let key = kbucket::Key::from(*node_id);
-        if let kbucket::Entry::Present(entry, _) = self.kbuckets.write().entry(&key) {
-            return Some(entry.value().clone().enr());
-        }
-
-        // Check whether this node id is in our discovery ENR cache
+    /// Adds `callback` to the in-progress find content query for `target`, if there is one, so
+    /// that concurrent lookups of the same content share a single query. Returns the ID of the
+    /// joined query, or gives `callback` back if there is no query to join.
+    fn coalesce_find_content_query(
+        &self,
+        target: &TContentKey,
+        callback: oneshot::Sender<RecursiveFindContentResult>,
+        is_trace: bool,
+    ) -> Result<QueryId, oneshot::Sender<RecursiveFindContentResult>> {
+        let content_id = target.content_id();
+        let mut pool = self.find_content_query_pool.write();
+        for (query_id, (query_info, _)) in pool.iter_mut() {
+            if let QueryType::FindContent {
+                target: query_target,
+                coalesced_callbacks,
+                ..
+            } = &mut query_info.query_type
+            {
+                if query_target.content_id() == content_id {
+                    coalesced_callbacks.push((callback, is_trace));
+                    return Ok(query_id);
+                }
+            }
+        }
+        Err(callback)
+    }
+
+    /// Builds the responder for the result of a find content query.
+    fn find_content_responder(
+        &self,
+        callbacks: Vec<(oneshot::Sender<RecursiveFindContentResult>, bool)>,
+        trace: Option<QueryTrace>,
+    ) -> FindContentResponder {
+        FindContentResponder {
+            callbacks,
+            trace,
+            recent_traces: Arc::clone(&self.recent_traces),
+        }
+    }
+
+    /// Records a failed request in the trace of the query it was sent for.
+    fn trace_query_request_failure(
+        &self,
+        query_id: QueryId,
+        request: &Request,
+        destination: &Enr,
+        error: &OverlayRequestError,
+    ) {
+        let record_failure = |query_info: &mut QueryInfo<TContentKey>| {
+            if let Some(trace) = &mut query_info.trace {
+                trace.node_request_failed(
+                    &destination.node_id().into(),
+                    error.kind(),
+                    error.to_string(),
+                );
+            }
+        };
+        match request {
+            Request::FindNodes(_) => {
+                if let Some((query_info, _)) = self.find_node_query_pool.write().get_mut(query_id) {
+                    record_failure(query_info);
+                }
+            }
+            Request::FindContent(_) => {
+                if let Some((query_info, _)) =
+                    self.find_content_query_pool.write().get_mut(query_id)
+                {
+                    record_failure(query_info);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns an ENR if one is known for the given NodeId.
+    pub fn find_enr(&self, node_id: &NodeId) -> Option<Enr> {
+        // Check whether we know this node id in our routing table.
+        let key = kbucket::Key::from(*node_id);
+        if let kbucket::Entry::Present(entry, _) = self.kbuckets.write().entry(&key) {
+            return Some(entry.value().clone().enr());
+        }
+
+        // Check whether this node id is in our discovery ENR cache
         if let Some(node_addr) = self.discovery.cached_node_addr(node_id) {
             return Some(node_addr.enr);
         }
@@ -2509,75 +3333,246 @@ where
     }
 
     /// Send `OverlayEvent` to the event stream.
-    #[allow(dead_code)] // TODO: remove when used
-    fn send_event(&mut self, event: OverlayEvent) {
-        if let Some(stream) = self.event_stream.as_mut() {
-            let event = EventEnvelope::new(event);
-            if let Err(mpsc::error::TrySendError::Closed(_)) = stream.try_send(event) {
-                // If the stream has been dropped prevent future attempts to send events
-                self.event_stream = None;
-            }
-        }
+    fn send_event(&self, event: OverlayEvent) {
+        Self::send_event_to(&self.event_stream, event);
+    }
+
+    /// Send `OverlayEvent` to the given event stream, for tasks that don't hold the service.
+    fn send_event_to(event_stream: &broadcast::Sender<EventEnvelope>, event: OverlayEvent) {
+        // Sending only fails if nobody is subscribed, in which case the event is dropped.
+        let _ = event_stream.send(EventEnvelope::new(event));
     }
 }
 
-// Propagate gossip in a way that can be used across threads, without &self
-pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey>(
+/// Propagates gossip in a way that can be used across threads, without &self, and waits for the
+/// outcome of every offer. Returns the number of content items that were accepted by, and
+/// successfully transferred to, interested peers.
+pub async fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey>(
     content: Vec<(TContentKey, Vec<u8>)>,
     kbuckets: Arc<RwLock<KBucketsTable<NodeId, Node>>>,
     command_tx: mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    gossip_config: GossipConfig,
+    offer_history: Arc<RwLock<OfferHistory>>,
+    rng: OverlayRng,
 ) -> usize {
-    // Get all connected nodes from overlay routing table
-    let kbuckets = kbuckets.read();
-    let mut all_nodes: Vec<&kbucket::Node<NodeId, Node>> = kbuckets
-        .buckets_iter()
-        .flat_map(|kbucket| {
-            kbucket
-                .iter()
-                .filter(|node| node.status.is_connected())
-                .collect::<Vec<&kbucket::Node<NodeId, Node>>>()
-        })
-        .collect();
+    trace_propagate_gossip_cross_thread(
+        content,
+        kbuckets,
+        command_tx,
+        gossip_config,
+        offer_history,
+        rng,
+    )
+    .await
+    .num_stored()
+}
 
-    if all_nodes.is_empty() {
-        warn!("No connected nodes, using disconnected nodes for gossip.");
-        all_nodes = kbuckets
-            .buckets_iter()
-            .flat_map(|kbucket| {
-                kbucket
-                    .iter()
-                    .collect::<Vec<&kbucket::Node<NodeId, Node>>>()
+/// Propagates gossip like `propagate_gossip_cross_thread`. Returns the peers selected for each
+/// content key, and the outcome of each offer.
+pub async fn trace_propagate_gossip_cross_thread<TContentKey: OverlayContentKey>(
+    content: Vec<(TContentKey, Vec<u8>)>,
+    kbuckets: Arc<RwLock<KBucketsTable<NodeId, Node>>>,
+    command_tx: mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    gossip_config: GossipConfig,
+    offer_history: Arc<RwLock<OfferHistory>>,
+    rng: OverlayRng,
+) -> GossipTrace {
+    let (offers, recipients) = select_gossip_offers(
+        content,
+        &kbuckets,
+        &command_tx,
+        gossip_config,
+        &offer_history,
+        &rng,
+    )
+    .await;
+
+    let offer_traces = offers
+        .into_iter()
+        .map(|(node_id, (enr, interested_content))| {
+            let content_keys: Vec<RawContentKey> = interested_content
+                .iter()
+                .map(|(content_key, _)| content_key.clone())
+                .collect();
+            let offer_request = Request::PopulatedOffer(PopulatedOffer {
+                content_items: interested_content,
+            });
+            let command_tx = command_tx.clone();
+            let offer_history = Arc::clone(&offer_history);
+            async move {
+                let offer_trace = trace_offer_cross_thread(offer_request, enr, &command_tx).await;
+                record_offer_responses(&offer_history, node_id, content_keys, &offer_trace);
+                (node_id.into(), offer_trace)
+            }
+        });
+    GossipTrace {
+        recipients: recipients
+            .into_iter()
+            .map(|(content_key, node_ids)| {
+                let node_ids = node_ids.into_iter().map(|node_id| node_id.into()).collect();
+                (content_key, node_ids)
             })
-            .collect();
+            .collect(),
+        offers: join_all(offer_traces).await.into_iter().collect(),
     }
+}
 
-    if all_nodes.is_empty() {
-        // If there are no nodes whatsoever in the routing table the gossip cannot proceed.
-        warn!("No nodes in routing table, gossip cannot proceed.");
-        return 0;
+/// Records which of `content_keys` were declined by `node_id`, and which were accepted and
+/// transferred to it. Content that was accepted, but failed to transfer, may be offered again, and
+/// so may declined content once the decline expires.
+fn record_offer_responses(
+    offer_history: &RwLock<OfferHistory>,
+    node_id: NodeId,
+    content_keys: Vec<RawContentKey>,
+    offer_trace: &OfferTrace,
+) {
+    let accepted = match &offer_trace.accepted {
+        Some(accepted) => accepted,
+        None => return,
+    };
+    let transferred = matches!(&offer_trace.transfer, Some(transfer) if transfer.error.is_none());
+    let mut offer_history = offer_history.write();
+    for (content_key, accepted) in content_keys.into_iter().zip(accepted) {
+        if !accepted || transferred {
+            offer_history.record(content_key, node_id, *accepted);
+        }
     }
+}
 
-    // HashMap to temporarily store all interested ENRs and the content.
-    // Key is base64 string of node's ENR.
-    let mut enrs_and_content: HashMap<String, Vec<(RawContentKey, Vec<u8>)>> = HashMap::new();
+/// Sends an OFFER request to `enr`, and waits for the ACCEPT response and the uTP transfer of
+/// the accepted content.
+pub async fn trace_offer_cross_thread<TContentKey: OverlayContentKey>(
+    offer: Request,
+    enr: Enr,
+    command_tx: &mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+) -> OfferTrace {
+    let content_keys = match &offer {
+        Request::Offer(offer) => offer.content_keys.iter().map(hex_encode).collect(),
+        Request::PopulatedOffer(offer) => offer
+            .content_items
+            .iter()
+            .map(|(content_key, _)| hex_encode(content_key))
+            .collect(),
+        _ => vec![],
+    };
+    let mut offer_trace = OfferTrace::new(content_keys);
+
+    let (tx, rx) = oneshot::channel();
+    let (utp_tx, utp_rx) = oneshot::channel();
+    let mut overlay_request = OverlayRequest::new(
+        offer,
+        RequestDirection::Outgoing { destination: enr },
+        Some(tx),
+        None,
+    );
+    overlay_request.utp_responder = Some(utp_tx);
+    if let Err(err) = command_tx.send(OverlayCommand::Request(overlay_request)) {
+        offer_trace.error = Some(OverlayRequestError::ChannelFailure(err.to_string()).to_string());
+        return offer_trace;
+    }
 
-    // Filter all nodes from overlay routing table where XOR_distance(content_id, nodeId) < node radius
-    for (content_key, content_value) in content {
-        let mut interested_enrs: Vec<Enr> = all_nodes
-            .clone()
-            .into_iter()
-            .filter(|node| {
-                XorMetric::distance(&content_key.content_id(), &node.key.preimage().raw())
-                    < node.value.data_radius()
-            })
-            .map(|node| node.value.enr())
-            .collect();
+    match rx.await {
+        Ok(Ok(Response::Accept(accept))) => {
+            offer_trace.accepted = Some(accept.content_keys.iter().collect());
+            // The responder is dropped without a result if no content is transferred.
+            offer_trace.transfer = utp_rx.await.ok();
+        }
+        Ok(Ok(_)) => offer_trace.error = Some(OverlayRequestError::InvalidResponse.to_string()),
+        Ok(Err(err)) => offer_trace.error = Some(err.to_string()),
+        Err(err) => {
+            offer_trace.error =
+                Some(OverlayRequestError::ChannelFailure(err.to_string()).to_string())
+        }
+    }
+    offer_trace
+}
+
+/// The content to offer to each selected gossip recipient, keyed by the recipient's node ID.
+/// Ordered by node ID, so that the offers are sent in a reproducible order.
+type GossipOffers = BTreeMap<NodeId, (Enr, Vec<(RawContentKey, Vec<u8>)>)>;
+
+/// Selects the recipients of `content` and the content to offer to each of them. Recipients are
+/// selected from the interested peers in the routing table, and from the nodes closest to the
+/// content if too few peers in the routing table are interested. Peers that already accepted or
+/// declined a content key are not offered it again. Also returns the recipients selected for
+/// each content key, keyed by the hex encoded content key.
+async fn select_gossip_offers<TContentKey: OverlayContentKey>(
+    content: Vec<(TContentKey, Vec<u8>)>,
+    kbuckets: &RwLock<KBucketsTable<NodeId, Node>>,
+    command_tx: &mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    gossip_config: GossipConfig,
+    offer_history: &RwLock<OfferHistory>,
+    rng: &Mutex<StdRng>,
+) -> (GossipOffers, HashMap<String, Vec<NodeId>>) {
+    let mut offers: GossipOffers = BTreeMap::new();
+    let mut recipients: HashMap<String, Vec<NodeId>> = HashMap::new();
+
+    let interested_content = match interested_enrs(content, kbuckets) {
+        Some(interested_content) => interested_content,
+        None => return (offers, recipients),
+    };
+
+    // Look up the nodes closest to the content that too few peers in the routing table are
+    // interested in, starting with the content that the fewest peers are interested in. Each
+    // content ID is looked up at most once, and at most `max_lookups` are looked up.
+    let mut lookup_targets: Vec<(usize, NodeId)> = interested_content
+        .iter()
+        .filter(|(_, _, interested_enrs)| {
+            interested_enrs.len() < gossip_config.min_interested_nodes
+        })
+        .map(|(content_key, _, interested_enrs)| {
+            (
+                interested_enrs.len(),
+                NodeId::new(&content_key.content_id()),
+            )
+        })
+        .collect();
+    lookup_targets.sort();
+    let mut unique_targets = HashSet::new();
+    let lookup_targets: Vec<NodeId> = lookup_targets
+        .into_iter()
+        .map(|(_, target)| target)
+        .filter(|target| unique_targets.insert(*target))
+        .take(gossip_config.max_lookups)
+        .collect();
+    let lookups = lookup_targets
+        .iter()
+        .map(|target| find_nodes_cross_thread(*target, command_tx));
+    let lookup_results = join_all(lookups).await;
+    let closest_enrs: HashMap<NodeId, Vec<Enr>> =
+        lookup_targets.into_iter().zip(lookup_results).collect();
+
+    for (content_key, content_value, mut interested_enrs) in interested_content {
+        let content_key_recipients = recipients
+            .entry(hex_encode(content_key.to_bytes()))
+            .or_default();
+        let content_id = content_key.content_id();
+        let raw_content_key: RawContentKey = content_key.into();
+        let closest_enrs = closest_enrs
+            .get(&NodeId::new(&content_id))
+            .cloned()
+            .unwrap_or_default();
+
+        // The radius of the looked up nodes is unknown, but as the closest nodes to the content,
+        // they are the most likely to be interested in it.
+        for enr in closest_enrs {
+            if !interested_enrs
+                .iter()
+                .any(|interested| interested.node_id() == enr.node_id())
+            {
+                interested_enrs.push(enr);
+            }
+        }
+        {
+            let offer_history = offer_history.read();
+            interested_enrs
+                .retain(|enr| !offer_history.was_offered(&raw_content_key, &enr.node_id()));
+        }
 
         // Continue if no nodes are interested in the content
         if interested_enrs.is_empty() {
             debug!(
-                content.id = %hex_encode(content_key.content_id()),
-                kbuckets.len = all_nodes.len(),
+                content.id = %hex_encode(content_id),
                 "No peers eligible for neighborhood gossip"
             );
             continue;
@@ -2585,72 +3580,122 @@ pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey>(
 
         // Sort all eligible nodes by proximity to the content.
         interested_enrs.sort_by(|a, b| {
-            let distance_a = XorMetric::distance(&content_key.content_id(), &a.node_id().raw());
-            let distance_b = XorMetric::distance(&content_key.content_id(), &b.node_id().raw());
+            let distance_a = XorMetric::distance(&content_id, &a.node_id().raw());
+            let distance_b = XorMetric::distance(&content_id, &b.node_id().raw());
             distance_a.partial_cmp(&distance_b).unwrap_or_else(|| {
                 warn!(a = %distance_a, b = %distance_b, "Error comparing two distances");
                 std::cmp::Ordering::Less
             })
         });
 
-        let gossip_recipients = select_gossip_recipients(interested_enrs);
+        let gossip_recipients =
+            select_gossip_recipients(interested_enrs, &gossip_config, &mut *rng.lock());
 
         // Temporarily store all randomly selected nodes with the content of interest.
         // We want this so we can offer all the content to interested node in one request.
-        let raw_item = (content_key.into(), content_value);
+        let raw_item = (raw_content_key, content_value);
         for enr in gossip_recipients {
-            enrs_and_content
-                .entry(enr.to_base64())
-                .or_default()
+            content_key_recipients.push(enr.node_id());
+            offers
+                .entry(enr.node_id())
+                .or_insert_with(|| (enr, vec![]))
+                .1
                 .push(raw_item.clone());
         }
     }
 
-    let num_propagated_peers = enrs_and_content.len();
-    // Create and send OFFER overlay request to the interested nodes
-    for (enr_string, interested_content) in enrs_and_content.into_iter() {
-        let enr = match Enr::from_str(&enr_string) {
-            Ok(enr) => enr,
-            Err(err) => {
-                error!(error = %err, enr.base64 = %enr_string, "Error decoding ENR from base-64");
-                continue;
-            }
-        };
+    (offers, recipients)
+}
 
-        let offer_request = Request::PopulatedOffer(PopulatedOffer {
-            content_items: interested_content,
-        });
+/// Pairs each piece of `content` with the peers in the routing table that are interested in it,
+/// where XOR_distance(content_id, nodeId) < node radius. Returns `None` if the routing table is
+/// empty.
+#[allow(clippy::type_complexity)]
+fn interested_enrs<TContentKey: OverlayContentKey>(
+    content: Vec<(TContentKey, Vec<u8>)>,
+    kbuckets: &RwLock<KBucketsTable<NodeId, Node>>,
+) -> Option<Vec<(TContentKey, Vec<u8>, Vec<Enr>)>> {
+    // Get all connected nodes from overlay routing table
+    let kbuckets = kbuckets.read();
+    let mut all_nodes: Vec<&kbucket::Node<NodeId, Node>> = kbuckets
+        .buckets_iter()
+        .flat_map(|kbucket| {
+            kbucket
+                .iter()
+                .filter(|node| node.status.is_connected())
+                .collect::<Vec<&kbucket::Node<NodeId, Node>>>()
+        })
+        .collect();
 
-        let overlay_request = OverlayRequest::new(
-            offer_request,
-            RequestDirection::Outgoing { destination: enr },
-            None,
-            None,
-        );
+    if all_nodes.is_empty() {
+        warn!("No connected nodes, using disconnected nodes for gossip.");
+        all_nodes = kbuckets
+            .buckets_iter()
+            .flat_map(|kbucket| {
+                kbucket
+                    .iter()
+                    .collect::<Vec<&kbucket::Node<NodeId, Node>>>()
+            })
+            .collect();
+    }
 
-        if let Err(err) = command_tx.send(OverlayCommand::Request(overlay_request)) {
-            error!(error = %err, "Error sending OFFER message to service")
-        }
+    if all_nodes.is_empty() {
+        // If there are no nodes whatsoever in the routing table the gossip cannot proceed.
+        warn!("No nodes in routing table, gossip cannot proceed.");
+        return None;
     }
 
-    num_propagated_peers
+    let interested_content = content
+        .into_iter()
+        .map(|(content_key, content_value)| {
+            let interested_enrs: Vec<Enr> = all_nodes
+                .iter()
+                .filter(|node| {
+                    XorMetric::distance(&content_key.content_id(), &node.key.preimage().raw())
+                        < node.value.data_radius()
+                })
+                .map(|node| node.value.enr())
+                .collect();
+            (content_key, content_value, interested_enrs)
+        })
+        .collect();
+    Some(interested_content)
+}
+
+/// Looks up the nodes closest to `target` in the network.
+async fn find_nodes_cross_thread<TContentKey: OverlayContentKey>(
+    target: NodeId,
+    command_tx: &mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+) -> Vec<Enr> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(err) = command_tx.send(OverlayCommand::FindNodeQuery {
+        target,
+        callback: tx,
+        is_trace: false,
+    }) {
+        warn!(error = %err, "Error submitting FindNode query to service");
+        return vec![];
+    }
+    rx.await.map(|(enrs, _)| enrs).unwrap_or_default()
 }
 
 /// Randomly select `num_enrs` nodes from `enrs`.
-fn select_random_enrs(num_enrs: usize, enrs: Vec<Enr>) -> Vec<Enr> {
-    let random_enrs: Vec<Enr> = enrs
-        .into_iter()
-        .choose_multiple(&mut rand::thread_rng(), num_enrs);
+fn select_random_enrs<R: Rng + ?Sized>(num_enrs: usize, enrs: Vec<Enr>, rng: &mut R) -> Vec<Enr> {
+    let random_enrs: Vec<Enr> = enrs.into_iter().choose_multiple(rng, num_enrs);
     random_enrs
 }
 
-const NUM_CLOSEST_NODES: usize = 4;
-const NUM_FARTHER_NODES: usize = 4;
 /// Selects gossip recipients from a vec of sorted interested ENRs.
 /// Returned vec is a concatenation of, at most:
-/// 1. First `NUM_CLOSEST_NODES` elements of `interested_sorted_enrs`.
-/// 2. `NUM_FARTHER_NODES` elements randomly selected from `interested_sorted_enrs[NUM_CLOSEST_NODES..]`
-fn select_gossip_recipients(interested_sorted_enrs: Vec<Enr>) -> Vec<Enr> {
+/// 1. First `num_closest_nodes` elements of `interested_sorted_enrs`.
+/// 2. `num_farther_nodes` elements randomly selected from
+///    `interested_sorted_enrs[num_closest_nodes..]`
+fn select_gossip_recipients<R: Rng + ?Sized>(
+    interested_sorted_enrs: Vec<Enr>,
+    gossip_config: &GossipConfig,
+    rng: &mut R,
+) -> Vec<Enr> {
+    let num_closest_nodes = gossip_config.num_closest_nodes;
     let mut gossip_recipients: Vec<Enr> = vec![];
 
     // Get first n closest nodes
@@ -2658,12 +3703,13 @@ fn select_gossip_recipients(interested_sorted_enrs: Vec<Enr>) -> Vec<Enr> {
         interested_sorted_enrs
             .clone()
             .into_iter()
-            .take(NUM_CLOSEST_NODES),
+            .take(num_closest_nodes),
     );
-    if interested_sorted_enrs.len() > NUM_CLOSEST_NODES {
-        let farther_enrs = interested_sorted_enrs[NUM_CLOSEST_NODES..].to_vec();
+    if interested_sorted_enrs.len() > num_closest_nodes {
+        let farther_enrs = interested_sorted_enrs[num_closest_nodes..].to_vec();
         // Get random non-close ENRs to gossip to.
-        let random_farther_enrs = select_random_enrs(NUM_FARTHER_NODES, farther_enrs);
+        let random_farther_enrs =
+            select_random_enrs(gossip_config.num_farther_nodes, farther_enrs, rng);
         gossip_recipients.extend(random_farther_enrs);
     }
     gossip_recipients
@@ -2693,10 +3739,11 @@ mod tests {
     use super::*;
 
     use std::net::SocketAddr;
-    use std::time::Instant;
 
+    use async_trait::async_trait;
     use discv5::kbucket::Entry;
     use ethereum_types::U256;
+    use rand::SeedableRng;
     use rstest::*;
     use serial_test::serial;
     use tokio::sync::mpsc::unbounded_channel;
@@ -2705,15 +3752,18 @@ mod tests {
     use crate::{
         config::PortalnetConfig,
         discovery::{Discovery, NodeAddress},
+        gossip::{DEFAULT_NUM_CLOSEST_NODES, DEFAULT_NUM_FARTHER_NODES},
         metrics::portalnet::PORTALNET_METRICS,
         overlay::OverlayConfig,
+        peer_score::DEFAULT_PEER_SCORE,
         storage::{DistanceFunction, MemoryContentStore},
         utils::db::setup_temp_dir,
     };
     use ethportal_api::types::content_key::overlay::IdentityContentKey;
     use ethportal_api::types::distance::XorMetric;
     use ethportal_api::types::enr::generate_random_remote_enr;
-    use trin_validation::validator::MockValidator;
+    use ethportal_api::types::query_trace::RequestOutcome;
+    use trin_validation::validator::{MockValidator, ValidationError};
 
     macro_rules! poll_command_rx {
         ($service:ident) => {
@@ -2786,10 +3836,68 @@ mod tests {
             phantom_metric: PhantomData,
             metrics,
             validator,
-            event_stream: None,
+            event_stream: broadcast::channel(EVENT_STREAM_CHANNEL_SIZE).0,
+            routing_table_store: None,
+            peer_scores: Arc::new(RwLock::new(PeerScores::new())),
+            recent_traces: Arc::new(RwLock::new(RecentQueryTraces::default())),
+            gossip_config: GossipConfig::default(),
+            offer_history: Arc::new(RwLock::new(OfferHistory::default())),
+            utp_connections: UtpConnections::new(
+                overlay_config.utp_conn_config,
+                overlay_config.max_utp_connections,
+            ),
+            bootnodes: Arc::new(RwLock::new(vec![])),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(0))),
         }
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn shutdown_persists_routing_table_for_restart() {
+        let routing_table_dir = setup_temp_dir().unwrap().into_path();
+        let mut service = build_service();
+        service.routing_table_store = Some(RoutingTableStore::new(
+            &routing_table_dir,
+            &service.protocol,
+        ));
+
+        let (_, enr) = generate_random_remote_enr();
+        let key = kbucket::Key::from(enr.node_id());
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let _ =
+            service
+                .kbuckets
+                .write()
+                .insert_or_update(&key, Node::new(enr, Distance::MAX), status);
+
+        let command_tx = service.command_tx.clone();
+        let service_task = tokio::spawn(async move {
+            service.start().await;
+            service
+        });
+        let (callback, stopped) = oneshot::channel();
+        command_tx
+            .send(OverlayCommand::Shutdown { callback })
+            .unwrap();
+        stopped.await.unwrap();
+        let mut service = service_task.await.unwrap();
+
+        // Restart with an empty routing table, which is reloaded from the node data dir.
+        assert!(service.kbuckets.write().remove(&key));
+        service.routing_table_store = Some(RoutingTableStore::new(
+            &routing_table_dir,
+            &service.protocol,
+        ));
+        service.add_persisted_nodes();
+        assert!(matches!(
+            service.kbuckets.write().entry(&key),
+            Entry::Present(_, status) if !status.is_connected()
+        ));
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn process_ping_source_in_table_higher_enr_seq() {
@@ -2894,7 +4002,9 @@ mod tests {
             kbucket::Entry::Present { .. }
         ));
 
-        let request_id = rand::random();
+        let request_id = NEXT_OVERLAY_REQUEST_ID
+            .fetch_add(1, Ordering::Relaxed)
+            .into();
         let error = OverlayRequestError::Timeout;
         service.process_request_failure(request_id, destination, error);
 
@@ -2908,6 +4018,103 @@ mod tests {
         };
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn penalize_peer_evicts_after_ban_threshold() {
+        let mut service = task::spawn(build_service());
+
+        let (_, destination) = generate_random_remote_enr();
+        let node_id = destination.node_id();
+        let key = kbucket::Key::from(node_id);
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let node = Node::new(destination.clone(), Distance::MAX);
+        let _ = service
+            .kbuckets
+            .write()
+            .insert_or_update(&key, node, status);
+
+        let error = OverlayRequestError::FailedValidation("invalid content".to_owned());
+        for _ in 0..4 {
+            service.penalize_peer(node_id, &error);
+        }
+        assert!(matches!(
+            service.kbuckets.write().entry(&key),
+            kbucket::Entry::Present { .. }
+        ));
+        assert!(service.peer_scores.read().score(&node_id) < 0);
+
+        service.penalize_peer(node_id, &error);
+        assert!(!matches!(
+            service.kbuckets.write().entry(&key),
+            kbucket::Entry::Present { .. }
+        ));
+    }
+
+    /// Fails the validation of all content, either as invalid or as unvalidatable.
+    struct FailingValidator {
+        invalid: bool,
+    }
+
+    #[async_trait]
+    impl Validator<IdentityContentKey> for FailingValidator {
+        async fn validate_content(
+            &self,
+            _content_key: &IdentityContentKey,
+            _content: &[u8],
+        ) -> Result<(), ValidationError>
+        where
+            IdentityContentKey: 'async_trait,
+        {
+            let err = anyhow!("validation failed");
+            match self.invalid {
+                true => Err(ValidationError::Invalid(err)),
+                false => Err(ValidationError::Unvalidatable(err)),
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::unvalidatable(false)]
+    #[case::invalid(true)]
+    #[tokio::test]
+    #[serial]
+    async fn process_received_content_penalizes_only_invalid_content(#[case] invalid: bool) {
+        let mut service = task::spawn(build_service());
+
+        let source = NodeId::random();
+        let content_key = IdentityContentKey::new(NodeId::random().raw());
+        let responder = service.find_content_responder(vec![], None);
+        OverlayService::<IdentityContentKey, XorMetric, FailingValidator, MemoryContentStore>::process_received_content(
+            Arc::clone(&service.kbuckets),
+            service.command_tx.clone(),
+            Arc::new(FailingValidator { invalid }),
+            Arc::clone(&service.store),
+            vec![0xef; 32],
+            false,
+            content_key,
+            source,
+            responder,
+            vec![],
+            service.metrics.clone(),
+            service.event_stream.clone(),
+        )
+        .await;
+        while let Ok(command) = service.command_rx.try_recv() {
+            if let OverlayCommand::PenalizePeer { node_id, error } = command {
+                service.penalize_peer(node_id, &error);
+            }
+        }
+
+        let score = service.peer_scores.read().score(&source);
+        match invalid {
+            true => assert!(score < DEFAULT_PEER_SCORE),
+            false => assert_eq!(score, DEFAULT_PEER_SCORE),
+        }
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn process_pong_source_in_table_higher_enr_seq() {
@@ -3468,7 +4675,7 @@ mod tests {
         service.add_bootnodes(bootnodes);
 
         // Initialize the query and call `poll` so that it starts
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, false);
         let _ = service.find_node_query_pool.write().poll();
 
         let pool = service.find_node_query_pool.read();
@@ -3494,6 +4701,73 @@ mod tests {
         assert!(query.started().is_some());
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_init_find_nodes_query_with_trace() {
+        let mut service = task::spawn(build_service());
+
+        let (_, bootnode) = generate_random_remote_enr();
+        service.add_bootnodes(vec![bootnode.clone()]);
+
+        let (_, target_enr) = generate_random_remote_enr();
+        let target_node_id = target_enr.node_id();
+
+        let query_id = service
+            .init_find_nodes_query(&target_node_id, None, true)
+            .unwrap();
+
+        let (_, enr) = generate_random_remote_enr();
+        service.advance_find_node_query(bootnode.clone(), vec![enr.clone()], query_id);
+
+        let mut pool = service.find_node_query_pool.write();
+        let (query_info, _) = pool.get_mut(query_id).unwrap();
+        let trace = query_info.trace.as_ref().unwrap();
+        let response = trace.responses.get(&bootnode.node_id().into()).unwrap();
+        assert_eq!(response.responded_with, vec![enr.node_id().into()]);
+    }
+
+    #[tokio::test]
+    async fn test_trace_offer_cross_thread() {
+        let (command_tx, mut command_rx) =
+            unbounded_channel::<OverlayCommand<IdentityContentKey>>();
+        let (_, enr) = generate_random_remote_enr();
+        let peer: ethportal_api::types::node_id::NodeId = enr.node_id().into();
+
+        let content_key = IdentityContentKey::new([0x01; 32]);
+        let raw_content_key: RawContentKey = content_key.into();
+        let offer = Request::PopulatedOffer(PopulatedOffer {
+            content_items: vec![(raw_content_key.clone(), vec![0xef; 64])],
+        });
+
+        tokio::spawn(async move {
+            if let Some(OverlayCommand::Request(request)) = command_rx.recv().await {
+                let mut content_keys = BitList::with_capacity(1).unwrap();
+                content_keys.set(0, true).unwrap();
+                let accept = Accept {
+                    connection_id: 1234,
+                    content_keys,
+                };
+                let _ = request
+                    .responder
+                    .unwrap()
+                    .send(Ok(Response::Accept(accept)));
+                let _ = request.utp_responder.unwrap().send(ContentTransfer {
+                    node: peer,
+                    size_bytes: 64,
+                    duration_millis: 10,
+                    error: None,
+                });
+            }
+        });
+
+        let trace = trace_offer_cross_thread(offer, enr, &command_tx).await;
+        let content_key = hex_encode(raw_content_key);
+        assert_eq!(trace.accepted_content_keys(), vec![&content_key]);
+        assert_eq!(trace.error, None);
+        let transfer = trace.transfer.unwrap();
+        assert_eq!(transfer.node, peer);
+        assert_eq!(transfer.size_bytes, 64);
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_advance_findnodes_query() {
         let mut service = build_service();
@@ -3507,7 +4781,7 @@ mod tests {
 
         service.add_bootnodes(bootnodes);
         service.query_num_results = 3;
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, false);
 
         // Test that the first query event contains a proper query ID and request to the bootnode
         let event = OverlayService::<
@@ -3628,7 +4902,7 @@ mod tests {
 
         service.add_bootnodes(bootnodes);
 
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, false);
 
         let _event = OverlayService::<
             IdentityContentKey,
@@ -3716,6 +4990,8 @@ mod tests {
             QueryType::FindContent {
                 target: _target_content_key,
                 callback: None,
+                is_trace: false,
+                ..
             }
         ));
 
@@ -3739,6 +5015,124 @@ mod tests {
 
         assert!(query_id.is_none());
         assert_eq!(rx.await.unwrap(), (None, false, None));
+
+        // The query is traced even though the caller did not ask for the trace.
+        let traces = service.recent_traces.read().latest(1);
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].termination, Some(QueryTermination::NoPeers));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_coalesce_find_content_query() {
+        let mut service = task::spawn(build_service());
+
+        let (_, bootnode) = generate_random_remote_enr();
+        service.add_bootnodes(vec![bootnode]);
+
+        let target_content = NodeId::random();
+        let target_content_key = IdentityContentKey::new(target_content.raw());
+
+        // There is no query in progress to join, so the callback is given back.
+        let (tx, _rx) = oneshot::channel();
+        let tx = match service.coalesce_find_content_query(&target_content_key, tx, false) {
+            Err(tx) => tx,
+            Ok(_) => panic!("Coalesced with a query that does not exist"),
+        };
+        let query_id = service
+            .init_find_content_query(target_content_key.clone(), Some(tx), false)
+            .unwrap();
+
+        let (tx, _rx) = oneshot::channel();
+        let coalesced = service.coalesce_find_content_query(&target_content_key, tx, true);
+        assert!(matches!(coalesced, Ok(id) if id == query_id));
+        assert_eq!(service.find_content_query_pool.read().iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_content_responder_coalesced_callbacks() {
+        let service = build_service();
+
+        let local_enr = service.local_enr();
+        let trace = QueryTrace::new(&local_enr, local_enr.node_id().into());
+        let (tx, rx) = oneshot::channel();
+        let (traced_tx, traced_rx) = oneshot::channel();
+        service
+            .find_content_responder(vec![(tx, false), (traced_tx, true)], Some(trace))
+            .respond(Some(vec![0xef]), false, QueryTermination::ContentFound);
+
+        // Every caller receives the content, but only the caller that asked for the trace
+        // receives it.
+        let (content, _, trace) = rx.await.unwrap();
+        assert_eq!(content, Some(vec![0xef]));
+        assert!(trace.is_none());
+        let (content, _, trace) = traced_rx.await.unwrap();
+        assert_eq!(content, Some(vec![0xef]));
+        assert_eq!(
+            trace.unwrap().termination,
+            Some(QueryTermination::ContentFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn trace_query_request_failure() {
+        let mut service = task::spawn(build_service());
+
+        let (_, bootnode_enr) = generate_random_remote_enr();
+        let bootnode_key = kbucket::Key::from(bootnode_enr.node_id());
+        let bootnode = Node {
+            enr: bootnode_enr.clone(),
+            data_radius: Distance::MAX,
+        };
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let _ = service
+            .kbuckets
+            .write()
+            .insert_or_update(&bootnode_key, bootnode, status);
+
+        let target_content_key = IdentityContentKey::new(NodeId::random().raw());
+        let query_id = service
+            .init_find_content_query(target_content_key, None, false)
+            .expect("Query ID for new find content query is `None`");
+
+        let request = {
+            let mut pool = service.find_content_query_pool.write();
+            let (query_info, _) = pool
+                .get_mut(query_id)
+                .expect("Query pool does not contain query");
+            query_info
+                .trace
+                .as_mut()
+                .expect("Content query is not traced")
+                .node_request_started(&bootnode_enr);
+            query_info.rpc_request(bootnode_enr.node_id()).unwrap()
+        };
+        service.trace_query_request_failure(
+            query_id,
+            &request,
+            &bootnode_enr,
+            &OverlayRequestError::Timeout,
+        );
+
+        let mut pool = service.find_content_query_pool.write();
+        let (query_info, _) = pool
+            .get_mut(query_id)
+            .expect("Query pool does not contain query");
+        let trace = query_info.trace.as_ref().unwrap();
+        let request = trace
+            .requests
+            .get(&bootnode_enr.node_id().into())
+            .expect("Request to bootnode is not traced");
+        assert_eq!(
+            request.outcome,
+            RequestOutcome::Failed {
+                error_kind: "timeout".to_owned(),
+                message: OverlayRequestError::Timeout.to_string(),
+            }
+        );
+        assert!(request.ended_at_millis.is_some());
     }
 
     #[tokio::test]
@@ -4036,29 +5430,198 @@ mod tests {
 
     #[rstest]
     #[case(vec![generate_random_remote_enr().1; 0], 0)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES - 1], NUM_CLOSEST_NODES - 1)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES], NUM_CLOSEST_NODES)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES + 1], NUM_CLOSEST_NODES + 1)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES + NUM_FARTHER_NODES], NUM_CLOSEST_NODES + NUM_FARTHER_NODES)]
-    #[case(vec![generate_random_remote_enr().1; 256], NUM_CLOSEST_NODES + NUM_FARTHER_NODES)]
+    #[case(vec![generate_random_remote_enr().1; DEFAULT_NUM_CLOSEST_NODES - 1], DEFAULT_NUM_CLOSEST_NODES - 1)]
+    #[case(vec![generate_random_remote_enr().1; DEFAULT_NUM_CLOSEST_NODES], DEFAULT_NUM_CLOSEST_NODES)]
+    #[case(vec![generate_random_remote_enr().1; DEFAULT_NUM_CLOSEST_NODES + 1], DEFAULT_NUM_CLOSEST_NODES + 1)]
+    #[case(vec![generate_random_remote_enr().1; DEFAULT_NUM_CLOSEST_NODES + DEFAULT_NUM_FARTHER_NODES], DEFAULT_NUM_CLOSEST_NODES + DEFAULT_NUM_FARTHER_NODES)]
+    #[case(vec![generate_random_remote_enr().1; 256], DEFAULT_NUM_CLOSEST_NODES + DEFAULT_NUM_FARTHER_NODES)]
     fn test_select_gossip_recipients_no_panic(
         #[case] all_nodes: Vec<Enr>,
         #[case] expected_size: usize,
     ) {
-        let gossip_recipients = select_gossip_recipients(all_nodes);
+        let gossip_recipients =
+            select_gossip_recipients(all_nodes, &GossipConfig::default(), &mut rand::thread_rng());
         assert_eq!(gossip_recipients.len(), expected_size);
     }
 
+    #[tokio::test]
+    async fn test_select_gossip_offers() {
+        let service = build_service();
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let insert_peer = |data_radius: Distance| {
+            let (_, enr) = generate_random_remote_enr();
+            let key = kbucket::Key::from(enr.node_id());
+            let node = Node {
+                enr: enr.clone(),
+                data_radius,
+            };
+            let _ = service
+                .kbuckets
+                .write()
+                .insert_or_update(&key, node, status);
+            enr
+        };
+        let declined_peer = insert_peer(Distance::MAX);
+        let _uninterested_peer = insert_peer(Distance::ZERO);
+
+        let content_key = IdentityContentKey::new([0x01; 32]);
+        let offer_history = RwLock::new(OfferHistory::default());
+        offer_history
+            .write()
+            .record(content_key.clone().into(), declined_peer.node_id(), false);
+
+        // Too few peers in the routing table are interested, so the closest nodes to the content
+        // are looked up.
+        let (_, closest_enr) = generate_random_remote_enr();
+        let (command_tx, mut command_rx) =
+            unbounded_channel::<OverlayCommand<IdentityContentKey>>();
+        let lookup_result = closest_enr.clone();
+        tokio::spawn(async move {
+            if let Some(OverlayCommand::FindNodeQuery { callback, .. }) = command_rx.recv().await {
+                let _ = callback.send((vec![lookup_result], None));
+            }
+        });
+
+        let gossip_config = GossipConfig {
+            min_interested_nodes: 2,
+            ..Default::default()
+        };
+        let (offers, recipients) = select_gossip_offers(
+            vec![(content_key.clone(), vec![0xef])],
+            &service.kbuckets,
+            &command_tx,
+            gossip_config,
+            &offer_history,
+            &Mutex::new(StdRng::seed_from_u64(0)),
+        )
+        .await;
+
+        // The peer that declined the content is not offered it again.
+        assert_eq!(offers.len(), 1);
+        assert!(offers.contains_key(&closest_enr.node_id()));
+        assert_eq!(
+            recipients.get(&hex_encode(content_key.to_bytes())),
+            Some(&vec![closest_enr.node_id()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_gossip_offers_limits_lookups() {
+        let service = build_service();
+        let (_, enr) = generate_random_remote_enr();
+        let node = Node {
+            enr: enr.clone(),
+            data_radius: Distance::ZERO,
+        };
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let _ = service.kbuckets.write().insert_or_update(
+            &kbucket::Key::from(enr.node_id()),
+            node,
+            status,
+        );
+
+        // No peer is interested in the content. A content key that is gossiped twice is looked up
+        // once.
+        let content_key = IdentityContentKey::new([0x01; 32]);
+        let content = vec![
+            (content_key.clone(), vec![0xef]),
+            (content_key, vec![0xef]),
+            (IdentityContentKey::new([0x02; 32]), vec![0xef]),
+        ];
+        let count_lookups = |max_lookups: usize| {
+            let content = content.clone();
+            let kbuckets = &service.kbuckets;
+            async move {
+                let (command_tx, mut command_rx) =
+                    unbounded_channel::<OverlayCommand<IdentityContentKey>>();
+                let responder = tokio::spawn(async move {
+                    let mut num_lookups = 0;
+                    while let Some(command) = command_rx.recv().await {
+                        if let OverlayCommand::FindNodeQuery { callback, .. } = command {
+                            num_lookups += 1;
+                            let _ = callback.send((vec![], None));
+                        }
+                    }
+                    num_lookups
+                });
+                let gossip_config = GossipConfig {
+                    max_lookups,
+                    ..Default::default()
+                };
+                select_gossip_offers(
+                    content,
+                    kbuckets,
+                    &command_tx,
+                    gossip_config,
+                    &RwLock::new(OfferHistory::default()),
+                    &Mutex::new(StdRng::seed_from_u64(0)),
+                )
+                .await;
+                drop(command_tx);
+                responder.await.unwrap()
+            }
+        };
+
+        assert_eq!(count_lookups(4).await, 2);
+        assert_eq!(count_lookups(1).await, 1);
+    }
+
     #[tokio::test]
     async fn test_event_stream() {
         // Get overlay service event stream
-        let mut service = task::spawn(build_service());
-        let (sender, mut receiver) = mpsc::channel(1);
-        service.event_stream = Some(sender);
+        let service = task::spawn(build_service());
+        let mut receiver = service.event_stream.subscribe();
         // Emit LightClientUpdate event
         service.send_event(OverlayEvent::LightClientOptimisticUpdate);
         // Check that the event is received
         let event = receiver.recv().await.unwrap();
         assert_eq!(event.payload, OverlayEvent::LightClientOptimisticUpdate);
     }
+
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn test_event_stream_node_added() {
+        let mut service = task::spawn(build_service());
+        let mut receiver = service.event_stream.subscribe();
+
+        let (_, enr) = generate_random_remote_enr();
+        let node_id = enr.node_id();
+        let node = Node::new(enr, Distance::MAX);
+        service.connect_node(node, ConnectionDirection::Outgoing);
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.payload, OverlayEvent::NodeAdded { node_id });
+    }
+
+    #[tokio::test]
+    async fn test_apply_tuning() {
+        let mut service = task::spawn(build_service());
+        let default_tuning = service.tuning();
+
+        service.apply_tuning(&OverlayTuning {
+            query_parallelism: Some(5),
+            query_timeout_ms: Some(30_000),
+            ..Default::default()
+        });
+
+        let tuning = service.tuning();
+        assert_eq!(tuning.query_parallelism, Some(5));
+        assert_eq!(tuning.query_timeout_ms, Some(30_000));
+        assert_eq!(
+            service.find_content_query_pool.read().query_timeout(),
+            Duration::from_secs(30)
+        );
+        // Unset parameters are left unchanged
+        assert_eq!(
+            tuning.query_peer_timeout_ms,
+            default_tuning.query_peer_timeout_ms
+        );
+        assert_eq!(tuning.query_num_results, default_tuning.query_num_results);
+    }
 }
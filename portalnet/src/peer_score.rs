@@ -0,0 +1,127 @@
+use std::{collections::HashMap, time::Duration};
+
+use discv5::enr::NodeId;
+
+use crate::overlay_service::OverlayRequestError;
+
+/// Score of a peer that has not misbehaved, or has since recovered.
+pub const DEFAULT_PEER_SCORE: i32 = 0;
+/// Peers whose score falls to this value are evicted from the routing table and banned.
+pub const PEER_BAN_THRESHOLD: i32 = -100;
+/// How long a peer is banned at the discv5 level once its score reaches the ban threshold.
+pub const PEER_BAN_DURATION: Duration = Duration::from_secs(30 * 60);
+/// Score recovered by a peer for every valid response.
+const PEER_REWARD: i32 = 1;
+
+/// Returns the score a peer loses for a request that failed with `error`, or zero if the failure
+/// is not the peer's fault.
+pub fn penalty(error: &OverlayRequestError) -> i32 {
+    match error {
+        OverlayRequestError::FailedValidation(_) => 20,
+        OverlayRequestError::InvalidResponse
+        | OverlayRequestError::DecodeError
+        | OverlayRequestError::InvalidRemoteDiscv5Packet => 10,
+        OverlayRequestError::Timeout => 5,
+        _ => 0,
+    }
+}
+
+/// Reputation of the peers of an overlay network, fed from the outcome of requests to them.
+///
+/// Only peers with a score below `DEFAULT_PEER_SCORE` are tracked, so the table stays small
+/// even though every peer we talk to is scored.
+#[derive(Debug, Default)]
+pub struct PeerScores {
+    scores: HashMap<NodeId, i32>,
+}
+
+impl PeerScores {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current score of `node_id`.
+    pub fn score(&self, node_id: &NodeId) -> i32 {
+        self.scores
+            .get(node_id)
+            .copied()
+            .unwrap_or(DEFAULT_PEER_SCORE)
+    }
+
+    /// Moves the score of `node_id` back towards `DEFAULT_PEER_SCORE` after a valid response.
+    pub fn reward(&mut self, node_id: &NodeId) {
+        if let Some(score) = self.scores.get_mut(node_id) {
+            *score += PEER_REWARD;
+            if *score >= DEFAULT_PEER_SCORE {
+                self.scores.remove(node_id);
+            }
+        }
+    }
+
+    /// Lowers the score of `node_id` for a request that failed with `error`.
+    ///
+    /// Returns `true` if the score fell to `PEER_BAN_THRESHOLD`, in which case the peer should be
+    /// banned. The score is then reset, so the peer starts over once the ban expires.
+    pub fn penalize(&mut self, node_id: &NodeId, error: &OverlayRequestError) -> bool {
+        let penalty = penalty(error);
+        if penalty == 0 {
+            return false;
+        }
+        let score = self.scores.entry(*node_id).or_insert(DEFAULT_PEER_SCORE);
+        *score -= penalty;
+        if *score <= PEER_BAN_THRESHOLD {
+            self.scores.remove(node_id);
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn penalize_until_ban() {
+        let mut scores = PeerScores::new();
+        let node_id = NodeId::random();
+        let error = OverlayRequestError::FailedValidation("invalid".to_owned());
+
+        for _ in 0..4 {
+            assert!(!scores.penalize(&node_id, &error));
+        }
+        assert_eq!(scores.score(&node_id), -80);
+        assert!(scores.penalize(&node_id, &error));
+        assert_eq!(scores.score(&node_id), DEFAULT_PEER_SCORE);
+    }
+
+    #[test]
+    fn reward_recovers_to_default() {
+        let mut scores = PeerScores::new();
+        let node_id = NodeId::random();
+
+        scores.reward(&node_id);
+        assert_eq!(scores.score(&node_id), DEFAULT_PEER_SCORE);
+
+        assert!(!scores.penalize(&node_id, &OverlayRequestError::Timeout));
+        assert_eq!(scores.score(&node_id), -5);
+        for _ in 0..10 {
+            scores.reward(&node_id);
+        }
+        assert_eq!(scores.score(&node_id), DEFAULT_PEER_SCORE);
+        assert!(scores.scores.is_empty());
+    }
+
+    #[test]
+    fn local_failures_are_not_penalized() {
+        let mut scores = PeerScores::new();
+        let node_id = NodeId::random();
+
+        assert!(!scores.penalize(
+            &node_id,
+            &OverlayRequestError::ChannelFailure("closed".to_owned())
+        ));
+        assert!(scores.scores.is_empty());
+    }
+}
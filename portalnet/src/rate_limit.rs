@@ -0,0 +1,416 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use discv5::enr::NodeId;
+use parking_lot::Mutex;
+use thiserror::Error;
+
+use crate::metrics::labels::UtpDirectionLabel;
+
+const REQUEST_WINDOW: Duration = Duration::from_secs(1);
+const BYTES_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits on the resources a node spends serving inbound requests, across all overlay networks.
+/// A limit of zero disables it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// FindContent and Offer requests served per second to a single peer.
+    pub max_peer_requests_per_sec: u32,
+    /// FindContent and Offer requests served per second to all peers.
+    pub max_requests_per_sec: u32,
+    /// Concurrent uTP streams opened for a single peer's requests, in each direction.
+    pub max_peer_utp_streams: usize,
+    /// Concurrent uTP streams opened for all peers' requests, in each direction.
+    pub max_utp_streams: usize,
+    /// Content bytes served to or accepted from a single peer per minute.
+    pub max_peer_bytes_per_min: u64,
+    /// Content bytes served to or accepted from all peers per minute.
+    pub max_bytes_per_min: u64,
+}
+
+impl RateLimitConfig {
+    /// A config without any limits.
+    pub fn unlimited() -> Self {
+        Self {
+            max_peer_requests_per_sec: 0,
+            max_requests_per_sec: 0,
+            max_peer_utp_streams: 0,
+            max_utp_streams: 0,
+            max_peer_bytes_per_min: 0,
+            max_bytes_per_min: 0,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_peer_requests_per_sec: 50,
+            max_requests_per_sec: 1000,
+            max_peer_utp_streams: 8,
+            max_utp_streams: 128,
+            max_peer_bytes_per_min: 256 * 1024 * 1024,
+            max_bytes_per_min: 2 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// The limit that caused a request to be rejected.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum RateLimitExceeded {
+    #[error("Peer request rate limit exceeded")]
+    PeerRequests,
+    #[error("Request rate limit exceeded")]
+    Requests,
+    #[error("Peer uTP stream limit exceeded")]
+    PeerUtpStreams,
+    #[error("uTP stream limit exceeded")]
+    UtpStreams,
+    #[error("Peer bandwidth limit exceeded")]
+    PeerBytes,
+    #[error("Bandwidth limit exceeded")]
+    Bytes,
+}
+
+/// A counter that resets at the start of every window.
+#[derive(Debug)]
+struct Window {
+    start: Instant,
+    count: u64,
+}
+
+impl Window {
+    fn new(now: Instant) -> Self {
+        Self {
+            start: now,
+            count: 0,
+        }
+    }
+
+    /// Returns the count of the window that `now` falls in.
+    fn current(&mut self, now: Instant, length: Duration) -> &mut u64 {
+        if now.duration_since(self.start) >= length {
+            self.start = now;
+            self.count = 0;
+        }
+        &mut self.count
+    }
+
+    fn is_expired(&self, now: Instant, length: Duration) -> bool {
+        now.duration_since(self.start) >= length
+    }
+}
+
+/// Resources in use by one peer, or by all peers.
+#[derive(Debug)]
+struct Usage {
+    requests: Window,
+    bytes: Window,
+    inbound_utp_streams: usize,
+    outbound_utp_streams: usize,
+}
+
+impl Usage {
+    fn new(now: Instant) -> Self {
+        Self {
+            requests: Window::new(now),
+            bytes: Window::new(now),
+            inbound_utp_streams: 0,
+            outbound_utp_streams: 0,
+        }
+    }
+
+    fn utp_streams(&mut self, direction: UtpDirectionLabel) -> &mut usize {
+        match direction {
+            UtpDirectionLabel::Inbound => &mut self.inbound_utp_streams,
+            UtpDirectionLabel::Outbound => &mut self.outbound_utp_streams,
+        }
+    }
+
+    /// Whether the usage can be forgotten without loosening any limit.
+    fn is_idle(&self, now: Instant) -> bool {
+        self.inbound_utp_streams == 0
+            && self.outbound_utp_streams == 0
+            && self.requests.is_expired(now, REQUEST_WINDOW)
+            && self.bytes.is_expired(now, BYTES_WINDOW)
+    }
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    global: Usage,
+    peers: HashMap<NodeId, Usage>,
+}
+
+/// Tracks the resources spent serving inbound requests, per peer and for the whole node.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<RateLimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            config,
+            state: Mutex::new(RateLimiterState {
+                global: Usage::new(now),
+                peers: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Counts a request from `peer`, unless it is over the per-peer or the global request rate.
+    pub fn check_request(&self, peer: &NodeId) -> Result<(), RateLimitExceeded> {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        let RateLimiterState { global, peers } = &mut *state;
+
+        if global.requests.is_expired(now, REQUEST_WINDOW) {
+            // Forget the peers that are not using anything, once per window.
+            peers.retain(|_, usage| !usage.is_idle(now));
+        }
+        let global_requests = global.requests.current(now, REQUEST_WINDOW);
+        if exceeds(*global_requests, 1, self.config.max_requests_per_sec.into()) {
+            return Err(RateLimitExceeded::Requests);
+        }
+        let peer_requests = peers
+            .entry(*peer)
+            .or_insert_with(|| Usage::new(now))
+            .requests
+            .current(now, REQUEST_WINDOW);
+        if exceeds(
+            *peer_requests,
+            1,
+            self.config.max_peer_requests_per_sec.into(),
+        ) {
+            return Err(RateLimitExceeded::PeerRequests);
+        }
+
+        *peer_requests += 1;
+        *global.requests.current(now, REQUEST_WINDOW) += 1;
+        Ok(())
+    }
+
+    /// Reserves `bytes` of the per-minute bandwidth of `peer`, unless it is over the per-peer or
+    /// the global bandwidth limit. A reservation of zero bytes only checks that the bandwidth is
+    /// not used up.
+    pub fn reserve_bytes(&self, peer: &NodeId, bytes: u64) -> Result<(), RateLimitExceeded> {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        let RateLimiterState { global, peers } = &mut *state;
+
+        let global_bytes = global.bytes.current(now, BYTES_WINDOW);
+        if exceeds(*global_bytes, bytes, self.config.max_bytes_per_min) {
+            return Err(RateLimitExceeded::Bytes);
+        }
+        let peer_bytes = peers
+            .entry(*peer)
+            .or_insert_with(|| Usage::new(now))
+            .bytes
+            .current(now, BYTES_WINDOW);
+        if exceeds(*peer_bytes, bytes, self.config.max_peer_bytes_per_min) {
+            return Err(RateLimitExceeded::PeerBytes);
+        }
+
+        *peer_bytes += bytes;
+        *global.bytes.current(now, BYTES_WINDOW) += bytes;
+        Ok(())
+    }
+
+    /// Records `bytes` received from `peer`, after the transfer completed.
+    pub fn consume_bytes(&self, peer: &NodeId, bytes: u64) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        *state.global.bytes.current(now, BYTES_WINDOW) += bytes;
+        *state
+            .peers
+            .entry(*peer)
+            .or_insert_with(|| Usage::new(now))
+            .bytes
+            .current(now, BYTES_WINDOW) += bytes;
+    }
+
+    /// Reserves a uTP stream for a request from `peer`, unless it is over the per-peer or the
+    /// global stream limit. The stream is released when the permit is dropped.
+    pub fn acquire_utp_stream(
+        self: &Arc<Self>,
+        peer: &NodeId,
+        direction: UtpDirectionLabel,
+    ) -> Result<UtpStreamPermit, RateLimitExceeded> {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        let RateLimiterState { global, peers } = &mut *state;
+
+        if exceeds(
+            *global.utp_streams(direction) as u64,
+            1,
+            self.config.max_utp_streams as u64,
+        ) {
+            return Err(RateLimitExceeded::UtpStreams);
+        }
+        let peer_streams = peers
+            .entry(*peer)
+            .or_insert_with(|| Usage::new(now))
+            .utp_streams(direction);
+        if exceeds(
+            *peer_streams as u64,
+            1,
+            self.config.max_peer_utp_streams as u64,
+        ) {
+            return Err(RateLimitExceeded::PeerUtpStreams);
+        }
+
+        *peer_streams += 1;
+        *global.utp_streams(direction) += 1;
+        Ok(UtpStreamPermit {
+            rate_limiter: Arc::clone(self),
+            peer: *peer,
+            direction,
+        })
+    }
+
+    fn release_utp_stream(&self, peer: &NodeId, direction: UtpDirectionLabel) {
+        let mut state = self.state.lock();
+        let global_streams = state.global.utp_streams(direction);
+        *global_streams = global_streams.saturating_sub(1);
+        if let Some(usage) = state.peers.get_mut(peer) {
+            let peer_streams = usage.utp_streams(direction);
+            *peer_streams = peer_streams.saturating_sub(1);
+        }
+    }
+}
+
+/// Whether using `amount` more than `used` goes over `limit`. A limit of zero is no limit.
+fn exceeds(used: u64, amount: u64, limit: u64) -> bool {
+    limit > 0 && (used >= limit || used.saturating_add(amount) > limit)
+}
+
+/// A uTP stream reserved with `RateLimiter::acquire_utp_stream`, released on drop.
+#[derive(Debug)]
+pub struct UtpStreamPermit {
+    rate_limiter: Arc<RateLimiter>,
+    peer: NodeId,
+    direction: UtpDirectionLabel,
+}
+
+impl Drop for UtpStreamPermit {
+    fn drop(&mut self) {
+        self.rate_limiter
+            .release_utp_stream(&self.peer, self.direction);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    fn config() -> RateLimitConfig {
+        RateLimitConfig {
+            max_peer_requests_per_sec: 2,
+            max_requests_per_sec: 3,
+            max_peer_utp_streams: 1,
+            max_utp_streams: 2,
+            max_peer_bytes_per_min: 100,
+            max_bytes_per_min: 150,
+        }
+    }
+
+    #[test]
+    fn request_limits() {
+        let rate_limiter = RateLimiter::new(config());
+        let (peer_a, peer_b) = (NodeId::random(), NodeId::random());
+
+        assert!(rate_limiter.check_request(&peer_a).is_ok());
+        assert!(rate_limiter.check_request(&peer_a).is_ok());
+        assert_eq!(
+            rate_limiter.check_request(&peer_a),
+            Err(RateLimitExceeded::PeerRequests)
+        );
+        assert!(rate_limiter.check_request(&peer_b).is_ok());
+        assert_eq!(
+            rate_limiter.check_request(&peer_b),
+            Err(RateLimitExceeded::Requests)
+        );
+    }
+
+    #[test]
+    fn utp_stream_permits_are_released_on_drop() {
+        let rate_limiter = Arc::new(RateLimiter::new(config()));
+        let (peer_a, peer_b, peer_c) = (NodeId::random(), NodeId::random(), NodeId::random());
+
+        let permit = rate_limiter
+            .acquire_utp_stream(&peer_a, UtpDirectionLabel::Inbound)
+            .unwrap();
+        assert_eq!(
+            rate_limiter
+                .acquire_utp_stream(&peer_a, UtpDirectionLabel::Inbound)
+                .unwrap_err(),
+            RateLimitExceeded::PeerUtpStreams
+        );
+        // Directions are limited separately
+        let _outbound = rate_limiter
+            .acquire_utp_stream(&peer_a, UtpDirectionLabel::Outbound)
+            .unwrap();
+        let _inbound = rate_limiter
+            .acquire_utp_stream(&peer_b, UtpDirectionLabel::Inbound)
+            .unwrap();
+        assert_eq!(
+            rate_limiter
+                .acquire_utp_stream(&peer_c, UtpDirectionLabel::Inbound)
+                .unwrap_err(),
+            RateLimitExceeded::UtpStreams
+        );
+
+        drop(permit);
+        assert!(rate_limiter
+            .acquire_utp_stream(&peer_a, UtpDirectionLabel::Inbound)
+            .is_ok());
+    }
+
+    #[test]
+    fn byte_limits() {
+        let rate_limiter = RateLimiter::new(config());
+        let (peer_a, peer_b) = (NodeId::random(), NodeId::random());
+
+        assert!(rate_limiter.reserve_bytes(&peer_a, 80).is_ok());
+        assert_eq!(
+            rate_limiter.reserve_bytes(&peer_a, 30),
+            Err(RateLimitExceeded::PeerBytes)
+        );
+        assert_eq!(
+            rate_limiter.reserve_bytes(&peer_b, 80),
+            Err(RateLimitExceeded::Bytes)
+        );
+
+        // Received bytes are recorded even when over the limit
+        rate_limiter.consume_bytes(&peer_b, 70);
+        assert_eq!(
+            rate_limiter.reserve_bytes(&peer_b, 0),
+            Err(RateLimitExceeded::Bytes)
+        );
+    }
+
+    #[test]
+    fn zero_disables_limits() {
+        let rate_limiter = Arc::new(RateLimiter::new(RateLimitConfig::unlimited()));
+        let peer = NodeId::random();
+
+        for _ in 0..100 {
+            assert!(rate_limiter.check_request(&peer).is_ok());
+            assert!(rate_limiter.reserve_bytes(&peer, u64::MAX / 200).is_ok());
+        }
+        let _permits: Vec<_> = (0..100)
+            .map(|_| {
+                rate_limiter
+                    .acquire_utp_stream(&peer, UtpDirectionLabel::Inbound)
+                    .unwrap()
+            })
+            .collect();
+    }
+}
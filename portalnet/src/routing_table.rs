@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use discv5::enr::NodeId;
+use ethereum_types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{messages::ProtocolId, node::Node};
+use ethportal_api::types::distance::Distance;
+use ethportal_api::types::enr::Enr;
+
+/// Entries that have not been seen connected for longer than this are dropped when the routing
+/// table is loaded.
+pub const ROUTING_TABLE_MAX_ENTRY_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A routing table entry, as persisted to the node data dir.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedNode {
+    pub enr: Enr,
+    pub data_radius: U256,
+    /// Unix timestamp (in seconds) of the last time the node was connected.
+    pub last_seen: u64,
+}
+
+impl PersistedNode {
+    pub fn node(&self) -> Node {
+        Node::new(self.enr.clone(), Distance::from(self.data_radius))
+    }
+}
+
+/// Persists the overlay routing table of a single subnetwork, so that a restarted node does not
+/// have to rebuild it from the bootnodes.
+#[derive(Clone, Debug)]
+pub struct RoutingTableStore {
+    path: PathBuf,
+    max_entry_age: Duration,
+    /// Last time each persisted node was seen connected, as of the last load or save.
+    last_seen: HashMap<NodeId, u64>,
+}
+
+impl RoutingTableStore {
+    pub fn new(node_data_dir: &Path, protocol: &ProtocolId) -> Self {
+        let file_name = format!(
+            "routing_table_{}.json",
+            protocol.to_string().to_lowercase().replace(' ', "_")
+        );
+        Self {
+            path: node_data_dir.join(file_name),
+            max_entry_age: ROUTING_TABLE_MAX_ENTRY_AGE,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the persisted entries, skipping those that are older than the maximum entry age.
+    ///
+    /// A missing file is not an error: it yields an empty routing table.
+    pub fn load(&mut self) -> anyhow::Result<Vec<PersistedNode>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let data = fs::read_to_string(&self.path)?;
+        let nodes: Vec<PersistedNode> = serde_json::from_str(&data).map_err(|err| {
+            anyhow!(
+                "Unable to decode routing table at {}: {err}",
+                self.path.display()
+            )
+        })?;
+
+        let cutoff = unix_timestamp().saturating_sub(self.max_entry_age.as_secs());
+        let nodes: Vec<PersistedNode> = nodes
+            .into_iter()
+            .filter(|node| node.last_seen >= cutoff)
+            .collect();
+        self.last_seen = nodes
+            .iter()
+            .map(|node| (node.enr.node_id(), node.last_seen))
+            .collect();
+        Ok(nodes)
+    }
+
+    /// Writes the routing table entries, given as `(node, is_connected)` pairs.
+    ///
+    /// Connected nodes are stamped with the current time. Disconnected nodes keep the time they
+    /// were last seen connected, and are dropped if they have never been seen connected or have
+    /// aged out. The file is written on the blocking thread pool, so the caller's runtime thread
+    /// is not held up by the disk.
+    pub async fn save(
+        &mut self,
+        entries: impl IntoIterator<Item = (Node, bool)>,
+    ) -> anyhow::Result<()> {
+        let now = unix_timestamp();
+        let cutoff = now.saturating_sub(self.max_entry_age.as_secs());
+        let nodes: Vec<PersistedNode> = entries
+            .into_iter()
+            .filter_map(|(node, is_connected)| {
+                let last_seen = if is_connected {
+                    now
+                } else {
+                    *self.last_seen.get(&node.enr.node_id())?
+                };
+                (last_seen >= cutoff).then(|| PersistedNode {
+                    data_radius: *node.data_radius,
+                    enr: node.enr,
+                    last_seen,
+                })
+            })
+            .collect();
+
+        // Write to a temporary file first, so that a crash mid-write doesn't corrupt the table.
+        let data = serde_json::to_string(&nodes)?;
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let tmp_path = path.with_extension("json.tmp");
+            fs::write(&tmp_path, data)?;
+            fs::rename(&tmp_path, &path)
+        })
+        .await??;
+
+        self.last_seen = nodes
+            .iter()
+            .map(|node| (node.enr.node_id(), node.last_seen))
+            .collect();
+        Ok(())
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    use ethportal_api::types::enr::generate_random_remote_enr;
+
+    #[tokio::test]
+    async fn save_and_load() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = RoutingTableStore::new(temp_dir.path(), &ProtocolId::History);
+        assert!(store.load().unwrap().is_empty());
+
+        let (_, connected) = generate_random_remote_enr();
+        let (_, disconnected) = generate_random_remote_enr();
+        let radius = Distance::from(U256::from(1000));
+        store
+            .save(vec![
+                (Node::new(connected.clone(), radius), true),
+                (Node::new(disconnected, Distance::MAX), false),
+            ])
+            .await
+            .unwrap();
+
+        // A node that was never seen connected is not persisted.
+        let mut store = RoutingTableStore::new(temp_dir.path(), &ProtocolId::History);
+        let nodes = store.load().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].node(), Node::new(connected.clone(), radius));
+
+        // Once loaded, a node keeps its entry while it is disconnected.
+        store
+            .save(vec![(Node::new(connected, radius), false)])
+            .await
+            .unwrap();
+        let mut store = RoutingTableStore::new(temp_dir.path(), &ProtocolId::History);
+        assert_eq!(store.load().unwrap().len(), 1);
+
+        // Each protocol has its own table.
+        let mut store = RoutingTableStore::new(temp_dir.path(), &ProtocolId::State);
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn load_drops_stale_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = RoutingTableStore::new(temp_dir.path(), &ProtocolId::History);
+
+        let now = unix_timestamp();
+        let (_, fresh) = generate_random_remote_enr();
+        let (_, stale) = generate_random_remote_enr();
+        let nodes = vec![
+            PersistedNode {
+                enr: fresh.clone(),
+                data_radius: U256::MAX,
+                last_seen: now,
+            },
+            PersistedNode {
+                enr: stale,
+                data_radius: U256::MAX,
+                last_seen: now - ROUTING_TABLE_MAX_ENTRY_AGE.as_secs() - 1,
+            },
+        ];
+        fs::write(store.path(), serde_json::to_string(&nodes).unwrap()).unwrap();
+
+        let nodes = store.load().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].enr, fresh);
+    }
+}
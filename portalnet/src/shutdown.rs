@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Signals that a node should shut down. Clones share their state, so a shutdown requested through
+/// one clone, eg. by the admin RPC module, is seen by every task waiting on another.
+#[derive(Clone, Debug)]
+pub struct ShutdownSignal {
+    requested: Arc<watch::Sender<bool>>,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self {
+            requested: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    /// Asks every task waiting on [`ShutdownSignal::wait`] to shut down, as if ctrl-c was pressed.
+    pub fn request(&self) {
+        info!("Shutdown requested");
+        self.requested.send_replace(true);
+    }
+
+    /// Resolves once the node should shut down, on ctrl-c, on SIGTERM or after
+    /// [`ShutdownSignal::request`].
+    pub async fn wait(&self) {
+        let mut requested = self.requested.subscribe();
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                if let Err(err) = result {
+                    warn!(error = %err, "Failed to listen for ctrl-c, waiting for a shutdown request");
+                    let _ = requested.wait_for(|requested| *requested).await;
+                }
+            }
+            _ = terminate_signal() => {}
+            _ = requested.wait_for(|requested| *requested) => {}
+        }
+    }
+}
+
+/// Resolves once the process receives SIGTERM, eg. from a service manager or container runtime.
+#[cfg(unix)]
+async fn terminate_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            terminate.recv().await;
+        }
+        Err(err) => {
+            warn!(error = %err, "Failed to listen for SIGTERM");
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// There is no SIGTERM outside of unix, so this never resolves.
+#[cfg(not(unix))]
+async fn terminate_signal() {
+    std::future::pending::<()>().await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn request_resolves_wait() {
+        let shutdown_signal = ShutdownSignal::new();
+        let signal = tokio::spawn({
+            let shutdown_signal = shutdown_signal.clone();
+            async move { shutdown_signal.wait().await }
+        });
+        shutdown_signal.request();
+        tokio::time::timeout(Duration::from_secs(1), signal)
+            .await
+            .expect("shutdown signal did not resolve after a shutdown request")
+            .expect("shutdown signal task failed");
+
+        // Tasks that start waiting after the request also shut down.
+        tokio::time::timeout(Duration::from_secs(1), shutdown_signal.wait())
+            .await
+            .expect("shutdown signal did not resolve after a shutdown request");
+    }
+
+    #[tokio::test]
+    async fn request_only_resolves_its_own_signal() {
+        ShutdownSignal::new().request();
+        let other_signal = ShutdownSignal::new();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), other_signal.wait())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Deterministic in-memory network for testing overlay networks at scale.
+//!
+//! A `SimNetwork` runs any number of overlay nodes in a single process. TALKREQ messages, and the
+//! uTP packets carried by them, are delivered over in-memory channels instead of UDP, with a
+//! configurable latency, jitter and packet loss. The randomness of the network is drawn from a
+//! single seeded RNG: node keys, jitter, loss and request ids, and the seed of each node's overlay
+//! RNG, which drives its routing table refreshes and gossip. Only uTP connection ids are left to
+//! the uTP socket, and they are not part of the recorded messages. A simulation is therefore
+//! reproducible when run on a current-thread runtime, down to the order of its messages, which
+//! the network records. Pausing the tokio clock (`#[tokio::test(start_paused = true)]`) lets
+//! simulated time pass instantly, so minutes of network activity take milliseconds to run.
+//!
+//! The simulator is only built for tests, and with the `simulator` feature.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use discv5::{enr::NodeId, rpc::RequestId, RequestError};
+use ethereum_types::H256;
+use parking_lot::{Mutex, RwLock};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tempfile::TempDir;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time,
+};
+use tracing::{debug, warn};
+use utp_rs::{socket::UtpSocket, udp::AsyncUdpSocket};
+
+use crate::{
+    config::PortalnetConfig,
+    discovery::{Discovery, NodeAddress, ProtocolRequest, TalkReqTransport, UtpEnr},
+    overlay::{OverlayConfig, OverlayProtocol},
+    storage::{DistanceFunction, MemoryContentStore},
+    types::messages::{Message, ProtocolId},
+};
+use ethportal_api::types::bootnodes::Bootnodes;
+use ethportal_api::types::content_key::overlay::IdentityContentKey;
+use ethportal_api::types::distance::XorMetric;
+use ethportal_api::types::enr::Enr;
+use trin_validation::validator::MockValidator;
+
+/// First UDP port advertised by simulated nodes. Nothing is bound to these ports.
+const SIM_BASE_PORT: u16 = 10000;
+
+/// The overlay protocol run by every simulated node.
+pub type SimOverlay =
+    OverlayProtocol<IdentityContentKey, XorMetric, MockValidator, MemoryContentStore>;
+
+/// Parameters of a simulated network.
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    /// Seed of the RNG that node keys, jitter and packet loss are drawn from.
+    pub seed: u64,
+    /// The subnetwork that every node runs.
+    pub protocol: ProtocolId,
+    /// One-way delay of every message.
+    pub latency: Duration,
+    /// Maximum random delay added to `latency`.
+    pub jitter: Duration,
+    /// Probability in `[0, 1]` that a message is dropped.
+    pub loss: f64,
+    /// How long a TALKREQ waits for its response before failing with a timeout.
+    pub request_timeout: Duration,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            protocol: ProtocolId::History,
+            latency: Duration::from_millis(50),
+            jitter: Duration::ZERO,
+            loss: 0.0,
+            request_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A TALKREQ sent over a simulated network, as recorded by the network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimMessage {
+    pub source: NodeId,
+    pub destination: NodeId,
+    pub protocol: ProtocolId,
+    /// The body of the TALKREQ. Empty for uTP packets, whose headers carry timestamps.
+    pub body: Vec<u8>,
+}
+
+/// A TALKREQ delivered to a simulated node.
+struct SimTalkRequest {
+    id: RequestId,
+    source: NodeId,
+    protocol: ProtocolId,
+    body: Vec<u8>,
+    response: oneshot::Sender<Vec<u8>>,
+}
+
+/// The receiving side of a simulated node, as seen by the network.
+#[derive(Clone)]
+struct SimEndpoint {
+    discovery: Arc<Discovery>,
+    talk_reqs: mpsc::UnboundedSender<SimTalkRequest>,
+    utp_packets: mpsc::UnboundedSender<(Vec<u8>, Enr)>,
+}
+
+/// A node of a simulated network.
+pub struct SimNode {
+    /// The overlay protocol of the node.
+    pub overlay: Arc<SimOverlay>,
+    /// Holds the node data dir, which is deleted on drop.
+    _data_dir: TempDir,
+}
+
+impl SimNode {
+    /// Returns the node ID of the node.
+    pub fn node_id(&self) -> NodeId {
+        self.overlay.local_enr().node_id()
+    }
+
+    /// Returns the ENR of the node.
+    pub fn enr(&self) -> Enr {
+        self.overlay.local_enr()
+    }
+}
+
+/// An in-memory network of overlay nodes.
+pub struct SimNetwork {
+    config: SimConfig,
+    rng: Mutex<StdRng>,
+    endpoints: RwLock<HashMap<NodeId, SimEndpoint>>,
+    next_port: Mutex<u16>,
+    messages: Mutex<Vec<SimMessage>>,
+}
+
+impl SimNetwork {
+    pub fn new(config: SimConfig) -> Arc<Self> {
+        let rng = Mutex::new(StdRng::seed_from_u64(config.seed));
+        Arc::new(Self {
+            config,
+            rng,
+            endpoints: RwLock::new(HashMap::new()),
+            next_port: Mutex::new(SIM_BASE_PORT),
+            messages: Mutex::new(vec![]),
+        })
+    }
+
+    /// Returns the parameters of the network.
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    /// Returns every TALKREQ sent over the network so far, in the order they were sent.
+    pub fn messages(&self) -> Vec<SimMessage> {
+        self.messages.lock().clone()
+    }
+
+    /// Starts a new node with `overlay_config` and connects it to the network.
+    ///
+    /// The node joins the overlay through the `bootnode_enrs` of `overlay_config`. Its overlay
+    /// RNG is seeded from the network RNG, unless `overlay_config` sets a seed.
+    pub async fn add_node(self: &Arc<Self>, mut overlay_config: OverlayConfig) -> SimNode {
+        let private_key = H256::from(self.rng.lock().gen::<[u8; 32]>());
+        if overlay_config.rng_seed.is_none() {
+            overlay_config.rng_seed = Some(self.rng.lock().gen());
+        }
+        let port = {
+            let mut next_port = self.next_port.lock();
+            let port = *next_port;
+            *next_port += 1;
+            port
+        };
+        let socket_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+        let portal_config = PortalnetConfig {
+            private_key,
+            listen_port: port,
+            external_addr: Some(socket_addr),
+            bootnodes: Bootnodes::None,
+            ..Default::default()
+        };
+
+        // Discv5 is never started, so no socket is bound. Outgoing TALKREQs go through the
+        // network instead.
+        let data_dir = tempfile::tempdir().expect("Unable to create simulated node data dir");
+        let discovery = Discovery::new(portal_config, data_dir.path().to_path_buf())
+            .expect("Unable to create simulated node discovery");
+        let local_addr = NodeAddress {
+            enr: discovery.local_enr(),
+            socket_addr,
+        };
+        let transport = Arc::new(SimTransport {
+            network: Arc::downgrade(self),
+            local_addr,
+        });
+        let discovery = Arc::new(discovery.with_talk_req_transport(transport));
+
+        let (utp_tx, utp_rx) = mpsc::unbounded_channel();
+        let utp_socket = SimUdpSocket {
+            discovery: Arc::clone(&discovery),
+            packets: utp_rx,
+        };
+        let utp_socket = Arc::new(UtpSocket::with_socket(utp_socket));
+
+        let node_id = discovery.local_enr().node_id();
+        let store = MemoryContentStore::new(node_id, DistanceFunction::Xor);
+        let overlay = OverlayProtocol::new(
+            overlay_config,
+            Arc::clone(&discovery),
+            utp_socket,
+            Arc::new(RwLock::new(store)),
+            self.config.protocol.clone(),
+            Arc::new(MockValidator {}),
+        )
+        .await;
+        let overlay = Arc::new(overlay);
+
+        let (talk_req_tx, talk_req_rx) = mpsc::unbounded_channel();
+        tokio::spawn(serve_talk_reqs(Arc::clone(&overlay), talk_req_rx));
+        self.endpoints.write().insert(
+            node_id,
+            SimEndpoint {
+                discovery,
+                talk_reqs: talk_req_tx,
+                utp_packets: utp_tx,
+            },
+        );
+
+        SimNode {
+            overlay,
+            _data_dir: data_dir,
+        }
+    }
+
+    /// Disconnects `node_id` from the network. Messages to the node are dropped from now on.
+    ///
+    /// Returns `true` if the node was connected.
+    pub fn disconnect(&self, node_id: &NodeId) -> bool {
+        self.endpoints.write().remove(node_id).is_some()
+    }
+
+    /// Returns the number of nodes connected to the network.
+    pub fn len(&self) -> usize {
+        self.endpoints.read().len()
+    }
+
+    /// Returns `true` if no node is connected to the network.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.read().is_empty()
+    }
+
+    /// Returns the delay of the next message and whether it is dropped.
+    fn sample_link(&self) -> (Duration, bool) {
+        let mut rng = self.rng.lock();
+        let jitter = if self.config.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            self.config.jitter.mul_f64(rng.gen::<f64>())
+        };
+        let lost = self.config.loss > 0.0 && rng.gen_bool(self.config.loss.min(1.0));
+        (self.config.latency + jitter, lost)
+    }
+
+    /// Delivers a TALKREQ from `source` to `destination` and waits for its response.
+    async fn deliver(
+        &self,
+        source: &NodeAddress,
+        destination: Enr,
+        protocol: ProtocolId,
+        request: ProtocolRequest,
+    ) -> Result<Vec<u8>, RequestError> {
+        if source.enr.node_id() == destination.node_id() {
+            return Err(RequestError::SelfRequest);
+        }
+        self.messages.lock().push(SimMessage {
+            source: source.enr.node_id(),
+            destination: destination.node_id(),
+            protocol: protocol.clone(),
+            body: if protocol == ProtocolId::Utp {
+                vec![]
+            } else {
+                request.clone()
+            },
+        });
+
+        let (delay, lost) = self.sample_link();
+        time::sleep(delay).await;
+        let endpoint = self.endpoints.read().get(&destination.node_id()).cloned();
+        let endpoint = match endpoint {
+            Some(endpoint) if !lost => endpoint,
+            _ => {
+                time::sleep(self.config.request_timeout).await;
+                return Err(RequestError::Timeout);
+            }
+        };
+        // Stands in for the session that discv5 would establish with the source.
+        endpoint.discovery.put_cached_node_addr(source.clone());
+
+        if protocol == ProtocolId::Utp {
+            // uTP ignores the TALKRESP, so there is no need to wait for it.
+            let _ = endpoint.utp_packets.send((request, source.enr.clone()));
+            return Ok(vec![]);
+        }
+
+        let id = RequestId(self.rng.lock().gen::<u64>().to_be_bytes().to_vec());
+        let (response_tx, response_rx) = oneshot::channel();
+        endpoint
+            .talk_reqs
+            .send(SimTalkRequest {
+                id,
+                source: source.enr.node_id(),
+                protocol,
+                body: request,
+                response: response_tx,
+            })
+            .map_err(|_| RequestError::ChannelFailed("Simulated node stopped".to_owned()))?;
+        let response = match time::timeout(self.config.request_timeout, response_rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => {
+                return Err(RequestError::ChannelFailed(
+                    "Simulated node dropped request".to_owned(),
+                ))
+            }
+            Err(_) => return Err(RequestError::Timeout),
+        };
+
+        let (delay, lost) = self.sample_link();
+        if lost {
+            time::sleep(self.config.request_timeout).await;
+            return Err(RequestError::Timeout);
+        }
+        time::sleep(delay).await;
+        Ok(response)
+    }
+}
+
+/// Sends the TALKREQs of a simulated node over its network.
+struct SimTransport {
+    network: Weak<SimNetwork>,
+    local_addr: NodeAddress,
+}
+
+#[async_trait]
+impl TalkReqTransport for SimTransport {
+    async fn send_talk_req(
+        &self,
+        enr: Enr,
+        protocol: ProtocolId,
+        request: ProtocolRequest,
+    ) -> Result<Vec<u8>, RequestError> {
+        match self.network.upgrade() {
+            Some(network) => {
+                network
+                    .deliver(&self.local_addr, enr, protocol, request)
+                    .await
+            }
+            None => Err(RequestError::ServiceNotStarted),
+        }
+    }
+}
+
+/// Answers the TALKREQs delivered to `overlay`, like the subnetwork event handlers of a real node.
+async fn serve_talk_reqs(
+    overlay: Arc<SimOverlay>,
+    mut talk_reqs: mpsc::UnboundedReceiver<SimTalkRequest>,
+) {
+    while let Some(talk_req) = talk_reqs.recv().await {
+        let overlay = Arc::clone(&overlay);
+        tokio::spawn(async move {
+            if &talk_req.protocol != overlay.protocol() {
+                debug!(protocol = %talk_req.protocol, "Simulated node received request for unsupported protocol");
+                let _ = talk_req.response.send(vec![]);
+                return;
+            }
+            let reply = match overlay
+                .process_request_bytes(talk_req.id, talk_req.source, &talk_req.body)
+                .await
+            {
+                Ok(response) => Message::from(response).into(),
+                Err(err) => {
+                    warn!(%err, "Error processing simulated request, responding with empty TALKRESP");
+                    vec![]
+                }
+            };
+            let _ = talk_req.response.send(reply);
+        });
+    }
+}
+
+/// A uTP socket over the TALKREQs of a simulated network.
+struct SimUdpSocket {
+    discovery: Arc<Discovery>,
+    packets: mpsc::UnboundedReceiver<(Vec<u8>, Enr)>,
+}
+
+#[async_trait]
+impl AsyncUdpSocket<UtpEnr> for SimUdpSocket {
+    async fn send_to(&mut self, buf: &[u8], target: &UtpEnr) -> io::Result<usize> {
+        let discovery = Arc::clone(&self.discovery);
+        let target = target.0.clone();
+        let data = buf.to_vec();
+        tokio::spawn(async move {
+            if let Err(err) = discovery.send_talk_req(target, ProtocolId::Utp, data).await {
+                debug!(?err, "Simulated uTP packet was not delivered");
+            }
+        });
+
+        Ok(buf.len())
+    }
+
+    async fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<(usize, UtpEnr)> {
+        while let Some((packet, source)) = self.packets.recv().await {
+            if !self
+                .discovery
+                .utp_read_limits()
+                .admit(source.node_id(), &packet)
+            {
+                continue;
+            }
+            let n = std::cmp::min(buf.len(), packet.len());
+            buf[..n].copy_from_slice(&packet[..n]);
+            return Ok((n, UtpEnr(source)));
+        }
+        Err(io::Error::from(io::ErrorKind::NotConnected))
+    }
+}
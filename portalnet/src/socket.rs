@@ -1,4 +1,4 @@
-use std::net::{SocketAddr, UdpSocket};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
 use tracing::{debug, info, warn};
 
 // This stun server is part of the testnet infrastructure.
@@ -8,7 +8,35 @@ const STUN_SERVER: &str = "159.223.0.83:3478";
 /// Ping a STUN server on the public network. This does two things:
 /// - Creates an externally-addressable UDP port, if you are behind a NAT
 /// - Returns the public IP and port that corresponds to your local port
-pub fn stun_for_external(local_socket_addr: &SocketAddr) -> Option<SocketAddr> {
+///
+/// The STUN server is chosen to match the IP family of `local_socket_addr`: the testnet server for
+/// IPv4, and `ipv6_stun_server` for IPv6. Returns `None` for IPv6 if no IPv6 server is configured.
+pub fn stun_for_external(
+    local_socket_addr: &SocketAddr,
+    ipv6_stun_server: Option<&str>,
+) -> Option<SocketAddr> {
+    let stun_server = match local_socket_addr {
+        SocketAddr::V4(_) => STUN_SERVER
+            .parse()
+            .expect("Parsing static STUN_SERVER to work"),
+        SocketAddr::V6(_) => {
+            let ipv6_stun_server = ipv6_stun_server?;
+            let stun_server = ipv6_stun_server
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.find(SocketAddr::is_ipv6));
+            match stun_server {
+                Some(addr) => addr,
+                None => {
+                    warn!(
+                        server = ipv6_stun_server,
+                        "Unable to resolve an IPv6 address for STUN server."
+                    );
+                    return None;
+                }
+            }
+        }
+    };
     let socket = match UdpSocket::bind(local_socket_addr) {
         Ok(val) => val,
         Err(err) => {
@@ -17,12 +45,7 @@ pub fn stun_for_external(local_socket_addr: &SocketAddr) -> Option<SocketAddr> {
         }
     };
     info!("Connecting to STUN server to find public network endpoint");
-    let external_addr = stunclient::StunClient::new(
-        STUN_SERVER
-            .parse()
-            .expect("Parsing static STUN_SERVER to work"),
-    )
-    .query_external_address(&socket);
+    let external_addr = stunclient::StunClient::new(stun_server).query_external_address(&socket);
 
     match external_addr {
         Ok(addr) => {
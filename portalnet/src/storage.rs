@@ -8,6 +8,8 @@ use std::{
 use anyhow::anyhow;
 use discv5::enr::NodeId;
 use ethportal_api::types::portal::PaginateLocalContentInfo;
+use lru::LruCache;
+use parking_lot::Mutex;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rocksdb::{Options, DB};
@@ -18,6 +20,7 @@ use tracing::{debug, error, info};
 use crate::metrics::portalnet::PORTALNET_METRICS;
 use crate::metrics::storage::StorageMetricsReporter;
 use crate::types::messages::ProtocolId;
+use ethportal_api::types::cli::DEFAULT_CACHE_CAPACITY_MB;
 use ethportal_api::types::distance::{Distance, Metric, XorMetric};
 use ethportal_api::utils::bytes::{hex_decode, hex_encode, ByteUtilsError};
 use ethportal_api::{ContentKeyError, HistoryContentKey, OverlayContentKey};
@@ -96,6 +99,77 @@ pub trait ContentStore {
 
     /// Returns the radius of the data store.
     fn radius(&self) -> Distance;
+
+    /// Puts a piece of content that is outside of the radius into the cache, evicting the least
+    /// recently used content if the cache is full. Cached content is only served to local
+    /// callers, and is never offered or served to peers.
+    fn put_cached<K: OverlayContentKey, V: AsRef<[u8]>>(&mut self, key: K, value: V);
+
+    /// Looks up a piece of cached content by `key`.
+    fn get_cached<K: OverlayContentKey>(&self, key: &K) -> Option<Vec<u8>>;
+}
+
+/// An LRU cache of content, bounded by the total size of the cached content.
+#[derive(Debug)]
+pub struct ContentCache {
+    entries: LruCache<[u8; 32], Vec<u8>>,
+    capacity_in_bytes: u64,
+    usage_in_bytes: u64,
+}
+
+impl ContentCache {
+    pub fn new(capacity_in_bytes: u64) -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            capacity_in_bytes,
+            usage_in_bytes: 0,
+        }
+    }
+
+    /// Looks up the content with `content_id`, marking it as the most recently used.
+    pub fn get(&mut self, content_id: &[u8; 32]) -> Option<Vec<u8>> {
+        self.entries.get(content_id).cloned()
+    }
+
+    /// Inserts content into the cache. Returns the number of entries that were evicted to make
+    /// room for it. Content larger than the capacity of the cache is not cached.
+    pub fn put(&mut self, content_id: [u8; 32], value: Vec<u8>) -> usize {
+        let size = value.len() as u64;
+        if size > self.capacity_in_bytes {
+            return 0;
+        }
+        if let Some(replaced) = self.entries.put(content_id, value) {
+            self.usage_in_bytes -= replaced.len() as u64;
+        }
+        self.usage_in_bytes += size;
+
+        let mut evicted = 0;
+        while self.usage_in_bytes > self.capacity_in_bytes {
+            match self.entries.pop_lru() {
+                Some((_, value)) => {
+                    self.usage_in_bytes -= value.len() as u64;
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total size of the cached content, in bytes.
+    pub fn usage_in_bytes(&self) -> u64 {
+        self.usage_in_bytes
+    }
 }
 
 /// An in-memory `ContentStore`.
@@ -108,6 +182,8 @@ pub struct MemoryContentStore {
     distance_fn: DistanceFunction,
     /// The radius of the store.
     radius: Distance,
+    /// The cache of content outside of the radius.
+    cache: Mutex<ContentCache>,
 }
 
 impl MemoryContentStore {
@@ -118,6 +194,9 @@ impl MemoryContentStore {
             node_id,
             distance_fn,
             radius: Distance::MAX,
+            cache: Mutex::new(ContentCache::new(
+                u64::from(DEFAULT_CACHE_CAPACITY_MB) * BYTES_IN_MB_U64,
+            )),
         }
     }
 
@@ -176,12 +255,24 @@ impl ContentStore for MemoryContentStore {
     fn radius(&self) -> Distance {
         self.radius
     }
+
+    fn put_cached<K: OverlayContentKey, V: AsRef<[u8]>>(&mut self, key: K, value: V) {
+        self.cache
+            .lock()
+            .put(key.content_id(), value.as_ref().to_vec());
+    }
+
+    fn get_cached<K: OverlayContentKey>(&self, key: &K) -> Option<Vec<u8>> {
+        self.cache.lock().get(&key.content_id())
+    }
 }
 
 /// Struct for configuring a `PortalStorage` instance.
 #[derive(Clone)]
 pub struct PortalStorageConfig {
     pub storage_capacity_mb: u64,
+    /// Capacity of the cache of content outside of the radius, in megabytes.
+    pub cache_capacity_mb: u64,
     pub node_id: NodeId,
     pub node_data_dir: PathBuf,
     pub distance_fn: DistanceFunction,
@@ -199,6 +290,7 @@ impl PortalStorageConfig {
         let sql_connection_pool = PortalStorage::setup_sql(&node_data_dir)?;
         Ok(Self {
             storage_capacity_mb,
+            cache_capacity_mb: u64::from(DEFAULT_CACHE_CAPACITY_MB),
             node_id,
             node_data_dir,
             distance_fn: DistanceFunction::Xor,
@@ -219,6 +311,12 @@ pub struct PortalStorage {
     sql_connection_pool: Pool<SqliteConnectionManager>,
     distance_fn: DistanceFunction,
     metrics: StorageMetricsReporter,
+    /// The subnetwork that the content is stored for. The metadata table is shared by every
+    /// subnetwork of the node, so rows are tagged with it.
+    protocol: ProtocolId,
+    /// The cache of content outside of the radius. It is kept in memory, separately from the
+    /// content within the radius.
+    cache: Mutex<ContentCache>,
 }
 
 impl ContentStore for PortalStorage {
@@ -255,6 +353,22 @@ impl ContentStore for PortalStorage {
     fn radius(&self) -> Distance {
         self.radius
     }
+
+    fn put_cached<K: OverlayContentKey, V: AsRef<[u8]>>(&mut self, key: K, value: V) {
+        let mut cache = self.cache.lock();
+        let evicted = cache.put(key.content_id(), value.as_ref().to_vec());
+        if evicted > 0 {
+            self.metrics.report_cache_evictions(evicted);
+        }
+        self.metrics
+            .report_cache_usage(cache.len(), cache.usage_in_bytes());
+    }
+
+    fn get_cached<K: OverlayContentKey>(&self, key: &K) -> Option<Vec<u8>> {
+        let content = self.cache.lock().get(&key.content_id());
+        self.metrics.report_cache_lookup(content.is_some());
+        content
+    }
 }
 
 impl PortalStorage {
@@ -278,6 +392,10 @@ impl PortalStorage {
             sql_connection_pool: config.sql_connection_pool,
             distance_fn: config.distance_fn,
             metrics,
+            protocol,
+            cache: Mutex::new(ContentCache::new(
+                config.cache_capacity_mb * BYTES_IN_MB_U64,
+            )),
         };
 
         // Set the metrics to the default radius, to start
@@ -326,19 +444,51 @@ impl PortalStorage {
         self.metrics.report_radius(radius);
     }
 
-    /// Returns a paginated list of all available content keys from local storage (from any
-    /// subnetwork) according to the provided offset and limit.
-    pub fn paginate(
+    /// Changes the storage capacity at runtime. The radius is reset as on startup. The store is
+    /// not pruned, call [`PortalStorage::prune`] or [`PortalStorage::prune_once`] afterwards.
+    pub fn set_storage_capacity_mb(&mut self, storage_capacity_mb: u64) {
+        self.storage_capacity_in_bytes = storage_capacity_mb * BYTES_IN_MB_U64;
+        self.metrics
+            .report_storage_capacity_bytes(self.storage_capacity_in_bytes as f64);
+        if self.storage_capacity_in_bytes == 0 {
+            self.set_radius(Distance::ZERO);
+        } else {
+            self.set_radius(Distance::MAX);
+        }
+    }
+
+    /// Prunes the farthest data while the store is over capacity.
+    /// Returns the number of items removed during pruning.
+    pub fn prune(&mut self) -> Result<usize, ContentStoreError> {
+        let num_removed_items = self.prune_db()?;
+        self.report_storage_usage()?;
+        Ok(num_removed_items)
+    }
+
+    /// Reports the storage used on disk to the metrics.
+    pub fn report_storage_usage(&self) -> Result<(), ContentStoreError> {
+        let total_bytes_on_disk = self.get_total_storage_usage_in_bytes_on_disk()?;
+        self.metrics
+            .report_total_storage_usage_bytes(total_bytes_on_disk as f64);
+        Ok(())
+    }
+
+    /// Returns a paginated list of the content keys stored locally by this subnetwork, according
+    /// to the provided offset and limit, decoded as `K`. The total counts the entries of this
+    /// subnetwork only, so that it is consistent with the pages.
+    pub fn paginate<K: OverlayContentKey>(
         &self,
         offset: &u64,
         limit: &u64,
-    ) -> Result<PaginateLocalContentInfo, ContentStoreError> {
+    ) -> Result<PaginateLocalContentInfo<K>, ContentStoreError> {
         let conn = self.sql_connection_pool.get()?;
         let mut query = conn.prepare(PAGINATE_QUERY)?;
+        let protocol = self.protocol.to_string();
 
-        let content_keys: Result<Vec<HistoryContentKey>, ContentStoreError> = query
+        let content_keys: Result<Vec<K>, ContentStoreError> = query
             .query_map(
                 &[
+                    (":protocol", protocol.as_str()),
                     (":offset", offset.to_string().as_str()),
                     (":limit", limit.to_string().as_str()),
                 ],
@@ -349,14 +499,17 @@ impl PortalStorage {
             )?
             .map(|row| {
                 // value is stored without 0x prefix, so we must add it
-                let bytes: Vec<u8> = hex_decode(&format!("0x{}", row?))
-                    .map_err(ContentStoreError::ByteUtilsError)?;
-                HistoryContentKey::try_from(bytes).map_err(ContentStoreError::ContentKey)
+                let row = row?;
+                let bytes: Vec<u8> =
+                    hex_decode(&format!("0x{row}")).map_err(ContentStoreError::ByteUtilsError)?;
+                K::try_from(bytes).map_err(|_| ContentStoreError::InvalidData {
+                    message: format!("Unable to decode stored content key: 0x{row}"),
+                })
             })
             .collect();
         Ok(PaginateLocalContentInfo {
             content_keys: content_keys?,
-            total_entries: self.total_entry_count()?,
+            total_entries: self.protocol_entry_count()?,
         })
     }
 
@@ -374,6 +527,23 @@ impl PortalStorage {
         }
     }
 
+    /// Returns the number of entries stored by this subnetwork.
+    fn protocol_entry_count(&self) -> Result<u64, ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        let mut query = conn.prepare(PROTOCOL_ENTRY_COUNT_QUERY)?;
+        let result: Result<Vec<EntryCount>, rusqlite::Error> = query
+            .query_map([self.protocol.to_string()], |row| {
+                Ok(EntryCount(row.get(0)?))
+            })?
+            .collect();
+        match result?.first() {
+            Some(val) => Ok(val.0),
+            None => Err(ContentStoreError::InvalidData {
+                message: "Invalid total entries count returned from sql query.".to_string(),
+            }),
+        }
+    }
+
     /// Returns the distance to `key` from the local `NodeId` according to the distance function.
     fn distance_to_key<K: OverlayContentKey>(&self, key: &K) -> Distance {
         match self.distance_fn {
@@ -427,48 +597,63 @@ impl PortalStorage {
     /// Resets the data radius if it prunes any data. Does nothing if the store is empty.
     /// Returns the number of items removed during pruning
     fn prune_db(&mut self) -> Result<usize, ContentStoreError> {
-        let mut farthest_content_id: Option<[u8; 32]> = self.find_farthest_content_id()?;
         let mut num_removed_items = 0;
         // Delete furthest data until our data usage is less than capacity.
-        while self.capacity_reached()? {
-            // If the database were empty, then `capacity_reached()` would be false, because the
-            // amount of content (zero) would not be greater than capacity.
-            let id_to_remove =
-                farthest_content_id.expect("Capacity reached, but no farthest id found!");
-            // Test if removing the item would put us under capacity
-            if self.does_eviction_cause_under_capacity(&id_to_remove)? {
-                // If so, we're done pruning
-                debug!(
-                    "Removing item would drop us below capacity. We target slight overfilling. {}",
-                    hex_encode(id_to_remove)
-                );
-                self.set_radius(self.distance_to_content_id(&id_to_remove));
-                break;
+        while let Some(evicted) = self.prune_once()? {
+            if evicted {
+                num_removed_items += 1;
             }
+        }
+        Ok(num_removed_items)
+    }
+
+    /// Runs a single pruning step: if the store is over capacity, the farthest item is deleted
+    /// and the radius shrinks to the new farthest item. Callers sharing the store behind a lock
+    /// can take the lock per step, so that the store stays usable while a large prune runs.
+    /// Returns `None` once the store fits its capacity, otherwise whether the item was deleted.
+    pub fn prune_once(&mut self) -> Result<Option<bool>, ContentStoreError> {
+        if !self.capacity_reached()? {
+            return Ok(None);
+        }
+        // If the database were empty, then `capacity_reached()` would be false, because the
+        // amount of content (zero) would not be greater than capacity.
+        let id_to_remove = self
+            .find_farthest_content_id()?
+            .expect("Capacity reached, but no farthest id found!");
+        // Test if removing the item would put us under capacity
+        if self.does_eviction_cause_under_capacity(&id_to_remove)? {
+            // If so, we're done pruning
             debug!(
-                "Capacity reached, deleting farthest: {}",
+                "Removing item would drop us below capacity. We target slight overfilling. {}",
                 hex_encode(id_to_remove)
             );
-            if let Err(err) = self.evict(id_to_remove) {
+            self.set_radius(self.distance_to_content_id(&id_to_remove));
+            return Ok(None);
+        }
+        debug!(
+            "Capacity reached, deleting farthest: {}",
+            hex_encode(id_to_remove)
+        );
+        let evicted = match self.evict(id_to_remove) {
+            Ok(()) => true,
+            Err(err) => {
                 debug!("Error writing content ID {id_to_remove:?} to meta db. Reverted: {err:?}",);
-            } else {
-                num_removed_items += 1;
+                false
             }
-            // Calculate new farthest_content_id and reset radius
-            match self.find_farthest_content_id()? {
-                None => {
-                    // We get here if the entire db has been pruned,
-                    // eg. user selected 0mb capacity for storage
-                    self.set_radius(Distance::ZERO);
-                }
-                Some(farthest) => {
-                    debug!("Found new farthest: {}", hex_encode(farthest));
-                    self.set_radius(self.distance_to_content_id(&farthest));
-                    farthest_content_id = Some(farthest);
-                }
+        };
+        // Calculate new farthest_content_id and reset radius
+        match self.find_farthest_content_id()? {
+            None => {
+                // We get here if the entire db has been pruned,
+                // eg. user selected 0mb capacity for storage
+                self.set_radius(Distance::ZERO);
+            }
+            Some(farthest) => {
+                debug!("Found new farthest: {}", hex_encode(farthest));
+                self.set_radius(self.distance_to_content_id(&farthest));
             }
         }
-        Ok(num_removed_items)
+        Ok(Some(evicted))
     }
 
     /// Internal method for testing if an eviction would cause the store to fall under capacity.
@@ -583,7 +768,8 @@ impl PortalStorage {
                 content_id.to_vec(),
                 content_id_as_u32,
                 content_key,
-                value_size
+                value_size,
+                self.protocol.to_string()
             ],
         ) {
             Ok(_) => Ok(()),
@@ -755,7 +941,15 @@ impl PortalStorage {
 
         let manager = SqliteConnectionManager::file(sql_path);
         let pool = Pool::new(manager)?;
-        pool.get()?.execute(CREATE_QUERY, params![])?;
+        let conn = pool.get()?;
+        conn.execute(CREATE_QUERY, params![])?;
+        // Databases created before content was tagged with its subnetwork lack the column. Their
+        // untagged content is still served and pruned, but not listed by `paginate`.
+        let has_protocol_column: bool =
+            conn.query_row(PROTOCOL_COLUMN_EXISTS_QUERY, [], |row| row.get(0))?;
+        if !has_protocol_column {
+            conn.execute(ADD_PROTOCOL_COLUMN_QUERY, params![])?;
+        }
         Ok(pool)
     }
 
@@ -770,15 +964,21 @@ const CREATE_QUERY: &str = "CREATE TABLE IF NOT EXISTS content_metadata (
                                 content_id_long TEXT PRIMARY KEY,
                                 content_id_short INTEGER NOT NULL,
                                 content_key TEXT NOT NULL,
-                                content_size INTEGER
+                                content_size INTEGER,
+                                protocol TEXT
                             );
                             CREATE INDEX content_size_idx ON content_metadata(content_size);
                             CREATE INDEX content_id_short_idx ON content_metadata(content_id_short);
                             CREATE INDEX content_id_long_idx ON content_metadata(content_id_long);";
 
 const INSERT_QUERY: &str =
-    "INSERT OR IGNORE INTO content_metadata (content_id_long, content_id_short, content_key, content_size)
-                            VALUES (?1, ?2, ?3, ?4)";
+    "INSERT OR IGNORE INTO content_metadata (content_id_long, content_id_short, content_key, content_size, protocol)
+                            VALUES (?1, ?2, ?3, ?4, ?5)";
+
+const PROTOCOL_COLUMN_EXISTS_QUERY: &str =
+    "SELECT COUNT(*) > 0 FROM pragma_table_info('content_metadata') WHERE name = 'protocol'";
+
+const ADD_PROTOCOL_COLUMN_QUERY: &str = "ALTER TABLE content_metadata ADD COLUMN protocol TEXT";
 
 const DELETE_QUERY: &str = "DELETE FROM content_metadata
                             WHERE content_id_long = (?1)";
@@ -795,8 +995,11 @@ const TOTAL_DATA_SIZE_QUERY: &str = "SELECT TOTAL(content_size) FROM content_met
 
 const TOTAL_ENTRY_COUNT_QUERY: &str = "SELECT COUNT(content_id_long) FROM content_metadata";
 
+const PROTOCOL_ENTRY_COUNT_QUERY: &str =
+    "SELECT COUNT(content_id_long) FROM content_metadata WHERE protocol = (?1)";
+
 const PAGINATE_QUERY: &str =
-    "SELECT content_key FROM content_metadata ORDER BY content_key LIMIT :limit OFFSET :offset";
+    "SELECT content_key FROM content_metadata WHERE protocol = :protocol ORDER BY content_key LIMIT :limit OFFSET :offset";
 
 const CONTENT_SIZE_LOOKUP_QUERY: &str =
     "SELECT content_size FROM content_metadata WHERE content_id_long = (?1)";
@@ -824,7 +1027,10 @@ pub mod test {
     use serial_test::serial;
 
     use crate::utils::db::{configure_node_data_dir, setup_temp_dir};
+    use ethereum_types::H256;
+    use ethportal_api::types::content_key::beacon::LightClientOptimisticUpdateKey;
     use ethportal_api::types::content_key::overlay::IdentityContentKey;
+    use ethportal_api::{BeaconContentKey, EpochAccumulatorKey};
 
     const CAPACITY_MB: u64 = 2;
 
@@ -903,6 +1109,75 @@ pub mod test {
         Ok(())
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn test_cached_content_is_not_stored() -> Result<(), ContentStoreError> {
+        let temp_dir = setup_temp_dir().unwrap();
+        let node_id = get_active_node_id(temp_dir.path().to_path_buf());
+        let storage_config =
+            PortalStorageConfig::new(CAPACITY_MB, temp_dir.path().to_path_buf(), node_id).unwrap();
+        let mut storage = PortalStorage::new(storage_config, ProtocolId::History)?;
+        let content_key = generate_random_content_key();
+        let value: Vec<u8> = "OGFWs179fWnqmjvHQFGHszXloc3Wzdb4".into();
+        storage.put_cached(content_key.clone(), &value);
+
+        // Cached content is only served to local callers.
+        assert_eq!(storage.get_cached(&content_key), Some(value));
+        assert_eq!(storage.get(&content_key)?, None);
+        assert_eq!(storage.total_entry_count()?, 0);
+
+        std::mem::drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn test_paginate() -> Result<(), ContentStoreError> {
+        let temp_dir = setup_temp_dir().unwrap();
+        let node_id = get_active_node_id(temp_dir.path().to_path_buf());
+        let storage_config =
+            PortalStorageConfig::new(CAPACITY_MB, temp_dir.path().to_path_buf(), node_id).unwrap();
+        let mut storage = PortalStorage::new(storage_config.clone(), ProtocolId::Beacon)?;
+        let mut history_storage = PortalStorage::new(storage_config, ProtocolId::History)?;
+        let beacon_keys: Vec<BeaconContentKey> = (1..=3)
+            .map(|slot| {
+                BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey::new(
+                    slot,
+                ))
+            })
+            .collect();
+        for beacon_key in &beacon_keys {
+            storage.store(beacon_key, &vec![1, 2, 3])?;
+        }
+        // Epoch accumulator keys share the selector of the optimistic update keys, so the keys of
+        // both subnetworks are interleaved in the table
+        for _ in 0..3 {
+            let history_key = HistoryContentKey::EpochAccumulator(EpochAccumulatorKey {
+                epoch_hash: H256::random(),
+            });
+            history_storage.store(&history_key, &vec![4, 5, 6])?;
+        }
+
+        let result = storage.paginate::<BeaconContentKey>(&0, &10)?;
+        assert_eq!(result.content_keys, beacon_keys);
+        assert_eq!(result.total_entries, 3);
+
+        // Offset and limit only count the keys of this subnetwork
+        let result = storage.paginate::<BeaconContentKey>(&1, &1)?;
+        assert_eq!(result.content_keys, vec![beacon_keys[1].clone()]);
+        assert_eq!(result.total_entries, 3);
+
+        let result = history_storage.paginate::<HistoryContentKey>(&0, &10)?;
+        assert_eq!(result.content_keys.len(), 3);
+        assert_eq!(result.total_entries, 3);
+
+        std::mem::drop(storage);
+        std::mem::drop(history_storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn test_get_total_storage() -> Result<(), ContentStoreError> {
@@ -1020,6 +1295,42 @@ pub mod test {
         Ok(())
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn test_set_storage_capacity() -> Result<(), ContentStoreError> {
+        let temp_dir = setup_temp_dir().unwrap();
+        let node_id = get_active_node_id(temp_dir.path().to_path_buf());
+        let storage_config =
+            PortalStorageConfig::new(CAPACITY_MB, temp_dir.path().to_path_buf(), node_id).unwrap();
+        let mut storage = PortalStorage::new(storage_config, ProtocolId::History)?;
+
+        for _ in 0..50 {
+            let content_key = generate_random_content_key();
+            let value: Vec<u8> = vec![0; 32000];
+            storage.store(&content_key, &value)?;
+        }
+        assert_eq!(storage.radius, Distance::MAX);
+
+        // Shrinking the capacity lets pruning remove the farthest data, one step at a time
+        storage.set_storage_capacity_mb(1);
+        assert_eq!(storage.total_entry_count()?, 50);
+        assert_eq!(storage.prune_once()?, Some(true));
+        assert_eq!(storage.total_entry_count()?, 49);
+        let pruned = storage.prune()? + 1;
+        assert!(storage.radius < Distance::MAX);
+        assert_eq!(storage.total_entry_count()?, 50 - pruned as u64);
+        assert_eq!(storage.prune_once()?, None);
+
+        // Growing the capacity again restores the full radius, without pruning
+        storage.set_storage_capacity_mb(CAPACITY_MB);
+        assert_eq!(storage.prune()?, 0);
+        assert_eq!(storage.radius, Distance::MAX);
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn test_restarting_storage_with_increased_capacity() -> Result<(), ContentStoreError> {
@@ -1185,6 +1496,27 @@ pub mod test {
         assert_eq!(store.get(&arb_key).unwrap(), Some(val));
     }
 
+    #[test]
+    fn content_cache_evicts_least_recently_used() {
+        let mut cache = ContentCache::new(3);
+        assert_eq!(cache.put([0x01; 32], vec![0xef]), 0);
+        assert_eq!(cache.put([0x02; 32], vec![0xef]), 0);
+        assert_eq!(cache.put([0x03; 32], vec![0xef]), 0);
+
+        // Using the oldest entry keeps it from being evicted.
+        assert_eq!(cache.get(&[0x01; 32]), Some(vec![0xef]));
+        assert_eq!(cache.put([0x04; 32], vec![0xef, 0xef]), 2);
+        assert_eq!(cache.get(&[0x01; 32]), Some(vec![0xef]));
+        assert_eq!(cache.get(&[0x02; 32]), None);
+        assert_eq!(cache.get(&[0x03; 32]), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.usage_in_bytes(), 3);
+
+        // Content larger than the cache is not cached.
+        assert_eq!(cache.put([0x05; 32], vec![0xef; 4]), 0);
+        assert_eq!(cache.get(&[0x05; 32]), None);
+    }
+
     #[test]
     fn memory_store_is_within_radius_and_unavailable() {
         let node_id = NodeId::random();
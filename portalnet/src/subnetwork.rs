@@ -0,0 +1,341 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use discv5::{enr::NodeId, TalkRequest};
+use ethportal_api::jsonrpsee::Methods;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::portal::{OverlayTuning, PruneInfo, SubnetworkSummary};
+use ethportal_api::Header;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use utp_rs::socket::UtpSocket;
+
+use crate::{
+    config::PortalnetConfig,
+    discovery::{Discovery, UtpEnr},
+    events::EventEnvelope,
+    shutdown::ShutdownSignal,
+    storage::PortalStorageConfig,
+    types::messages::ProtocolId,
+};
+use trin_validation::oracle::HeaderOracle;
+
+/// A Portal subnetwork that can be activated on a node with `--networks`.
+///
+/// Implementations own everything that is specific to the subnetwork: the content key type and
+/// validator of its overlay, the handler for its TALKREQ events, and the JSON-RPC methods it
+/// serves. The node only routes TALKREQs by protocol id, so a subnetwork can be added without
+/// touching event dispatch.
+///
+/// RPC modules are not pluggable: the JSON-RPC methods of a subnetwork are served under the
+/// `PortalRpcModule` named after it, and the node fails to start if a subnetwork serves methods
+/// but has no such module. The eth module is built on the history and transaction gossip
+/// subnetworks only.
+#[async_trait]
+pub trait PortalSubnetwork: Send + Sync {
+    /// Name used to select the subnetwork with `--networks`.
+    fn name(&self) -> &'static str;
+
+    /// Protocol id of the subnetwork's TALKREQs.
+    fn protocol_id(&self) -> ProtocolId;
+
+    /// Builds the subnetwork's overlay and spawns its event and JSON-RPC handlers.
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle>;
+}
+
+/// Management operations on a running subnetwork, served by the admin JSON-RPC module.
+#[async_trait]
+pub trait SubnetworkAdmin: Send + Sync {
+    /// Returns the current overlay parameters.
+    async fn overlay_tuning(&self) -> anyhow::Result<OverlayTuning>;
+
+    /// Updates the overlay parameters that are set in `tuning`, and returns the resulting
+    /// parameters.
+    async fn tune_overlay(&self, tuning: OverlayTuning) -> anyhow::Result<OverlayTuning>;
+
+    /// Changes the capacity of the content store, pruning the farthest content if the store no
+    /// longer fits.
+    fn set_storage_capacity(&self, storage_capacity_mb: u64) -> anyhow::Result<PruneInfo>;
+
+    /// Prunes the farthest content while the content store is over capacity.
+    fn prune(&self) -> anyhow::Result<PruneInfo>;
+
+    /// Adds `enr` to the overlay bootnodes and routing table, and pings it.
+    async fn add_bootnode(&self, enr: Enr) -> anyhow::Result<()>;
+
+    /// Removes `node_id` from the overlay routing table. Returns `true` if the node was present.
+    fn remove_node(&self, node_id: NodeId) -> bool;
+
+    /// Returns the storage, message and uTP metrics of the subnetwork.
+    fn summary(&self) -> SubnetworkSummary;
+
+    /// Stops the subnetwork's overlay service, once it has persisted its state.
+    async fn shutdown(&self);
+}
+
+impl fmt::Debug for dyn SubnetworkAdmin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubnetworkAdmin").finish_non_exhaustive()
+    }
+}
+
+/// Node-wide resources shared by every subnetwork.
+#[derive(Clone)]
+pub struct SubnetworkContext {
+    pub discovery: Arc<Discovery>,
+    pub utp_socket: Arc<UtpSocket<UtpEnr>>,
+    pub portalnet_config: PortalnetConfig,
+    pub storage_config: PortalStorageConfig,
+    pub header_oracle: Arc<RwLock<HeaderOracle>>,
+    /// Resolves once the node shuts down.
+    pub shutdown_signal: ShutdownSignal,
+}
+
+/// A running subnetwork.
+pub struct SubnetworkHandle {
+    /// Name the subnetwork was selected with.
+    pub name: &'static str,
+    /// Protocol id of the subnetwork's TALKREQs.
+    pub protocol_id: ProtocolId,
+    /// Receives the TALKREQs sent to `protocol_id`.
+    pub event_tx: mpsc::UnboundedSender<TalkRequest>,
+    /// JSON-RPC methods served by the subnetwork, on the RPC transports that select the rpc module
+    /// named after the subnetwork. A subnetwork can only serve methods if there is a
+    /// `PortalRpcModule` with its name.
+    pub rpc_methods: Option<Methods>,
+    /// Events emitted by the subnetwork's overlay, streamed to `portal_subscribe` subscribers.
+    pub event_stream: Option<broadcast::Sender<EventEnvelope>>,
+    /// Execution headers of the chain head verified by the subnetwork, streamed to
+    /// `eth_subscribe("newHeads")` subscribers.
+    pub head_stream: Option<broadcast::Sender<Header>>,
+    /// Management operations served by the admin JSON-RPC module.
+    pub admin: Option<Arc<dyn SubnetworkAdmin>>,
+    /// Typed channel to the subnetwork's JSON-RPC handler, see [`SubnetworkHandle::jsonrpc_tx`].
+    jsonrpc_tx: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl SubnetworkHandle {
+    pub fn new(
+        name: &'static str,
+        protocol_id: ProtocolId,
+        event_tx: mpsc::UnboundedSender<TalkRequest>,
+    ) -> Self {
+        Self {
+            name,
+            protocol_id,
+            event_tx,
+            rpc_methods: None,
+            event_stream: None,
+            head_stream: None,
+            admin: None,
+            jsonrpc_tx: None,
+        }
+    }
+
+    pub fn with_rpc_methods(mut self, rpc_methods: impl Into<Methods>) -> Self {
+        self.rpc_methods = Some(rpc_methods.into());
+        self
+    }
+
+    pub fn with_event_stream(mut self, event_stream: broadcast::Sender<EventEnvelope>) -> Self {
+        self.event_stream = Some(event_stream);
+        self
+    }
+
+    pub fn with_head_stream(mut self, head_stream: broadcast::Sender<Header>) -> Self {
+        self.head_stream = Some(head_stream);
+        self
+    }
+
+    pub fn with_admin(mut self, admin: Arc<dyn SubnetworkAdmin>) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
+    /// Exposes the channel to the subnetwork's JSON-RPC handler, for node components that query
+    /// the subnetwork directly rather than through the RPC server.
+    pub fn with_jsonrpc_tx<T: Send + 'static>(
+        mut self,
+        jsonrpc_tx: mpsc::UnboundedSender<T>,
+    ) -> Self {
+        self.jsonrpc_tx = Some(Box::new(jsonrpc_tx));
+        self
+    }
+
+    /// Returns the channel to the subnetwork's JSON-RPC handler, if it accepts requests of type `T`.
+    pub fn jsonrpc_tx<T: Send + 'static>(&self) -> Option<mpsc::UnboundedSender<T>> {
+        self.jsonrpc_tx
+            .as_ref()?
+            .downcast_ref::<mpsc::UnboundedSender<T>>()
+            .cloned()
+    }
+}
+
+/// The subnetworks a node knows how to run, keyed by their `--networks` name.
+#[derive(Default)]
+pub struct SubnetworkRegistry {
+    subnetworks: Vec<Box<dyn PortalSubnetwork>>,
+}
+
+impl SubnetworkRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a subnetwork to the registry. Names and protocol ids must be unique.
+    pub fn register(&mut self, subnetwork: impl PortalSubnetwork + 'static) -> anyhow::Result<()> {
+        if self.get(subnetwork.name()).is_some() {
+            bail!("Subnetwork {} is already registered", subnetwork.name());
+        }
+        let protocol_id = subnetwork.protocol_id();
+        if protocol_id == ProtocolId::Utp {
+            bail!(
+                "Subnetwork {} cannot use the uTP protocol id",
+                subnetwork.name()
+            );
+        }
+        if let Some(existing) = self
+            .subnetworks
+            .iter()
+            .find(|existing| existing.protocol_id() == protocol_id)
+        {
+            bail!(
+                "Subnetwork {} uses the {protocol_id} protocol id, already used by {}",
+                subnetwork.name(),
+                existing.name()
+            );
+        }
+        self.subnetworks.push(Box::new(subnetwork));
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn PortalSubnetwork> {
+        self.subnetworks
+            .iter()
+            .find(|subnetwork| subnetwork.name() == name)
+            .map(|subnetwork| subnetwork.as_ref())
+    }
+
+    /// Names of all registered subnetworks, in registration order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.subnetworks
+            .iter()
+            .map(|subnetwork| subnetwork.name())
+            .collect()
+    }
+
+    /// Initializes the selected subnetworks, in the order they were selected.
+    pub async fn initialize(
+        &self,
+        networks: &[String],
+        context: &SubnetworkContext,
+    ) -> anyhow::Result<Vec<SubnetworkHandle>> {
+        let mut handles = vec![];
+        for network in networks {
+            let subnetwork = match self.get(network) {
+                Some(subnetwork) => subnetwork,
+                None => bail!(
+                    "Unknown subnetwork: {network}, expected one of: {}",
+                    self.names().join(", ")
+                ),
+            };
+            if handles
+                .iter()
+                .any(|handle: &SubnetworkHandle| handle.name == subnetwork.name())
+            {
+                continue;
+            }
+            handles.push(subnetwork.initialize(context).await?);
+        }
+        Ok(handles)
+    }
+}
+
+/// Routes TALKREQs to the subnetworks that were initialized, by protocol id.
+pub fn overlay_senders(
+    handles: &[SubnetworkHandle],
+) -> HashMap<ProtocolId, mpsc::UnboundedSender<TalkRequest>> {
+    handles
+        .iter()
+        .map(|handle| (handle.protocol_id.clone(), handle.event_tx.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    struct TestSubnetwork {
+        name: &'static str,
+        protocol_id: ProtocolId,
+    }
+
+    #[async_trait]
+    impl PortalSubnetwork for TestSubnetwork {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn protocol_id(&self) -> ProtocolId {
+            self.protocol_id.clone()
+        }
+
+        async fn initialize(
+            &self,
+            _context: &SubnetworkContext,
+        ) -> anyhow::Result<SubnetworkHandle> {
+            let (event_tx, _) = mpsc::unbounded_channel();
+            Ok(SubnetworkHandle::new(
+                self.name,
+                self.protocol_id.clone(),
+                event_tx,
+            ))
+        }
+    }
+
+    #[test]
+    fn register_rejects_duplicates() {
+        let mut registry = SubnetworkRegistry::new();
+        registry
+            .register(TestSubnetwork {
+                name: "history",
+                protocol_id: ProtocolId::History,
+            })
+            .unwrap();
+
+        assert!(registry
+            .register(TestSubnetwork {
+                name: "history",
+                protocol_id: ProtocolId::State,
+            })
+            .is_err());
+        assert!(registry
+            .register(TestSubnetwork {
+                name: "experimental",
+                protocol_id: ProtocolId::History,
+            })
+            .is_err());
+        assert!(registry
+            .register(TestSubnetwork {
+                name: "experimental",
+                protocol_id: ProtocolId::Utp,
+            })
+            .is_err());
+        assert_eq!(registry.names(), vec!["history"]);
+    }
+
+    #[test]
+    fn handle_jsonrpc_tx_is_typed() {
+        let (event_tx, _) = mpsc::unbounded_channel();
+        let (jsonrpc_tx, _) = mpsc::unbounded_channel::<u8>();
+        let handle = SubnetworkHandle::new("history", ProtocolId::History, event_tx)
+            .with_jsonrpc_tx(jsonrpc_tx);
+
+        assert!(handle.jsonrpc_tx::<u8>().is_some());
+        assert!(handle.jsonrpc_tx::<u16>().is_none());
+    }
+}
@@ -161,7 +161,7 @@ pub enum ProtocolIdError {
 }
 
 /// Protocol identifiers
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ProtocolId {
     State,
     History,
@@ -538,6 +538,10 @@ impl From<PopulatedOffer> for Offer {
     }
 }
 
+/// The maximum number of content keys in a single OFFER, bounded by the size of the ACCEPT
+/// bitlist.
+pub const MAX_CONTENT_KEYS_PER_OFFER: usize = 8;
+
 #[derive(Debug, PartialEq, Clone, Encode, Decode, Serialize, Deserialize)]
 pub struct Accept {
     pub connection_id: u16,
@@ -554,9 +558,18 @@ impl From<Accept> for Value {
 #[allow(clippy::unwrap_used)]
 mod test {
     use super::*;
+    use ethportal_api::types::portal::MAX_UTP_PACKET_SIZE;
     use ethportal_api::utils::bytes::hex_encode_upper;
     use test_log::test;
 
+    #[test]
+    fn max_utp_packet_fits_in_talk_request() {
+        assert_eq!(
+            MAX_UTP_PACKET_SIZE as usize,
+            MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE
+        );
+    }
+
     #[test]
     #[should_panic]
     fn protocol_id_invalid() {
@@ -12,7 +12,7 @@ use ethportal_api::utils::bytes::{hex_decode, hex_encode};
 
 const TRIN_DATA_ENV_VAR: &str = "TRIN_DATA_PATH";
 const TRIN_DATA_DIR: &str = "trin";
-const UNSAFE_PRIVATE_KEY_FILE_NAME: &str = "unsafe_private_key.hex";
+pub const UNSAFE_PRIVATE_KEY_FILE_NAME: &str = "unsafe_private_key.hex";
 
 /// Create a directory on the file system that is deleted once it goes out of scope
 pub fn setup_temp_dir() -> anyhow::Result<TempDir> {
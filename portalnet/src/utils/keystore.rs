@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use anyhow::{anyhow, bail};
+use discv5::enr::{CombinedKey, EnrBuilder};
+use ethereum_types::H256;
+
+use ethportal_api::utils::bytes::hex_encode;
+
+/// Environment variable holding the keystore password, used when no password file is supplied.
+pub const KEYSTORE_PASSWORD_ENV_VAR: &str = "TRIN_KEYSTORE_PASSWORD";
+const KEYSTORE_DIR: &str = "keystore";
+
+/// Returns the directory where `trin account` stores keystore files.
+pub fn keystore_dir(trin_data_dir: &Path) -> PathBuf {
+    trin_data_dir.join(KEYSTORE_DIR)
+}
+
+/// Reads the keystore password from the given file, falling back to the
+/// `TRIN_KEYSTORE_PASSWORD` environment variable.
+pub fn read_password(password_file: Option<&Path>) -> anyhow::Result<String> {
+    let password = match password_file {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| anyhow!("Unable to read password file {path:?}: {err}"))?
+            .trim_end_matches(&['\r', '\n'][..])
+            .to_owned(),
+        None => env::var(KEYSTORE_PASSWORD_ENV_VAR).map_err(|_| {
+            anyhow!("A keystore password is required: use --password-file or set {KEYSTORE_PASSWORD_ENV_VAR}")
+        })?,
+    };
+    if password.is_empty() {
+        bail!("Keystore password must not be empty");
+    }
+    Ok(password)
+}
+
+/// Encrypts the private key into a new Web3 secret storage keystore file in `dir`.
+/// Returns the path of the keystore file, which is named after the node id of the key.
+pub fn encrypt_private_key(
+    dir: &Path,
+    private_key: H256,
+    password: &str,
+) -> anyhow::Result<PathBuf> {
+    let key = CombinedKey::secp256k1_from_bytes(private_key.0.clone().as_mut_slice())
+        .map_err(|e| anyhow!("Invalid private key: {e:?}"))?;
+    let node_id = EnrBuilder::new("v4").build(&key)?.node_id();
+    let file_name = format!("{}.json", hex_encode(node_id.raw()));
+    let path = dir.join(&file_name);
+    if path.exists() {
+        bail!("A keystore for this key already exists at {path:?}");
+    }
+
+    fs::create_dir_all(dir)?;
+    eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        private_key.as_bytes(),
+        password,
+        Some(&file_name),
+    )?;
+    Ok(path)
+}
+
+/// Decrypts the private key stored in the keystore file at `path`.
+pub fn decrypt_private_key(path: &Path, password: &str) -> anyhow::Result<H256> {
+    let private_key = eth_keystore::decrypt_key(path, password)
+        .map_err(|err| anyhow!("Unable to decrypt keystore {path:?}: {err}"))?;
+    if private_key.len() != 32 {
+        bail!(
+            "Invalid private key length in keystore: {}, expected 32 bytes",
+            private_key.len()
+        );
+    }
+    Ok(H256::from_slice(&private_key))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    use crate::utils::db::setup_temp_dir;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn keystore_roundtrip() {
+        let temp_dir = setup_temp_dir().unwrap();
+        let pk = H256::from_slice(&CombinedKey::generate_secp256k1().encode());
+
+        let path = encrypt_private_key(temp_dir.path(), pk, "password").unwrap();
+        assert_eq!(decrypt_private_key(&path, "password").unwrap(), pk);
+        assert!(decrypt_private_key(&path, "wrong password").is_err());
+
+        // a keystore is never overwritten
+        assert!(encrypt_private_key(temp_dir.path(), pk, "password").is_err());
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn password_file_strips_trailing_newline() {
+        let temp_dir = setup_temp_dir().unwrap();
+        let password_file = temp_dir.path().join("password.txt");
+        fs::write(&password_file, "password\n").unwrap();
+
+        assert_eq!(read_password(Some(&password_file)).unwrap(), "password");
+        temp_dir.close().unwrap();
+    }
+}
@@ -1,2 +1,3 @@
 pub mod db;
+pub mod keystore;
 pub mod portal_wire;
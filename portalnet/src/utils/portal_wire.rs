@@ -52,9 +52,13 @@ pub fn encode_content_payload(content_items: &[Bytes]) -> anyhow::Result<BytesMu
     Ok(content_payload.into_inner())
 }
 
+/// The maximum size of the LEB128 varint prefixed to a content item, for items of up to `u32`
+/// bytes.
+pub const MAX_VARINT_SIZE: usize = 5;
+
 /// Try to read up to five LEB128 bytes (The maximum content size allowed for this application is limited to `uint32`).
 pub fn read_varint(buf: &[u8]) -> anyhow::Result<(usize, u32)> {
-    for i in 1..6 {
+    for i in 1..=MAX_VARINT_SIZE {
         match leb128::read::unsigned(&mut &buf[0..i]) {
             Ok(varint) => {
                 let varint = u32::try_from(varint).map_err(|_| {
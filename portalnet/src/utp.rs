@@ -0,0 +1,333 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use discv5::enr::NodeId;
+use parking_lot::Mutex;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use utp_rs::{cid::ConnectionId, conn::ConnectionConfig, stream::UtpStream};
+
+use crate::discovery::UtpEnr;
+
+/// The type of a uTP packet that carries data, as found in the high nibble of its first byte.
+const UTP_ST_DATA: u8 = 0;
+/// The size of a uTP packet header, without extensions.
+const UTP_HEADER_SIZE: usize = 20;
+
+/// The settings of the uTP connections of an overlay, and the limit on how many of them are open
+/// at once.
+#[derive(Clone)]
+pub struct UtpConnections {
+    config: ConnectionConfig,
+    max_connections: usize,
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl UtpConnections {
+    /// A `max_connections` of zero disables the limit.
+    pub fn new(config: ConnectionConfig, max_connections: usize) -> Self {
+        let permits = (max_connections > 0).then(|| Arc::new(Semaphore::new(max_connections)));
+        Self {
+            config,
+            max_connections,
+            permits,
+        }
+    }
+
+    /// Returns the settings to open uTP connections with.
+    pub fn config(&self) -> ConnectionConfig {
+        self.config
+    }
+
+    /// Returns the maximum number of concurrent uTP connections, or zero if unlimited.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Waits until another connection can be opened without going over the limit. The connection
+    /// counts towards the limit until the returned permit is dropped.
+    pub async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.permits {
+            // The semaphore is never closed, so acquiring a permit cannot fail.
+            Some(permits) => Arc::clone(permits).acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Reserves another connection without waiting, or fails if the limit is reached. Used before
+    /// a peer is given the ID of a connection to open, so that the peer is never asked to connect
+    /// while no connection can be accepted. The connection counts towards the limit until the
+    /// returned permit is dropped.
+    pub fn try_acquire(&self) -> Result<Option<OwnedSemaphorePermit>, TryAcquireError> {
+        match &self.permits {
+            Some(permits) => Arc::clone(permits).try_acquire_owned().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Limits on how much data peers send over inbound uTP connections, shared by the uTP socket and
+/// the overlays.
+///
+/// utp-rs only reads a stream as a whole, so the limit is enforced by the socket instead: the data
+/// packets of each limited connection are counted as they arrive, and once a connection goes over
+/// its limit, its packets are dropped before they reach utp-rs. The connection then sees no more
+/// packets from the peer, and reading the stream fails when the connection's idle timeout expires.
+#[derive(Clone, Default)]
+pub struct UtpReadLimits {
+    limits: Arc<Mutex<HashMap<(NodeId, u16), ReadLimit>>>,
+    /// The source of the IDs that tell the limits of successive connections with the same key
+    /// apart.
+    next_id: Arc<AtomicU64>,
+}
+
+/// The data received so far over a limited connection.
+struct ReadLimit {
+    id: u64,
+    max_size: usize,
+    received: usize,
+    /// The sequence numbers of the data packets received, so that retransmissions are only
+    /// counted once.
+    seq_nrs: HashSet<u16>,
+    exceeded: bool,
+}
+
+impl UtpReadLimits {
+    /// Limits the data that the peer sends over the connection with `cid` to `max_size` bytes,
+    /// until the returned guard is dropped. Set before the connection is opened, so that every
+    /// packet is counted.
+    pub fn limit(&self, cid: &ConnectionId<UtpEnr>, max_size: usize) -> UtpReadLimit {
+        let key = (cid.peer.node_id(), cid.recv);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.limits.lock().insert(
+            key,
+            ReadLimit {
+                id,
+                max_size,
+                received: 0,
+                seq_nrs: HashSet::new(),
+                exceeded: false,
+            },
+        );
+        UtpReadLimit {
+            limits: self.clone(),
+            key,
+            id,
+            max_size,
+        }
+    }
+
+    /// Returns `true` if the connection with `cid` is limited.
+    pub fn is_limited(&self, cid: &ConnectionId<UtpEnr>) -> bool {
+        self.limits
+            .lock()
+            .contains_key(&(cid.peer.node_id(), cid.recv))
+    }
+
+    /// Counts `packet`, received from `node_id`, towards the limit of its connection. Returns
+    /// `false` if the packet should be dropped, because its connection went over its limit.
+    pub fn admit(&self, node_id: NodeId, packet: &[u8]) -> bool {
+        if packet.len() < UTP_HEADER_SIZE || packet[0] >> 4 != UTP_ST_DATA {
+            return true;
+        }
+        let conn_id = u16::from_be_bytes([packet[2], packet[3]]);
+        let mut limits = self.limits.lock();
+        let limit = match limits.get_mut(&(node_id, conn_id)) {
+            Some(limit) => limit,
+            None => return true,
+        };
+        if limit.exceeded {
+            return false;
+        }
+        let seq_nr = u16::from_be_bytes([packet[16], packet[17]]);
+        if limit.seq_nrs.insert(seq_nr) {
+            limit.received += utp_payload_size(packet);
+        }
+        limit.exceeded = limit.received > limit.max_size;
+        !limit.exceeded
+    }
+}
+
+/// Returns the size of the payload of a uTP `packet`, which follows its header and extensions.
+fn utp_payload_size(packet: &[u8]) -> usize {
+    let mut extension = packet[1];
+    let mut offset = UTP_HEADER_SIZE;
+    // Each extension starts with the type of the next extension, and the length of its data.
+    while extension != 0 && offset + 2 <= packet.len() {
+        extension = packet[offset];
+        offset += 2 + packet[offset + 1] as usize;
+    }
+    packet.len().saturating_sub(offset)
+}
+
+/// The limit of a single uTP connection, which is removed when the guard is dropped.
+pub struct UtpReadLimit {
+    limits: UtpReadLimits,
+    key: (NodeId, u16),
+    id: u64,
+    max_size: usize,
+}
+
+impl UtpReadLimit {
+    /// Returns `true` if the peer sent more data than the limit allows.
+    pub fn exceeded(&self) -> bool {
+        self.limits
+            .limits
+            .lock()
+            .get(&self.key)
+            .map_or(false, |limit| limit.id == self.id && limit.exceeded)
+    }
+}
+
+impl Drop for UtpReadLimit {
+    fn drop(&mut self) {
+        let mut limits = self.limits.limits.lock();
+        // The limit may have been replaced by the limit of a later connection with the same ID.
+        if limits
+            .get(&self.key)
+            .map_or(false, |limit| limit.id == self.id)
+        {
+            limits.remove(&self.key);
+        }
+    }
+}
+
+/// Reads `stream` to its end into `buf`, and fails if the peer sent more data than `read_limit`
+/// allows.
+///
+/// The socket drops the packets that go over the limit, so at most the limit is buffered. Reading
+/// a stream that went over its limit fails once the connection's idle timeout expires, because
+/// the connection no longer sees any packets from the peer.
+pub async fn read_to_eof_bounded(
+    stream: &mut UtpStream<UtpEnr>,
+    buf: &mut Vec<u8>,
+    read_limit: &UtpReadLimit,
+) -> io::Result<usize> {
+    let result = stream.read_to_eof(buf).await;
+    let max_size = read_limit.max_size;
+    if read_limit.exceeded() || buf.len() > max_size {
+        buf.clear();
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Received more than the max of {max_size} bytes over uTP"),
+        ));
+    }
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    use std::time::Duration;
+
+    use ethportal_api::types::enr::generate_random_remote_enr;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn limits_concurrent_connections() {
+        let connections = UtpConnections::new(ConnectionConfig::default(), 1);
+        let permit = connections.acquire().await;
+        assert!(permit.is_some());
+        assert!(timeout(Duration::from_millis(10), connections.acquire())
+            .await
+            .is_err());
+
+        drop(permit);
+        let permit = timeout(Duration::from_millis(10), connections.acquire())
+            .await
+            .unwrap();
+        assert!(permit.is_some());
+    }
+
+    #[tokio::test]
+    async fn zero_disables_the_limit() {
+        let connections = UtpConnections::new(ConnectionConfig::default(), 0);
+        assert!(connections.acquire().await.is_none());
+        assert!(connections.acquire().await.is_none());
+        assert!(connections.try_acquire().unwrap().is_none());
+    }
+
+    /// Builds a uTP data packet with the given connection ID and sequence number, and a payload of
+    /// `payload_size` bytes.
+    fn data_packet(conn_id: u16, seq_nr: u16, payload_size: usize) -> Vec<u8> {
+        let mut packet = vec![0; UTP_HEADER_SIZE + payload_size];
+        packet[0] = (UTP_ST_DATA << 4) | 1;
+        packet[2..4].copy_from_slice(&conn_id.to_be_bytes());
+        packet[16..18].copy_from_slice(&seq_nr.to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn drops_packets_over_the_limit() {
+        let limits = UtpReadLimits::default();
+        let (_, enr) = generate_random_remote_enr();
+        let node_id = enr.node_id();
+        let cid = ConnectionId {
+            send: 2,
+            recv: 1,
+            peer: UtpEnr(enr),
+        };
+        let read_limit = limits.limit(&cid, 1000);
+
+        assert!(limits.admit(node_id, &data_packet(1, 1, 600)));
+        // A retransmission is not counted again.
+        assert!(limits.admit(node_id, &data_packet(1, 1, 600)));
+        assert!(!read_limit.exceeded());
+        // Packets of other connections are not limited.
+        assert!(limits.admit(node_id, &data_packet(3, 2, 600)));
+        assert!(limits.admit(NodeId::random(), &data_packet(1, 2, 600)));
+
+        assert!(!limits.admit(node_id, &data_packet(1, 2, 600)));
+        assert!(read_limit.exceeded());
+        // Once over the limit, every packet of the connection is dropped.
+        assert!(!limits.admit(node_id, &data_packet(1, 3, 1)));
+
+        drop(read_limit);
+        assert!(limits.admit(node_id, &data_packet(1, 4, 600)));
+    }
+
+    #[test]
+    fn replaced_limit_outlives_the_previous_guard() {
+        let limits = UtpReadLimits::default();
+        let (_, enr) = generate_random_remote_enr();
+        let cid = ConnectionId {
+            send: 2,
+            recv: 1,
+            peer: UtpEnr(enr),
+        };
+        let first = limits.limit(&cid, 1000);
+        let second = limits.limit(&cid, 1000);
+
+        drop(first);
+        assert!(limits.is_limited(&cid));
+        drop(second);
+        assert!(!limits.is_limited(&cid));
+    }
+
+    #[test]
+    fn payload_size_excludes_extensions() {
+        let mut packet = data_packet(1, 1, 10);
+        // A selective ACK extension with 4 bytes of data.
+        packet[1] = 1;
+        packet.splice(UTP_HEADER_SIZE..UTP_HEADER_SIZE, [0, 4, 0, 0, 0, 0]);
+        assert_eq!(utp_payload_size(&packet), 10);
+    }
+
+    #[tokio::test]
+    async fn try_acquire_fails_at_the_limit() {
+        let connections = UtpConnections::new(ConnectionConfig::default(), 1);
+        let permit = connections.try_acquire().unwrap();
+        assert!(permit.is_some());
+        assert!(connections.try_acquire().is_err());
+
+        drop(permit);
+        assert!(connections.try_acquire().unwrap().is_some());
+    }
+}
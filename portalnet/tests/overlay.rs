@@ -255,5 +255,7 @@ async fn overlay_event_stream() {
     let discovery = Arc::new(Discovery::new(portal_config, temp_dir).unwrap());
     let overlay = init_overlay(discovery, ProtocolId::Beacon).await;
 
-    overlay.event_stream().await.unwrap();
+    // No events have been emitted yet
+    let mut event_stream = overlay.event_stream().await.unwrap();
+    assert!(event_stream.try_recv().is_err());
 }
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use ethereum_types::U256;
+use tokio::time::{self, Duration};
+
+use ethportal_api::types::content_key::overlay::IdentityContentKey;
+use ethportal_api::types::distance::{Distance, Metric, XorMetric};
+use ethportal_api::types::query_trace::{QueryTermination, QueryTrace};
+use ethportal_api::OverlayContentKey;
+use portalnet::{
+    overlay::OverlayConfig,
+    simulator::{SimConfig, SimMessage, SimNetwork, SimNode},
+    storage::ContentStore,
+};
+
+/// Time given to the nodes to fill their routing tables.
+const CONVERGENCE_TIME: Duration = Duration::from_secs(10 * 60);
+
+/// Starts `num_nodes` nodes that join the overlay through the first node.
+async fn start_nodes(network: &Arc<SimNetwork>, num_nodes: usize) -> Vec<SimNode> {
+    let bootnode = network.add_node(OverlayConfig::default()).await;
+    let bootnode_enrs = vec![bootnode.enr()];
+    let mut nodes = vec![bootnode];
+    for _ in 1..num_nodes {
+        let overlay_config = OverlayConfig {
+            bootnode_enrs: bootnode_enrs.clone(),
+            ..Default::default()
+        };
+        nodes.push(network.add_node(overlay_config).await);
+    }
+    nodes
+}
+
+/// Returns the distance from `node` to `content_key`.
+fn distance(node: &SimNode, content_key: &IdentityContentKey) -> Distance {
+    XorMetric::distance(&content_key.content_id(), &node.node_id().raw())
+}
+
+/// Returns the index of the node closest to `content_key`.
+fn closest_node(nodes: &[SimNode], content_key: &IdentityContentKey) -> usize {
+    (0..nodes.len())
+        .min_by_key(|&i| distance(&nodes[i], content_key))
+        .unwrap()
+}
+
+/// The route of a traced query: who provided the content, who responded with which peers, which
+/// peers were never contacted, and why the query ended. Timings are left out, because traces
+/// measure them with the system clock, which is not paused.
+type QueryRoute = (
+    Option<[u8; 32]>,
+    Vec<([u8; 32], Vec<[u8; 32]>)>,
+    Vec<[u8; 32]>,
+    Option<QueryTermination>,
+);
+
+fn query_route(trace: &QueryTrace) -> QueryRoute {
+    let mut responses: Vec<_> = trace
+        .responses
+        .iter()
+        .map(|(node_id, response)| {
+            let responded_with = response
+                .responded_with
+                .iter()
+                .map(|node_id| node_id.0)
+                .collect();
+            (node_id.0, responded_with)
+        })
+        .collect();
+    responses.sort();
+    let mut uncontacted: Vec<_> = trace.uncontacted.iter().map(|node_id| node_id.0).collect();
+    uncontacted.sort();
+    (
+        trace.received_content_from_node.map(|node_id| node_id.0),
+        responses,
+        uncontacted,
+        trace.termination,
+    )
+}
+
+/// Runs a lossy network with `seed`, then looks up content over uTP with a traced query.
+/// Returns every message sent over the network, and the route of the query.
+async fn traced_lookup(seed: u64) -> (Vec<SimMessage>, QueryRoute) {
+    let network = SimNetwork::new(SimConfig {
+        seed,
+        jitter: Duration::from_millis(20),
+        loss: 0.01,
+        ..Default::default()
+    });
+    let nodes = start_nodes(&network, 16).await;
+    time::sleep(Duration::from_secs(2 * 60)).await;
+
+    let content_key = IdentityContentKey::new([0xef; 32]);
+    let content = vec![0xef; 4096];
+    let holder = closest_node(&nodes, &content_key);
+    nodes[holder]
+        .overlay
+        .store
+        .write()
+        .put(content_key.clone(), &content)
+        .unwrap();
+    let requester = (holder + 1) % nodes.len();
+    let (found_content, _, trace) = nodes[requester]
+        .overlay
+        .lookup_content(content_key, true)
+        .await;
+    assert_eq!(found_content, Some(content));
+
+    (network.messages(), query_route(&trace.unwrap()))
+}
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn simulated_network_routes_and_gossips() {
+    const NUM_NODES: usize = 256;
+    let network = SimNetwork::new(SimConfig {
+        seed: 42,
+        jitter: Duration::from_millis(20),
+        loss: 0.01,
+        ..Default::default()
+    });
+    let nodes = start_nodes(&network, NUM_NODES).await;
+    assert_eq!(network.len(), NUM_NODES);
+    time::sleep(CONVERGENCE_TIME).await;
+
+    // Every node has discovered peers beyond the bootnode.
+    for node in nodes.iter() {
+        assert!(node.overlay.table_entries_id().len() >= 2);
+    }
+
+    // Content too large for a TALKRESP is found with a recursive lookup and transferred via uTP.
+    let content_key = IdentityContentKey::new([0xef; 32]);
+    let content = vec![0xef; 4096];
+    let holder = closest_node(&nodes, &content_key);
+    nodes[holder]
+        .overlay
+        .store
+        .write()
+        .put(content_key.clone(), &content)
+        .unwrap();
+    let requester = (holder + 1) % nodes.len();
+    match nodes[requester]
+        .overlay
+        .lookup_content(content_key, false)
+        .await
+    {
+        (Some(found_content), utp_transfer, _) => {
+            assert_eq!(found_content, content);
+            assert!(utp_transfer);
+        }
+        (None, _, _) => panic!("Unable to find content stored with peer"),
+    }
+
+    // Gossiped content is offered to peers and stored by them.
+    let content_key = IdentityContentKey::new([0x01; 32]);
+    let content = vec![0x01; 4096];
+    let num_transferred = nodes[0]
+        .overlay
+        .propagate_gossip(vec![(content_key.clone(), content.clone())])
+        .await;
+    assert!(num_transferred > 0);
+    time::sleep(Duration::from_secs(60)).await;
+    let num_stored = nodes[1..]
+        .iter()
+        .filter(|node| {
+            node.overlay.store.read().get(&content_key).unwrap() == Some(content.clone())
+        })
+        .count();
+    assert!(num_stored > 0);
+}
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn gossip_is_stored_within_radius() {
+    let network = SimNetwork::new(SimConfig {
+        seed: 3,
+        ..Default::default()
+    });
+    let nodes = start_nodes(&network, 64).await;
+    // Every node is interested in an eighth of the content. The radius is advertised in the
+    // pings exchanged while the routing tables fill.
+    let radius = Distance::from(U256::MAX >> 3);
+    for node in nodes.iter() {
+        node.overlay.store.write().set_radius(radius);
+    }
+    time::sleep(CONVERGENCE_TIME).await;
+
+    let content_key = IdentityContentKey::new([0x01; 32]);
+    let content = vec![0x01; 4096];
+    let gossiper = (0..nodes.len())
+        .max_by_key(|&i| distance(&nodes[i], &content_key))
+        .unwrap();
+    assert!(distance(&nodes[gossiper], &content_key) > radius);
+    let num_transferred = nodes[gossiper]
+        .overlay
+        .propagate_gossip(vec![(content_key.clone(), content.clone())])
+        .await;
+    assert!(num_transferred > 0);
+    time::sleep(Duration::from_secs(60)).await;
+
+    let mut num_stored = 0;
+    for node in nodes.iter() {
+        let stored = node
+            .overlay
+            .store
+            .read()
+            .get(&content_key)
+            .unwrap()
+            .is_some();
+        let within_radius = distance(node, &content_key) <= radius;
+        assert!(
+            !stored || within_radius,
+            "Content stored outside of the radius"
+        );
+        if stored {
+            num_stored += 1;
+        }
+    }
+    assert!(num_stored > 0);
+}
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn simulated_network_is_reproducible() {
+    let (messages, route) = traced_lookup(7).await;
+    assert!(!messages.is_empty());
+    assert!(!route.1.is_empty());
+
+    // The same seed sends the same messages in the same order, and routes the query the same way.
+    let (same_messages, same_route) = traced_lookup(7).await;
+    assert_eq!(messages.len(), same_messages.len());
+    assert!(messages == same_messages);
+    assert_eq!(route, same_route);
+
+    // Another seed gives another network.
+    let (other_messages, _) = traced_lookup(8).await;
+    assert!(messages != other_messages);
+}
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn disconnected_node_times_out() {
+    let network = SimNetwork::new(SimConfig::default());
+    let nodes = start_nodes(&network, 2).await;
+
+    assert!(network.disconnect(&nodes[1].node_id()));
+    assert!(nodes[0].overlay.send_ping(nodes[1].enr()).await.is_err());
+    assert_eq!(network.len(), 1);
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use discv5::enr::NodeId;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::portal::{OverlayTuning, PruneInfo, SubnetworkSummary};
+use ethportal_api::AdminApiServer;
+use portalnet::discovery::Discovery;
+use portalnet::shutdown::ShutdownSignal;
+use portalnet::subnetwork::SubnetworkAdmin;
+use tracing::warn;
+use trin_utils::log::set_log_filter;
+
+use crate::errors::RpcServeError;
+use crate::jsonrpsee::core::{async_trait, RpcResult};
+
+pub struct AdminApi {
+    discv5: Arc<Discovery>,
+    /// Management handles of the active subnetworks, keyed by subnetwork name.
+    subnetworks: HashMap<String, Arc<dyn SubnetworkAdmin>>,
+    /// Shuts the node down on `admin_shutdown`.
+    shutdown_signal: ShutdownSignal,
+}
+
+impl AdminApi {
+    pub fn new(
+        discv5: Arc<Discovery>,
+        subnetworks: HashMap<String, Arc<dyn SubnetworkAdmin>>,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        Self {
+            discv5,
+            subnetworks,
+            shutdown_signal,
+        }
+    }
+
+    fn subnetwork(&self, network: &str) -> Result<&Arc<dyn SubnetworkAdmin>, RpcServeError> {
+        self.subnetworks.get(network).ok_or_else(|| {
+            RpcServeError::Message(format!("Network {network} is not active on this node"))
+        })
+    }
+
+    /// Removes `node_id` from every subnetwork routing table. Returns `true` if any contained it.
+    fn remove_from_subnetworks(&self, node_id: NodeId) -> bool {
+        self.subnetworks.values().fold(false, |removed, admin| {
+            admin.remove_node(node_id) || removed
+        })
+    }
+}
+
+#[async_trait]
+impl AdminApiServer for AdminApi {
+    async fn overlay_config(&self, network: String) -> RpcResult<OverlayTuning> {
+        Ok(self
+            .subnetwork(&network)?
+            .overlay_tuning()
+            .await
+            .map_err(|err| RpcServeError::Message(err.to_string()))?)
+    }
+
+    async fn set_overlay_config(
+        &self,
+        network: String,
+        config: OverlayTuning,
+    ) -> RpcResult<OverlayTuning> {
+        Ok(self
+            .subnetwork(&network)?
+            .tune_overlay(config)
+            .await
+            .map_err(|err| RpcServeError::Message(err.to_string()))?)
+    }
+
+    async fn set_storage_capacity(
+        &self,
+        network: String,
+        capacity_mb: u64,
+    ) -> RpcResult<PruneInfo> {
+        Ok(self
+            .subnetwork(&network)?
+            .set_storage_capacity(capacity_mb)
+            .map_err(|err| RpcServeError::Message(err.to_string()))?)
+    }
+
+    async fn prune(&self, network: String) -> RpcResult<PruneInfo> {
+        Ok(self
+            .subnetwork(&network)?
+            .prune()
+            .map_err(|err| RpcServeError::Message(err.to_string()))?)
+    }
+
+    async fn add_bootnode(&self, enr: Enr) -> RpcResult<bool> {
+        self.discv5
+            .add_enr(enr.clone())
+            .map_err(|err| RpcServeError::Message(err.to_string()))?;
+        for (network, admin) in &self.subnetworks {
+            // The node stays in the routing tables of the other subnetworks, so a failure here
+            // is not fatal.
+            if let Err(err) = admin.add_bootnode(enr.clone()).await {
+                warn!(network = %network, error = %err, "Unable to add bootnode to subnetwork");
+            }
+        }
+        Ok(true)
+    }
+
+    async fn remove_bootnode(&self, node_id: NodeId) -> RpcResult<bool> {
+        let removed = self.discv5.remove_node(&node_id);
+        let removed_from_subnetworks = self.remove_from_subnetworks(node_id);
+        Ok(removed || removed_from_subnetworks)
+    }
+
+    async fn ban_peer(&self, node_id: NodeId, duration_secs: Option<u64>) -> RpcResult<bool> {
+        self.discv5
+            .ban_node(&node_id, duration_secs.map(Duration::from_secs));
+        self.remove_from_subnetworks(node_id);
+        Ok(true)
+    }
+
+    async fn unban_peer(&self, node_id: NodeId) -> RpcResult<bool> {
+        self.discv5.unban_node(&node_id);
+        Ok(true)
+    }
+
+    async fn set_log_filter(&self, filter: String) -> RpcResult<bool> {
+        set_log_filter(&filter).map_err(RpcServeError::Message)?;
+        Ok(true)
+    }
+
+    async fn summary(&self, network: String) -> RpcResult<SubnetworkSummary> {
+        Ok(self.subnetwork(&network)?.summary())
+    }
+
+    async fn shutdown(&self) -> RpcResult<bool> {
+        self.shutdown_signal.request();
+        Ok(true)
+    }
+}
+
+impl std::fmt::Debug for AdminApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminApi").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    use ethportal_api::types::portal::DataRadius;
+    use portalnet::utils::db::setup_temp_dir;
+    use std::sync::Mutex;
+
+    /// Records the admin operations applied to a subnetwork.
+    #[derive(Default)]
+    struct TestAdmin {
+        tuning: Mutex<OverlayTuning>,
+        bootnodes: Mutex<Vec<Enr>>,
+        storage_capacity_mb: Mutex<Option<u64>>,
+    }
+
+    #[async_trait]
+    impl SubnetworkAdmin for TestAdmin {
+        async fn overlay_tuning(&self) -> anyhow::Result<OverlayTuning> {
+            Ok(self.tuning.lock().unwrap().clone())
+        }
+
+        async fn tune_overlay(&self, tuning: OverlayTuning) -> anyhow::Result<OverlayTuning> {
+            let mut current = self.tuning.lock().unwrap();
+            current.merge(&tuning).map_err(anyhow::Error::msg)?;
+            Ok(current.clone())
+        }
+
+        fn set_storage_capacity(&self, storage_capacity_mb: u64) -> anyhow::Result<PruneInfo> {
+            *self.storage_capacity_mb.lock().unwrap() = Some(storage_capacity_mb);
+            self.prune()
+        }
+
+        fn prune(&self) -> anyhow::Result<PruneInfo> {
+            Ok(PruneInfo {
+                pruned_items: 0,
+                radius: DataRadius::MAX,
+            })
+        }
+
+        async fn add_bootnode(&self, enr: Enr) -> anyhow::Result<()> {
+            self.bootnodes.lock().unwrap().push(enr);
+            Ok(())
+        }
+
+        fn remove_node(&self, node_id: NodeId) -> bool {
+            let mut bootnodes = self.bootnodes.lock().unwrap();
+            let len = bootnodes.len();
+            bootnodes.retain(|enr| enr.node_id() != node_id);
+            bootnodes.len() != len
+        }
+
+        fn summary(&self) -> SubnetworkSummary {
+            SubnetworkSummary {
+                storage: String::new(),
+                messages: String::new(),
+                utp: String::new(),
+            }
+        }
+
+        async fn shutdown(&self) {}
+    }
+
+    fn discovery() -> Arc<Discovery> {
+        let temp_dir = setup_temp_dir().unwrap().into_path();
+        Arc::new(Discovery::new(Default::default(), temp_dir).unwrap())
+    }
+
+    fn admin_api() -> (AdminApi, Arc<TestAdmin>, Arc<TestAdmin>) {
+        let history = Arc::new(TestAdmin::default());
+        let beacon = Arc::new(TestAdmin::default());
+        let subnetworks: HashMap<String, Arc<dyn SubnetworkAdmin>> = HashMap::from([
+            (
+                "history".to_string(),
+                history.clone() as Arc<dyn SubnetworkAdmin>,
+            ),
+            (
+                "beacon".to_string(),
+                beacon.clone() as Arc<dyn SubnetworkAdmin>,
+            ),
+        ]);
+        (
+            AdminApi::new(discovery(), subnetworks, ShutdownSignal::new()),
+            history,
+            beacon,
+        )
+    }
+
+    #[tokio::test]
+    async fn overlay_config_is_routed_to_the_network() {
+        let (api, history, beacon) = admin_api();
+        let tuning = OverlayTuning {
+            query_parallelism: Some(5),
+            ..Default::default()
+        };
+
+        let result = api
+            .set_overlay_config("history".to_string(), tuning.clone())
+            .await
+            .unwrap();
+        assert_eq!(result, tuning);
+        assert_eq!(*history.tuning.lock().unwrap(), tuning);
+        assert_eq!(*beacon.tuning.lock().unwrap(), OverlayTuning::default());
+        assert_eq!(
+            api.overlay_config("history".to_string()).await.unwrap(),
+            tuning
+        );
+    }
+
+    #[tokio::test]
+    async fn inactive_network_is_rejected() {
+        let (api, _, _) = admin_api();
+        assert!(api.overlay_config("state".to_string()).await.is_err());
+        assert!(api.prune("state".to_string()).await.is_err());
+        assert!(api
+            .set_storage_capacity("state".to_string(), 10)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn set_storage_capacity_reaches_the_network() {
+        let (api, history, beacon) = admin_api();
+        let prune_info = api
+            .set_storage_capacity("beacon".to_string(), 10)
+            .await
+            .unwrap();
+        assert_eq!(prune_info.pruned_items, 0);
+        assert_eq!(*beacon.storage_capacity_mb.lock().unwrap(), Some(10));
+        assert_eq!(*history.storage_capacity_mb.lock().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn bootnode_is_added_to_and_removed_from_every_network() {
+        let (api, history, beacon) = admin_api();
+        let enr = discovery().local_enr();
+
+        assert!(api.add_bootnode(enr.clone()).await.unwrap());
+        assert_eq!(*history.bootnodes.lock().unwrap(), vec![enr.clone()]);
+        assert_eq!(*beacon.bootnodes.lock().unwrap(), vec![enr.clone()]);
+
+        assert!(api.remove_bootnode(enr.node_id()).await.unwrap());
+        assert!(history.bootnodes.lock().unwrap().is_empty());
+        assert!(beacon.bootnodes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_log_filter_is_rejected() {
+        let (api, _, _) = admin_api();
+        assert!(api
+            .set_log_filter("info,discv5=notalevel".to_string())
+            .await
+            .is_err());
+    }
+}
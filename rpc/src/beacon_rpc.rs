@@ -5,12 +5,14 @@ use crate::jsonrpsee::core::{async_trait, RpcResult};
 use discv5::enr::NodeId;
 use ethportal_api::types::constants::CONTENT_ABSENT;
 use ethportal_api::types::enr::Enr;
+use ethportal_api::types::gossip_trace::{GossipTrace, OfferTrace};
 use ethportal_api::types::jsonrpc::endpoints::BeaconEndpoint;
 use ethportal_api::types::jsonrpc::request::BeaconJsonRpcRequest;
 use ethportal_api::types::portal::{
-    AcceptInfo, ContentInfo, DataRadius, FindNodesInfo, PaginateLocalContentInfo, PongInfo,
-    TraceContentInfo,
+    AcceptInfo, BeaconContentInfo, BeaconPaginateLocalContentInfo, BeaconTraceContentInfo,
+    DataRadius, FindNodesInfo, PongInfo, TraceNodesInfo,
 };
+use ethportal_api::types::query_trace::QueryTrace;
 use ethportal_api::BeaconContentKey;
 use ethportal_api::BeaconContentValue;
 use ethportal_api::BeaconNetworkApiServer;
@@ -119,6 +121,14 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(result)
     }
 
+    /// Lookup a target node within in the network. Return tracing info.
+    async fn trace_recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<TraceNodesInfo> {
+        let endpoint = BeaconEndpoint::TraceRecursiveFindNodes(node_id);
+        let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
+        let info: TraceNodesInfo = from_value(result)?;
+        Ok(info)
+    }
+
     /// Lookup a target node within in the network
     async fn radius(&self) -> RpcResult<DataRadius> {
         let endpoint = BeaconEndpoint::DataRadius;
@@ -132,10 +142,10 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         &self,
         enr: Enr,
         content_key: BeaconContentKey,
-    ) -> RpcResult<ContentInfo> {
+    ) -> RpcResult<BeaconContentInfo> {
         let endpoint = BeaconEndpoint::FindContent(enr, content_key);
         let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
-        let result: ContentInfo = from_value(result)?;
+        let result: BeaconContentInfo = from_value(result)?;
         Ok(result)
     }
 
@@ -157,22 +167,30 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     async fn trace_recursive_find_content(
         &self,
         content_key: BeaconContentKey,
-    ) -> RpcResult<TraceContentInfo> {
+    ) -> RpcResult<BeaconTraceContentInfo> {
         let endpoint = BeaconEndpoint::TraceRecursiveFindContent(content_key);
         let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
-        let info: TraceContentInfo = from_value(result)?;
+        let info: BeaconTraceContentInfo = from_value(result)?;
         Ok(info)
     }
 
+    /// Return the traces of recently completed content lookups, newest first.
+    async fn recent_query_traces(&self, limit: Option<u64>) -> RpcResult<Vec<QueryTrace>> {
+        let endpoint = BeaconEndpoint::RecentQueryTraces(limit);
+        let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
+        let traces: Vec<QueryTrace> = from_value(result)?;
+        Ok(traces)
+    }
+
     /// Pagination of local content keys
     async fn paginate_local_content_keys(
         &self,
         offset: u64,
         limit: u64,
-    ) -> RpcResult<PaginateLocalContentInfo> {
+    ) -> RpcResult<BeaconPaginateLocalContentInfo> {
         let endpoint = BeaconEndpoint::PaginateLocalContentKeys(offset, limit);
         let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
-        let result: PaginateLocalContentInfo = from_value(result)?;
+        let result: BeaconPaginateLocalContentInfo = from_value(result)?;
         Ok(result)
     }
 
@@ -189,6 +207,18 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(result)
     }
 
+    /// Send the provided content to interested peers, and wait for the outcome of every offer.
+    async fn trace_gossip(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: BeaconContentValue,
+    ) -> RpcResult<GossipTrace> {
+        let endpoint = BeaconEndpoint::TraceGossip(content_key, content_value);
+        let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
+        let trace: GossipTrace = from_value(result)?;
+        Ok(trace)
+    }
+
     /// Send an OFFER request with given ContentKey, to the designated peer and wait for a response.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist receive.
     async fn offer(
@@ -203,6 +233,20 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(result)
     }
 
+    /// Send an OFFER request with given ContentKey, to the designated peer and wait for the uTP
+    /// transfer of the accepted content.
+    async fn trace_offer(
+        &self,
+        enr: Enr,
+        content_key: BeaconContentKey,
+        content_value: Option<BeaconContentValue>,
+    ) -> RpcResult<OfferTrace> {
+        let endpoint = BeaconEndpoint::TraceOffer(enr, content_key, content_value);
+        let result = self.proxy_query_to_beacon_subnet(endpoint).await?;
+        let trace: OfferTrace = from_value(result)?;
+        Ok(trace)
+    }
+
     /// Store content key with a content data to the local database.
     async fn store(
         &self,
@@ -1,52 +1,18 @@
 use crate::errors::{RpcError, WsHttpSamePortError};
 use crate::jsonrpsee::{Methods, RpcModule};
 use crate::rpc_server::{RpcServerConfig, RpcServerHandle};
-use crate::{BeaconNetworkApi, Discv5Api, EthApi, HistoryNetworkApi, Web3Api};
-use ethportal_api::types::jsonrpc::request::{
-    BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest,
-};
-use ethportal_api::{
-    BeaconNetworkApiServer, Discv5ApiServer, EthApiServer, HistoryNetworkApiServer, Web3ApiServer,
-};
+use crate::{AdminApi, Discv5Api, PortalApi, Web3Api};
+pub use ethportal_api::types::cli::PortalRpcModule;
+use ethportal_api::{AdminApiServer, Discv5ApiServer, PortalApiServer, Web3ApiServer};
 use portalnet::discovery::Discovery;
-use serde::Deserialize;
+use portalnet::events::EventEnvelope;
+use portalnet::shutdown::ShutdownSignal;
+use portalnet::subnetwork::SubnetworkAdmin;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::sync::Arc;
-use strum::{AsRefStr, EnumString, EnumVariantNames, VariantNames};
-use tokio::sync::mpsc;
-
-/// Represents RPC modules that are supported by Trin
-#[derive(
-    Debug, Clone, Copy, Eq, PartialEq, Hash, AsRefStr, EnumVariantNames, EnumString, Deserialize,
-)]
-#[serde(rename_all = "snake_case")]
-#[strum(serialize_all = "kebab-case")]
-pub enum PortalRpcModule {
-    /// `portal_beacon` module
-    Beacon,
-    /// `discv5_` module
-    Discv5,
-    /// `eth_` module
-    Eth,
-    /// `portal_history` module
-    History,
-    /// `web3_` module
-    Web3,
-}
-
-impl PortalRpcModule {
-    /// Returns all variants of the enum
-    pub const fn all_variants() -> &'static [&'static str] {
-        Self::VARIANTS
-    }
-}
-
-impl fmt::Display for PortalRpcModule {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.pad(self.as_ref())
-    }
-}
+use strum::VariantNames;
+use tokio::sync::broadcast;
 
 /// Holds modules to be installed per transport type
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
@@ -276,12 +242,12 @@ pub struct RpcModuleBuilder {
     modules: HashMap<PortalRpcModule, Methods>,
     /// Discv5 protocol
     discv5: Arc<Discovery>,
-    /// History protocol
-    history_tx: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
-    /// Beacon protocol
-    beacon_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
-    /// State protocol
-    state_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
+    /// Overlay event streams of the subnetworks, keyed by subnetwork name
+    event_streams: HashMap<String, broadcast::Sender<EventEnvelope>>,
+    /// Management handles of the subnetworks, keyed by subnetwork name
+    subnetwork_admins: HashMap<String, Arc<dyn SubnetworkAdmin>>,
+    /// Requested by `admin_shutdown`
+    shutdown_signal: ShutdownSignal,
 }
 
 impl RpcModuleBuilder {
@@ -289,51 +255,40 @@ impl RpcModuleBuilder {
         Self {
             modules: HashMap::new(),
             discv5,
-            history_tx: None,
-            beacon_tx: None,
-            state_tx: None,
+            event_streams: HashMap::new(),
+            subnetwork_admins: HashMap::new(),
+            shutdown_signal: ShutdownSignal::new(),
         }
     }
 
-    pub fn maybe_with_history(
-        mut self,
-        history_tx: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
-    ) -> Self {
-        self.history_tx = history_tx;
-        self
-    }
-
-    pub fn maybe_with_state(
-        mut self,
-        state_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
-    ) -> Self {
-        self.state_tx = state_tx;
+    /// Adds the methods of a module that the builder does not build itself, like the methods
+    /// that a subnetwork serves, or the `eth_` methods that proxy to the subnetworks.
+    pub fn with_methods(mut self, module: PortalRpcModule, methods: impl Into<Methods>) -> Self {
+        self.modules.insert(module, methods.into());
         self
     }
 
-    pub fn maybe_with_beacon(
+    /// Adds the overlay event streams served by `portal_subscribe` and `eth_subscribe`.
+    pub fn with_event_streams(
         mut self,
-        beacon_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
+        event_streams: HashMap<String, broadcast::Sender<EventEnvelope>>,
     ) -> Self {
-        self.beacon_tx = beacon_tx;
+        self.event_streams.extend(event_streams);
         self
     }
 
-    pub fn with_history(
+    /// Adds the subnetwork management handles served by the `admin_` module.
+    pub fn with_subnetwork_admins(
         mut self,
-        history_tx: mpsc::UnboundedSender<HistoryJsonRpcRequest>,
+        subnetwork_admins: HashMap<String, Arc<dyn SubnetworkAdmin>>,
     ) -> Self {
-        self.history_tx = Some(history_tx);
-        self
-    }
-
-    pub fn with_beacon(mut self, beacon_tx: mpsc::UnboundedSender<BeaconJsonRpcRequest>) -> Self {
-        self.beacon_tx = Some(beacon_tx);
+        self.subnetwork_admins.extend(subnetwork_admins);
         self
     }
 
-    pub fn with_state(mut self, state_tx: mpsc::UnboundedSender<StateJsonRpcRequest>) -> Self {
-        self.state_tx = Some(state_tx);
+    /// Sets the shutdown signal of the node, which is requested by `admin_shutdown`.
+    pub fn with_shutdown_signal(mut self, shutdown_signal: ShutdownSignal) -> Self {
+        self.shutdown_signal = shutdown_signal;
         self
     }
 
@@ -389,43 +344,39 @@ impl RpcModuleBuilder {
     /// Returns the [Methods] for the given [PortalRpcModule]
     ///
     /// If this is the first time the namespace is requested, a new instance of API implementation
-    /// will be created.
+    /// will be created. Modules that the builder does not build itself are skipped, unless they
+    /// were added with [RpcModuleBuilder::with_methods].
     pub fn portal_methods(
         &mut self,
         namespaces: impl Iterator<Item = PortalRpcModule>,
     ) -> Vec<Methods> {
         namespaces
-            .map(|namespace| {
-                self.modules
-                    .entry(namespace)
-                    .or_insert_with(|| match namespace {
-                        PortalRpcModule::Discv5 => {
-                            Discv5Api::new(self.discv5.clone()).into_rpc().into()
-                        }
-                        PortalRpcModule::Eth => {
-                            let history_tx = self
-                                .history_tx
-                                .clone()
-                                .expect("History protocol not initialized");
-                            EthApi::new(history_tx).into_rpc().into()
-                        }
-                        PortalRpcModule::History => {
-                            let history_tx = self
-                                .history_tx
-                                .clone()
-                                .expect("History protocol not initialized");
-                            HistoryNetworkApi::new(history_tx).into_rpc().into()
-                        }
-                        PortalRpcModule::Beacon => {
-                            let beacon_tx = self
-                                .beacon_tx
-                                .clone()
-                                .expect("Beacon protocol not initialized");
-                            BeaconNetworkApi::new(beacon_tx).into_rpc().into()
-                        }
-                        PortalRpcModule::Web3 => Web3Api.into_rpc().into(),
-                    })
-                    .clone()
+            .filter_map(|namespace| {
+                if let Some(methods) = self.modules.get(&namespace) {
+                    return Some(methods.clone());
+                }
+                let methods: Methods = match namespace {
+                    PortalRpcModule::Admin => AdminApi::new(
+                        self.discv5.clone(),
+                        self.subnetwork_admins.clone(),
+                        self.shutdown_signal.clone(),
+                    )
+                    .into_rpc()
+                    .into(),
+                    PortalRpcModule::Discv5 => {
+                        Discv5Api::new(self.discv5.clone()).into_rpc().into()
+                    }
+                    PortalRpcModule::Portal => {
+                        PortalApi::new(self.event_streams.clone()).into_rpc().into()
+                    }
+                    PortalRpcModule::Web3 => Web3Api.into_rpc().into(),
+                    PortalRpcModule::Beacon
+                    | PortalRpcModule::CanonicalIndices
+                    | PortalRpcModule::Eth
+                    | PortalRpcModule::History => return None,
+                };
+                self.modules.insert(namespace, methods.clone());
+                Some(methods)
             })
             .collect::<Vec<_>>()
     }
@@ -463,8 +414,10 @@ mod tests {
         assert_rpc_module!
         (
                 "beacon" =>  PortalRpcModule::Beacon,
+                "canonical-indices" =>  PortalRpcModule::CanonicalIndices,
                 "discv5" =>  PortalRpcModule::Discv5,
                 "history" =>  PortalRpcModule::History,
+                "portal" =>  PortalRpcModule::Portal,
                 "web3" =>  PortalRpcModule::Web3,
             );
     }
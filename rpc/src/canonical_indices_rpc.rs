@@ -0,0 +1,129 @@
+use crate::errors::RpcServeError;
+use crate::serde::from_value;
+
+use crate::jsonrpsee::core::{async_trait, RpcResult};
+use ethportal_api::types::constants::CONTENT_ABSENT;
+use ethportal_api::types::jsonrpc::endpoints::CanonicalIndicesEndpoint;
+use ethportal_api::types::jsonrpc::request::CanonicalIndicesJsonRpcRequest;
+use ethportal_api::types::portal::DataRadius;
+use ethportal_api::CanonicalIndicesContentKey;
+use ethportal_api::CanonicalIndicesContentValue;
+use ethportal_api::CanonicalIndicesNetworkApiServer;
+use ethportal_api::PossibleCanonicalIndicesContentValue;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+pub struct CanonicalIndicesNetworkApi {
+    network: mpsc::UnboundedSender<CanonicalIndicesJsonRpcRequest>,
+}
+
+impl CanonicalIndicesNetworkApi {
+    pub fn new(network: mpsc::UnboundedSender<CanonicalIndicesJsonRpcRequest>) -> Self {
+        Self { network }
+    }
+
+    pub async fn proxy_query_to_canonical_indices_subnet(
+        &self,
+        endpoint: CanonicalIndicesEndpoint,
+    ) -> Result<Value, RpcServeError> {
+        let (resp_tx, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
+        let message = CanonicalIndicesJsonRpcRequest {
+            endpoint,
+            resp: resp_tx,
+        };
+        let _ = self.network.send(message);
+
+        match resp_rx.recv().await {
+            Some(val) => match val {
+                Ok(result) => Ok(result),
+                Err(msg) => Err(RpcServeError::Message(msg)),
+            },
+            None => Err(RpcServeError::Message(
+                "Internal error: No response from canonical indices subnetwork".to_string(),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl CanonicalIndicesNetworkApiServer for CanonicalIndicesNetworkApi {
+    /// Returns the node data radios
+    async fn radius(&self) -> RpcResult<DataRadius> {
+        let endpoint = CanonicalIndicesEndpoint::DataRadius;
+        let result = self
+            .proxy_query_to_canonical_indices_subnet(endpoint)
+            .await?;
+        let result: DataRadius = from_value(result)?;
+        Ok(result)
+    }
+
+    /// Lookup a target content key in the network
+    async fn recursive_find_content(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+    ) -> RpcResult<PossibleCanonicalIndicesContentValue> {
+        let endpoint = CanonicalIndicesEndpoint::RecursiveFindContent(content_key);
+        let result = self
+            .proxy_query_to_canonical_indices_subnet(endpoint)
+            .await?;
+        if result == serde_json::Value::String(CONTENT_ABSENT.to_string()) {
+            return Ok(PossibleCanonicalIndicesContentValue::ContentAbsent);
+        };
+        let result: CanonicalIndicesContentValue = from_value(result)?;
+        Ok(PossibleCanonicalIndicesContentValue::ContentPresent(result))
+    }
+
+    /// Send the provided content value to interested peers. Clients may choose to send to some or all peers.
+    /// Return the number of peers that the content was gossiped to.
+    async fn gossip(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+        content_value: CanonicalIndicesContentValue,
+    ) -> RpcResult<u32> {
+        let endpoint = CanonicalIndicesEndpoint::Gossip(content_key, content_value);
+        let result = self
+            .proxy_query_to_canonical_indices_subnet(endpoint)
+            .await?;
+        let result: u32 = from_value(result)?;
+        Ok(result)
+    }
+
+    /// Store content key with a content data to the local database.
+    async fn store(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+        content_value: CanonicalIndicesContentValue,
+    ) -> RpcResult<bool> {
+        let endpoint = CanonicalIndicesEndpoint::Store(content_key, content_value);
+        let result = self
+            .proxy_query_to_canonical_indices_subnet(endpoint)
+            .await?;
+        let result: bool = from_value(result)?;
+        Ok(result)
+    }
+
+    /// Get a content from the local database.
+    async fn local_content(
+        &self,
+        content_key: CanonicalIndicesContentKey,
+    ) -> RpcResult<PossibleCanonicalIndicesContentValue> {
+        let endpoint = CanonicalIndicesEndpoint::LocalContent(content_key);
+        let result = self
+            .proxy_query_to_canonical_indices_subnet(endpoint)
+            .await?;
+        if result == serde_json::Value::String(CONTENT_ABSENT.to_string()) {
+            return Ok(PossibleCanonicalIndicesContentValue::ContentAbsent);
+        };
+        let content: CanonicalIndicesContentValue = from_value(result)?;
+        Ok(PossibleCanonicalIndicesContentValue::ContentPresent(
+            content,
+        ))
+    }
+}
+
+impl std::fmt::Debug for CanonicalIndicesNetworkApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CanonicalIndicesNetworkApi")
+            .finish_non_exhaustive()
+    }
+}
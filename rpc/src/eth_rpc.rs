@@ -1,23 +1,58 @@
 use ethereum_types::{H256, U256};
-use reth_rpc_types::{Block, BlockTransactions};
-use tokio::sync::mpsc;
+use reth_rpc_types::{Block, BlockTransactions, Header as RpcHeader};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc,
+};
+use tracing::warn;
 
 use ethportal_api::types::execution::block_body::BlockBody;
-use ethportal_api::types::jsonrpc::request::HistoryJsonRpcRequest;
-use ethportal_api::EthApiServer;
+use ethportal_api::types::execution::transaction::Transaction;
+use ethportal_api::types::jsonrpc::endpoints::TransactionGossipEndpoint;
+use ethportal_api::types::jsonrpc::request::{
+    HistoryJsonRpcRequest, TransactionGossipJsonRpcRequest,
+};
+use ethportal_api::utils::bytes::hex_decode;
+use ethportal_api::{
+    EthApiServer, Header, TransactionGossipContentKey, TransactionGossipContentValue,
+    TransactionKey,
+};
 use trin_validation::constants::CHAIN_ID;
 
 use crate::errors::RpcServeError;
-use crate::fetch::{find_block_body_by_hash, find_header_by_hash};
-use crate::jsonrpsee::core::{async_trait, RpcResult};
+use crate::fetch::{find_block_body_by_hash, find_header_by_hash, proxy_query_to_txgossip_subnet};
+use crate::jsonrpsee::core::{async_trait, RpcResult, SubscriptionResult};
+use crate::jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
+
+/// The only `eth_subscribe` subscription kind supported.
+const NEW_HEADS: &str = "newHeads";
 
 pub struct EthApi {
-    network: mpsc::UnboundedSender<HistoryJsonRpcRequest>,
+    network: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
+    txgossip_network: Option<mpsc::UnboundedSender<TransactionGossipJsonRpcRequest>>,
+    /// Execution headers of the chain head, verified by the beacon light client.
+    head_stream: Option<broadcast::Sender<Header>>,
 }
 
 impl EthApi {
-    pub fn new(network: mpsc::UnboundedSender<HistoryJsonRpcRequest>) -> Self {
-        Self { network }
+    pub fn new(
+        network: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
+        txgossip_network: Option<mpsc::UnboundedSender<TransactionGossipJsonRpcRequest>>,
+        head_stream: Option<broadcast::Sender<Header>>,
+    ) -> Self {
+        Self {
+            network,
+            txgossip_network,
+            head_stream,
+        }
+    }
+
+    fn history_network(
+        &self,
+    ) -> Result<&mpsc::UnboundedSender<HistoryJsonRpcRequest>, RpcServeError> {
+        self.network.as_ref().ok_or_else(|| {
+            RpcServeError::Message("History network is not active on this node".into())
+        })
     }
 }
 
@@ -39,8 +74,9 @@ impl EthApiServer for EthApi {
             .into());
         }
 
-        let header = find_header_by_hash(&self.network, block_hash).await?;
-        let body = find_block_body_by_hash(&self.network, block_hash).await?;
+        let network = self.history_network()?;
+        let header = find_header_by_hash(network, block_hash).await?;
+        let body = find_block_body_by_hash(network, block_hash).await?;
         let transactions = match body {
             BlockBody::Legacy(body) => body.txs,
             BlockBody::Merge(body) => body.txs,
@@ -64,6 +100,67 @@ impl EthApiServer for EthApi {
         };
         Ok(block)
     }
+
+    async fn send_raw_transaction(&self, raw_transaction: String) -> RpcResult<H256> {
+        let network = self.txgossip_network.as_ref().ok_or_else(|| {
+            RpcServeError::Message("Transaction gossip network is not active on this node".into())
+        })?;
+        let raw_transaction = hex_decode(&raw_transaction)
+            .map_err(|err| RpcServeError::Message(format!("Invalid hex encoding: {err}")))?;
+        let transaction = Transaction::decode(&raw_transaction).map_err(|err| {
+            RpcServeError::Message(format!("Invalid transaction encoding: {err:?}"))
+        })?;
+        let tx_hash = transaction.hash();
+
+        let content_key =
+            TransactionGossipContentKey::Transaction(TransactionKey { tx_hash: tx_hash.0 });
+        let content_value = TransactionGossipContentValue::Transaction(transaction);
+        let endpoint = TransactionGossipEndpoint::Gossip(content_key, content_value);
+        proxy_query_to_txgossip_subnet(network, endpoint).await?;
+        Ok(tx_hash)
+    }
+
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: String,
+    ) -> SubscriptionResult {
+        if kind != NEW_HEADS {
+            pending
+                .reject(RpcServeError::Message(format!(
+                    "Unsupported subscription: {kind}, only {NEW_HEADS} is supported"
+                )))
+                .await;
+            return Ok(());
+        }
+        let Some(head_stream) = self.head_stream.as_ref() else {
+            pending
+                .reject(RpcServeError::Message(
+                    "newHeads needs the beacon light client, start trin with the beacon network and --trusted-block-root".into(),
+                ))
+                .await;
+            return Ok(());
+        };
+        let mut receiver = head_stream.subscribe();
+        let sink = pending.accept().await?;
+
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break,
+                header = receiver.recv() => match header {
+                    Ok(header) => {
+                        let header: RpcHeader = header.into();
+                        sink.send(SubscriptionMessage::from_json(&header)?).await?;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "eth_subscribe subscriber is lagging, heads dropped");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for EthApi {
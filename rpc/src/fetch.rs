@@ -6,8 +6,10 @@ use tokio::sync::mpsc;
 use ethportal_api::types::constants::CONTENT_ABSENT;
 use ethportal_api::types::execution::block_body::BlockBody;
 use ethportal_api::types::execution::header::Header;
-use ethportal_api::types::jsonrpc::endpoints::HistoryEndpoint;
-use ethportal_api::types::jsonrpc::request::HistoryJsonRpcRequest;
+use ethportal_api::types::jsonrpc::endpoints::{HistoryEndpoint, TransactionGossipEndpoint};
+use ethportal_api::types::jsonrpc::request::{
+    HistoryJsonRpcRequest, TransactionGossipJsonRpcRequest,
+};
 use ethportal_api::utils::bytes::hex_decode;
 use ethportal_api::{ContentValue, HistoryContentKey, HistoryContentValue};
 
@@ -35,6 +37,28 @@ pub async fn proxy_query_to_history_subnet(
     }
 }
 
+pub async fn proxy_query_to_txgossip_subnet(
+    network: &mpsc::UnboundedSender<TransactionGossipJsonRpcRequest>,
+    endpoint: TransactionGossipEndpoint,
+) -> Result<Value, RpcServeError> {
+    let (resp_tx, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
+    let message = TransactionGossipJsonRpcRequest {
+        endpoint,
+        resp: resp_tx,
+    };
+    let _ = network.send(message);
+
+    match resp_rx.recv().await {
+        Some(val) => match val {
+            Ok(result) => Ok(result),
+            Err(msg) => Err(RpcServeError::Message(msg)),
+        },
+        None => Err(RpcServeError::Message(
+            "Internal error: No response from transaction gossip subnetwork".to_string(),
+        )),
+    }
+}
+
 pub async fn find_header_by_hash(
     network: &mpsc::UnboundedSender<HistoryJsonRpcRequest>,
     block_hash: H256,
@@ -5,12 +5,14 @@ use crate::jsonrpsee::core::{async_trait, RpcResult};
 use discv5::enr::NodeId;
 use ethportal_api::types::constants::CONTENT_ABSENT;
 use ethportal_api::types::enr::Enr;
+use ethportal_api::types::gossip_trace::{GossipTrace, OfferOutcome, OfferTrace};
 use ethportal_api::types::jsonrpc::endpoints::HistoryEndpoint;
 use ethportal_api::types::jsonrpc::request::HistoryJsonRpcRequest;
 use ethportal_api::types::portal::{
-    AcceptInfo, ContentInfo, DataRadius, FindNodesInfo, PaginateLocalContentInfo, PongInfo,
-    TraceContentInfo,
+    AcceptInfo, DataRadius, FindNodesInfo, HistoryContentInfo, HistoryPaginateLocalContentInfo,
+    HistoryTraceContentInfo, PongInfo, TraceNodesInfo,
 };
+use ethportal_api::types::query_trace::QueryTrace;
 use ethportal_api::HistoryContentKey;
 use ethportal_api::HistoryContentValue;
 use ethportal_api::HistoryNetworkApiServer;
@@ -95,6 +97,14 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
         Ok(result)
     }
 
+    /// Lookup a target node within in the network. Return tracing info.
+    async fn trace_recursive_find_nodes(&self, node_id: NodeId) -> RpcResult<TraceNodesInfo> {
+        let endpoint = HistoryEndpoint::TraceRecursiveFindNodes(node_id);
+        let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
+        let info: TraceNodesInfo = from_value(result)?;
+        Ok(info)
+    }
+
     /// Lookup a target node within in the network
     async fn radius(&self) -> RpcResult<DataRadius> {
         let endpoint = HistoryEndpoint::DataRadius;
@@ -108,10 +118,10 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
         &self,
         enr: Enr,
         content_key: HistoryContentKey,
-    ) -> RpcResult<ContentInfo> {
+    ) -> RpcResult<HistoryContentInfo> {
         let endpoint = HistoryEndpoint::FindContent(enr, content_key);
         let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
-        let result: ContentInfo = from_value(result)?;
+        let result: HistoryContentInfo = from_value(result)?;
         Ok(result)
     }
 
@@ -119,16 +129,16 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
     async fn recursive_find_content(
         &self,
         content_key: HistoryContentKey,
-    ) -> RpcResult<ContentInfo> {
+    ) -> RpcResult<HistoryContentInfo> {
         let endpoint = HistoryEndpoint::RecursiveFindContent(content_key);
         let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
         if result == serde_json::Value::String(CONTENT_ABSENT.to_string()) {
-            return Ok(ContentInfo::Content {
+            return Ok(HistoryContentInfo::Content {
                 content: PossibleHistoryContentValue::ContentAbsent,
                 utp_transfer: false,
             });
         };
-        let result: ContentInfo = from_value(result)?;
+        let result: HistoryContentInfo = from_value(result)?;
         Ok(result)
     }
 
@@ -136,22 +146,30 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
     async fn trace_recursive_find_content(
         &self,
         content_key: HistoryContentKey,
-    ) -> RpcResult<TraceContentInfo> {
+    ) -> RpcResult<HistoryTraceContentInfo> {
         let endpoint = HistoryEndpoint::TraceRecursiveFindContent(content_key);
         let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
-        let info: TraceContentInfo = from_value(result)?;
+        let info: HistoryTraceContentInfo = from_value(result)?;
         Ok(info)
     }
 
+    /// Return the traces of recently completed content lookups, newest first.
+    async fn recent_query_traces(&self, limit: Option<u64>) -> RpcResult<Vec<QueryTrace>> {
+        let endpoint = HistoryEndpoint::RecentQueryTraces(limit);
+        let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
+        let traces: Vec<QueryTrace> = from_value(result)?;
+        Ok(traces)
+    }
+
     /// Pagination of local content keys
     async fn paginate_local_content_keys(
         &self,
         offset: u64,
         limit: u64,
-    ) -> RpcResult<PaginateLocalContentInfo> {
+    ) -> RpcResult<HistoryPaginateLocalContentInfo> {
         let endpoint = HistoryEndpoint::PaginateLocalContentKeys(offset, limit);
         let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
-        let result: PaginateLocalContentInfo = from_value(result)?;
+        let result: HistoryPaginateLocalContentInfo = from_value(result)?;
         Ok(result)
     }
 
@@ -168,6 +186,18 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
         Ok(result)
     }
 
+    /// Send the provided content to interested peers, and wait for the outcome of every offer.
+    async fn trace_gossip(
+        &self,
+        content_key: HistoryContentKey,
+        content_value: HistoryContentValue,
+    ) -> RpcResult<GossipTrace> {
+        let endpoint = HistoryEndpoint::TraceGossip(content_key, content_value);
+        let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
+        let trace: GossipTrace = from_value(result)?;
+        Ok(trace)
+    }
+
     /// Send an OFFER request with given ContentKey, to the designated peer and wait for a response.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist receive.
     async fn offer(
@@ -182,6 +212,33 @@ impl HistoryNetworkApiServer for HistoryNetworkApi {
         Ok(result)
     }
 
+    /// Send an OFFER request with given ContentKey, to the designated peer and wait for the uTP
+    /// transfer of the accepted content.
+    async fn trace_offer(
+        &self,
+        enr: Enr,
+        content_key: HistoryContentKey,
+        content_value: Option<HistoryContentValue>,
+    ) -> RpcResult<OfferTrace> {
+        let endpoint = HistoryEndpoint::TraceOffer(enr, content_key, content_value);
+        let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
+        let trace: OfferTrace = from_value(result)?;
+        Ok(trace)
+    }
+
+    /// Offer many content items to the designated peer, and wait for the uTP transfers of the
+    /// accepted content.
+    async fn offer_many(
+        &self,
+        enr: Enr,
+        content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+    ) -> RpcResult<Vec<(String, OfferOutcome)>> {
+        let endpoint = HistoryEndpoint::OfferMany(enr, content_items);
+        let result = proxy_query_to_history_subnet(&self.network, endpoint).await?;
+        let outcomes: Vec<(String, OfferOutcome)> = from_value(result)?;
+        Ok(outcomes)
+    }
+
     /// Store content key with a content data to the local database.
     async fn store(
         &self,
@@ -1,105 +1,232 @@
 #![warn(clippy::unwrap_used)]
 
+mod admin_rpc;
 mod beacon_rpc;
 mod builder;
+mod canonical_indices_rpc;
 mod cors;
 mod discv5_rpc;
 mod errors;
 mod eth_rpc;
 mod fetch;
 mod history_rpc;
+mod portal_rpc;
 mod rpc_server;
 mod serde;
 mod web3_rpc;
 
 use crate::jsonrpsee::server::ServerBuilder;
 pub use crate::rpc_server::RpcServerHandle;
-use beacon_rpc::BeaconNetworkApi;
+use admin_rpc::AdminApi;
+pub use beacon_rpc::BeaconNetworkApi;
 pub use builder::{PortalRpcModule, RpcModuleBuilder, TransportRpcModuleConfig};
+pub use canonical_indices_rpc::CanonicalIndicesNetworkApi;
 use discv5_rpc::Discv5Api;
 use errors::RpcError;
 use eth_rpc::EthApi;
 use ethportal_api::jsonrpsee;
-use ethportal_api::types::cli::{
-    TrinConfig, Web3TransportType, BEACON_NETWORK, HISTORY_NETWORK, STATE_NETWORK,
-};
+use ethportal_api::types::cli::{RpcApiSelection, TrinConfig, Web3TransportType};
 use ethportal_api::types::jsonrpc::request::{
-    BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest,
+    HistoryJsonRpcRequest, TransactionGossipJsonRpcRequest,
 };
-use history_rpc::HistoryNetworkApi;
+use ethportal_api::EthApiServer;
+pub use history_rpc::HistoryNetworkApi;
+use portal_rpc::PortalApi;
 use web3_rpc::Web3Api;
 
 use crate::rpc_server::RpcServerConfig;
 use portalnet::discovery::Discovery;
+use portalnet::events::EventEnvelope;
+use portalnet::shutdown::ShutdownSignal;
+use portalnet::subnetwork::{SubnetworkAdmin, SubnetworkHandle};
 use reth_ipc::server::Builder as IpcServerBuilder;
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 
 pub async fn launch_jsonrpc_server(
     trin_config: TrinConfig,
     discv5: Arc<Discovery>,
-    history_handler: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
-    state_handler: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
-    beacon_handler: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
+    subnetworks: &[SubnetworkHandle],
+    shutdown_signal: ShutdownSignal,
 ) -> Result<RpcServerHandle, RpcError> {
     // Discv5 and Web3 modules are enabled with every network
     let mut modules = vec![PortalRpcModule::Discv5, PortalRpcModule::Web3];
+    let mut builder = RpcModuleBuilder::new(discv5);
+
+    // Each subnetwork serves its methods under the module named after it
+    for subnetwork in subnetworks {
+        let Some(methods) = subnetwork.rpc_methods.clone() else {
+            continue;
+        };
+        let module = PortalRpcModule::from_str(subnetwork.name).map_err(|_| {
+            RpcError::Custom(format!(
+                "The {} subnetwork serves JSON-RPC methods, but there is no rpc module for it",
+                subnetwork.name
+            ))
+        })?;
+        builder = builder.with_methods(module, methods);
+        modules.push(module);
+    }
+    let event_streams: HashMap<String, broadcast::Sender<EventEnvelope>> = subnetworks
+        .iter()
+        .filter_map(|subnetwork| {
+            let event_stream = subnetwork.event_stream.clone()?;
+            Some((subnetwork.name.to_string(), event_stream))
+        })
+        .collect();
+    if !event_streams.is_empty() {
+        modules.push(PortalRpcModule::Portal);
+    }
+    // The eth module is served on top of the subnetworks: blocks are looked up in the history
+    // network, transactions are broadcast through the transaction gossip network, and
+    // `eth_subscribe` follows the head of the chain verified by the beacon light client
+    let history_tx = find_jsonrpc_tx::<HistoryJsonRpcRequest>(subnetworks);
+    let txgossip_tx = find_jsonrpc_tx::<TransactionGossipJsonRpcRequest>(subnetworks);
+    let head_stream = subnetworks
+        .iter()
+        .find_map(|subnetwork| subnetwork.head_stream.clone());
+    if history_tx.is_some() || txgossip_tx.is_some() || head_stream.is_some() {
+        let eth_api = EthApi::new(history_tx, txgossip_tx, head_stream);
+        builder = builder.with_methods(PortalRpcModule::Eth, eth_api.into_rpc());
+        modules.push(PortalRpcModule::Eth);
+    }
+    let subnetwork_admins: HashMap<String, Arc<dyn SubnetworkAdmin>> = subnetworks
+        .iter()
+        .filter_map(|subnetwork| {
+            let admin = subnetwork.admin.clone()?;
+            Some((subnetwork.name.to_string(), admin))
+        })
+        .collect();
+    modules.push(PortalRpcModule::Admin);
 
-    for network in trin_config.networks.iter() {
-        match network.as_str() {
-            HISTORY_NETWORK => {
-                modules.push(PortalRpcModule::History);
-                modules.push(PortalRpcModule::Eth);
+    let http_modules = select_modules(&modules, trin_config.http_api.as_ref())?;
+    let ws_modules = select_modules(&modules, trin_config.ws_api.as_ref())?;
+    let ipc_modules = select_modules(&modules, trin_config.ipc_api.as_ref())?;
+
+    let mut transport = TransportRpcModuleConfig::default();
+    let mut server_config = RpcServerConfig::default();
+    for transport_type in &trin_config.web3_transport {
+        match transport_type {
+            Web3TransportType::HTTP => {
+                transport = transport.with_http(http_modules.clone());
+                server_config = server_config
+                    .with_http_address(
+                        trin_config
+                            .web3_http_address
+                            .socket_addrs(|| None)
+                            .expect("Invalid socket address")[0],
+                    )
+                    .with_http(ServerBuilder::default());
+            }
+            Web3TransportType::WS => {
+                transport = transport.with_ws(ws_modules.clone());
+                server_config = server_config
+                    .with_ws_address(
+                        trin_config
+                            .web3_ws_address
+                            .socket_addrs(|| None)
+                            .expect("Invalid socket address")[0],
+                    )
+                    .with_ws(ServerBuilder::default());
             }
-            STATE_NETWORK => {
-                // not implemented
+            Web3TransportType::IPC => {
+                transport = transport.with_ipc(ipc_modules.clone());
+                server_config = server_config
+                    .with_ipc_endpoint(
+                        trin_config
+                            .web3_ipc_path
+                            .to_str()
+                            .expect("Path should be string"),
+                    )
+                    .with_ipc(IpcServerBuilder::default());
             }
-            BEACON_NETWORK => modules.push(PortalRpcModule::Beacon),
-            _ => panic!("Unexpected network type: {}", network),
         }
     }
+    let transport_modules = builder
+        .with_event_streams(event_streams)
+        .with_subnetwork_admins(subnetwork_admins)
+        .with_shutdown_signal(shutdown_signal)
+        .build(transport);
+    let handle = server_config.start(transport_modules).await?;
 
-    let handle: RpcServerHandle = match trin_config.web3_transport {
-        Web3TransportType::IPC => {
-            let transport = TransportRpcModuleConfig::default().with_ipc(modules);
-            let transport_modules = RpcModuleBuilder::new(discv5)
-                .maybe_with_history(history_handler)
-                .maybe_with_beacon(beacon_handler)
-                .maybe_with_state(state_handler)
-                .build(transport);
+    Ok(handle)
+}
 
-            RpcServerConfig::default()
-                .with_ipc_endpoint(
-                    trin_config
-                        .web3_ipc_path
-                        .to_str()
-                        .expect("Path should be string"),
-                )
-                .with_ipc(IpcServerBuilder::default())
-                .start(transport_modules)
-                .await?
+/// Returns the modules to serve over a transport, given the `--<transport>.api` flag.
+///
+/// Without the flag, every available module except admin is served, and with "all" every
+/// available module is served. Otherwise the selected modules must all be available on this node.
+fn select_modules(
+    available: &[PortalRpcModule],
+    api: Option<&RpcApiSelection>,
+) -> Result<Vec<PortalRpcModule>, RpcError> {
+    match api {
+        None => Ok(available
+            .iter()
+            .copied()
+            .filter(|module| *module != PortalRpcModule::Admin)
+            .collect()),
+        Some(RpcApiSelection::All) => Ok(available.to_vec()),
+        Some(RpcApiSelection::Modules(selection)) => {
+            if let Some(module) = selection.iter().find(|module| !available.contains(module)) {
+                let available = available
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(RpcError::Custom(format!(
+                    "The {module} rpc module is not available, enabled modules: {available}"
+                )));
+            }
+            Ok(selection.clone())
         }
-        Web3TransportType::HTTP => {
-            let transport = TransportRpcModuleConfig::default().with_http(modules);
-            let transport_modules = RpcModuleBuilder::new(discv5)
-                .maybe_with_history(history_handler)
-                .maybe_with_beacon(beacon_handler)
-                .maybe_with_state(state_handler)
-                .build(transport);
+    }
+}
 
-            RpcServerConfig::default()
-                .with_http_address(
-                    trin_config
-                        .web3_http_address
-                        .socket_addrs(|| None)
-                        .expect("Invalid socket address")[0],
-                )
-                .with_http(ServerBuilder::default())
-                .with_ws(ServerBuilder::default())
-                .start(transport_modules)
-                .await?
-        }
-    };
+/// Returns the channel to the JSON-RPC handler of the subnetwork that accepts requests of type `T`.
+fn find_jsonrpc_tx<T: Send + 'static>(
+    subnetworks: &[SubnetworkHandle],
+) -> Option<mpsc::UnboundedSender<T>> {
+    subnetworks
+        .iter()
+        .find_map(|subnetwork| subnetwork.jsonrpc_tx::<T>())
+}
 
-    Ok(handle)
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_modules() {
+        let available = [
+            PortalRpcModule::Discv5,
+            PortalRpcModule::Web3,
+            PortalRpcModule::Eth,
+        ];
+        assert_eq!(select_modules(&available, None).unwrap(), available);
+        assert_eq!(
+            select_modules(&available, Some(&"all".parse().unwrap())).unwrap(),
+            available
+        );
+        assert_eq!(
+            select_modules(&available, Some(&"eth,web3".parse().unwrap())).unwrap(),
+            [PortalRpcModule::Eth, PortalRpcModule::Web3]
+        );
+        // History is not active
+        assert!(select_modules(&available, Some(&"eth,history".parse().unwrap())).is_err());
+        // Admin is only served when selected
+        let available = [PortalRpcModule::Admin, PortalRpcModule::Web3];
+        assert_eq!(
+            select_modules(&available, None).unwrap(),
+            [PortalRpcModule::Web3]
+        );
+        assert_eq!(
+            select_modules(&available, Some(&"admin".parse().unwrap())).unwrap(),
+            [PortalRpcModule::Admin]
+        );
+        assert!("unknown".parse::<RpcApiSelection>().is_err());
+    }
 }
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+use ethportal_api::types::portal::{PortalEvent, PortalEventKind};
+use ethportal_api::utils::bytes::hex_encode;
+use ethportal_api::PortalApiServer;
+use portalnet::events::{EventEnvelope, OverlayEvent};
+
+use crate::errors::RpcServeError;
+use crate::jsonrpsee::core::{async_trait, SubscriptionResult};
+use crate::jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
+
+pub struct PortalApi {
+    /// Overlay event streams, keyed by subnetwork name.
+    event_streams: HashMap<String, broadcast::Sender<EventEnvelope>>,
+}
+
+impl PortalApi {
+    pub fn new(event_streams: HashMap<String, broadcast::Sender<EventEnvelope>>) -> Self {
+        Self { event_streams }
+    }
+}
+
+#[async_trait]
+impl PortalApiServer for PortalApi {
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        network: String,
+    ) -> SubscriptionResult {
+        let Some(event_stream) = self.event_streams.get(&network) else {
+            pending
+                .reject(RpcServeError::Message(format!(
+                    "Network {network} is not active on this node"
+                )))
+                .await;
+            return Ok(());
+        };
+        let mut receiver = event_stream.subscribe();
+        let sink = pending.accept().await?;
+
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break,
+                event = receiver.recv() => match event {
+                    Ok(event) => {
+                        let event = portal_event(&network, event);
+                        sink.send(SubscriptionMessage::from_json(&event)?).await?;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(network, skipped, "portal_subscribe subscriber is lagging, events dropped");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts an overlay event into its JSON-RPC representation.
+fn portal_event(network: &str, envelope: EventEnvelope) -> PortalEvent {
+    let event = match envelope.payload {
+        OverlayEvent::LightClientOptimisticUpdate => PortalEventKind::LightClientOptimisticUpdate,
+        OverlayEvent::LightClientFinalityUpdate => PortalEventKind::LightClientFinalityUpdate,
+        OverlayEvent::ContentReceived {
+            content_key,
+            content_value,
+        } => PortalEventKind::ContentReceived {
+            content_key: hex_encode(content_key),
+            content_value: hex_encode(content_value),
+        },
+        OverlayEvent::OfferAccepted {
+            source,
+            content_keys,
+        } => PortalEventKind::OfferAccepted {
+            source: source.into(),
+            content_keys: content_keys.into_iter().map(hex_encode).collect(),
+        },
+        OverlayEvent::NodeAdded { node_id } => PortalEventKind::NodeAdded {
+            node_id: node_id.into(),
+        },
+    };
+    PortalEvent {
+        network: network.to_string(),
+        timestamp: envelope.timestamp.to_millis(),
+        event,
+    }
+}
+
+impl std::fmt::Debug for PortalApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortalApi").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    use discv5::enr::NodeId;
+    use portalnet::events::Timestamp;
+    use serde_json::json;
+
+    #[test]
+    fn portal_event_serialization() {
+        let node_id = NodeId::new(&[1; 32]);
+        let envelope = EventEnvelope {
+            timestamp: Timestamp::CreateTime(100),
+            payload: OverlayEvent::OfferAccepted {
+                source: node_id,
+                content_keys: vec![vec![0, 1]],
+            },
+        };
+        let event = portal_event("history", envelope);
+        assert_eq!(
+            serde_json::to_value(event).unwrap(),
+            json!({
+                "network": "history",
+                "timestamp": 100,
+                "type": "offerAccepted",
+                "source": hex_encode([1; 32]),
+                "contentKeys": ["0x0001"],
+            })
+        );
+    }
+}
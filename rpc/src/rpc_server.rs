@@ -610,7 +610,8 @@ impl WsHttpServerKind {
 mod tests {
     use super::*;
     use crate::builder::RpcModuleSelection;
-    use crate::{PortalRpcModule, RpcModuleBuilder};
+    use crate::{BeaconNetworkApi, HistoryNetworkApi, PortalRpcModule, RpcModuleBuilder};
+    use ethportal_api::{BeaconNetworkApiServer, HistoryNetworkApiServer};
     use portalnet::discovery::Discovery;
     use portalnet::utils::db::setup_temp_dir;
     use std::io;
@@ -637,8 +638,14 @@ mod tests {
         let temp_dir = setup_temp_dir().unwrap().into_path();
         let discv5 = Arc::new(Discovery::new(Default::default(), temp_dir).unwrap());
         RpcModuleBuilder::new(discv5)
-            .with_history(history_tx)
-            .with_beacon(beacon_tx)
+            .with_methods(
+                PortalRpcModule::History,
+                HistoryNetworkApi::new(history_tx).into_rpc(),
+            )
+            .with_methods(
+                PortalRpcModule::Beacon,
+                BeaconNetworkApi::new(beacon_tx).into_rpc(),
+            )
     }
 
     /// Launches a new server with http only with the given modules
@@ -713,6 +720,20 @@ mod tests {
         assert!(is_addr_in_use_kind(&err, ServerKind::WS(addr)), "{err:?}");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subnetwork_methods_follow_module_selection() {
+        let mut builder = test_rpc_builder();
+        let module = builder.module_for(&vec![PortalRpcModule::History].into());
+        let methods: Vec<_> = module.method_names().collect();
+        assert!(methods.contains(&"portal_historyPing"));
+        assert!(!methods.contains(&"portal_beaconPing"));
+
+        let module = builder.module_for(&RpcModuleSelection::All);
+        let methods: Vec<_> = module.method_names().collect();
+        assert!(methods.contains(&"portal_historyPing"));
+        assert!(methods.contains(&"portal_beaconPing"));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_launch_same_port() {
         let handle = launch_http_ws_same_port(vec![PortalRpcModule::History]).await;
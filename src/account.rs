@@ -0,0 +1,70 @@
+use std::fs;
+
+use anyhow::{anyhow, bail};
+use discv5::enr::CombinedKey;
+use ethereum_types::H256;
+
+use ethportal_api::types::cli::{AccountCommands, TrinConfig};
+use ethportal_api::utils::bytes::{hex_decode, hex_encode};
+use portalnet::utils::{
+    db::{configure_trin_data_dir, UNSAFE_PRIVATE_KEY_FILE_NAME},
+    keystore::{decrypt_private_key, encrypt_private_key, keystore_dir, read_password},
+};
+
+/// Runs a `trin account` subcommand.
+pub fn run_account_command(
+    command: &AccountCommands,
+    trin_config: &TrinConfig,
+) -> anyhow::Result<()> {
+    let password = read_password(trin_config.password_file.as_deref())?;
+    match command {
+        AccountCommands::New => {
+            let trin_data_dir = configure_trin_data_dir(trin_config.ephemeral)?;
+            let private_key = H256::from_slice(&CombinedKey::generate_secp256k1().encode());
+            let path = encrypt_private_key(&keystore_dir(&trin_data_dir), private_key, &password)?;
+            println!("Created keystore: {}", path.display());
+        }
+        AccountCommands::Import { private_key_file } => {
+            let trin_data_dir = configure_trin_data_dir(trin_config.ephemeral)?;
+            let private_key_file = private_key_file
+                .clone()
+                .unwrap_or_else(|| trin_data_dir.join(UNSAFE_PRIVATE_KEY_FILE_NAME));
+            let private_key = fs::read_to_string(&private_key_file).map_err(|err| {
+                anyhow!("Unable to read private key file {private_key_file:?}: {err}")
+            })?;
+            let private_key = hex_decode(private_key.trim())?;
+            if private_key.len() != 32 {
+                bail!(
+                    "Invalid private key length: {}, expected 32 bytes",
+                    private_key.len()
+                );
+            }
+            let path = encrypt_private_key(
+                &keystore_dir(&trin_data_dir),
+                H256::from_slice(&private_key),
+                &password,
+            )?;
+            println!(
+                "Imported {} into keystore: {}",
+                private_key_file.display(),
+                path.display()
+            );
+        }
+        AccountCommands::Export { keystore } => {
+            let private_key = decrypt_private_key(keystore, &password)?;
+            println!("{}", hex_encode(private_key));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the node private key from `--keystore` or `--unsafe-private-key`, if either is set.
+pub fn configured_private_key(trin_config: &TrinConfig) -> anyhow::Result<Option<H256>> {
+    match &trin_config.keystore {
+        Some(keystore) => {
+            let password = read_password(trin_config.password_file.as_deref())?;
+            Ok(Some(decrypt_private_key(keystore, &password)?))
+        }
+        None => Ok(trin_config.private_key),
+    }
+}
@@ -1,37 +1,91 @@
 #![warn(clippy::unwrap_used)]
 
+pub mod account;
+
 use std::sync::Arc;
 
+use futures::future::join_all;
 use rpc::{launch_jsonrpc_server, RpcServerHandle};
 use tokio::sync::mpsc;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{error, info};
 use utp_rs::socket::UtpSocket;
 
+use ethportal_api::types::cli::TrinConfig;
 #[cfg(windows)]
 use ethportal_api::types::cli::Web3TransportType;
-use ethportal_api::types::cli::{TrinConfig, BEACON_NETWORK, HISTORY_NETWORK, STATE_NETWORK};
 use portalnet::{
     config::PortalnetConfig,
     discovery::{Discovery, Discv5UdpSocket},
     events::PortalnetEvents,
+    shutdown::ShutdownSignal,
     storage::PortalStorageConfig,
+    subnetwork::{overlay_senders, SubnetworkContext, SubnetworkHandle, SubnetworkRegistry},
     utils::db::{configure_node_data_dir, configure_trin_data_dir},
 };
-use trin_beacon::initialize_beacon_network;
-use trin_history::initialize_history_network;
-use trin_state::initialize_state_network;
+use trin_beacon::BeaconSubnetwork;
+use trin_canonical_indices::CanonicalIndicesSubnetwork;
+use trin_history::HistorySubnetwork;
+use trin_state::StateSubnetwork;
+use trin_txgossip::TransactionGossipSubnetwork;
 use trin_utils::version::get_trin_version;
 use trin_validation::{accumulator::MasterAccumulator, oracle::HeaderOracle};
 
+/// Returns a registry of the subnetworks that are built into trin.
+pub fn default_subnetworks() -> anyhow::Result<SubnetworkRegistry> {
+    let mut registry = SubnetworkRegistry::new();
+    registry.register(HistorySubnetwork)?;
+    registry.register(StateSubnetwork)?;
+    registry.register(BeaconSubnetwork)?;
+    registry.register(TransactionGossipSubnetwork)?;
+    registry.register(CanonicalIndicesSubnetwork)?;
+    Ok(registry)
+}
+
+/// A running trin node, see [`run_trin_with_subnetworks`].
+pub struct TrinNode {
+    pub rpc_handle: RpcServerHandle,
+    pub subnetworks: Vec<SubnetworkHandle>,
+    /// Resolves once the node should shut down, eg. on ctrl-c or after an `admin_shutdown` call.
+    pub shutdown_signal: ShutdownSignal,
+}
+
+impl TrinNode {
+    /// Stops the JSON-RPC server, then stops every subnetwork and waits until they have persisted
+    /// their state, eg. their routing tables.
+    pub async fn shutdown(self) {
+        if let Err(err) = self.rpc_handle.stop() {
+            error!(err = %err, "Failed to close RPC server")
+        }
+        join_all(
+            self.subnetworks
+                .iter()
+                .filter_map(|subnetwork| subnetwork.admin.as_ref())
+                .map(|admin| admin.shutdown()),
+        )
+        .await;
+        info!("Trin stopped");
+    }
+}
+
 pub async fn run_trin(
     trin_config: TrinConfig,
 ) -> Result<RpcServerHandle, Box<dyn std::error::Error>> {
+    let node = run_trin_with_subnetworks(trin_config, default_subnetworks()?).await?;
+    Ok(node.rpc_handle)
+}
+
+/// Runs trin with the given subnetworks available for selection with `--networks`. Downstream
+/// crates can register their own subnetworks next to the built-in ones.
+pub async fn run_trin_with_subnetworks(
+    trin_config: TrinConfig,
+    subnetworks: SubnetworkRegistry,
+) -> Result<TrinNode, Box<dyn std::error::Error>> {
     // Panic early on a windows build that is trying to use IPC, which is unsupported for now
     // Make sure not to panic on non-windows configurations.
     #[cfg(windows)]
-    if let Web3TransportType::IPC = trin_config.web3_transport {
-        panic!("Tokio doesn't support Windows Unix Domain Sockets IPC, use --web3-transport http");
+    if trin_config.web3_transport.contains(&Web3TransportType::IPC) {
+        panic!("Tokio doesn't support Windows Unix Domain Sockets IPC, use --web3-transport http or ws");
     }
 
     let trin_version = get_trin_version();
@@ -42,8 +96,10 @@ pub async fn run_trin(
     let trin_data_dir = configure_trin_data_dir(trin_config.ephemeral)?;
 
     // Configure node data dir based on the provided private key
-    let (node_data_dir, private_key) =
-        configure_node_data_dir(trin_data_dir, trin_config.private_key)?;
+    let (node_data_dir, private_key) = configure_node_data_dir(
+        trin_data_dir,
+        account::configured_private_key(&trin_config)?,
+    )?;
 
     let portalnet_config = PortalnetConfig::new(&trin_config, private_key);
 
@@ -63,11 +119,14 @@ pub async fn run_trin(
     let utp_socket = UtpSocket::with_socket(discv5_utp_socket);
     let utp_socket = Arc::new(utp_socket);
 
-    let storage_config = PortalStorageConfig::new(
-        trin_config.mb.into(),
-        node_data_dir,
-        discovery.local_enr().node_id(),
-    )?;
+    let storage_config = PortalStorageConfig {
+        cache_capacity_mb: trin_config.cache_mb.into(),
+        ..PortalStorageConfig::new(
+            trin_config.mb.into(),
+            node_data_dir,
+            discovery.local_enr().node_id(),
+        )?
+    };
 
     // Initialize validation oracle
     let master_accumulator = MasterAccumulator::try_from_file(trin_config.master_acc_path.clone())?;
@@ -78,54 +137,19 @@ pub async fn run_trin(
     let header_oracle = HeaderOracle::new(master_accumulator);
     let header_oracle = Arc::new(RwLock::new(header_oracle));
 
-    // Initialize state sub-network service and event handlers, if selected
-    let (state_handler, state_network_task, state_event_tx, state_jsonrpc_tx) =
-        if trin_config.networks.iter().any(|val| val == STATE_NETWORK) {
-            initialize_state_network(
-                &discovery,
-                Arc::clone(&utp_socket),
-                portalnet_config.clone(),
-                storage_config.clone(),
-                header_oracle.clone(),
-            )
-            .await?
-        } else {
-            (None, None, None, None)
-        };
-
-    // Initialize trin-beacon sub-network service and event handlers, if selected
-    let (beacon_handler, beacon_network_task, beacon_event_tx, beacon_jsonrpc_tx) =
-        if trin_config.networks.iter().any(|val| val == BEACON_NETWORK) {
-            initialize_beacon_network(
-                &discovery,
-                Arc::clone(&utp_socket),
-                portalnet_config.clone(),
-                storage_config.clone(),
-                header_oracle.clone(),
-            )
-            .await?
-        } else {
-            (None, None, None, None)
-        };
-
-    // Initialize chain history sub-network service and event handlers, if selected
-    let (history_handler, history_network_task, history_event_tx, history_jsonrpc_tx) =
-        if trin_config
-            .networks
-            .iter()
-            .any(|val| val == HISTORY_NETWORK)
-        {
-            initialize_history_network(
-                &discovery,
-                utp_socket,
-                portalnet_config.clone(),
-                storage_config.clone(),
-                header_oracle.clone(),
-            )
-            .await?
-        } else {
-            (None, None, None, None)
-        };
+    // Initialize the selected sub-networks and their event handlers
+    let shutdown_signal = ShutdownSignal::new();
+    let subnetwork_context = SubnetworkContext {
+        discovery: Arc::clone(&discovery),
+        utp_socket,
+        portalnet_config,
+        storage_config,
+        header_oracle,
+        shutdown_signal: shutdown_signal.clone(),
+    };
+    let subnetworks = subnetworks
+        .initialize(&trin_config.networks, &subnetwork_context)
+        .await?;
 
     // Launch JSON-RPC server
     let jsonrpc_trin_config = trin_config.clone();
@@ -133,44 +157,21 @@ pub async fn run_trin(
     let rpc_handle: RpcServerHandle = launch_jsonrpc_server(
         jsonrpc_trin_config,
         jsonrpc_discovery,
-        history_jsonrpc_tx,
-        state_jsonrpc_tx,
-        beacon_jsonrpc_tx,
+        &subnetworks,
+        shutdown_signal.clone(),
     )
     .await?;
 
-    if let Some(handler) = state_handler {
-        tokio::spawn(handler.handle_client_queries());
-    }
-    if let Some(handler) = history_handler {
-        tokio::spawn(async move { handler.handle_client_queries().await });
-    }
-    if let Some(handler) = beacon_handler {
-        tokio::spawn(async move { handler.handle_client_queries().await });
-    }
-
     // Spawn main portal events handler
+    let overlay_senders = overlay_senders(&subnetworks);
     tokio::spawn(async move {
-        let events = PortalnetEvents::new(
-            talk_req_rx,
-            history_event_tx,
-            state_event_tx,
-            beacon_event_tx,
-            utp_talk_reqs_tx,
-        )
-        .await;
+        let events = PortalnetEvents::new(talk_req_rx, overlay_senders, utp_talk_reqs_tx).await;
         events.start().await;
     });
 
-    if let Some(network) = history_network_task {
-        tokio::spawn(network);
-    }
-    if let Some(network) = state_network_task {
-        tokio::spawn(network);
-    }
-    if let Some(network) = beacon_network_task {
-        tokio::spawn(network);
-    }
-
-    Ok(rpc_handle)
+    Ok(TrinNode {
+        rpc_handle,
+        subnetworks,
+        shutdown_signal,
+    })
 }
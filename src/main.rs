@@ -1,24 +1,22 @@
 #![warn(clippy::unwrap_used)]
 
-use ethportal_api::types::cli::TrinConfig;
-use tracing::error;
+use ethportal_api::types::cli::{TrinConfig, TrinConfigCommands};
 use trin_utils::log::init_tracing_logger;
 
-use trin::run_trin;
+use trin::{account::run_account_command, default_subnetworks, run_trin_with_subnetworks};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     init_tracing_logger();
     let trin_config = TrinConfig::from_cli();
-    let rpc_handle = run_trin(trin_config).await?;
-
-    tokio::signal::ctrl_c()
-        .await
-        .expect("failed to pause until ctrl-c");
-
-    if let Err(err) = rpc_handle.stop() {
-        error!(err = %err, "Failed to close RPC server")
+    if let Some(TrinConfigCommands::Account(command)) = &trin_config.command {
+        run_account_command(command, &trin_config)?;
+        return Ok(());
     }
+    let node = run_trin_with_subnetworks(trin_config, default_subnetworks()?).await?;
+
+    node.shutdown_signal.wait().await;
+    node.shutdown().await;
 
     Ok(())
 }
@@ -41,6 +41,7 @@ async fn peertest_stateless() {
     peertest::scenarios::find::test_recursive_find_nodes_self(&peertest).await;
     peertest::scenarios::find::test_recursive_find_nodes_peer(&peertest).await;
     peertest::scenarios::find::test_recursive_find_nodes_random(&peertest).await;
+    peertest::scenarios::find::test_trace_recursive_find_nodes_random(&peertest).await;
     peertest::scenarios::eth_rpc::test_eth_chain_id(&peertest).await;
     peertest.exit_all_nodes();
     handle.stop().unwrap();
@@ -55,6 +56,24 @@ async fn peertest_populated_offer() {
     handle.stop().unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_trace_populated_offer() {
+    let (peertest, target, handle) = setup_peertest().await;
+    peertest::scenarios::offer_accept::test_trace_populated_offer(&peertest, &target).await;
+    peertest.exit_all_nodes();
+    handle.stop().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_offer_many() {
+    let (peertest, target, handle) = setup_peertest().await;
+    peertest::scenarios::offer_accept::test_offer_many(&peertest, &target).await;
+    peertest.exit_all_nodes();
+    handle.stop().unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn peertest_unpopulated_offer() {
@@ -156,10 +175,25 @@ async fn peertest_trace_recursive_utp() {
     handle.stop().unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_scenario_files() {
+    utils::init_tracing();
+    let paths =
+        peertest::scenario_file::scenario_paths(&["ethportal-peertest/scenarios".into()]).unwrap();
+    assert!(!paths.is_empty());
+    for path in paths {
+        let scenario = peertest::scenario_file::ScenarioFile::from_path(&path).unwrap();
+        peertest::scenario_file::run_scenario(&scenario)
+            .await
+            .unwrap();
+    }
+}
+
 async fn setup_peertest() -> (peertest::Peertest, Client, RpcServerHandle) {
     utils::init_tracing();
     // Run a client, as a buddy peer for ping tests, etc.
-    let peertest = peertest::launch_peertest_nodes(2).await;
+    let peertest = peertest::launch_peertest_nodes(2).await.unwrap();
     // Short sleep to make sure all peertest nodes can connect
     sleep(Duration::from_millis(100)).await;
 
@@ -199,7 +233,7 @@ async fn setup_peertest() -> (peertest::Peertest, Client, RpcServerHandle) {
 async fn setup_peertest_bridge() -> (Peertest, HttpClient, RpcServerHandle) {
     utils::init_tracing();
     // Run a client, as a buddy peer for ping tests, etc.
-    let peertest = peertest::launch_peertest_nodes(1).await;
+    let peertest = peertest::launch_peertest_nodes(1).await.unwrap();
     // Short sleep to make sure all peertest nodes can connect
     sleep(Duration::from_millis(100)).await;
 
@@ -250,6 +284,24 @@ async fn peertest_history_bridge() {
     handle.stop().unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_history_bridge_offer_many() {
+    let (peertest, target, handle) = setup_peertest_bridge().await;
+    peertest::scenarios::bridge::test_history_bridge_offer_many(&peertest, &target).await;
+    peertest.exit_all_nodes();
+    handle.stop().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_native_history_gossip() {
+    let (peertest, _target, handle) = setup_peertest().await;
+    peertest::scenarios::bridge::test_native_history_gossip(&peertest).await;
+    peertest.exit_all_nodes();
+    handle.stop().unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn peertest_beacon_bridge() {
@@ -6,9 +6,12 @@ use ethportal_api::types::distance::Distance;
 use ethportal_api::types::jsonrpc::endpoints::BeaconEndpoint;
 use ethportal_api::types::jsonrpc::request::BeaconJsonRpcRequest;
 use ethportal_api::types::portal::{
-    AcceptInfo, ContentInfo, FindNodesInfo, PongInfo, TraceContentInfo,
+    AcceptInfo, BeaconContentInfo, BeaconTraceContentInfo, FindNodesInfo, PongInfo, TraceNodesInfo,
+};
+use ethportal_api::types::{
+    constants::CONTENT_ABSENT,
+    query_trace::{QueryTermination, QueryTrace},
 };
-use ethportal_api::types::{constants::CONTENT_ABSENT, query_trace::QueryTrace};
 use ethportal_api::utils::bytes::hex_encode;
 use ethportal_api::{BeaconContentKey, BeaconContentValue, OverlayContentKey, RawContentKey};
 use portalnet::storage::ContentStore;
@@ -53,6 +56,16 @@ async fn complete_request(network: Arc<RwLock<BeaconNetwork>>, request: BeaconJs
         BeaconEndpoint::TraceRecursiveFindContent(content_key) => {
             recursive_find_content(network, content_key, true).await
         }
+        BeaconEndpoint::RecentQueryTraces(limit) => recent_query_traces(network, limit).await,
+        BeaconEndpoint::TraceRecursiveFindNodes(node_id) => {
+            trace_recursive_find_nodes(network, node_id).await
+        }
+        BeaconEndpoint::TraceGossip(content_key, content_value) => {
+            trace_gossip(network, content_key, content_value).await
+        }
+        BeaconEndpoint::TraceOffer(enr, content_key, content_value) => {
+            trace_offer(network, enr, content_key, content_value).await
+        }
         BeaconEndpoint::AddEnr(enr) => add_enr(network, enr).await,
         BeaconEndpoint::DataRadius => {
             let radius = network.read().await.overlay.data_radius();
@@ -84,11 +97,12 @@ async fn recursive_find_content(
     content_key: BeaconContentKey,
     is_trace: bool,
 ) -> Result<Value, String> {
-    // Check whether we have the data locally.
+    // Check whether we have the data locally, either stored or recently found.
     let overlay = network.read().await.overlay.clone();
-    let local_content: Option<Vec<u8>> = match overlay.store.read().get(&content_key) {
+    let stored_content = overlay.store.read().get(&content_key);
+    let local_content: Option<Vec<u8>> = match stored_content {
         Ok(Some(data)) => Some(data),
-        Ok(None) => None,
+        Ok(None) => overlay.cached_content(&content_key),
         Err(err) => {
             error!(
                 error = %err,
@@ -106,6 +120,7 @@ async fn recursive_find_content(
                 NodeId::new(&content_key.content_id()).into(),
             );
             trace.node_responded_with_content(&local_enr);
+            trace.finish(QueryTermination::ContentFound);
             (Some(val), false, if is_trace { Some(trace) } else { None })
         }
         None => overlay.lookup_content(content_key.clone(), is_trace).await,
@@ -119,13 +134,13 @@ async fn recursive_find_content(
 
     // If tracing is not required, return content.
     if !is_trace {
-        return Ok(json!(ContentInfo::Content {
+        return Ok(json!(BeaconContentInfo::Content {
             content: serde_json::from_value(content_response_string).map_err(|e| e.to_string())?,
             utp_transfer,
         }));
     }
     if let Some(trace) = trace {
-        Ok(json!(TraceContentInfo {
+        Ok(json!(BeaconTraceContentInfo {
             content: serde_json::from_value(content_response_string).map_err(|e| e.to_string())?,
             utp_transfer,
             trace,
@@ -135,6 +150,18 @@ async fn recursive_find_content(
     }
 }
 
+/// Constructs a JSON call for the RecentQueryTraces method.
+async fn recent_query_traces(
+    network: Arc<RwLock<BeaconNetwork>>,
+    limit: Option<u64>,
+) -> Result<Value, String> {
+    let limit = limit.map_or(usize::MAX, |limit| {
+        usize::try_from(limit).unwrap_or(usize::MAX)
+    });
+    let traces = network.read().await.overlay.recent_traces(limit);
+    Ok(json!(traces))
+}
+
 /// Constructs a JSON call for the LocalContent method.
 async fn local_content(
     network: Arc<RwLock<BeaconNetwork>>,
@@ -165,7 +192,7 @@ async fn paginate_local_content_keys(
     limit: u64,
 ) -> Result<Value, String> {
     let store = network.read().await.overlay.store.clone();
-    let response = match store.read().paginate(&offset, &limit)
+    let response = match store.read().paginate::<BeaconContentKey>(&offset, &limit)
         {
             Ok(val) => Ok(json!(val)),
             Err(err) => Err(format!(
@@ -280,8 +307,21 @@ async fn gossip(
     let data = content_value.encode();
     let content_values = vec![(content_key, data)];
     let overlay = network.read().await.overlay.clone();
-    let num_peers = overlay.propagate_gossip(content_values);
-    Ok(num_peers.into())
+    let num_stored = overlay.propagate_gossip(content_values).await;
+    Ok(num_stored.into())
+}
+
+/// Constructs a JSON call for the TraceGossip method.
+async fn trace_gossip(
+    network: Arc<RwLock<BeaconNetwork>>,
+    content_key: BeaconContentKey,
+    content_value: BeaconContentValue,
+) -> Result<Value, String> {
+    let data = content_value.encode();
+    let content_values = vec![(content_key, data)];
+    let overlay = network.read().await.overlay.clone();
+    let trace = overlay.trace_propagate_gossip(content_values).await;
+    Ok(json!(trace))
 }
 
 /// Constructs a JSON call for the Offer method.
@@ -314,6 +354,28 @@ async fn offer(
     }
 }
 
+/// Constructs a JSON call for the TraceOffer method.
+async fn trace_offer(
+    network: Arc<RwLock<BeaconNetwork>>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_key: BeaconContentKey,
+    content_value: Option<BeaconContentValue>,
+) -> Result<Value, String> {
+    let overlay = network.read().await.overlay.clone();
+    let trace = match content_value {
+        Some(content_value) => {
+            overlay
+                .trace_populated_offer(enr, content_key.into(), content_value.encode())
+                .await
+        }
+        None => {
+            let content_key: Vec<RawContentKey> = vec![content_key.as_ssz_bytes()];
+            overlay.trace_offer(content_key, enr).await
+        }
+    };
+    Ok(json!(trace))
+}
+
 /// Constructs a JSON call for the Ping method.
 async fn ping(
     network: Arc<RwLock<BeaconNetwork>>,
@@ -338,3 +400,13 @@ async fn recursive_find_nodes(
     let nodes = overlay.lookup_node(node_id).await;
     Ok(json!(nodes))
 }
+
+/// Constructs a JSON call for the TraceRecursiveFindNodes method.
+async fn trace_recursive_find_nodes(
+    network: Arc<RwLock<BeaconNetwork>>,
+    node_id: NodeId,
+) -> Result<Value, String> {
+    let overlay = network.read().await.overlay.clone();
+    let (nodes, trace) = overlay.trace_lookup_node(node_id).await;
+    Ok(json!(TraceNodesInfo { nodes, trace }))
+}
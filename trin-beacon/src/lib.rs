@@ -3,79 +3,105 @@
 pub mod events;
 mod jsonrpc;
 pub mod network;
+pub mod sync;
 pub mod validation;
 
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use discv5::TalkRequest;
 use tokio::{
-    sync::{mpsc, Mutex, RwLock},
+    sync::{broadcast, mpsc, Mutex, RwLock},
     task::JoinHandle,
     time::{interval, Duration},
 };
 use tracing::info;
-use utp_rs::socket::UtpSocket;
 
 use crate::network::BeaconNetwork;
+use crate::sync::{spawn_light_client, HEAD_STREAM_CHANNEL_SIZE};
 use crate::{events::BeaconEvents, jsonrpc::BeaconRequestHandler};
+use ethportal_api::types::cli::BEACON_NETWORK;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::types::jsonrpc::request::BeaconJsonRpcRequest;
+use ethportal_api::BeaconNetworkApiServer;
 use portalnet::{
     config::PortalnetConfig,
-    discovery::{Discovery, UtpEnr},
-    storage::PortalStorageConfig,
+    shutdown::ShutdownSignal,
+    subnetwork::{PortalSubnetwork, SubnetworkContext, SubnetworkHandle},
+    types::messages::ProtocolId,
 };
-use trin_validation::oracle::HeaderOracle;
+use rpc::BeaconNetworkApi;
 
-type BeaconHandler = Option<BeaconRequestHandler>;
-type BeaconNetworkTask = Option<JoinHandle<()>>;
-type BeaconEventTx = Option<mpsc::UnboundedSender<TalkRequest>>;
-type BeaconJsonRpcTx = Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>;
+/// The beacon chain light client subnetwork.
+pub struct BeaconSubnetwork;
 
-pub async fn initialize_beacon_network(
-    discovery: &Arc<Discovery>,
-    utp_socket: Arc<UtpSocket<UtpEnr>>,
+#[async_trait]
+impl PortalSubnetwork for BeaconSubnetwork {
+    fn name(&self) -> &'static str {
+        BEACON_NETWORK
+    }
 
-    portalnet_config: PortalnetConfig,
-    storage_config: PortalStorageConfig,
-    header_oracle: Arc<RwLock<HeaderOracle>>,
-) -> anyhow::Result<(
-    BeaconHandler,
-    BeaconNetworkTask,
-    BeaconEventTx,
-    BeaconJsonRpcTx,
-)> {
-    let (beacon_jsonrpc_tx, beacon_jsonrpc_rx) = mpsc::unbounded_channel::<BeaconJsonRpcRequest>();
-    header_oracle.write().await.beacon_jsonrpc_tx = Some(beacon_jsonrpc_tx.clone());
-    let (beacon_event_tx, beacon_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
-    let beacon_network = BeaconNetwork::new(
-        Arc::clone(discovery),
-        utp_socket,
-        storage_config,
-        portalnet_config.clone(),
-        header_oracle,
-    )
-    .await?;
-    let beacon_handler = BeaconRequestHandler {
-        network: Arc::new(RwLock::new(beacon_network.clone())),
-        rpc_rx: Arc::new(Mutex::new(beacon_jsonrpc_rx)),
-    };
-    let beacon_network = Arc::new(beacon_network);
-    let beacon_network_task =
-        spawn_beacon_network(beacon_network.clone(), portalnet_config, beacon_event_rx);
-    spawn_beacon_heartbeat(beacon_network);
-    Ok((
-        Some(beacon_handler),
-        Some(beacon_network_task),
-        Some(beacon_event_tx),
-        Some(beacon_jsonrpc_tx),
-    ))
+    fn protocol_id(&self) -> ProtocolId {
+        ProtocolId::Beacon
+    }
+
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle> {
+        let (beacon_jsonrpc_tx, beacon_jsonrpc_rx) =
+            mpsc::unbounded_channel::<BeaconJsonRpcRequest>();
+        context.header_oracle.write().await.beacon_jsonrpc_tx = Some(beacon_jsonrpc_tx.clone());
+        let (beacon_event_tx, beacon_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
+        let beacon_network = BeaconNetwork::new(
+            Arc::clone(&context.discovery),
+            Arc::clone(&context.utp_socket),
+            context.storage_config.clone(),
+            context.portalnet_config.clone(),
+            context.header_oracle.clone(),
+        )
+        .await?;
+        let beacon_handler = BeaconRequestHandler {
+            network: Arc::new(RwLock::new(beacon_network.clone())),
+            rpc_rx: Arc::new(Mutex::new(beacon_jsonrpc_rx)),
+        };
+        tokio::spawn(async move { beacon_handler.handle_client_queries().await });
+        let beacon_network = Arc::new(beacon_network);
+        let event_stream = beacon_network.overlay.event_stream_sender();
+        let admin = beacon_network.overlay.clone();
+        spawn_beacon_network(
+            beacon_network.clone(),
+            context.portalnet_config.clone(),
+            beacon_event_rx,
+            context.shutdown_signal.clone(),
+        );
+        let rpc_methods = BeaconNetworkApi::new(beacon_jsonrpc_tx.clone()).into_rpc();
+        let mut handle = SubnetworkHandle::new(self.name(), self.protocol_id(), beacon_event_tx)
+            .with_rpc_methods(rpc_methods)
+            .with_jsonrpc_tx(beacon_jsonrpc_tx)
+            .with_event_stream(event_stream)
+            .with_admin(admin);
+        // Following the head of the chain needs a trusted block root to bootstrap from
+        match context.portalnet_config.trusted_block_root {
+            Some(trusted_block_root) => {
+                let (head_tx, _) = broadcast::channel(HEAD_STREAM_CHANNEL_SIZE);
+                spawn_light_client(
+                    beacon_network.clone(),
+                    trusted_block_root,
+                    context.header_oracle.clone(),
+                    head_tx.clone(),
+                );
+                handle = handle.with_head_stream(head_tx);
+            }
+            None => info!("No trusted block root set, the beacon light client is not started"),
+        }
+        spawn_beacon_heartbeat(beacon_network);
+        Ok(handle)
+    }
 }
 
 pub fn spawn_beacon_network(
     network: Arc<BeaconNetwork>,
     portalnet_config: PortalnetConfig,
     beacon_event_rx: mpsc::UnboundedReceiver<TalkRequest>,
+    shutdown_signal: ShutdownSignal,
 ) -> JoinHandle<()> {
     let bootnode_enrs: Vec<Enr> = portalnet_config.bootnodes.into();
     info!(
@@ -95,9 +121,7 @@ pub fn spawn_beacon_network(
         // hacky test: make sure we establish a session with the boot node
         network.overlay.ping_bootnodes().await;
 
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        shutdown_signal.wait().await;
     })
 }
 
@@ -5,6 +5,7 @@ use tokio::sync::RwLock;
 use utp_rs::socket::UtpSocket;
 
 use crate::validation::BeaconValidator;
+use ethportal_api::types::cli::BEACON_NETWORK;
 use ethportal_api::types::distance::XorMetric;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::BeaconContentKey;
@@ -31,11 +32,14 @@ impl BeaconNetwork {
         portal_config: PortalnetConfig,
         header_oracle: Arc<RwLock<HeaderOracle>>,
     ) -> anyhow::Result<Self> {
+        let overlay_tuning = portal_config.overlay_tuning(BEACON_NETWORK);
         let bootnode_enrs: Vec<Enr> = portal_config.bootnodes.into();
         let config = OverlayConfig {
             bootnode_enrs,
+            routing_table_dir: Some(storage_config.node_data_dir.clone()),
             ..Default::default()
-        };
+        }
+        .with_tuning(&overlay_tuning);
         let storage = Arc::new(PLRwLock::new(PortalStorage::new(
             storage_config,
             ProtocolId::Beacon,
@@ -51,8 +55,6 @@ impl BeaconNetwork {
         )
         .await;
 
-        let _ = overlay.event_stream().await?;
-
         Ok(Self {
             overlay: Arc::new(overlay),
         })
@@ -0,0 +1,251 @@
+use std::cmp;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use ethereum_types::H256;
+use eyre::eyre;
+use futures::future::join_all;
+use ssz::Decode;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+use crate::network::BeaconNetwork;
+use ethportal_api::types::consensus::light_client::{
+    bootstrap::LightClientBootstrap, finality_update::LightClientFinalityUpdate,
+    optimistic_update::LightClientOptimisticUpdate, update::LightClientUpdate,
+};
+use ethportal_api::types::content_key::beacon::{
+    LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey,
+};
+use ethportal_api::types::content_value::beacon::{
+    ForkVersionedLightClientBootstrap, ForkVersionedLightClientFinalityUpdate,
+    ForkVersionedLightClientOptimisticUpdate, LightClientUpdatesByRange,
+};
+use ethportal_api::utils::bytes::hex_encode;
+use ethportal_api::{
+    BeaconContentKey, Header, LightClientBootstrapKey, LightClientUpdatesByRangeKey,
+};
+use light_client::config::client_config::Config;
+use light_client::config::networks;
+use light_client::consensus::rpc::ConsensusRpc;
+use light_client::consensus::ConsensusLightClient;
+use portalnet::storage::ContentStore;
+use trin_validation::oracle::HeaderOracle;
+
+const SECONDS_PER_SLOT: u64 = 12;
+const SLOTS_PER_SYNC_COMMITTEE_PERIOD: u64 = 32 * 256;
+
+/// Finality and optimistic updates are keyed by their signature slot. The updates for this many
+/// slots before the current one are looked up too, in case the latest one has not been gossiped
+/// yet.
+const UPDATE_LOOKBACK_SLOTS: u64 = 2;
+
+/// How long to wait before retrying to bootstrap the light client.
+const SYNC_RETRY_INTERVAL: Duration = Duration::from_secs(SECONDS_PER_SLOT);
+
+/// Number of heads buffered for each `eth_subscribe("newHeads")` subscriber.
+pub const HEAD_STREAM_CHANNEL_SIZE: usize = 16;
+
+/// Serves the light client data of the beacon chain from the beacon network, instead of from the
+/// REST API of a beacon node.
+pub struct PortalConsensusRpc {
+    network: Arc<BeaconNetwork>,
+    genesis_time: u64,
+}
+
+impl PortalConsensusRpc {
+    pub fn new(network: Arc<BeaconNetwork>, genesis_time: u64) -> Self {
+        Self {
+            network,
+            genesis_time,
+        }
+    }
+
+    fn expected_current_slot(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.genesis_time) / SECONDS_PER_SLOT
+    }
+
+    /// Returns the content stored or cached locally, or else found on the network.
+    async fn find_content(&self, content_key: BeaconContentKey) -> eyre::Result<Vec<u8>> {
+        let overlay = &self.network.overlay;
+        let local_content = overlay.store.read().get(&content_key);
+        match local_content {
+            Ok(Some(content)) => return Ok(content),
+            Ok(None) => {}
+            Err(err) => {
+                warn!(error = %err, content.key = %content_key, "Error reading local content")
+            }
+        }
+        if let Some(content) = overlay.cached_content(&content_key) {
+            return Ok(content);
+        }
+        let (content, _, _) = overlay.lookup_content(content_key.clone(), false).await;
+        content.ok_or_else(|| eyre!("{content_key} was not found on the beacon network"))
+    }
+
+    /// Returns the latest update keyed by a recent signature slot.
+    async fn find_latest_update(
+        &self,
+        content_key: impl Fn(u64) -> BeaconContentKey,
+    ) -> eyre::Result<Vec<u8>> {
+        let current_slot = self.expected_current_slot();
+        let oldest_slot = current_slot.saturating_sub(UPDATE_LOOKBACK_SLOTS);
+        let lookups = (oldest_slot..=current_slot)
+            .rev()
+            .map(|slot| self.find_content(content_key(slot)));
+        join_all(lookups)
+            .await
+            .into_iter()
+            .find_map(Result::ok)
+            .ok_or_else(|| {
+                eyre!("No update was found on the beacon network for slots {oldest_slot} to {current_slot}")
+            })
+    }
+}
+
+/// Light client data is decoded for the fork it was created in.
+#[async_trait]
+impl ConsensusRpc for PortalConsensusRpc {
+    async fn get_bootstrap(&self, block_root: &'_ [u8]) -> eyre::Result<LightClientBootstrap> {
+        let block_hash: [u8; 32] = block_root
+            .try_into()
+            .map_err(|_| eyre!("Invalid block root: {}", hex_encode(block_root)))?;
+        let content_key =
+            BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey { block_hash });
+        let content = self.find_content(content_key).await?;
+        let bootstrap = ForkVersionedLightClientBootstrap::from_ssz_bytes(&content)
+            .map_err(|err| eyre!("Invalid light client bootstrap: {err:?}"))?;
+        Ok(bootstrap.bootstrap)
+    }
+
+    /// Updates are gossiped one sync committee period at a time. The updates are returned up to
+    /// the first period that is not found.
+    async fn get_updates(&self, period: u64, count: u8) -> eyre::Result<Vec<LightClientUpdate>> {
+        let current_period = self.expected_current_slot() / SLOTS_PER_SYNC_COMMITTEE_PERIOD;
+        let end_period = cmp::min(period + u64::from(count), current_period + 1);
+        let mut updates = vec![];
+        for period in period..end_period {
+            let content_key =
+                BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                    start_period: period,
+                    count: 1,
+                });
+            let content = match self.find_content(content_key).await {
+                Ok(content) => content,
+                Err(err) => {
+                    debug!(error = %err, period, "Light client update not found");
+                    break;
+                }
+            };
+            let range = LightClientUpdatesByRange::from_ssz_bytes(&content)
+                .map_err(|err| eyre!("Invalid light client updates: {err:?}"))?;
+            updates.extend(range.iter().map(|update| update.update.clone()));
+        }
+        Ok(updates)
+    }
+
+    async fn get_finality_update(&self) -> eyre::Result<LightClientFinalityUpdate> {
+        let content = self
+            .find_latest_update(|slot| {
+                BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(slot))
+            })
+            .await?;
+        let update = ForkVersionedLightClientFinalityUpdate::from_ssz_bytes(&content)
+            .map_err(|err| eyre!("Invalid light client finality update: {err:?}"))?;
+        Ok(update.update)
+    }
+
+    async fn get_optimistic_update(&self) -> eyre::Result<LightClientOptimisticUpdate> {
+        let content = self
+            .find_latest_update(|slot| {
+                BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey::new(
+                    slot,
+                ))
+            })
+            .await?;
+        let update = ForkVersionedLightClientOptimisticUpdate::from_ssz_bytes(&content)
+            .map_err(|err| eyre!("Invalid light client optimistic update: {err:?}"))?;
+        Ok(update.update)
+    }
+
+    /// The beacon network only carries mainnet data.
+    async fn chain_id(&self) -> eyre::Result<u64> {
+        Ok(1)
+    }
+}
+
+/// Follows the head of the beacon chain with a light client that is bootstrapped from
+/// `trusted_block_root` and fed from the beacon network.
+///
+/// The execution header of each new head verified by the light client is sent to `head_tx`, and
+/// the finalized execution block is set in the header oracle, as the anchor of the post-merge
+/// canonical chain.
+pub fn spawn_light_client(
+    network: Arc<BeaconNetwork>,
+    trusted_block_root: H256,
+    header_oracle: Arc<RwLock<HeaderOracle>>,
+    head_tx: broadcast::Sender<Header>,
+) {
+    tokio::spawn(async move {
+        let base_config = networks::mainnet();
+        let config = Config {
+            chain: base_config.chain,
+            forks: base_config.forks,
+            max_checkpoint_age: base_config.max_checkpoint_age,
+            ..Default::default()
+        };
+        let rpc = PortalConsensusRpc::new(network, config.chain.genesis_time);
+        let mut light_client =
+            match ConsensusLightClient::new(rpc, trusted_block_root.as_bytes(), Arc::new(config)) {
+                Ok(light_client) => light_client,
+                Err(err) => {
+                    warn!(error = %err, "Unable to create the beacon light client");
+                    return;
+                }
+            };
+
+        while let Err(err) = light_client.sync().await {
+            warn!(error = %err, "Beacon light client failed to sync, retrying");
+            sleep(SYNC_RETRY_INTERVAL).await;
+        }
+        info!(trusted_block_root = %trusted_block_root, "Beacon light client synced");
+
+        let mut latest_block_number = 0;
+        loop {
+            let finalized = light_client
+                .get_finalized_execution_header()
+                .map(|execution| (execution.block_number, execution.block_hash));
+            if let Some((block_number, block_hash)) = finalized {
+                header_oracle
+                    .write()
+                    .await
+                    .set_finalized_execution_block(block_number, block_hash);
+            }
+
+            let head = light_client
+                .get_execution_header()
+                .filter(|execution| execution.block_number > latest_block_number)
+                .map(Header::from);
+            if let Some(head) = head {
+                latest_block_number = head.number;
+                // There may be no subscribers
+                let _ = head_tx.send(head);
+            }
+
+            let next_update = light_client
+                .duration_until_next_update()
+                .to_std()
+                .unwrap_or(SYNC_RETRY_INTERVAL);
+            sleep(next_update).await;
+            if let Err(err) = light_client.advance().await {
+                debug!(error = %err, "Beacon light client did not advance");
+            }
+        }
+    });
+}
@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use ethportal_api::BeaconContentKey;
 use tokio::sync::RwLock;
 
-use trin_validation::{oracle::HeaderOracle, validator::Validator};
+use trin_validation::{
+    oracle::HeaderOracle,
+    validator::{ValidationError, Validator},
+};
 
 pub struct BeaconValidator {
     // TODO: HeaderOracle is not network agnostic name
@@ -17,7 +20,7 @@ impl Validator<BeaconContentKey> for BeaconValidator {
         &self,
         _content_key: &BeaconContentKey,
         _content: &[u8],
-    ) -> anyhow::Result<()>
+    ) -> Result<(), ValidationError>
     where
         BeaconContentKey: 'async_trait,
     {
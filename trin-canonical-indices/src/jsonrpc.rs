@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use ethportal_api::types::constants::CONTENT_ABSENT;
+use ethportal_api::types::jsonrpc::endpoints::CanonicalIndicesEndpoint;
+use ethportal_api::types::jsonrpc::request::CanonicalIndicesJsonRpcRequest;
+use ethportal_api::utils::bytes::hex_encode;
+use ethportal_api::{CanonicalIndicesContentKey, CanonicalIndicesContentValue, ContentValue};
+use portalnet::storage::ContentStore;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tracing::error;
+
+use crate::network::CanonicalIndicesNetwork;
+
+/// Handles Canonical indices network JSON-RPC requests
+pub struct CanonicalIndicesRequestHandler {
+    pub network: Arc<CanonicalIndicesNetwork>,
+    pub rpc_rx: mpsc::UnboundedReceiver<CanonicalIndicesJsonRpcRequest>,
+}
+
+impl CanonicalIndicesRequestHandler {
+    /// Complete RPC requests for the Canonical indices network.
+    pub async fn handle_client_queries(mut self) {
+        while let Some(request) = self.rpc_rx.recv().await {
+            let network = Arc::clone(&self.network);
+            tokio::spawn(async move { complete_request(network, request).await });
+        }
+    }
+}
+
+/// Generates a response for a given request and sends it to the receiver.
+async fn complete_request(
+    network: Arc<CanonicalIndicesNetwork>,
+    request: CanonicalIndicesJsonRpcRequest,
+) {
+    let response: Result<Value, String> = match request.endpoint {
+        CanonicalIndicesEndpoint::DataRadius => {
+            let radius = network.overlay.data_radius();
+            Ok(json!(*radius))
+        }
+        CanonicalIndicesEndpoint::LocalContent(content_key) => {
+            local_content(network, content_key).await
+        }
+        CanonicalIndicesEndpoint::Gossip(content_key, content_value) => {
+            gossip(network, content_key, content_value).await
+        }
+        CanonicalIndicesEndpoint::RecursiveFindContent(content_key) => {
+            recursive_find_content(network, content_key).await
+        }
+        CanonicalIndicesEndpoint::Store(content_key, content_value) => {
+            store(network, content_key, content_value).await
+        }
+    };
+    let _ = request.resp.send(response);
+}
+
+/// Constructs a JSON call for the RecursiveFindContent method.
+async fn recursive_find_content(
+    network: Arc<CanonicalIndicesNetwork>,
+    content_key: CanonicalIndicesContentKey,
+) -> Result<Value, String> {
+    // Check whether we have the data locally, either stored or recently found.
+    let stored_content = network.overlay.store.read().get(&content_key);
+    let local_content: Option<Vec<u8>> = match stored_content {
+        Ok(Some(data)) => Some(data),
+        Ok(None) => network.overlay.cached_content(&content_key),
+        Err(err) => {
+            error!(
+                error = %err,
+                content.key = %content_key,
+                "Error checking data store for content",
+            );
+            None
+        }
+    };
+    let possible_content_bytes = match local_content {
+        Some(val) => Some(val),
+        None => network.overlay.lookup_content(content_key, false).await.0,
+    };
+
+    match possible_content_bytes {
+        Some(bytes) => Ok(Value::String(hex_encode(bytes))),
+        None => Ok(Value::String(CONTENT_ABSENT.to_string())),
+    }
+}
+
+/// Constructs a JSON call for the LocalContent method.
+async fn local_content(
+    network: Arc<CanonicalIndicesNetwork>,
+    content_key: CanonicalIndicesContentKey,
+) -> Result<Value, String> {
+    match network.overlay.store.read().get(&content_key) {
+        Ok(Some(val)) => Ok(Value::String(hex_encode(val))),
+        Ok(None) => Ok(Value::String(CONTENT_ABSENT.to_string())),
+        Err(err) => Err(format!(
+            "Database error while looking for content key in local storage: {content_key:?}, with error: {err}",
+        )),
+    }
+}
+
+/// Constructs a JSON call for the Gossip method.
+async fn gossip(
+    network: Arc<CanonicalIndicesNetwork>,
+    content_key: CanonicalIndicesContentKey,
+    content_value: CanonicalIndicesContentValue,
+) -> Result<Value, String> {
+    let data = content_value.encode();
+    let content_values = vec![(content_key, data)];
+    let num_stored = network.overlay.propagate_gossip(content_values).await;
+    Ok(num_stored.into())
+}
+
+/// Constructs a JSON call for the Store method.
+async fn store(
+    network: Arc<CanonicalIndicesNetwork>,
+    content_key: CanonicalIndicesContentKey,
+    content_value: CanonicalIndicesContentValue,
+) -> Result<Value, String> {
+    let data = content_value.encode();
+    match network
+        .overlay
+        .store
+        .write()
+        .put::<CanonicalIndicesContentKey, Vec<u8>>(content_key, data)
+    {
+        Ok(_) => Ok(Value::Bool(true)),
+        Err(err) => Ok(Value::String(err.to_string())),
+    }
+}
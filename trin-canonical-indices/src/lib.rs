@@ -0,0 +1,105 @@
+#![warn(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use discv5::TalkRequest;
+use network::CanonicalIndicesNetwork;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::info;
+
+use crate::{events::CanonicalIndicesEvents, jsonrpc::CanonicalIndicesRequestHandler};
+use ethportal_api::types::cli::CANONICAL_INDICES_NETWORK;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::jsonrpc::request::CanonicalIndicesJsonRpcRequest;
+use ethportal_api::CanonicalIndicesNetworkApiServer;
+use portalnet::{
+    config::PortalnetConfig,
+    shutdown::ShutdownSignal,
+    subnetwork::{PortalSubnetwork, SubnetworkContext, SubnetworkHandle},
+    types::messages::ProtocolId,
+};
+use rpc::CanonicalIndicesNetworkApi;
+
+pub mod events;
+mod jsonrpc;
+pub mod network;
+pub mod validation;
+
+/// The canonical indices subnetwork.
+pub struct CanonicalIndicesSubnetwork;
+
+#[async_trait]
+impl PortalSubnetwork for CanonicalIndicesSubnetwork {
+    fn name(&self) -> &'static str {
+        CANONICAL_INDICES_NETWORK
+    }
+
+    fn protocol_id(&self) -> ProtocolId {
+        ProtocolId::CanonicalIndices
+    }
+
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle> {
+        let (canonical_indices_jsonrpc_tx, canonical_indices_jsonrpc_rx) =
+            mpsc::unbounded_channel::<CanonicalIndicesJsonRpcRequest>();
+        let (canonical_indices_event_tx, canonical_indices_event_rx) =
+            mpsc::unbounded_channel::<TalkRequest>();
+        let canonical_indices_network = CanonicalIndicesNetwork::new(
+            Arc::clone(&context.discovery),
+            Arc::clone(&context.utp_socket),
+            context.storage_config.clone(),
+            context.portalnet_config.clone(),
+            context.header_oracle.clone(),
+        )
+        .await?;
+        let canonical_indices_network = Arc::new(canonical_indices_network);
+        let canonical_indices_handler = CanonicalIndicesRequestHandler {
+            network: Arc::clone(&canonical_indices_network),
+            rpc_rx: canonical_indices_jsonrpc_rx,
+        };
+        tokio::spawn(canonical_indices_handler.handle_client_queries());
+        let event_stream = canonical_indices_network.overlay.event_stream_sender();
+        let admin = canonical_indices_network.overlay.clone();
+        spawn_canonical_indices_network(
+            canonical_indices_network,
+            context.portalnet_config.clone(),
+            canonical_indices_event_rx,
+            context.shutdown_signal.clone(),
+        );
+        let rpc_methods = CanonicalIndicesNetworkApi::new(canonical_indices_jsonrpc_tx).into_rpc();
+        Ok(
+            SubnetworkHandle::new(self.name(), self.protocol_id(), canonical_indices_event_tx)
+                .with_rpc_methods(rpc_methods)
+                .with_event_stream(event_stream)
+                .with_admin(admin),
+        )
+    }
+}
+
+pub fn spawn_canonical_indices_network(
+    network: Arc<CanonicalIndicesNetwork>,
+    portalnet_config: PortalnetConfig,
+    canonical_indices_event_rx: mpsc::UnboundedReceiver<TalkRequest>,
+    shutdown_signal: ShutdownSignal,
+) -> JoinHandle<()> {
+    let bootnode_enrs: Vec<Enr> = portalnet_config.bootnodes.into();
+    info!(
+        "About to spawn Canonical Indices Network with {} boot nodes.",
+        bootnode_enrs.len()
+    );
+
+    tokio::spawn(async move {
+        let canonical_indices_events = CanonicalIndicesEvents {
+            network: Arc::clone(&network),
+            event_rx: canonical_indices_event_rx,
+        };
+
+        // Spawn canonical indices event handler
+        tokio::spawn(canonical_indices_events.start());
+
+        // hacky test: make sure we establish a session with the boot node
+        network.overlay.ping_bootnodes().await;
+
+        shutdown_signal.wait().await;
+    })
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock as PLRwLock;
+use tokio::sync::RwLock;
+use utp_rs::socket::UtpSocket;
+
+use ethportal_api::types::cli::CANONICAL_INDICES_NETWORK;
+use ethportal_api::types::distance::XorMetric;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::CanonicalIndicesContentKey;
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, UtpEnr},
+    overlay::{OverlayConfig, OverlayProtocol},
+    storage::{PortalStorage, PortalStorageConfig},
+    types::messages::ProtocolId,
+};
+use trin_validation::oracle::HeaderOracle;
+
+use crate::validation::CanonicalIndicesValidator;
+
+/// Canonical indices network layer on top of the overlay protocol. Encapsulates canonical
+/// indices network specific data and logic.
+#[derive(Clone)]
+pub struct CanonicalIndicesNetwork {
+    pub overlay: Arc<
+        OverlayProtocol<
+            CanonicalIndicesContentKey,
+            XorMetric,
+            CanonicalIndicesValidator,
+            PortalStorage,
+        >,
+    >,
+}
+
+impl CanonicalIndicesNetwork {
+    pub async fn new(
+        discovery: Arc<Discovery>,
+        utp_socket: Arc<UtpSocket<UtpEnr>>,
+        storage_config: PortalStorageConfig,
+        portal_config: PortalnetConfig,
+        header_oracle: Arc<RwLock<HeaderOracle>>,
+    ) -> anyhow::Result<Self> {
+        let overlay_tuning = portal_config.overlay_tuning(CANONICAL_INDICES_NETWORK);
+        let bootnode_enrs: Vec<Enr> = portal_config.bootnodes.into();
+        let config = OverlayConfig {
+            bootnode_enrs,
+            routing_table_dir: Some(storage_config.node_data_dir.clone()),
+            ..Default::default()
+        }
+        .with_tuning(&overlay_tuning);
+        let storage = Arc::new(PLRwLock::new(PortalStorage::new(
+            storage_config,
+            ProtocolId::CanonicalIndices,
+        )?));
+        let validator = Arc::new(CanonicalIndicesValidator { header_oracle });
+        let overlay = OverlayProtocol::new(
+            config,
+            discovery,
+            utp_socket,
+            storage,
+            ProtocolId::CanonicalIndices,
+            validator,
+        )
+        .await;
+
+        Ok(Self {
+            overlay: Arc::new(overlay),
+        })
+    }
+}
@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethereum_types::H256;
+use ssz::Decode;
+use tokio::sync::RwLock;
+
+use ethportal_api::{
+    utils::bytes::hex_encode, BlockHashIndex, CanonicalIndicesContentKey, TransactionIndex,
+};
+use trin_validation::{
+    oracle::HeaderOracle,
+    validator::{ValidationError, Validator},
+};
+
+pub struct CanonicalIndicesValidator {
+    pub header_oracle: Arc<RwLock<HeaderOracle>>,
+}
+
+#[async_trait]
+impl Validator<CanonicalIndicesContentKey> for CanonicalIndicesValidator {
+    async fn validate_content(
+        &self,
+        content_key: &CanonicalIndicesContentKey,
+        content: &[u8],
+    ) -> Result<(), ValidationError>
+    where
+        CanonicalIndicesContentKey: 'async_trait,
+    {
+        match content_key {
+            CanonicalIndicesContentKey::BlockNumber(key) => {
+                let index = BlockHashIndex::from_ssz_bytes(content).map_err(|err| {
+                    anyhow!("Block hash index content has invalid encoding: {err:?}")
+                })?;
+                let block_hash = H256::from(index.block_hash);
+                let canonical_hash = self
+                    .header_oracle
+                    .read()
+                    .await
+                    .get_canonical_hash_at_height(key.block_number)
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?;
+                if canonical_hash != block_hash {
+                    return Err(anyhow!(
+                        "Content validation failed: Invalid block hash. Found: {block_hash:?} - Expected: {canonical_hash:?}"
+                    ).into());
+                }
+                Ok(())
+            }
+            CanonicalIndicesContentKey::TransactionHash(key) => {
+                let index = TransactionIndex::from_ssz_bytes(content).map_err(|err| {
+                    anyhow!("Transaction index content has invalid encoding: {err:?}")
+                })?;
+                let block_hash = H256::from(index.block_hash);
+                let header_oracle = self.header_oracle.read().await;
+                let header = header_oracle
+                    .recursive_find_header_with_proof(block_hash)
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?
+                    .header;
+                // Post-merge headers are served without a proof, so the block may be an uncle or
+                // part of a fork.
+                let canonical_hash = header_oracle
+                    .get_canonical_hash_at_height(header.number)
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?;
+                if canonical_hash != block_hash {
+                    return Err(anyhow!(
+                        "Content validation failed: Block {block_hash:?} is not canonical. Expected: {canonical_hash:?}"
+                    )
+                    .into());
+                }
+                let block_body = header_oracle
+                    .recursive_find_block_body(block_hash)
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?;
+                block_body.validate_against_header(&header)?;
+                let transactions = block_body.transactions()?;
+                let tx = transactions.get(index.index as usize).ok_or_else(|| {
+                    anyhow!(
+                        "Content validation failed: Block {block_hash:?} has {} transactions, index {} is out of range",
+                        transactions.len(),
+                        index.index
+                    )
+                })?;
+                let tx_hash = tx.hash();
+                if tx_hash != H256::from(key.tx_hash) {
+                    return Err(anyhow!(
+                        "Content validation failed: Invalid transaction hash. Found: {tx_hash:?} - Expected: {:?}",
+                        hex_encode(key.tx_hash)
+                    ).into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    use ethereum_types::{Bloom, H160, U256};
+    use ssz::Encode;
+    use tokio::sync::mpsc;
+
+    use ethportal_api::types::execution::header::{
+        BlockHeaderProof, Header, HeaderWithProof, SszNone,
+    };
+    use ethportal_api::types::jsonrpc::endpoints::HistoryEndpoint;
+    use ethportal_api::types::jsonrpc::request::HistoryJsonRpcRequest;
+    use ethportal_api::types::portal::ContentInfo;
+    use ethportal_api::{
+        BlockNumberKey, HistoryContentKey, HistoryContentValue, PossibleHistoryContentValue,
+        TransactionHashKey,
+    };
+    use trin_validation::{accumulator::MasterAccumulator, constants::MERGE_BLOCK_NUMBER};
+
+    const FINALIZED_BLOCK_NUMBER: u64 = MERGE_BLOCK_NUMBER + 11;
+
+    #[test_log::test(tokio::test)]
+    async fn validate_post_merge_block_number() {
+        let headers = post_merge_headers();
+        let validator = CanonicalIndicesValidator {
+            header_oracle: post_merge_header_oracle(&headers),
+        };
+        let header = &headers[3];
+        let content_key = CanonicalIndicesContentKey::BlockNumber(BlockNumberKey {
+            block_number: header.number,
+        });
+        let content = BlockHashIndex {
+            block_hash: header.hash().0,
+        };
+
+        validator
+            .validate_content(&content_key, &content.as_ssz_bytes())
+            .await
+            .unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn invalidate_post_merge_block_number_with_wrong_hash() {
+        let headers = post_merge_headers();
+        let validator = CanonicalIndicesValidator {
+            header_oracle: post_merge_header_oracle(&headers),
+        };
+        // A header at the right height, that is not part of the canonical chain.
+        let mut header = headers[3].clone();
+        header.extra_data = b"fork".to_vec();
+        let content_key = CanonicalIndicesContentKey::BlockNumber(BlockNumberKey {
+            block_number: header.number,
+        });
+        let content = BlockHashIndex {
+            block_hash: header.hash().0,
+        };
+
+        let err = validator
+            .validate_content(&content_key, &content.as_ssz_bytes())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid block hash"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn invalidate_transaction_index_into_fork_block() {
+        let mut headers = post_merge_headers();
+        // A block at the right height, that is not part of the canonical chain.
+        let mut fork_header = headers[3].clone();
+        fork_header.extra_data = b"fork".to_vec();
+        let fork_hash = fork_header.hash();
+        headers.insert(0, fork_header);
+        let validator = CanonicalIndicesValidator {
+            header_oracle: post_merge_header_oracle(&headers),
+        };
+        let content_key =
+            CanonicalIndicesContentKey::TransactionHash(TransactionHashKey { tx_hash: [1; 32] });
+        let content = TransactionIndex {
+            block_hash: fork_hash.0,
+            index: 0,
+        };
+
+        let err = validator
+            .validate_content(&content_key, &content.as_ssz_bytes())
+            .await
+            .unwrap_err();
+        assert!(err.is_invalid());
+        assert!(err.to_string().contains("is not canonical"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn invalidate_block_number_with_invalid_encoding() {
+        let validator = CanonicalIndicesValidator {
+            header_oracle: default_header_oracle(),
+        };
+        let content_key = CanonicalIndicesContentKey::BlockNumber(BlockNumberKey {
+            block_number: 1_000_001,
+        });
+
+        let err = validator
+            .validate_content(&content_key, &[0u8; 40])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid encoding"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn invalidate_transaction_index_with_invalid_encoding() {
+        let validator = CanonicalIndicesValidator {
+            header_oracle: default_header_oracle(),
+        };
+        let content_key =
+            CanonicalIndicesContentKey::TransactionHash(TransactionHashKey { tx_hash: [1; 32] });
+
+        let err = validator
+            .validate_content(&content_key, &[0u8; 32])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid encoding"));
+    }
+
+    fn default_header_oracle() -> Arc<RwLock<HeaderOracle>> {
+        Arc::new(RwLock::new(HeaderOracle::new(MasterAccumulator::default())))
+    }
+
+    /// Returns a chain of post-merge headers, from the first block after the merge up to the
+    /// finalized block.
+    fn post_merge_headers() -> Vec<Header> {
+        let mut headers: Vec<Header> = vec![];
+        for number in (MERGE_BLOCK_NUMBER + 1)..=FINALIZED_BLOCK_NUMBER {
+            let parent_hash = headers.last().map(Header::hash).unwrap_or_default();
+            headers.push(Header {
+                parent_hash,
+                uncles_hash: H256::zero(),
+                author: H160::zero(),
+                state_root: H256::zero(),
+                transactions_root: H256::zero(),
+                receipts_root: H256::zero(),
+                logs_bloom: Bloom::zero(),
+                difficulty: U256::zero(),
+                number,
+                gas_limit: U256::from(30_000_000),
+                gas_used: U256::zero(),
+                timestamp: number,
+                extra_data: vec![],
+                mix_hash: Some(H256::zero()),
+                nonce: Some(Default::default()),
+                base_fee_per_gas: Some(U256::from(7)),
+                withdrawals_root: None,
+            });
+        }
+        headers
+    }
+
+    /// Returns a header oracle anchored at the last of `headers`, which serves `headers` in place
+    /// of the history network.
+    fn post_merge_header_oracle(headers: &[Header]) -> Arc<RwLock<HeaderOracle>> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        let headers = headers.to_vec();
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                let block_hash = match request.endpoint {
+                    HistoryEndpoint::RecursiveFindContent(
+                        HistoryContentKey::BlockHeaderWithProof(key),
+                    ) => H256::from(key.block_hash),
+                    _ => panic!("Unexpected request: {:?}", request.endpoint),
+                };
+                let response = headers
+                    .iter()
+                    .find(|header| header.hash() == block_hash)
+                    .map(|header| {
+                        let content = HistoryContentValue::BlockHeaderWithProof(HeaderWithProof {
+                            header: header.clone(),
+                            proof: BlockHeaderProof::None(SszNone::default()),
+                        });
+                        serde_json::to_value(ContentInfo::Content {
+                            content: PossibleHistoryContentValue::ContentPresent(content),
+                            utp_transfer: false,
+                        })
+                        .unwrap()
+                    })
+                    .ok_or_else(|| "Content not found".to_string());
+                let _ = request.resp.send(response);
+            }
+        });
+
+        let mut header_oracle = HeaderOracle::new(MasterAccumulator::default());
+        header_oracle.history_jsonrpc_tx = Some(tx);
+        let finalized = headers.last().unwrap();
+        header_oracle.set_finalized_execution_block(finalized.number, finalized.hash());
+        Arc::new(RwLock::new(header_oracle))
+    }
+}
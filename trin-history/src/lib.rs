@@ -8,6 +8,7 @@ pub mod validation;
 
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use discv5::TalkRequest;
 use network::HistoryNetwork;
 use tokio::{
@@ -16,68 +17,77 @@ use tokio::{
     time::{interval, Duration},
 };
 use tracing::info;
-use utp_rs::socket::UtpSocket;
 
 use crate::{events::HistoryEvents, jsonrpc::HistoryRequestHandler};
+use ethportal_api::types::cli::HISTORY_NETWORK;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::types::jsonrpc::request::HistoryJsonRpcRequest;
+use ethportal_api::HistoryNetworkApiServer;
 use portalnet::{
     config::PortalnetConfig,
-    discovery::{Discovery, UtpEnr},
-    storage::PortalStorageConfig,
+    shutdown::ShutdownSignal,
+    subnetwork::{PortalSubnetwork, SubnetworkContext, SubnetworkHandle},
+    types::messages::ProtocolId,
 };
-use trin_validation::oracle::HeaderOracle;
+use rpc::HistoryNetworkApi;
 
-type HistoryHandler = Option<HistoryRequestHandler>;
-type HistoryNetworkTask = Option<JoinHandle<()>>;
-type HistoryEventTx = Option<mpsc::UnboundedSender<TalkRequest>>;
-type HistoryJsonRpcTx = Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>;
+/// The chain history subnetwork.
+pub struct HistorySubnetwork;
 
-pub async fn initialize_history_network(
-    discovery: &Arc<Discovery>,
-    utp_socket: Arc<UtpSocket<UtpEnr>>,
+#[async_trait]
+impl PortalSubnetwork for HistorySubnetwork {
+    fn name(&self) -> &'static str {
+        HISTORY_NETWORK
+    }
 
-    portalnet_config: PortalnetConfig,
-    storage_config: PortalStorageConfig,
-    header_oracle: Arc<RwLock<HeaderOracle>>,
-) -> anyhow::Result<(
-    HistoryHandler,
-    HistoryNetworkTask,
-    HistoryEventTx,
-    HistoryJsonRpcTx,
-)> {
-    let (history_jsonrpc_tx, history_jsonrpc_rx) =
-        mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
-    header_oracle.write().await.history_jsonrpc_tx = Some(history_jsonrpc_tx.clone());
-    let (history_event_tx, history_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
-    let history_network = HistoryNetwork::new(
-        Arc::clone(discovery),
-        utp_socket,
-        storage_config,
-        portalnet_config.clone(),
-        header_oracle,
-    )
-    .await?;
-    let history_handler = HistoryRequestHandler {
-        network: Arc::new(RwLock::new(history_network.clone())),
-        history_rx: Arc::new(Mutex::new(history_jsonrpc_rx)),
-    };
-    let history_network = Arc::new(history_network);
-    let history_network_task =
-        spawn_history_network(history_network.clone(), portalnet_config, history_event_rx);
-    spawn_history_heartbeat(history_network);
-    Ok((
-        Some(history_handler),
-        Some(history_network_task),
-        Some(history_event_tx),
-        Some(history_jsonrpc_tx),
-    ))
+    fn protocol_id(&self) -> ProtocolId {
+        ProtocolId::History
+    }
+
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle> {
+        let (history_jsonrpc_tx, history_jsonrpc_rx) =
+            mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        context.header_oracle.write().await.history_jsonrpc_tx = Some(history_jsonrpc_tx.clone());
+        let (history_event_tx, history_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
+        let history_network = HistoryNetwork::new(
+            Arc::clone(&context.discovery),
+            Arc::clone(&context.utp_socket),
+            context.storage_config.clone(),
+            context.portalnet_config.clone(),
+            context.header_oracle.clone(),
+        )
+        .await?;
+        let history_handler = HistoryRequestHandler {
+            network: Arc::new(RwLock::new(history_network.clone())),
+            history_rx: Arc::new(Mutex::new(history_jsonrpc_rx)),
+        };
+        tokio::spawn(async move { history_handler.handle_client_queries().await });
+        let history_network = Arc::new(history_network);
+        let event_stream = history_network.overlay.event_stream_sender();
+        let admin = history_network.overlay.clone();
+        spawn_history_network(
+            history_network.clone(),
+            context.portalnet_config.clone(),
+            history_event_rx,
+            context.shutdown_signal.clone(),
+        );
+        spawn_history_heartbeat(history_network);
+        let rpc_methods = HistoryNetworkApi::new(history_jsonrpc_tx.clone()).into_rpc();
+        Ok(
+            SubnetworkHandle::new(self.name(), self.protocol_id(), history_event_tx)
+                .with_rpc_methods(rpc_methods)
+                .with_jsonrpc_tx(history_jsonrpc_tx)
+                .with_event_stream(event_stream)
+                .with_admin(admin),
+        )
+    }
 }
 
 pub fn spawn_history_network(
     network: Arc<HistoryNetwork>,
     portalnet_config: PortalnetConfig,
     history_event_rx: mpsc::UnboundedReceiver<TalkRequest>,
+    shutdown_signal: ShutdownSignal,
 ) -> JoinHandle<()> {
     let bootnode_enrs: Vec<Enr> = portalnet_config.bootnodes.into();
     info!(
@@ -97,9 +107,7 @@ pub fn spawn_history_network(
         // hacky test: make sure we establish a session with the boot node
         network.overlay.ping_bootnodes().await;
 
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        shutdown_signal.wait().await;
     })
 }
 
@@ -4,6 +4,7 @@ use parking_lot::RwLock as PLRwLock;
 use tokio::sync::RwLock;
 use utp_rs::socket::UtpSocket;
 
+use ethportal_api::types::cli::HISTORY_NETWORK;
 use ethportal_api::types::distance::XorMetric;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::HistoryContentKey;
@@ -33,11 +34,14 @@ impl HistoryNetwork {
         portal_config: PortalnetConfig,
         header_oracle: Arc<RwLock<HeaderOracle>>,
     ) -> anyhow::Result<Self> {
+        let overlay_tuning = portal_config.overlay_tuning(HISTORY_NETWORK);
         let bootnode_enrs: Vec<Enr> = portal_config.bootnodes.into();
         let config = OverlayConfig {
             bootnode_enrs,
+            routing_table_dir: Some(storage_config.node_data_dir.clone()),
             ..Default::default()
-        };
+        }
+        .with_tuning(&overlay_tuning);
         let storage = Arc::new(PLRwLock::new(PortalStorage::new(
             storage_config,
             ProtocolId::History,
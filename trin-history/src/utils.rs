@@ -10,7 +10,7 @@ use ethportal_api::types::enr::Enr;
 use ethportal_api::utils::bytes::hex_encode;
 
 type NodeMap = BTreeMap<String, String>;
-type NodeTuple = (NodeId, Enr, NodeStatus, Distance, Option<String>);
+type NodeTuple = (NodeId, Enr, NodeStatus, Distance, Option<String>, i32);
 
 /// Converts the output of the Overlay's bucket_entries method to a JSON Value
 pub fn bucket_entries_to_json(bucket_entries: BTreeMap<usize, Vec<NodeTuple>>) -> Value {
@@ -23,38 +23,42 @@ pub fn bucket_entries_to_json(bucket_entries: BTreeMap<usize, Vec<NodeTuple>>) -
                 bucket_index,
                 bucket
                     .iter()
-                    .map(|(node_id, enr, node_status, data_radius, client_info)| {
-                        node_count += 1;
-                        if node_status.state == ConnectionState::Connected {
-                            connected_count += 1
-                        }
-                        let mut map = BTreeMap::new();
-                        map.insert("node_id".to_owned(), hex_encode(node_id.raw()));
-                        map.insert("enr".to_owned(), enr.to_base64());
-                        map.insert("status".to_owned(), format!("{:?}", node_status.state));
-                        map.insert("radius".to_owned(), format!("{data_radius}"));
-                        if let Some(client_info) = client_info {
-                            // Expand client name if possible, otherwise leave as-is.
-                            match expand_client_name(client_info) {
-                                Some(expanded_name) => {
-                                    map.insert("client".to_owned(), expanded_name);
-                                }
-                                None => {
-                                    map.insert("client".to_owned(), client_info.to_string());
-                                }
-                            };
-                        } else {
-                            // Include address (IP:port) for convenience.
-                            // TODO: Can be removed once a portal dashboard does UI-side ENR decoding.
-                            let port = match enr.udp4_socket() {
-                                Some(port) => format!("{port}"),
-                                None => "None".to_string(),
-                            };
-                            map.insert("address".to_owned(), port);
-                        }
+                    .map(
+                        |(node_id, enr, node_status, data_radius, client_info, score)| {
+                            node_count += 1;
+                            if node_status.state == ConnectionState::Connected {
+                                connected_count += 1
+                            }
+                            let mut map = BTreeMap::new();
+                            map.insert("node_id".to_owned(), hex_encode(node_id.raw()));
+                            map.insert("enr".to_owned(), enr.to_base64());
+                            map.insert("status".to_owned(), format!("{:?}", node_status.state));
+                            map.insert("radius".to_owned(), format!("{data_radius}"));
+                            map.insert("score".to_owned(), score.to_string());
+                            if let Some(client_info) = client_info {
+                                // Expand client name if possible, otherwise leave as-is.
+                                match expand_client_name(client_info) {
+                                    Some(expanded_name) => {
+                                        map.insert("client".to_owned(), expanded_name);
+                                    }
+                                    None => {
+                                        map.insert("client".to_owned(), client_info.to_string());
+                                    }
+                                };
+                            } else {
+                                // Include address (IP:port) for convenience.
+                                // TODO: Can be removed once a portal dashboard does UI-side ENR decoding.
+                                let port = match (enr.udp4_socket(), enr.udp6_socket()) {
+                                    (Some(socket), _) => format!("{socket}"),
+                                    (None, Some(socket)) => format!("{socket}"),
+                                    (None, None) => "None".to_string(),
+                                };
+                                map.insert("address".to_owned(), port);
+                            }
 
-                        map
-                    })
+                            map
+                        },
+                    )
                     .collect(),
             )
         })
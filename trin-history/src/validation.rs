@@ -14,7 +14,10 @@ use ethportal_api::types::execution::{
     receipts::Receipts,
 };
 use ethportal_api::{utils::bytes::hex_encode, HistoryContentKey};
-use trin_validation::{oracle::HeaderOracle, validator::Validator};
+use trin_validation::{
+    oracle::HeaderOracle,
+    validator::{ValidationError, Validator},
+};
 
 pub struct ChainHistoryValidator {
     pub header_oracle: Arc<RwLock<HeaderOracle>>,
@@ -26,7 +29,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
         &self,
         content_key: &HistoryContentKey,
         content: &[u8],
-    ) -> anyhow::Result<()>
+    ) -> Result<(), ValidationError>
     where
         HistoryContentKey: 'async_trait,
     {
@@ -41,13 +44,14 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     return Err(anyhow!(
                         "Content validation failed: Invalid header hash. Found: {header_hash:?} - Expected: {:?}",
                         hex_encode(key.block_hash)
-                    ));
+                    ).into());
                 }
                 self.header_oracle
                     .read()
                     .await
                     .master_acc
                     .validate_header_with_proof(&header_with_proof)
+                    .map_err(ValidationError::Invalid)
             }
             HistoryContentKey::BlockBody(key) => {
                 let block_body = BlockBody::from_ssz_bytes(content)
@@ -57,7 +61,8 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     .read()
                     .await
                     .recursive_find_header_with_proof(H256::from(key.block_hash))
-                    .await?
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?
                     .header;
                 let actual_uncles_root = block_body.uncles_root()?;
                 if actual_uncles_root != trusted_header.uncles_hash {
@@ -65,7 +70,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                         "Content validation failed: Invalid uncles root. Found: {:?} - Expected: {:?}",
                         actual_uncles_root,
                         trusted_header.uncles_hash
-                    ));
+                    ).into());
                 }
                 let actual_txs_root = block_body.transactions_root()?;
                 if actual_txs_root != trusted_header.transactions_root {
@@ -73,7 +78,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                         "Content validation failed: Invalid transactions root. Found: {:?} - Expected: {:?}",
                         actual_txs_root,
                         trusted_header.transactions_root
-                    ));
+                    ).into());
                 }
                 Ok(())
             }
@@ -86,7 +91,8 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     .read()
                     .await
                     .recursive_find_header_with_proof(H256::from(key.block_hash))
-                    .await?
+                    .await
+                    .map_err(ValidationError::Unvalidatable)?
                     .header;
                 let actual_receipts_root = receipts.root()?;
                 if actual_receipts_root != trusted_header.receipts_root {
@@ -94,7 +100,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                         "Content validation failed: Invalid receipts root. Found: {:?} - Expected: {:?}",
                         actual_receipts_root,
                         trusted_header.receipts_root
-                    ));
+                    ).into());
                 }
                 Ok(())
             }
@@ -110,13 +116,14 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                         Found: {:?} - Expected: {:?}",
                         tree_hash_root,
                         key.epoch_hash,
-                    ));
+                    )
+                    .into());
                 }
                 let master_acc = &self.header_oracle.read().await.master_acc;
                 if !master_acc.historical_epochs.contains(&tree_hash_root) {
                     return Err(anyhow!(
                         "Content validation failed: Invalid epoch accumulator, missing from master accumulator."
-                    ));
+                    ).into());
                 }
                 Ok(())
             }
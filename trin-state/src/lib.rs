@@ -2,24 +2,22 @@
 
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use discv5::TalkRequest;
 use network::StateNetwork;
-use tokio::{
-    sync::{mpsc, RwLock},
-    task::JoinHandle,
-};
+use tokio::{sync::mpsc, task::JoinHandle};
 use tracing::info;
-use utp_rs::socket::UtpSocket;
 
 use crate::{events::StateEvents, jsonrpc::StateRequestHandler};
+use ethportal_api::types::cli::STATE_NETWORK;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::types::jsonrpc::request::StateJsonRpcRequest;
 use portalnet::{
     config::PortalnetConfig,
-    discovery::{Discovery, UtpEnr},
-    storage::PortalStorageConfig,
+    shutdown::ShutdownSignal,
+    subnetwork::{PortalSubnetwork, SubnetworkContext, SubnetworkHandle},
+    types::messages::ProtocolId,
 };
-use trin_validation::oracle::HeaderOracle;
 
 pub mod events;
 mod jsonrpc;
@@ -28,47 +26,58 @@ mod trie;
 pub mod utils;
 pub mod validation;
 
-type StateHandler = Option<StateRequestHandler>;
-type StateNetworkTask = Option<JoinHandle<()>>;
-type StateEventTx = Option<mpsc::UnboundedSender<TalkRequest>>;
-type StateJsonRpcTx = Option<mpsc::UnboundedSender<StateJsonRpcRequest>>;
+/// The state subnetwork.
+pub struct StateSubnetwork;
 
-pub async fn initialize_state_network(
-    discovery: &Arc<Discovery>,
-    utp_socket: Arc<UtpSocket<UtpEnr>>,
-    portalnet_config: PortalnetConfig,
-    storage_config: PortalStorageConfig,
-    header_oracle: Arc<RwLock<HeaderOracle>>,
-) -> anyhow::Result<(StateHandler, StateNetworkTask, StateEventTx, StateJsonRpcTx)> {
-    let (state_jsonrpc_tx, state_jsonrpc_rx) = mpsc::unbounded_channel::<StateJsonRpcRequest>();
-    let (state_event_tx, state_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
-    let state_network = StateNetwork::new(
-        Arc::clone(discovery),
-        utp_socket,
-        storage_config,
-        portalnet_config.clone(),
-        header_oracle,
-    )
-    .await?;
-    let state_network = Arc::new(state_network);
-    let state_handler = StateRequestHandler {
-        network: Arc::clone(&state_network),
-        state_rx: state_jsonrpc_rx,
-    };
-    let state_network_task =
-        spawn_state_network(Arc::clone(&state_network), portalnet_config, state_event_rx);
-    Ok((
-        Some(state_handler),
-        Some(state_network_task),
-        Some(state_event_tx),
-        Some(state_jsonrpc_tx),
-    ))
+#[async_trait]
+impl PortalSubnetwork for StateSubnetwork {
+    fn name(&self) -> &'static str {
+        STATE_NETWORK
+    }
+
+    fn protocol_id(&self) -> ProtocolId {
+        ProtocolId::State
+    }
+
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle> {
+        let (state_jsonrpc_tx, state_jsonrpc_rx) = mpsc::unbounded_channel::<StateJsonRpcRequest>();
+        let (state_event_tx, state_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
+        let state_network = StateNetwork::new(
+            Arc::clone(&context.discovery),
+            Arc::clone(&context.utp_socket),
+            context.storage_config.clone(),
+            context.portalnet_config.clone(),
+            context.header_oracle.clone(),
+        )
+        .await?;
+        let state_network = Arc::new(state_network);
+        let state_handler = StateRequestHandler {
+            network: Arc::clone(&state_network),
+            state_rx: state_jsonrpc_rx,
+        };
+        tokio::spawn(state_handler.handle_client_queries());
+        let event_stream = state_network.overlay.event_stream_sender();
+        let admin = state_network.overlay.clone();
+        spawn_state_network(
+            state_network,
+            context.portalnet_config.clone(),
+            state_event_rx,
+            context.shutdown_signal.clone(),
+        );
+        Ok(
+            SubnetworkHandle::new(self.name(), self.protocol_id(), state_event_tx)
+                .with_jsonrpc_tx(state_jsonrpc_tx)
+                .with_event_stream(event_stream)
+                .with_admin(admin),
+        )
+    }
 }
 
 pub fn spawn_state_network(
     network: Arc<StateNetwork>,
     portalnet_config: PortalnetConfig,
     state_event_rx: mpsc::UnboundedReceiver<TalkRequest>,
+    shutdown_signal: ShutdownSignal,
 ) -> JoinHandle<()> {
     let bootnode_enrs: Vec<Enr> = portalnet_config.bootnodes.into();
     info!(
@@ -88,8 +97,6 @@ pub fn spawn_state_network(
         // hacky test: make sure we establish a session with the boot node
         network.overlay.ping_bootnodes().await;
 
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        shutdown_signal.wait().await;
     })
 }
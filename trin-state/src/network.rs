@@ -5,6 +5,7 @@ use parking_lot::RwLock as PLRwLock;
 use tokio::sync::RwLock;
 use utp_rs::socket::UtpSocket;
 
+use ethportal_api::types::cli::STATE_NETWORK;
 use ethportal_api::types::distance::XorMetric;
 use ethportal_api::types::enr::Enr;
 use ethportal_api::StateContentKey;
@@ -38,15 +39,18 @@ impl StateNetwork {
         let triedb = TrieDB::new(Arc::new(db));
         let trie = EthTrie::new(Arc::new(triedb));
         let storage = Arc::new(PLRwLock::new(PortalStorage::new(
-            storage_config,
+            storage_config.clone(),
             ProtocolId::State,
         )?));
         let validator = Arc::new(StateValidator { header_oracle });
+        let overlay_tuning = portal_config.overlay_tuning(STATE_NETWORK);
         let bootnode_enrs: Vec<Enr> = portal_config.bootnodes.into();
         let config = OverlayConfig {
             bootnode_enrs,
+            routing_table_dir: Some(storage_config.node_data_dir),
             ..Default::default()
-        };
+        }
+        .with_tuning(&overlay_tuning);
         let overlay = OverlayProtocol::new(
             config,
             discovery,
@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use tokio::sync::RwLock;
 
 use ethportal_api::StateContentKey;
-use trin_validation::{oracle::HeaderOracle, validator::Validator};
+use trin_validation::{
+    oracle::HeaderOracle,
+    validator::{ValidationError, Validator},
+};
 
 pub struct StateValidator {
     pub header_oracle: Arc<RwLock<HeaderOracle>>,
@@ -16,7 +19,7 @@ impl Validator<StateContentKey> for StateValidator {
         &self,
         _content_key: &StateContentKey,
         _content: &[u8],
-    ) -> anyhow::Result<()>
+    ) -> Result<(), ValidationError>
     where
         StateContentKey: 'async_trait,
     {
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use ethportal_api::types::constants::CONTENT_ABSENT;
+use ethportal_api::types::jsonrpc::endpoints::TransactionGossipEndpoint;
+use ethportal_api::types::jsonrpc::request::TransactionGossipJsonRpcRequest;
+use ethportal_api::utils::bytes::hex_encode;
+use ethportal_api::{ContentValue, TransactionGossipContentKey, TransactionGossipContentValue};
+use portalnet::storage::ContentStore;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use trin_validation::validator::Validator;
+
+use crate::network::TransactionGossipNetwork;
+use crate::validation::TransactionGossipValidator;
+
+/// Handles Transaction gossip network JSON-RPC requests
+pub struct TransactionGossipRequestHandler {
+    pub network: Arc<TransactionGossipNetwork>,
+    pub rpc_rx: mpsc::UnboundedReceiver<TransactionGossipJsonRpcRequest>,
+}
+
+impl TransactionGossipRequestHandler {
+    /// Complete RPC requests for the Transaction gossip network.
+    pub async fn handle_client_queries(mut self) {
+        while let Some(request) = self.rpc_rx.recv().await {
+            let network = Arc::clone(&self.network);
+            tokio::spawn(async move { complete_request(network, request).await });
+        }
+    }
+}
+
+/// Generates a response for a given request and sends it to the receiver.
+async fn complete_request(
+    network: Arc<TransactionGossipNetwork>,
+    request: TransactionGossipJsonRpcRequest,
+) {
+    let response: Result<Value, String> = match request.endpoint {
+        TransactionGossipEndpoint::DataRadius => {
+            let radius = network.overlay.data_radius();
+            Ok(json!(*radius))
+        }
+        TransactionGossipEndpoint::LocalContent(content_key) => {
+            local_content(network, content_key).await
+        }
+        TransactionGossipEndpoint::Gossip(content_key, content_value) => {
+            gossip(network, content_key, content_value).await
+        }
+    };
+    let _ = request.resp.send(response);
+}
+
+/// Constructs a JSON call for the LocalContent method.
+async fn local_content(
+    network: Arc<TransactionGossipNetwork>,
+    content_key: TransactionGossipContentKey,
+) -> Result<Value, String> {
+    match network.overlay.store.read().get(&content_key) {
+        Ok(Some(val)) => Ok(Value::String(hex_encode(val))),
+        Ok(None) => Ok(Value::String(CONTENT_ABSENT.to_string())),
+        Err(err) => Err(format!(
+            "Database error while looking for content key in local storage: {content_key:?}, with error: {err}",
+        )),
+    }
+}
+
+/// Constructs a JSON call for the Gossip method. The transaction is validated before it is
+/// gossiped, so that invalid transactions are rejected locally, rather than by every peer.
+async fn gossip(
+    network: Arc<TransactionGossipNetwork>,
+    content_key: TransactionGossipContentKey,
+    content_value: TransactionGossipContentValue,
+) -> Result<Value, String> {
+    let data = content_value.encode();
+    TransactionGossipValidator
+        .validate_content(&content_key, &data)
+        .await
+        .map_err(|err| format!("Invalid transaction: {err}"))?;
+    let content_values = vec![(content_key, data)];
+    let num_stored = network.overlay.propagate_gossip(content_values).await;
+    Ok(num_stored.into())
+}
@@ -0,0 +1,100 @@
+#![warn(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use discv5::TalkRequest;
+use network::TransactionGossipNetwork;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::info;
+
+use crate::{events::TransactionGossipEvents, jsonrpc::TransactionGossipRequestHandler};
+use ethportal_api::types::cli::TRANSACTION_GOSSIP_NETWORK;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::types::jsonrpc::request::TransactionGossipJsonRpcRequest;
+use portalnet::{
+    config::PortalnetConfig,
+    shutdown::ShutdownSignal,
+    subnetwork::{PortalSubnetwork, SubnetworkContext, SubnetworkHandle},
+    types::messages::ProtocolId,
+};
+
+pub mod events;
+mod jsonrpc;
+pub mod network;
+pub mod validation;
+
+/// The transaction gossip subnetwork.
+pub struct TransactionGossipSubnetwork;
+
+#[async_trait]
+impl PortalSubnetwork for TransactionGossipSubnetwork {
+    fn name(&self) -> &'static str {
+        TRANSACTION_GOSSIP_NETWORK
+    }
+
+    fn protocol_id(&self) -> ProtocolId {
+        ProtocolId::TransactionGossip
+    }
+
+    async fn initialize(&self, context: &SubnetworkContext) -> anyhow::Result<SubnetworkHandle> {
+        let (txgossip_jsonrpc_tx, txgossip_jsonrpc_rx) =
+            mpsc::unbounded_channel::<TransactionGossipJsonRpcRequest>();
+        let (txgossip_event_tx, txgossip_event_rx) = mpsc::unbounded_channel::<TalkRequest>();
+        let txgossip_network = TransactionGossipNetwork::new(
+            Arc::clone(&context.discovery),
+            Arc::clone(&context.utp_socket),
+            context.storage_config.clone(),
+            context.portalnet_config.clone(),
+        )
+        .await?;
+        let txgossip_network = Arc::new(txgossip_network);
+        let txgossip_handler = TransactionGossipRequestHandler {
+            network: Arc::clone(&txgossip_network),
+            rpc_rx: txgossip_jsonrpc_rx,
+        };
+        tokio::spawn(txgossip_handler.handle_client_queries());
+        let event_stream = txgossip_network.overlay.event_stream_sender();
+        let admin = txgossip_network.overlay.clone();
+        spawn_txgossip_network(
+            txgossip_network,
+            context.portalnet_config.clone(),
+            txgossip_event_rx,
+            context.shutdown_signal.clone(),
+        );
+        Ok(
+            SubnetworkHandle::new(self.name(), self.protocol_id(), txgossip_event_tx)
+                .with_jsonrpc_tx(txgossip_jsonrpc_tx)
+                .with_event_stream(event_stream)
+                .with_admin(admin),
+        )
+    }
+}
+
+pub fn spawn_txgossip_network(
+    network: Arc<TransactionGossipNetwork>,
+    portalnet_config: PortalnetConfig,
+    txgossip_event_rx: mpsc::UnboundedReceiver<TalkRequest>,
+    shutdown_signal: ShutdownSignal,
+) -> JoinHandle<()> {
+    let bootnode_enrs: Vec<Enr> = portalnet_config.bootnodes.into();
+    info!(
+        "About to spawn Transaction Gossip Network with {} boot nodes.",
+        bootnode_enrs.len()
+    );
+
+    tokio::spawn(async move {
+        let txgossip_events = TransactionGossipEvents {
+            network: Arc::clone(&network),
+            event_rx: txgossip_event_rx,
+        };
+
+        // Spawn transaction gossip event handler
+        tokio::spawn(txgossip_events.start());
+
+        // hacky test: make sure we establish a session with the boot node
+        network.overlay.ping_bootnodes().await;
+
+        shutdown_signal.wait().await;
+    })
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock as PLRwLock;
+use utp_rs::socket::UtpSocket;
+
+use ethportal_api::types::cli::TRANSACTION_GOSSIP_NETWORK;
+use ethportal_api::types::distance::XorMetric;
+use ethportal_api::types::enr::Enr;
+use ethportal_api::TransactionGossipContentKey;
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, UtpEnr},
+    overlay::{OverlayConfig, OverlayProtocol},
+    storage::{PortalStorage, PortalStorageConfig},
+    types::messages::ProtocolId,
+};
+
+use crate::validation::TransactionGossipValidator;
+
+/// Transaction gossip network layer on top of the overlay protocol. Encapsulates transaction
+/// gossip network specific data and logic.
+#[derive(Clone)]
+pub struct TransactionGossipNetwork {
+    pub overlay: Arc<
+        OverlayProtocol<
+            TransactionGossipContentKey,
+            XorMetric,
+            TransactionGossipValidator,
+            PortalStorage,
+        >,
+    >,
+}
+
+impl TransactionGossipNetwork {
+    pub async fn new(
+        discovery: Arc<Discovery>,
+        utp_socket: Arc<UtpSocket<UtpEnr>>,
+        storage_config: PortalStorageConfig,
+        portal_config: PortalnetConfig,
+    ) -> anyhow::Result<Self> {
+        let overlay_tuning = portal_config.overlay_tuning(TRANSACTION_GOSSIP_NETWORK);
+        let bootnode_enrs: Vec<Enr> = portal_config.bootnodes.into();
+        let config = OverlayConfig {
+            bootnode_enrs,
+            routing_table_dir: Some(storage_config.node_data_dir.clone()),
+            ..Default::default()
+        }
+        .with_tuning(&overlay_tuning);
+        let storage = Arc::new(PLRwLock::new(PortalStorage::new(
+            storage_config,
+            ProtocolId::TransactionGossip,
+        )?));
+        let validator = Arc::new(TransactionGossipValidator);
+        let overlay = OverlayProtocol::new(
+            config,
+            discovery,
+            utp_socket,
+            storage,
+            ProtocolId::TransactionGossip,
+            validator,
+        )
+        .await;
+
+        Ok(Self {
+            overlay: Arc::new(overlay),
+        })
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use ethereum_types::{H160, U256};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+use ethportal_api::types::execution::transaction::Transaction;
+use ethportal_api::utils::bytes::hex_encode;
+use ethportal_api::TransactionGossipContentKey;
+use trin_validation::{
+    constants::CHAIN_ID,
+    validator::{ValidationError, Validator},
+};
+
+/// Gas charged for every transaction, before any calldata or execution costs.
+const TX_BASE_GAS: u64 = 21_000;
+
+/// Validates gossiped transactions without access to the state of the chain.
+///
+/// Only stateless checks are done: the encoding and hash of the transaction, its chain id, gas
+/// limit and fee caps, and its signature. The nonce and balance of the sender are not checked,
+/// since the state network can't serve account proofs yet, so a transaction that passes may still
+/// be rejected by block builders.
+pub struct TransactionGossipValidator;
+
+#[async_trait]
+impl Validator<TransactionGossipContentKey> for TransactionGossipValidator {
+    async fn validate_content(
+        &self,
+        content_key: &TransactionGossipContentKey,
+        content: &[u8],
+    ) -> Result<(), ValidationError>
+    where
+        TransactionGossipContentKey: 'async_trait,
+    {
+        match content_key {
+            TransactionGossipContentKey::Transaction(key) => {
+                let tx = Transaction::decode(content)
+                    .map_err(|err| anyhow!("Transaction content has invalid encoding: {err:?}"))?;
+                let tx_hash = tx.hash();
+                if tx_hash.as_bytes() != key.tx_hash {
+                    return Err(anyhow!(
+                        "Content validation failed: Invalid transaction hash. Found: {tx_hash:?} - Expected: {:?}",
+                        hex_encode(key.tx_hash)
+                    )
+                    .into());
+                }
+                validate_transaction(&tx)?;
+                recover_sender(&tx)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Performs the stateless checks that every transaction must pass to be includable in a block.
+pub fn validate_transaction(tx: &Transaction) -> anyhow::Result<()> {
+    if let Some(chain_id) = tx.chain_id() {
+        if chain_id != U256::from(CHAIN_ID) {
+            bail!("Transaction is bound to chain id {chain_id}, expected {CHAIN_ID}");
+        }
+    }
+    if tx.gas_limit() < U256::from(TX_BASE_GAS) {
+        bail!(
+            "Transaction gas limit {} is below the intrinsic gas of {TX_BASE_GAS}",
+            tx.gas_limit()
+        );
+    }
+    if let Transaction::EIP1559(tx) = tx {
+        if tx.max_priority_fee_per_gas > tx.max_fee_per_gas {
+            bail!("Transaction max priority fee per gas is higher than its max fee per gas");
+        }
+    }
+    Ok(())
+}
+
+/// Recovers the address of the account that signed the transaction.
+pub fn recover_sender(tx: &Transaction) -> anyhow::Result<H160> {
+    let (recovery_id, r, s) = tx
+        .signature()
+        .map_err(|err| anyhow!("Invalid transaction signature: {err:?}"))?;
+    let mut signature_bytes = [0u8; 64];
+    r.to_big_endian(&mut signature_bytes[..32]);
+    s.to_big_endian(&mut signature_bytes[32..]);
+    let signature = Signature::from_slice(&signature_bytes)?;
+    // EIP-2: signatures with a high s value are malleable, and rejected by the network.
+    if signature.normalize_s().is_some() {
+        bail!("Invalid transaction signature: s value is too high");
+    }
+    let recovery_id = RecoveryId::from_byte(recovery_id)
+        .ok_or_else(|| anyhow!("Invalid transaction signature recovery id"))?;
+    let verifying_key = VerifyingKey::recover_from_prehash(
+        tx.signature_hash().as_bytes(),
+        &signature,
+        recovery_id,
+    )?;
+    let public_key = verifying_key.to_encoded_point(false);
+    let public_key_hash = keccak_hash::keccak(&public_key.as_bytes()[1..]);
+    Ok(H160::from_slice(&public_key_hash[12..]))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use ethereum_types::H256;
+    use ethportal_api::utils::bytes::hex_decode;
+    use ethportal_api::TransactionKey;
+
+    // Signed transaction from the EIP-155 specification.
+    const EIP155_TX: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    #[test]
+    fn eip155_signature_hash() {
+        let tx = Transaction::decode(&hex_decode(EIP155_TX).unwrap()).unwrap();
+        assert_eq!(
+            tx.signature_hash(),
+            H256::from_str("0xdaf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn eip155_recover_sender() {
+        let tx = Transaction::decode(&hex_decode(EIP155_TX).unwrap()).unwrap();
+        assert_eq!(
+            recover_sender(&tx).unwrap(),
+            H160::from_str("0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn validate_transaction_content() {
+        let content = hex_decode(EIP155_TX).unwrap();
+        let tx = Transaction::decode(&content).unwrap();
+        let content_key = TransactionGossipContentKey::Transaction(TransactionKey {
+            tx_hash: tx.hash().0,
+        });
+
+        TransactionGossipValidator
+            .validate_content(&content_key, &content)
+            .await
+            .unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    #[should_panic(expected = "Invalid transaction hash")]
+    async fn invalidate_transaction_with_wrong_hash() {
+        let content = hex_decode(EIP155_TX).unwrap();
+        let content_key =
+            TransactionGossipContentKey::Transaction(TransactionKey { tx_hash: [0; 32] });
+
+        TransactionGossipValidator
+            .validate_content(&content_key, &content)
+            .await
+            .unwrap();
+    }
+}
@@ -1,10 +1,31 @@
+use std::sync::OnceLock;
+
 use tracing_subscriber::EnvFilter;
 
+type ReloadLogFilter = Box<dyn Fn(EnvFilter) -> Result<(), String> + Send + Sync>;
+
+/// Replaces the filter of the logger installed by `init_tracing_logger`.
+static RELOAD_LOG_FILTER: OnceLock<ReloadLogFilter> = OnceLock::new();
+
 pub fn init_tracing_logger() {
-    tracing_subscriber::fmt()
+    let builder = tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
         .with_ansi(detect_ansi_support())
-        .init();
+        .with_filter_reloading();
+    let handle = builder.reload_handle();
+    builder.init();
+    let _ = RELOAD_LOG_FILTER.set(Box::new(move |filter| {
+        handle.reload(filter).map_err(|err| err.to_string())
+    }));
+}
+
+/// Changes the log filter at runtime, using the `RUST_LOG` syntax, eg. "info,discv5=debug".
+pub fn set_log_filter(filter: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(filter).map_err(|err| err.to_string())?;
+    let reload = RELOAD_LOG_FILTER
+        .get()
+        .ok_or("The log filter can only be changed after the logger is initialized")?;
+    reload(filter)
 }
 
 pub fn detect_ansi_support() -> bool {
@@ -29,3 +50,21 @@ pub fn detect_ansi_support() -> bool {
             .unwrap_or(false)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_log_filter_validates_and_reloads() {
+        assert!(set_log_filter("info,discv5=notalevel").is_err());
+        assert_eq!(
+            set_log_filter("info"),
+            Err("The log filter can only be changed after the logger is initialized".to_string())
+        );
+
+        init_tracing_logger();
+        assert_eq!(set_log_filter("info,discv5=debug"), Ok(()));
+        assert!(set_log_filter("info,discv5=notalevel").is_err());
+    }
+}
@@ -4,14 +4,21 @@ use serde_json::Value;
 use tokio::sync::mpsc;
 
 use crate::accumulator::MasterAccumulator;
+use crate::constants::MERGE_BLOCK_NUMBER;
+use ethportal_api::types::execution::block_body::BlockBody;
 use ethportal_api::types::execution::header::HeaderWithProof;
 use ethportal_api::types::jsonrpc::endpoints::HistoryEndpoint;
 use ethportal_api::types::jsonrpc::request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest};
-use ethportal_api::types::portal::ContentInfo;
+use ethportal_api::types::portal::HistoryContentInfo;
 use ethportal_api::{
-    BlockHeaderKey, HistoryContentKey, HistoryContentValue, PossibleHistoryContentValue,
+    BlockBodyKey, BlockHeaderKey, HistoryContentKey, HistoryContentValue,
+    PossibleHistoryContentValue,
 };
 
+/// The maximum number of blocks that are walked back from the latest finalized block to find the
+/// canonical hash at a post-merge height. It is the number of slots covered by a historical root.
+pub const MAX_CANONICAL_WALK_DEPTH: u64 = 8192;
+
 /// Responsible for dispatching cross-overlay-network requests
 /// for data to perform validation.
 #[derive(Clone, Debug)]
@@ -22,6 +29,9 @@ pub struct HeaderOracle {
     pub history_jsonrpc_tx: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
     pub beacon_jsonrpc_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
     pub master_acc: MasterAccumulator,
+    /// The number and hash of the execution block of the latest finalized beacon block, once the
+    /// beacon network has verified one. It anchors the canonical chain after the merge.
+    pub finalized_execution_block: Option<(u64, H256)>,
 }
 
 impl HeaderOracle {
@@ -30,9 +40,52 @@ impl HeaderOracle {
             history_jsonrpc_tx: None,
             beacon_jsonrpc_tx: None,
             master_acc,
+            finalized_execution_block: None,
         }
     }
 
+    /// Records the execution block of the latest verified finalized beacon block.
+    pub fn set_finalized_execution_block(&mut self, block_number: u64, block_hash: H256) {
+        self.finalized_execution_block = Some((block_number, block_hash));
+    }
+
+    /// Returns the hash of the canonical block at `block_number`. Pre-merge hashes are committed
+    /// to by the master accumulator. Post-merge hashes are found by following parent hashes back
+    /// from the latest finalized block, so only recent, finalized blocks can be looked up.
+    pub async fn get_canonical_hash_at_height(&self, block_number: u64) -> anyhow::Result<H256> {
+        if block_number <= MERGE_BLOCK_NUMBER {
+            return self.get_hash_at_height(block_number).await;
+        }
+        let (finalized_number, finalized_hash) = self
+            .finalized_execution_block
+            .ok_or_else(|| anyhow!("No finalized block is known to anchor post-merge headers"))?;
+        if block_number > finalized_number {
+            return Err(anyhow!(
+                "Block {block_number} is not finalized yet, the latest finalized block is {finalized_number}"
+            ));
+        }
+        if finalized_number - block_number > MAX_CANONICAL_WALK_DEPTH {
+            return Err(anyhow!(
+                "Block {block_number} is too far behind the latest finalized block {finalized_number} to be looked up"
+            ));
+        }
+        let mut block_hash = finalized_hash;
+        for number in (block_number..finalized_number).rev() {
+            let header = self
+                .recursive_find_header_with_proof(block_hash)
+                .await?
+                .header;
+            if header.hash() != block_hash || header.number != number + 1 {
+                return Err(anyhow!(
+                    "Received an invalid header while looking up block {}",
+                    number + 1
+                ));
+            }
+            block_hash = header.parent_hash;
+        }
+        Ok(block_hash)
+    }
+
     // Only serves pre-block hashes aka. portal-network verified data only
     pub async fn get_hash_at_height(&self, block_number: u64) -> anyhow::Result<H256> {
         self.master_acc
@@ -49,6 +102,39 @@ impl HeaderOracle {
         let content_key = HistoryContentKey::BlockHeaderWithProof(BlockHeaderKey {
             block_hash: block_hash.0,
         });
+        let content = self
+            .recursive_find_history_content(content_key, "HeaderWithProof")
+            .await?;
+        match content {
+            HistoryContentValue::BlockHeaderWithProof(content) => Ok(content),
+            _ => Err(anyhow!(
+                "Invalid HistoryContentValue received from HeaderWithProof lookup, expected BlockHeaderWithProof: {content:?}"
+            )),
+        }
+    }
+
+    /// Returns the BlockBody for the given block hash by performing a recursive find content
+    /// request.
+    pub async fn recursive_find_block_body(&self, block_hash: H256) -> anyhow::Result<BlockBody> {
+        let content_key = HistoryContentKey::BlockBody(BlockBodyKey {
+            block_hash: block_hash.0,
+        });
+        let content = self
+            .recursive_find_history_content(content_key, "BlockBody")
+            .await?;
+        match content {
+            HistoryContentValue::BlockBody(content) => Ok(content),
+            _ => Err(anyhow!(
+                "Invalid HistoryContentValue received from BlockBody lookup, expected BlockBody: {content:?}"
+            )),
+        }
+    }
+
+    async fn recursive_find_history_content(
+        &self,
+        content_key: HistoryContentKey,
+        content_name: &str,
+    ) -> anyhow::Result<HistoryContentValue> {
         let endpoint = HistoryEndpoint::RecursiveFindContent(content_key);
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
         let request = HistoryJsonRpcRequest { endpoint, resp };
@@ -62,31 +148,23 @@ impl HeaderOracle {
             None => return Err(anyhow!("No response from chain history subnetwork")),
         };
         let content = match serde_json::from_value(content)? {
-            ContentInfo::Content { content, .. } => content,
-            ContentInfo::ConnectionId { .. } => {
+            HistoryContentInfo::Content { content, .. } => content,
+            HistoryContentInfo::ConnectionId { .. } => {
                 return Err(anyhow!(
-                    "Invalid ContentInfo (cid) received from HeaderWithProof lookup"
+                    "Invalid ContentInfo (cid) received from {content_name} lookup"
                 ))
             }
-            ContentInfo::Enrs { .. } => {
+            HistoryContentInfo::Enrs { .. } => {
                 return Err(anyhow!(
-                    "Invalid ContentInfo (enrs) received from HeaderWithProof lookup"
+                    "Invalid ContentInfo (enrs) received from {content_name} lookup"
                 ))
             }
         };
-        let content = match content {
-            PossibleHistoryContentValue::ContentPresent(header) => header,
+        match content {
+            PossibleHistoryContentValue::ContentPresent(content) => Ok(content),
             PossibleHistoryContentValue::ContentAbsent => {
-                return Err(anyhow!(
-                    "ContentAbsent received from HeaderWithProof lookup"
-                ))
+                Err(anyhow!("ContentAbsent received from {content_name} lookup"))
             }
-        };
-        match content {
-            HistoryContentValue::BlockHeaderWithProof(content) => Ok(content),
-            _ => Err(anyhow!(
-                "Invalid HistoryContentValue received from HeaderWithProof lookup, expected BlockHeaderWithProof: {content:?}"
-            )),
         }
     }
 
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use async_trait::async_trait;
 
 use ethportal_api::types::content_key::overlay::IdentityContentKey;
@@ -9,11 +11,46 @@ pub trait Validator<TContentKey> {
         &self,
         content_key: &TContentKey,
         content: &[u8],
-    ) -> anyhow::Result<()>
+    ) -> Result<(), ValidationError>
     where
         TContentKey: 'async_trait;
 }
 
+/// The reason content failed validation.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The content is provably invalid, so the peer that served it misbehaved.
+    Invalid(anyhow::Error),
+    /// This node is unable to validate the content yet, e.g. because the data it is checked
+    /// against could not be found. The content may well be valid.
+    Unvalidatable(anyhow::Error),
+}
+
+impl ValidationError {
+    /// Returns `true` if the content is provably invalid.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::Invalid(_))
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "Invalid content: {err}"),
+            Self::Unvalidatable(err) => write!(f, "Unable to validate content: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Errors are assumed to be caused by the content, unless marked as `Unvalidatable`.
+impl From<anyhow::Error> for ValidationError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Invalid(err)
+    }
+}
+
 /// For use in tests where no validation needs to be performed.
 pub struct MockValidator {}
 
@@ -23,7 +60,7 @@ impl Validator<IdentityContentKey> for MockValidator {
         &self,
         _content_key: &IdentityContentKey,
         _content: &[u8],
-    ) -> anyhow::Result<()>
+    ) -> Result<(), ValidationError>
     where
         IdentityContentKey: 'async_trait,
     {